@@ -1,6 +1,38 @@
 //! Emits linker flags depending on platforms and features.
 
-fn main() {
+/// Links GNUstep Base and GUI. `gnustep-config` knows where these live, so we defer to it when
+/// it's available (or pointed to by `GNUSTEP_CONFIG`), and fall back to the standard library names
+/// otherwise. The runtime itself (libobjc2) is linked by the `objc` crate.
+#[cfg(feature = "gnustep")]
+fn link() {
+    use std::process::Command;
+
+    println!("cargo:rerun-if-env-changed=GNUSTEP_CONFIG");
+
+    let config = std::env::var("GNUSTEP_CONFIG").unwrap_or_else(|_| "gnustep-config".into());
+
+    match Command::new(&config).args(&["--base-libs", "--gui-libs"]).output() {
+        Ok(output) if output.status.success() => {
+            for flag in String::from_utf8_lossy(&output.stdout).split_whitespace() {
+                if let Some(path) = flag.strip_prefix("-L") {
+                    println!("cargo:rustc-link-search=native={}", path);
+                } else if let Some(lib) = flag.strip_prefix("-l") {
+                    println!("cargo:rustc-link-lib=dylib={}", lib);
+                }
+            }
+        },
+
+        _ => {
+            println!("cargo:warning=`{}` wasn't found; linking GNUstep by library name", config);
+            println!("cargo:rustc-link-lib=dylib=gnustep-gui");
+            println!("cargo:rustc-link-lib=dylib=gnustep-base");
+            println!("cargo:rustc-link-lib=dylib=objc");
+        }
+    }
+}
+
+#[cfg(not(feature = "gnustep"))]
+fn link() {
     println!("cargo:rustc-link-lib=framework=Foundation");
 
     #[cfg(feature = "appkit")]
@@ -25,3 +57,7 @@ fn main() {
     #[cfg(feature = "quicklook")]
     println!("cargo:rustc-link-lib=framework=QuickLook");
 }
+
+fn main() {
+    link();
+}
@@ -120,12 +120,10 @@ impl WindowDelegate for AppWindow {
             .map(|frame| {
                 LayoutConstraint::activate(frame);
 
-                vec![
-                    frame[0].animator.clone(),
-                    frame[1].animator.clone(),
-                    frame[2].animator.clone(),
-                    frame[3].animator.clone(),
-                ]
+                frame
+                    .iter()
+                    .map(|constraint| constraint.animator.clone().expect("View constraints always have an animator"))
+                    .collect()
             })
             .collect::<Vec<Vec<LayoutConstraintAnimatorProxy>>>();
 
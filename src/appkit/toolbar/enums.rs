@@ -36,6 +36,8 @@ pub enum ItemIdentifier {
     Custom(&'static str),
 
     /// Represents a standard cloud-sharing icon. Available from 10.12 onwards.
+    ///
+    /// GNUstep has no such item, and will ask your delegate for it like a custom one.
     CloudSharing,
 
     /// A flexible space identifier. Fills space, flexibly.
@@ -59,6 +61,7 @@ pub enum ItemIdentifier {
     ///
     /// Note that this API was introduced in Big Sur (11.0), and you may need to check against this
     /// at runtime to ensure behavior is appropriate on older OS versions (if you support them).
+    /// GNUstep has no such item, and will ask your delegate for it like a custom one.
     ToggleSidebar,
 
     /// Standard toolbar item for a spot that tracks the sidebar border. In your delegate, use this
@@ -81,8 +84,12 @@ pub enum ItemIdentifier {
 }
 
 extern "C" {
+    #[cfg(not(feature = "gnustep"))]
     static NSToolbarToggleSidebarItemIdentifier: id;
+
+    #[cfg(not(feature = "gnustep"))]
     static NSToolbarCloudSharingItemIdentifier: id;
+
     static NSToolbarFlexibleSpaceItemIdentifier: id;
     static NSToolbarPrintItemIdentifier: id;
     static NSToolbarShowColorsItemIdentifier: id;
@@ -96,14 +103,25 @@ impl ItemIdentifier {
         unsafe {
             match self {
                 Self::Custom(s) => NSString::new(s).into(),
+                #[cfg(not(feature = "gnustep"))]
                 Self::CloudSharing => NSToolbarCloudSharingItemIdentifier,
+
+                // GNUstep doesn't export these, and treats them like any other custom identifier.
+                #[cfg(feature = "gnustep")]
+                Self::CloudSharing => NSString::no_copy("NSToolbarCloudSharingItemIdentifier").into(),
+
                 Self::FlexibleSpace => NSToolbarFlexibleSpaceItemIdentifier,
                 Self::Print => NSToolbarPrintItemIdentifier,
                 Self::Colors => NSToolbarShowColorsItemIdentifier,
                 Self::Fonts => NSToolbarShowFontsItemIdentifier,
                 Self::Space => NSToolbarSpaceItemIdentifier,
+
+                #[cfg(not(feature = "gnustep"))]
                 Self::ToggleSidebar => NSToolbarToggleSidebarItemIdentifier,
 
+                #[cfg(feature = "gnustep")]
+                Self::ToggleSidebar => NSString::no_copy("NSToolbarToggleSidebarItemIdentifier").into(),
+
                 // This ensures that the framework compiles and runs on 10.15.7 and lower; it will
                 // not actually work on anything except 11.0+. Use a runtime check to be safe.
                 Self::SidebarTracker => NSString::no_copy("NSToolbarSidebarTrackingSeparatorItemIdentifier").into()
@@ -3,7 +3,7 @@
 //!
 //! UNFORTUNATELY, this is a very old and janky API. So... yeah.
 
-use core_graphics::geometry::CGSize;
+use crate::core_graphics::geometry::CGSize;
 use std::fmt;
 
 use objc::runtime::Object;
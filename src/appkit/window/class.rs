@@ -3,15 +3,14 @@
 
 use std::sync::Once;
 
-use core_graphics::base::CGFloat;
-
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{class, sel, sel_impl};
 
 use crate::appkit::window::{WindowDelegate, WINDOW_DELEGATE_PTR};
 use crate::foundation::{id, load_or_register_class, NSUInteger, BOOL, NO, YES};
-use crate::utils::{load, CGSize};
+use crate::geometry::Size;
+use crate::utils::load;
 
 /// Called when an `NSWindowDelegate` receives a `windowWillClose:` event.
 /// Good place to clean up memory and what not.
@@ -56,14 +55,11 @@ extern "C" fn did_change_screen_profile<T: WindowDelegate>(this: &Object, _: Sel
 }
 
 /// Called when an `NSWindowDelegate` receives a `windowDidChangeScreen:` event.
-extern "C" fn will_resize<T: WindowDelegate>(this: &Object, _: Sel, _: id, size: CGSize) -> CGSize {
+extern "C" fn will_resize<T: WindowDelegate>(this: &Object, _: Sel, _: id, size: Size) -> Size {
     let window = load::<T>(this, WINDOW_DELEGATE_PTR);
-    let s = window.will_resize(size.width as f64, size.height as f64);
+    let s = window.will_resize(size.width, size.height);
 
-    CGSize {
-        width: s.0 as CGFloat,
-        height: s.1 as CGFloat
-    }
+    Size::new(s.0, s.1)
 }
 
 /// Called when an `NSWindowDelegate` receives a `windowDidChangeScreen:` event.
@@ -115,15 +111,12 @@ extern "C" fn did_enter_full_screen<T: WindowDelegate>(this: &Object, _: Sel, _:
 }
 
 /// Called when an `NSWindowDelegate` receives a `windowDidChangeScreenProfile:` event.
-extern "C" fn content_size_for_full_screen<T: WindowDelegate>(this: &Object, _: Sel, _: id, size: CGSize) -> CGSize {
+extern "C" fn content_size_for_full_screen<T: WindowDelegate>(this: &Object, _: Sel, _: id, size: Size) -> Size {
     let window = load::<T>(this, WINDOW_DELEGATE_PTR);
 
-    let (width, height) = window.content_size_for_full_screen(size.width as f64, size.height as f64);
+    let (width, height) = window.content_size_for_full_screen(size.width, size.height);
 
-    CGSize {
-        width: width as CGFloat,
-        height: height as CGFloat
-    }
+    Size::new(width, height)
 }
 
 /// Called when an `NSWindowDelegate` receives a `windowDidChangeScreenProfile:` event.
@@ -241,7 +234,7 @@ pub(crate) fn register_window_class_with_delegate<T: WindowDelegate>(instance: &
         // Sizing
         decl.add_method(
             sel!(windowWillResize:toSize:),
-            will_resize::<T> as extern "C" fn(&Object, _, _, CGSize) -> CGSize
+            will_resize::<T> as extern "C" fn(&Object, _, _, Size) -> Size
         );
         decl.add_method(sel!(windowDidResize:), did_resize::<T> as extern "C" fn(&Object, _, _));
         decl.add_method(
@@ -270,7 +263,7 @@ pub(crate) fn register_window_class_with_delegate<T: WindowDelegate>(instance: &
         // Full Screen
         decl.add_method(
             sel!(window:willUseFullScreenContentSize:),
-            content_size_for_full_screen::<T> as extern "C" fn(&Object, _, _, CGSize) -> CGSize
+            content_size_for_full_screen::<T> as extern "C" fn(&Object, _, _, Size) -> Size
         );
         decl.add_method(
             sel!(window:willUseFullScreenPresentationOptions:),
@@ -10,8 +10,8 @@
 
 use block::ConcreteBlock;
 
-use core_graphics::base::CGFloat;
-use core_graphics::geometry::{CGRect, CGSize};
+use crate::core_graphics::base::CGFloat;
+use crate::core_graphics::geometry::{CGRect, CGSize};
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
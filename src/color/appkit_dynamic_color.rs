@@ -13,7 +13,7 @@
 use std::os::raw::c_void;
 use std::sync::Once;
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel, BOOL};
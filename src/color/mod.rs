@@ -13,10 +13,13 @@
 /// fallbacks, specify the `color_fallbacks` target_os in your `Cargo.toml`.
 ///
 /// @TODO: bundle iOS/tvOS support.
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
-use core_graphics::base::CGFloat;
-use core_graphics::color::CGColor;
+use crate::core_graphics::base::CGFloat;
+
+#[cfg(target_vendor = "apple")]
+use crate::core_graphics::color::CGColor;
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
@@ -26,9 +29,21 @@ use crate::foundation::id;
 use crate::utils::os;
 
 #[cfg(feature = "appkit")]
+use crate::foundation::nil;
+
+#[cfg(all(feature = "uikit", not(feature = "appkit")))]
+use crate::foundation::{to_bool, BOOL};
+
+#[cfg(all(feature = "appkit", not(feature = "gnustep")))]
 mod appkit_dynamic_color;
 
-#[cfg(feature = "appkit")]
+mod parser;
+pub use parser::{ColorParseError, Rgba};
+
+mod value;
+pub use value::{ColorSpace, ColorValue, ContrastLevel};
+
+#[cfg(all(feature = "appkit", not(feature = "gnustep")))]
 use appkit_dynamic_color::{
     AQUA_DARK_COLOR_HIGH_CONTRAST, AQUA_DARK_COLOR_NORMAL_CONTRAST, AQUA_LIGHT_COLOR_HIGH_CONTRAST,
     AQUA_LIGHT_COLOR_NORMAL_CONTRAST
@@ -309,20 +324,37 @@ impl Color {
         Color::white_alpha(level, 1.0)
     }
 
-    /// Given a hex code and alpha level, returns a `Color` in the RGB space.
+    /// Given a hex code and alpha level, returns a `Color` in the RGB space. Accepts `#rgb`,
+    /// `#rgba`, `#rrggbb` and `#rrggbbaa` (the leading `#` is optional); the `alpha` passed here
+    /// takes precedence over any alpha component in the string.
     ///
     /// This method is not an ideal one to use, but is offered as a convenience method for those
     /// coming from other environments where these are more common.
-    pub fn hexa(_hex: &str, _alpha: u8) -> Self {
-        Color::SystemRed
+    pub fn hexa(hex: &str, alpha: u8) -> Result<Self, ColorParseError> {
+        let rgba = parser::parse_hex(hex)?;
+        Ok(Rgba {
+            alpha: alpha as f64 / 255.,
+            ..rgba
+        }
+        .into())
     }
 
-    /// Given a hex code, returns a `Color` in the RGB space with alpha pre-set to `255`.
+    /// Given a hex code, returns a `Color` in the RGB space. If the hex code carries no alpha
+    /// component (`#rgb`, `#rrggbb`), alpha is set to `255`.
     ///
     /// This method is not an ideal one to use, but is offered as a convenience method for those
     /// coming from other environments where these are more common.
-    pub fn hex(hex: &str) -> Self {
-        Color::hexa(hex, 255)
+    pub fn hex(hex: &str) -> Result<Self, ColorParseError> {
+        Ok(parser::parse_hex(hex)?.into())
+    }
+
+    /// Parses a color string and returns a `Color` in the RGB space. In addition to the hex
+    /// formats supported by `hex`, this accepts the CSS functional syntax (`rgb()`, `rgba()`,
+    /// `hsl()`, `hsla()`) and CSS color names (e.g, `"rebeccapurple"`).
+    ///
+    /// If you need the parsed components without creating a platform color, use `Rgba::from_str`.
+    pub fn parse(color: &str) -> Result<Self, ColorParseError> {
+        Ok(parser::parse(color)?.into())
     }
 
     // @TODO: This is currently appkit-only but should be for uikit as well.
@@ -334,7 +366,10 @@ impl Color {
     /// "default" or "light" color.
     ///
     /// Returning a dynamic color in your handler is unsupported and may panic.
-    #[cfg(feature = "appkit")]
+    ///
+    /// GNUstep has no appearances to resolve against, so this isn't available with the `gnustep`
+    /// feature.
+    #[cfg(all(feature = "appkit", not(feature = "gnustep")))]
     pub fn dynamic<F>(handler: F) -> Self
     where
         F: Fn(Style) -> Color + 'static
@@ -391,12 +426,48 @@ impl Color {
         })))
     }
 
+    /// Reads the components of this color back out as an sRGB `ColorValue`. System and dynamic
+    /// colors are resolved against the current appearance at the time of the call.
+    ///
+    /// Returns `None` if the color can't be represented in sRGB (e.g, pattern colors).
+    pub fn to_value(&self) -> Option<ColorValue> {
+        let (mut red, mut green, mut blue, mut alpha): (CGFloat, CGFloat, CGFloat, CGFloat) = (0., 0., 0., 0.);
+
+        unsafe {
+            let objc: id = self.into();
+
+            #[cfg(feature = "appkit")]
+            {
+                let srgb: id = msg_send![class!(NSColorSpace), sRGBColorSpace];
+                let converted: id = msg_send![objc, colorUsingColorSpace: srgb];
+                if converted == nil {
+                    return None;
+                }
+
+                let _: () = msg_send![converted, getRed:&mut red green:&mut green blue:&mut blue alpha:&mut alpha];
+            }
+
+            #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+            {
+                let success: BOOL = msg_send![objc, getRed:&mut red green:&mut green blue:&mut blue alpha:&mut alpha];
+                if !to_bool(success) {
+                    return None;
+                }
+            }
+        }
+
+        Some(Rgba::new(red as f64, green as f64, blue as f64, alpha as f64).into())
+    }
+
     /// Returns a CGColor, which can be used in Core Graphics calls as well as other areas.
     ///
     /// Note that CGColor is _not_ a context-aware color, unlike our `NSColor` and `UIColor`
     /// objects. If you're painting in a context that requires dark mode support, make sure
     /// you're not using a cached version of this unless you explicitly want the _same_ color
     /// in every context it's used in.
+    ///
+    /// Core Graphics is Apple-only, so this isn't available on GNUstep.
+    #[cfg(target_vendor = "apple")]
     pub fn cg_color(&self) -> CGColor {
         // @TODO: This should probably return a CGColorRef...
         unsafe {
@@ -406,6 +477,34 @@ impl Color {
     }
 }
 
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::parse(s)
+    }
+}
+
+impl From<Rgba> for Color {
+    /// Creates a `Color` in the sRGB space from parsed components.
+    fn from(rgba: Rgba) -> Self {
+        ColorValue::from(rgba).into()
+    }
+}
+
+impl From<ColorValue> for Color {
+    /// Creates a platform color from a `ColorValue`, in the color space the value is expressed in.
+    fn from(value: ColorValue) -> Self {
+        Color::Custom(Arc::new(RwLock::new(unsafe { Id::from_ptr(value_to_objc(value)) })))
+    }
+}
+
+impl From<&ColorValue> for Color {
+    fn from(value: &ColorValue) -> Self {
+        Color::from(*value)
+    }
+}
+
 impl AsRef<Color> for Color {
     /// Provided to make passing `Color` types around less of a headache.
     #[inline]
@@ -428,10 +527,80 @@ impl From<&Color> for id {
     }
 }
 
-/// Handles color fallback for system-provided colors.
+/// Allocates an `NSColor` or `UIColor` for a `ColorValue`. HSL has no native counterpart, so it
+/// goes through HSB.
+unsafe fn value_to_objc(value: ColorValue) -> id {
+    #[cfg(feature = "appkit")]
+    let color = class!(NSColor);
+
+    #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+    let color = class!(UIColor);
+
+    match value {
+        ColorValue::Srgb { red, green, blue, alpha } => {
+            let (r, g, b, a) = (red as CGFloat, green as CGFloat, blue as CGFloat, alpha as CGFloat);
+
+            #[cfg(feature = "appkit")]
+            {
+                msg_send![color, colorWithSRGBRed:r green:g blue:b alpha:a]
+            }
+
+            #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+            {
+                msg_send![color, colorWithRed:r green:g blue:b alpha:a]
+            }
+        },
+
+        ColorValue::DisplayP3 { red, green, blue, alpha } => {
+            let (r, g, b, a) = (red as CGFloat, green as CGFloat, blue as CGFloat, alpha as CGFloat);
+            msg_send![color, colorWithDisplayP3Red:r green:g blue:b alpha:a]
+        },
+
+        ColorValue::Hsb {
+            hue,
+            saturation,
+            brightness,
+            alpha
+        } => {
+            let (h, s, b, a) = (hue as CGFloat, saturation as CGFloat, brightness as CGFloat, alpha as CGFloat);
+
+            msg_send![color, colorWithHue:h saturation:s brightness:b alpha:a]
+        },
+
+        ColorValue::Hsl { .. } => value_to_objc(value.to_hsb()),
+
+        ColorValue::Gray { white, alpha } => {
+            let (w, a) = (white as CGFloat, alpha as CGFloat);
+
+            // GNUstep doesn't have the generic gamma 2.2 gray space.
+            #[cfg(feature = "gnustep")]
+            {
+                msg_send![color, colorWithCalibratedWhite:w alpha:a]
+            }
+
+            #[cfg(all(feature = "appkit", not(feature = "gnustep")))]
+            {
+                msg_send![color, colorWithGenericGamma22White:w alpha:a]
+            }
+
+            #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+            {
+                msg_send![color, colorWithWhite:w alpha:a]
+            }
+        }
+    }
+}
+
+/// Handles color fallback for system-provided colors. GNUstep doesn't implement the `system*`
+/// colors, so it always gets the fallback.
 macro_rules! system_color_with_fallback {
     ($class:ident, $color:ident, $fallback:ident) => {{
-        #[cfg(feature = "appkit")]
+        #[cfg(feature = "gnustep")]
+        {
+            msg_send![$class, $fallback]
+        }
+
+        #[cfg(all(feature = "appkit", not(feature = "gnustep")))]
         {
             #[cfg(feature = "color-fallbacks")]
             if os::minimum_semversion(10, 10, 0) {
@@ -0,0 +1,567 @@
+//! A small, pure-Rust parser for color strings. This handles the formats you'd typically find in
+//! config files or design tools:
+//!
+//! - Hex: `#rgb`, `#rgba`, `#rrggbb` and `#rrggbbaa` (the leading `#` is optional).
+//! - CSS functional syntax: `rgb()`, `rgba()`, `hsl()` and `hsla()`, in both the legacy
+//!   comma-separated form and the newer space-separated form with a `/ alpha` suffix.
+//! - CSS named colors (e.g, `rebeccapurple`), plus `transparent`.
+//!
+//! Nothing in here touches the Objective-C runtime, so it can be used (and tested) anywhere.
+
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A color in the sRGB space, with each component (alpha included) in the range `0.0..=1.0`.
+/// This is what the parser hands back; `Color` can be created from it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rgba {
+    /// The red component.
+    pub red: f64,
+
+    /// The green component.
+    pub green: f64,
+
+    /// The blue component.
+    pub blue: f64,
+
+    /// The alpha component.
+    pub alpha: f64
+}
+
+impl Rgba {
+    /// Returns a new `Rgba` from components in the range `0.0..=1.0`. Values outside of that range
+    /// are clamped.
+    pub fn new(red: f64, green: f64, blue: f64, alpha: f64) -> Self {
+        Rgba {
+            red: clamp_unit(red),
+            green: clamp_unit(green),
+            blue: clamp_unit(blue),
+            alpha: clamp_unit(alpha)
+        }
+    }
+
+    /// Returns a new `Rgba` from 8-bit components.
+    pub fn from_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Rgba {
+            red: red as f64 / 255.,
+            green: green as f64 / 255.,
+            blue: blue as f64 / 255.,
+            alpha: alpha as f64 / 255.
+        }
+    }
+
+    /// Returns the components as 8-bit values, in `(red, green, blue, alpha)` order.
+    pub fn to_u8(&self) -> (u8, u8, u8, u8) {
+        (
+            unit_to_u8(self.red),
+            unit_to_u8(self.green),
+            unit_to_u8(self.blue),
+            unit_to_u8(self.alpha)
+        )
+    }
+}
+
+impl FromStr for Rgba {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+/// Errors that can occur when parsing a color string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColorParseError {
+    /// The input was empty (or only whitespace).
+    Empty,
+
+    /// The input looked like a hex color, but wasn't a valid one. Holds the offending input.
+    InvalidHex(String),
+
+    /// The input looked like a CSS color function (e.g, `rgb(...)`) but was malformed. Holds the
+    /// offending input.
+    InvalidFunction(String),
+
+    /// A component inside a color function couldn't be parsed. Holds the offending component.
+    InvalidComponent(String),
+
+    /// The input wasn't a known CSS color name. Holds the offending input.
+    UnknownName(String)
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "Cannot parse a color from an empty string"),
+            ColorParseError::InvalidHex(input) => write!(f, "Invalid hex color: \"{}\"", input),
+            ColorParseError::InvalidFunction(input) => write!(f, "Invalid color function: \"{}\"", input),
+            ColorParseError::InvalidComponent(input) => write!(f, "Invalid color component: \"{}\"", input),
+            ColorParseError::UnknownName(input) => write!(f, "Unknown color name: \"{}\"", input)
+        }
+    }
+}
+
+impl error::Error for ColorParseError {}
+
+/// Parses any supported color string - hex, CSS functional syntax, or a CSS color name.
+pub fn parse(input: &str) -> Result<Rgba, ColorParseError> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Err(ColorParseError::Empty);
+    }
+
+    if input.starts_with('#') {
+        return parse_hex(input);
+    }
+
+    if let Some(open) = input.find('(') {
+        return parse_function(input, open);
+    }
+
+    let lowercased = input.to_ascii_lowercase();
+    if let Some(color) = named_color(&lowercased) {
+        return Ok(color);
+    }
+
+    // Allow bare hex strings (e.g, from config files that strip the `#`), but only after we've
+    // ruled out names - `bada55` is valid hex, but so are a few words, and names should win.
+    if input.chars().all(|c| c.is_ascii_hexdigit()) {
+        return parse_hex(input);
+    }
+
+    Err(ColorParseError::UnknownName(input.to_string()))
+}
+
+/// Parses a hex color string: `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`. The leading `#` is
+/// optional.
+pub fn parse_hex(input: &str) -> Result<Rgba, ColorParseError> {
+    let trimmed = input.trim();
+    let hex = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    let invalid = || ColorParseError::InvalidHex(input.to_string());
+
+    if hex.is_empty() {
+        return Err(if trimmed.is_empty() {
+            ColorParseError::Empty
+        } else {
+            invalid()
+        });
+    }
+
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let digits: Vec<u8> = hex.bytes().map(hex_digit).collect();
+
+    let (red, green, blue, alpha) = match digits.len() {
+        3 => (digits[0] * 17, digits[1] * 17, digits[2] * 17, 255),
+        4 => (digits[0] * 17, digits[1] * 17, digits[2] * 17, digits[3] * 17),
+        6 => (
+            digits[0] << 4 | digits[1],
+            digits[2] << 4 | digits[3],
+            digits[4] << 4 | digits[5],
+            255
+        ),
+        8 => (
+            digits[0] << 4 | digits[1],
+            digits[2] << 4 | digits[3],
+            digits[4] << 4 | digits[5],
+            digits[6] << 4 | digits[7]
+        ),
+        _ => return Err(invalid())
+    };
+
+    Ok(Rgba::from_u8(red, green, blue, alpha))
+}
+
+/// Parses `rgb()`, `rgba()`, `hsl()` and `hsla()` - `open` is the index of the opening paren.
+fn parse_function(input: &str, open: usize) -> Result<Rgba, ColorParseError> {
+    let invalid = || ColorParseError::InvalidFunction(input.to_string());
+
+    if !input.ends_with(')') {
+        return Err(invalid());
+    }
+
+    let name = input[..open].trim().to_ascii_lowercase();
+    let body = &input[open + 1..input.len() - 1];
+    let (channels, alpha) = split_components(body).ok_or_else(invalid)?;
+
+    if channels.len() != 3 {
+        return Err(invalid());
+    }
+
+    let alpha = match alpha {
+        Some(alpha) => parse_alpha(alpha)?,
+        None => 1.
+    };
+
+    match name.as_str() {
+        "rgb" | "rgba" => {
+            let red = parse_rgb_channel(channels[0])?;
+            let green = parse_rgb_channel(channels[1])?;
+            let blue = parse_rgb_channel(channels[2])?;
+            Ok(Rgba::new(red, green, blue, alpha))
+        },
+
+        "hsl" | "hsla" => {
+            let hue = parse_hue(channels[0])?;
+            let saturation = parse_percentage(channels[1])?;
+            let lightness = parse_percentage(channels[2])?;
+            let (red, green, blue) = hsl_to_rgb(hue, saturation, lightness);
+            Ok(Rgba::new(red, green, blue, alpha))
+        },
+
+        _ => Err(invalid())
+    }
+}
+
+/// Splits the inside of a color function into its three channels and an optional alpha. Both the
+/// legacy `a, b, c, d` and modern `a b c / d` forms are accepted, but not mixed.
+fn split_components(body: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    if body.contains(',') {
+        if body.contains('/') {
+            return None;
+        }
+
+        let mut parts: Vec<&str> = body.split(',').map(str::trim).collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            return None;
+        }
+
+        let alpha = match parts.len() {
+            3 => None,
+            4 => parts.pop(),
+            _ => return None
+        };
+
+        return Some((parts, alpha));
+    }
+
+    let mut halves = body.splitn(2, '/');
+    let channels: Vec<&str> = halves.next()?.split_whitespace().collect();
+    let alpha = match halves.next() {
+        Some(alpha) if alpha.trim().is_empty() || alpha.contains('/') => return None,
+        Some(alpha) => Some(alpha.trim()),
+        None => None
+    };
+
+    Some((channels, alpha))
+}
+
+/// Parses a numeric component, optionally suffixed with `%`. Returns the value and whether it was
+/// a percentage.
+fn parse_number(component: &str) -> Result<(f64, bool), ColorParseError> {
+    let invalid = || ColorParseError::InvalidComponent(component.to_string());
+
+    let (number, is_percentage) = match component.strip_suffix('%') {
+        Some(number) => (number, true),
+        None => (component, false)
+    };
+
+    let value: f64 = number.parse().map_err(|_| invalid())?;
+    if !value.is_finite() {
+        return Err(invalid());
+    }
+
+    Ok((value, is_percentage))
+}
+
+/// Parses an `rgb()` channel, either `0-255` or `0%-100%`, into `0.0..=1.0`.
+fn parse_rgb_channel(component: &str) -> Result<f64, ColorParseError> {
+    let (value, is_percentage) = parse_number(component)?;
+    Ok(clamp_unit(if is_percentage { value / 100. } else { value / 255. }))
+}
+
+/// Parses an alpha component, either `0-1` or `0%-100%`, into `0.0..=1.0`.
+fn parse_alpha(component: &str) -> Result<f64, ColorParseError> {
+    let (value, is_percentage) = parse_number(component)?;
+    Ok(clamp_unit(if is_percentage { value / 100. } else { value }))
+}
+
+/// Parses a component that must be a percentage (saturation and lightness in `hsl()`).
+fn parse_percentage(component: &str) -> Result<f64, ColorParseError> {
+    match parse_number(component)? {
+        (value, true) => Ok(clamp_unit(value / 100.)),
+        (_, false) => Err(ColorParseError::InvalidComponent(component.to_string()))
+    }
+}
+
+/// Parses a hue, in degrees by default, with optional `deg`, `rad`, `grad` or `turn` units. The
+/// result is normalized to `0.0..360.0`.
+fn parse_hue(component: &str) -> Result<f64, ColorParseError> {
+    let units: [(&str, f64); 4] = [
+        ("deg", 1.),
+        ("grad", 360. / 400.),
+        ("rad", 180. / std::f64::consts::PI),
+        ("turn", 360.)
+    ];
+
+    let lowercased = component.to_ascii_lowercase();
+    let (number, multiplier) = units
+        .iter()
+        .find_map(|(suffix, multiplier)| lowercased.strip_suffix(suffix).map(|number| (number, *multiplier)))
+        .unwrap_or((lowercased.as_str(), 1.));
+
+    let degrees = match parse_number(number) {
+        Ok((value, false)) => value * multiplier,
+        _ => return Err(ColorParseError::InvalidComponent(component.to_string()))
+    };
+
+    Ok(degrees.rem_euclid(360.))
+}
+
+/// Converts HSL (hue in degrees, saturation and lightness in `0.0..=1.0`) to RGB.
+pub(crate) fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (f64, f64, f64) {
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let sector = hue.rem_euclid(360.) / 60.;
+    let x = chroma * (1. - (sector % 2. - 1.).abs());
+    let m = lightness - chroma / 2.;
+
+    let (red, green, blue) = match sector as u32 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x)
+    };
+
+    (red + m, green + m, blue + m)
+}
+
+fn hex_digit(byte: u8) -> u8 {
+    match byte {
+        b'0'..=b'9' => byte - b'0',
+        b'a'..=b'f' => byte - b'a' + 10,
+        b'A'..=b'F' => byte - b'A' + 10,
+        _ => 0
+    }
+}
+
+fn clamp_unit(value: f64) -> f64 {
+    value.clamp(0., 1.)
+}
+
+fn unit_to_u8(value: f64) -> u8 {
+    (clamp_unit(value) * 255.).round() as u8
+}
+
+/// Looks up a (lowercased) CSS color name.
+fn named_color(name: &str) -> Option<Rgba> {
+    if name == "transparent" {
+        return Some(Rgba::from_u8(0, 0, 0, 0));
+    }
+
+    NAMED_COLORS
+        .binary_search_by(|(candidate, _)| candidate.cmp(&name))
+        .ok()
+        .map(|index| {
+            let rgb = NAMED_COLORS[index].1;
+            Rgba::from_u8((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 255)
+        })
+}
+
+/// The CSS named colors, sorted by name so that we can binary search.
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32)
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse("#f00").unwrap().to_u8(), (255, 0, 0, 255));
+        assert_eq!(parse("#f008").unwrap().to_u8(), (255, 0, 0, 136));
+        assert_eq!(parse("#1E90FF").unwrap().to_u8(), (30, 144, 255, 255));
+        assert_eq!(parse("#1e90ff80").unwrap().to_u8(), (30, 144, 255, 128));
+        assert_eq!(parse("1e90ff").unwrap().to_u8(), (30, 144, 255, 255));
+        assert_eq!(parse_hex("abc").unwrap().to_u8(), (170, 187, 204, 255));
+
+        assert_eq!(parse("#12345"), Err(ColorParseError::InvalidHex("#12345".to_string())));
+        assert_eq!(parse("#ggg"), Err(ColorParseError::InvalidHex("#ggg".to_string())));
+        assert_eq!(parse_hex("#"), Err(ColorParseError::InvalidHex("#".to_string())));
+        assert_eq!(parse("  "), Err(ColorParseError::Empty));
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        assert_eq!(parse("rgb(255, 0, 0)").unwrap().to_u8(), (255, 0, 0, 255));
+        assert_eq!(parse("rgba(0, 0, 255, 0.5)").unwrap().to_u8(), (0, 0, 255, 128));
+        assert_eq!(parse("rgb(100% 50% 0% / 25%)").unwrap().to_u8(), (255, 128, 0, 64));
+        assert_eq!(parse("RGB(300, -5, 0)").unwrap().to_u8(), (255, 0, 0, 255));
+        assert_eq!(parse("hsl(120, 100%, 25%)").unwrap().to_u8(), (0, 128, 0, 255));
+        assert_eq!(parse("hsla(0.5turn 100% 50% / 1)").unwrap().to_u8(), (0, 255, 255, 255));
+        assert_eq!(parse("hsl(-120deg, 100%, 50%)").unwrap().to_u8(), (0, 0, 255, 255));
+
+        assert!(matches!(parse("rgb(1, 2)"), Err(ColorParseError::InvalidFunction(_))));
+        assert!(matches!(parse("rgb(1, 2, 3 / 4)"), Err(ColorParseError::InvalidFunction(_))));
+        assert!(matches!(parse("rgb(1, 2, 3"), Err(ColorParseError::InvalidFunction(_))));
+        assert!(matches!(parse("cmyk(1, 2, 3)"), Err(ColorParseError::InvalidFunction(_))));
+        assert_eq!(parse("rgb(1, x, 3)"), Err(ColorParseError::InvalidComponent("x".to_string())));
+        assert_eq!(
+            parse("hsl(0, 100, 50%)"),
+            Err(ColorParseError::InvalidComponent("100".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_names() {
+        assert_eq!(parse("RebeccaPurple").unwrap().to_u8(), (102, 51, 153, 255));
+        assert_eq!(parse("transparent").unwrap().to_u8(), (0, 0, 0, 0));
+        assert_eq!(parse("bisque").unwrap().to_u8(), (255, 228, 196, 255));
+        assert_eq!(parse("blurple"), Err(ColorParseError::UnknownName("blurple".to_string())));
+
+        assert!(NAMED_COLORS.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
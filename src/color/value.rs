@@ -0,0 +1,678 @@
+//! A pure-Rust color value type. Unlike `Color`, which wraps an `NSColor`/`UIColor`, a
+//! `ColorValue` is just numbers: you can inspect, compare, convert, blend and serialize it without
+//! the Objective-C runtime, and turn it into a `Color` when you need to render it.
+//!
+//! All components (hue included) are in the range `0.0..=1.0`, matching `NSColor` and `UIColor`.
+
+use std::str::FromStr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::parser::{self, hsl_to_rgb, ColorParseError, Rgba};
+
+/// The color spaces (and models) that a `ColorValue` can be expressed in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(rename_all = "snake_case"))]
+pub enum ColorSpace {
+    /// The sRGB color space.
+    Srgb,
+
+    /// The Display P3 color space, used by wide-gamut displays.
+    DisplayP3,
+
+    /// Hue, saturation and brightness, in the sRGB color space.
+    Hsb,
+
+    /// Hue, saturation and lightness, in the sRGB color space.
+    Hsl,
+
+    /// A grayscale value, with the same 2.2 gamma as sRGB.
+    Gray
+}
+
+/// WCAG 2 contrast levels, used for checking whether two colors are legible together.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ContrastLevel {
+    /// Level AA for normal text, which requires a contrast ratio of at least 4.5:1.
+    AA,
+
+    /// Level AA for large text (and UI components), which requires a contrast ratio of at least 3:1.
+    AALargeText,
+
+    /// Level AAA for normal text, which requires a contrast ratio of at least 7:1.
+    AAA,
+
+    /// Level AAA for large text, which requires a contrast ratio of at least 4.5:1.
+    AAALargeText
+}
+
+impl ContrastLevel {
+    /// Returns the minimum contrast ratio required to meet this level.
+    pub fn minimum_ratio(&self) -> f64 {
+        match self {
+            ContrastLevel::AA => 4.5,
+            ContrastLevel::AALargeText => 3.,
+            ContrastLevel::AAA => 7.,
+            ContrastLevel::AAALargeText => 4.5
+        }
+    }
+}
+
+/// A color, as plain numbers in a given color space. All components are in the range
+/// `0.0..=1.0`.
+///
+/// Equality is structural: an `Hsb` value and an `Srgb` value are never equal, even if they
+/// describe the same color. Convert one to the other's space first (e.g, with `to_space`) if you
+/// need to compare across spaces.
+///
+/// With the `serde` feature enabled, a value serializes as its components, tagged with its space:
+/// `{"space": "srgb", "red": 1.0, "green": 0.5, "blue": 0.0, "alpha": 1.0}`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "space", rename_all = "snake_case")
+)]
+pub enum ColorValue {
+    /// A color in the sRGB color space.
+    Srgb {
+        /// The red component.
+        red: f64,
+
+        /// The green component.
+        green: f64,
+
+        /// The blue component.
+        blue: f64,
+
+        /// The alpha component.
+        alpha: f64
+    },
+
+    /// A color in the Display P3 color space.
+    DisplayP3 {
+        /// The red component.
+        red: f64,
+
+        /// The green component.
+        green: f64,
+
+        /// The blue component.
+        blue: f64,
+
+        /// The alpha component.
+        alpha: f64
+    },
+
+    /// A color expressed as hue, saturation and brightness.
+    Hsb {
+        /// The hue, where `0.0` and `1.0` are both red.
+        hue: f64,
+
+        /// The saturation.
+        saturation: f64,
+
+        /// The brightness.
+        brightness: f64,
+
+        /// The alpha component.
+        alpha: f64
+    },
+
+    /// A color expressed as hue, saturation and lightness.
+    Hsl {
+        /// The hue, where `0.0` and `1.0` are both red.
+        hue: f64,
+
+        /// The saturation.
+        saturation: f64,
+
+        /// The lightness.
+        lightness: f64,
+
+        /// The alpha component.
+        alpha: f64
+    },
+
+    /// A grayscale color.
+    Gray {
+        /// The white level, where `0.0` is black and `1.0` is white.
+        white: f64,
+
+        /// The alpha component.
+        alpha: f64
+    }
+}
+
+impl ColorValue {
+    /// Returns an sRGB color from 8-bit components.
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Rgba::from_u8(red, green, blue, alpha).into()
+    }
+
+    /// Returns an sRGB color from 8-bit components, with alpha set to `255`.
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        ColorValue::rgba(red, green, blue, 255)
+    }
+
+    /// Returns an HSB color from components in the range `0.0..=1.0`.
+    pub fn hsba(hue: f64, saturation: f64, brightness: f64, alpha: f64) -> Self {
+        ColorValue::Hsb {
+            hue,
+            saturation,
+            brightness,
+            alpha
+        }
+    }
+
+    /// Returns an HSL color from components in the range `0.0..=1.0`.
+    pub fn hsla(hue: f64, saturation: f64, lightness: f64, alpha: f64) -> Self {
+        ColorValue::Hsl {
+            hue,
+            saturation,
+            lightness,
+            alpha
+        }
+    }
+
+    /// Returns a grayscale color.
+    pub fn white_alpha(white: f64, alpha: f64) -> Self {
+        ColorValue::Gray { white, alpha }
+    }
+
+    /// Returns the color space this value is expressed in.
+    pub fn space(&self) -> ColorSpace {
+        match self {
+            ColorValue::Srgb { .. } => ColorSpace::Srgb,
+            ColorValue::DisplayP3 { .. } => ColorSpace::DisplayP3,
+            ColorValue::Hsb { .. } => ColorSpace::Hsb,
+            ColorValue::Hsl { .. } => ColorSpace::Hsl,
+            ColorValue::Gray { .. } => ColorSpace::Gray
+        }
+    }
+
+    /// Returns the alpha component.
+    pub fn alpha(&self) -> f64 {
+        match *self {
+            ColorValue::Srgb { alpha, .. }
+            | ColorValue::DisplayP3 { alpha, .. }
+            | ColorValue::Hsb { alpha, .. }
+            | ColorValue::Hsl { alpha, .. }
+            | ColorValue::Gray { alpha, .. } => alpha
+        }
+    }
+
+    /// Returns a copy of this value with the alpha component replaced.
+    pub fn with_alpha(mut self, new_alpha: f64) -> Self {
+        match &mut self {
+            ColorValue::Srgb { alpha, .. }
+            | ColorValue::DisplayP3 { alpha, .. }
+            | ColorValue::Hsb { alpha, .. }
+            | ColorValue::Hsl { alpha, .. }
+            | ColorValue::Gray { alpha, .. } => *alpha = clamp_unit(new_alpha)
+        }
+
+        self
+    }
+
+    /// Converts this value to the sRGB color space. Display P3 colors that fall outside of the
+    /// sRGB gamut are clamped.
+    pub fn to_srgb(&self) -> Rgba {
+        match *self {
+            ColorValue::Srgb { red, green, blue, alpha } => Rgba::new(red, green, blue, alpha),
+
+            ColorValue::DisplayP3 { red, green, blue, alpha } => {
+                let (red, green, blue) = convert_gamut(&DISPLAY_P3_TO_SRGB, red, green, blue);
+                Rgba::new(red, green, blue, alpha)
+            },
+
+            ColorValue::Hsb {
+                hue,
+                saturation,
+                brightness,
+                alpha
+            } => {
+                let (red, green, blue) = hsb_to_rgb(hue, saturation, brightness);
+                Rgba::new(red, green, blue, alpha)
+            },
+
+            ColorValue::Hsl {
+                hue,
+                saturation,
+                lightness,
+                alpha
+            } => {
+                let (red, green, blue) = hsl_to_rgb(hue * 360., clamp_unit(saturation), clamp_unit(lightness));
+                Rgba::new(red, green, blue, alpha)
+            },
+
+            ColorValue::Gray { white, alpha } => Rgba::new(white, white, white, alpha)
+        }
+    }
+
+    /// Converts this value to the Display P3 color space.
+    pub fn to_display_p3(&self) -> ColorValue {
+        if let ColorValue::DisplayP3 { .. } = self {
+            return *self;
+        }
+
+        let srgb = self.to_srgb();
+        let (red, green, blue) = convert_gamut(&SRGB_TO_DISPLAY_P3, srgb.red, srgb.green, srgb.blue);
+
+        ColorValue::DisplayP3 {
+            red,
+            green,
+            blue,
+            alpha: srgb.alpha
+        }
+    }
+
+    /// Converts this value to hue, saturation and brightness.
+    pub fn to_hsb(&self) -> ColorValue {
+        if let ColorValue::Hsb { .. } = self {
+            return *self;
+        }
+
+        let srgb = self.to_srgb();
+        let max = srgb.red.max(srgb.green).max(srgb.blue);
+        let min = srgb.red.min(srgb.green).min(srgb.blue);
+        let delta = max - min;
+
+        ColorValue::Hsb {
+            hue: hue_of(srgb.red, srgb.green, srgb.blue, max, delta),
+            saturation: if max > 0. { delta / max } else { 0. },
+            brightness: max,
+            alpha: srgb.alpha
+        }
+    }
+
+    /// Converts this value to hue, saturation and lightness.
+    pub fn to_hsl(&self) -> ColorValue {
+        if let ColorValue::Hsl { .. } = self {
+            return *self;
+        }
+
+        let srgb = self.to_srgb();
+        let max = srgb.red.max(srgb.green).max(srgb.blue);
+        let min = srgb.red.min(srgb.green).min(srgb.blue);
+        let delta = max - min;
+        let lightness = (max + min) / 2.;
+
+        let saturation = match delta > 0. {
+            true => delta / (1. - (2. * lightness - 1.).abs()),
+            false => 0.
+        };
+
+        ColorValue::Hsl {
+            hue: hue_of(srgb.red, srgb.green, srgb.blue, max, delta),
+            saturation: clamp_unit(saturation),
+            lightness,
+            alpha: srgb.alpha
+        }
+    }
+
+    /// Converts this value to grayscale, preserving its relative luminance.
+    pub fn to_gray(&self) -> ColorValue {
+        if let ColorValue::Gray { .. } = self {
+            return *self;
+        }
+
+        ColorValue::Gray {
+            white: encode_srgb(self.relative_luminance()),
+            alpha: self.alpha()
+        }
+    }
+
+    /// Converts this value to the given color space.
+    pub fn to_space(&self, space: ColorSpace) -> ColorValue {
+        match space {
+            ColorSpace::Srgb => self.to_srgb().into(),
+            ColorSpace::DisplayP3 => self.to_display_p3(),
+            ColorSpace::Hsb => self.to_hsb(),
+            ColorSpace::Hsl => self.to_hsl(),
+            ColorSpace::Gray => self.to_gray()
+        }
+    }
+
+    /// Linearly interpolates between this color and `other`, where a `fraction` of `0.0` returns
+    /// this color and `1.0` returns `other`. Display P3 colors are blended in Display P3;
+    /// everything else is blended in sRGB. The result is expressed in this value's color space.
+    pub fn blend(&self, other: &ColorValue, fraction: f64) -> ColorValue {
+        let fraction = clamp_unit(fraction);
+        let mix = |from: f64, to: f64| from + (to - from) * fraction;
+
+        if let ColorValue::DisplayP3 { red, green, blue, alpha } = *self {
+            if let ColorValue::DisplayP3 {
+                red: other_red,
+                green: other_green,
+                blue: other_blue,
+                alpha: other_alpha
+            } = other.to_display_p3()
+            {
+                return ColorValue::DisplayP3 {
+                    red: mix(red, other_red),
+                    green: mix(green, other_green),
+                    blue: mix(blue, other_blue),
+                    alpha: mix(alpha, other_alpha)
+                };
+            }
+        }
+
+        let from = self.to_srgb();
+        let to = other.to_srgb();
+        let blended: ColorValue = Rgba::new(
+            mix(from.red, to.red),
+            mix(from.green, to.green),
+            mix(from.blue, to.blue),
+            mix(from.alpha, to.alpha)
+        )
+        .into();
+
+        blended.to_space(self.space())
+    }
+
+    /// Returns a lighter version of this color, by increasing its HSL lightness by `amount`
+    /// (clamped at white). The result is expressed in this value's color space.
+    pub fn lighten(&self, amount: f64) -> ColorValue {
+        self.adjust_lightness(amount)
+    }
+
+    /// Returns a darker version of this color, by decreasing its HSL lightness by `amount`
+    /// (clamped at black). The result is expressed in this value's color space.
+    pub fn darken(&self, amount: f64) -> ColorValue {
+        self.adjust_lightness(-amount)
+    }
+
+    fn adjust_lightness(&self, amount: f64) -> ColorValue {
+        match self.to_hsl() {
+            ColorValue::Hsl {
+                hue,
+                saturation,
+                lightness,
+                alpha
+            } => ColorValue::Hsl {
+                hue,
+                saturation,
+                lightness: clamp_unit(lightness + amount),
+                alpha
+            }
+            .to_space(self.space()),
+
+            _ => unreachable!()
+        }
+    }
+
+    /// Composites this color over an opaque `background` (source-over), returning an opaque sRGB
+    /// color. Useful for checking contrast of translucent colors.
+    pub fn composite_over(&self, background: &ColorValue) -> ColorValue {
+        let source = self.to_srgb();
+        let destination = background.to_srgb();
+        let over = |source_component: f64, destination_component: f64| {
+            source_component * source.alpha + destination_component * (1. - source.alpha)
+        };
+
+        Rgba::new(
+            over(source.red, destination.red),
+            over(source.green, destination.green),
+            over(source.blue, destination.blue),
+            1.
+        )
+        .into()
+    }
+
+    /// Returns the relative luminance of this color, as defined by WCAG 2. Alpha is ignored; use
+    /// `composite_over` first for translucent colors.
+    pub fn relative_luminance(&self) -> f64 {
+        let srgb = self.to_srgb();
+        0.2126 * decode_srgb(srgb.red) + 0.7152 * decode_srgb(srgb.green) + 0.0722 * decode_srgb(srgb.blue)
+    }
+
+    /// Returns the WCAG 2 contrast ratio between this color and `other`, in the range `1.0..=21.0`.
+    pub fn contrast_ratio(&self, other: &ColorValue) -> f64 {
+        let first = self.relative_luminance();
+        let second = other.relative_luminance();
+        (first.max(second) + 0.05) / (first.min(second) + 0.05)
+    }
+
+    /// Returns whether this color and `other` have enough contrast to meet the given WCAG level.
+    pub fn meets_contrast(&self, other: &ColorValue, level: ContrastLevel) -> bool {
+        self.contrast_ratio(other) >= level.minimum_ratio()
+    }
+}
+
+impl From<Rgba> for ColorValue {
+    fn from(rgba: Rgba) -> Self {
+        ColorValue::Srgb {
+            red: rgba.red,
+            green: rgba.green,
+            blue: rgba.blue,
+            alpha: rgba.alpha
+        }
+    }
+}
+
+impl From<ColorValue> for Rgba {
+    fn from(value: ColorValue) -> Self {
+        value.to_srgb()
+    }
+}
+
+impl FromStr for ColorValue {
+    type Err = ColorParseError;
+
+    /// Parses any string supported by `Color::parse` into an sRGB value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(parser::parse(s)?.into())
+    }
+}
+
+/// Linear sRGB to linear Display P3 (both D65).
+const SRGB_TO_DISPLAY_P3: [[f64; 3]; 3] = [[0.822_462_1, 0.177_538_0, 0.], [0.033_194_1, 0.966_805_8, 0.], [
+    0.017_082_7,
+    0.072_397_4,
+    0.910_519_9
+]];
+
+/// Linear Display P3 to linear sRGB (both D65).
+const DISPLAY_P3_TO_SRGB: [[f64; 3]; 3] = [[1.224_940_1, -0.224_940_4, 0.], [-0.042_056_9, 1.042_057_1, 0.], [
+    -0.019_637_6,
+    -0.078_636_1,
+    1.098_273_5
+]];
+
+/// Converts between sRGB and Display P3. Both use the sRGB transfer function, so we linearize,
+/// apply the matrix, and re-encode.
+fn convert_gamut(matrix: &[[f64; 3]; 3], red: f64, green: f64, blue: f64) -> (f64, f64, f64) {
+    let linear = [decode_srgb(red), decode_srgb(green), decode_srgb(blue)];
+    let row = |index: usize| {
+        let row = matrix[index];
+        encode_srgb(row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2])
+    };
+
+    (row(0), row(1), row(2))
+}
+
+/// The sRGB transfer function, from encoded to linear light.
+fn decode_srgb(component: f64) -> f64 {
+    let component = clamp_unit(component);
+
+    match component <= 0.040_45 {
+        true => component / 12.92,
+        false => ((component + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// The inverse sRGB transfer function, from linear light to encoded.
+fn encode_srgb(component: f64) -> f64 {
+    let component = clamp_unit(component);
+
+    match component <= 0.003_130_8 {
+        true => component * 12.92,
+        false => 1.055 * component.powf(1. / 2.4) - 0.055
+    }
+}
+
+fn hsb_to_rgb(hue: f64, saturation: f64, brightness: f64) -> (f64, f64, f64) {
+    let saturation = clamp_unit(saturation);
+    let brightness = clamp_unit(brightness);
+    let chroma = brightness * saturation;
+    let sector = (hue * 360.).rem_euclid(360.) / 60.;
+    let x = chroma * (1. - (sector % 2. - 1.).abs());
+    let m = brightness - chroma;
+
+    let (red, green, blue) = match sector as u32 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x)
+    };
+
+    (red + m, green + m, blue + m)
+}
+
+/// Shared hue calculation for HSB and HSL, returned in `0.0..1.0`.
+fn hue_of(red: f64, green: f64, blue: f64, max: f64, delta: f64) -> f64 {
+    if delta <= 0. {
+        return 0.;
+    }
+
+    let degrees = if max == red {
+        60. * ((green - blue) / delta).rem_euclid(6.)
+    } else if max == green {
+        60. * ((blue - red) / delta + 2.)
+    } else {
+        60. * ((red - green) / delta + 4.)
+    };
+
+    degrees.rem_euclid(360.) / 360.
+}
+
+fn clamp_unit(value: f64) -> f64 {
+    value.clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(first: f64, second: f64) {
+        assert!((first - second).abs() < 0.001, "{} != {}", first, second);
+    }
+
+    fn assert_srgb(value: ColorValue, expected: (u8, u8, u8, u8)) {
+        assert_eq!(value.to_srgb().to_u8(), expected);
+    }
+
+    #[test]
+    fn test_conversions_round_trip() {
+        let coral = ColorValue::rgb(255, 127, 80);
+
+        for space in [ColorSpace::DisplayP3, ColorSpace::Hsb, ColorSpace::Hsl].iter() {
+            let converted = coral.to_space(*space);
+            assert_eq!(converted.space(), *space);
+            assert_srgb(converted, (255, 127, 80, 255));
+        }
+
+        match ColorValue::rgb(0, 0, 255).to_hsb() {
+            ColorValue::Hsb {
+                hue,
+                saturation,
+                brightness,
+                ..
+            } => {
+                assert_close(hue, 240. / 360.);
+                assert_close(saturation, 1.);
+                assert_close(brightness, 1.);
+            },
+            other => panic!("Expected HSB, got {:?}", other)
+        }
+
+        assert_srgb(ColorValue::hsla(1. / 3., 1., 0.25, 1.), (0, 128, 0, 255));
+        assert_srgb(ColorValue::white_alpha(0.5, 0.5), (128, 128, 128, 128));
+    }
+
+    #[test]
+    fn test_display_p3() {
+        // Pure sRGB red sits inside the P3 gamut, so it can't be fully saturated there.
+        match ColorValue::rgb(255, 0, 0).to_display_p3() {
+            ColorValue::DisplayP3 { red, green, blue, .. } => {
+                assert_close(red, 0.9176);
+                assert_close(green, 0.2003);
+                assert_close(blue, 0.1386);
+            },
+            other => panic!("Expected Display P3, got {:?}", other)
+        }
+
+        // ...and pure P3 red is outside of sRGB, so it clamps.
+        let p3_red = ColorValue::DisplayP3 {
+            red: 1.,
+            green: 0.,
+            blue: 0.,
+            alpha: 1.
+        };
+        assert_srgb(p3_red, (255, 0, 0, 255));
+    }
+
+    #[test]
+    fn test_blend_lighten_darken() {
+        let black = ColorValue::rgb(0, 0, 0);
+        let white = ColorValue::rgb(255, 255, 255);
+
+        assert_srgb(black.blend(&white, 0.5), (128, 128, 128, 255));
+        assert_eq!(black.blend(&white, 0.), black);
+        assert_eq!(black.blend(&white, 2.), white);
+
+        let hsb_red = ColorValue::hsba(0., 1., 1., 1.);
+        assert_eq!(hsb_red.blend(&white, 0.5).space(), ColorSpace::Hsb);
+
+        let red = ColorValue::rgb(255, 0, 0);
+        assert_srgb(red.lighten(0.25), (255, 128, 128, 255));
+        assert_srgb(red.darken(0.25), (128, 0, 0, 255));
+        assert_srgb(red.darken(2.), (0, 0, 0, 255));
+        assert_eq!(red.lighten(0.1).space(), ColorSpace::Srgb);
+    }
+
+    #[test]
+    fn test_contrast() {
+        let black = ColorValue::rgb(0, 0, 0);
+        let white = ColorValue::rgb(255, 255, 255);
+
+        assert_close(black.contrast_ratio(&white), 21.);
+        assert_close(white.contrast_ratio(&black), 21.);
+        assert_close(white.contrast_ratio(&white), 1.);
+
+        let gray: ColorValue = "#767676".parse().unwrap();
+        assert!(gray.meets_contrast(&white, ContrastLevel::AA));
+        assert!(!gray.meets_contrast(&white, ContrastLevel::AAA));
+
+        let translucent = black.with_alpha(0.5).composite_over(&white);
+        assert_srgb(translucent, (128, 128, 128, 255));
+        assert_close(white.to_gray().relative_luminance(), 1.);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        use crate::defaults::{from_value, to_value, Value};
+
+        let coral = ColorValue::rgb(255, 127, 80).to_display_p3();
+        let value = to_value(&coral).unwrap();
+
+        match &value {
+            Value::Dictionary(map) => assert_eq!(map["space"], Value::String("display_p3".into())),
+            other => panic!("Expected a dictionary, got {:?}", other)
+        }
+
+        assert_eq!(from_value::<ColorValue>(value).unwrap(), coral);
+
+        let gray = ColorValue::white_alpha(0.5, 1.);
+        assert_eq!(from_value::<ColorValue>(to_value(&gray).unwrap()).unwrap(), gray);
+        assert_eq!(
+            from_value::<ColorSpace>(to_value(&ColorSpace::Hsl).unwrap()).unwrap(),
+            ColorSpace::Hsl
+        );
+    }
+}
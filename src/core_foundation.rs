@@ -0,0 +1,25 @@
+//! Stand-ins for the `core-foundation` types this crate uses, for platforms without Core
+//! Foundation (i.e, GNUstep).
+//!
+//! On Apple platforms, `cacao::core_foundation` is the `core-foundation` crate itself.
+
+/// Core Foundation's base types.
+pub mod base {
+    /// A signed index or count.
+    pub type CFIndex = isize;
+
+    /// A range of items, laid out like `NSRange`.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct CFRange {
+        pub location: CFIndex,
+        pub length: CFIndex
+    }
+
+    impl CFRange {
+        /// Creates a new range.
+        pub fn init(location: CFIndex, length: CFIndex) -> CFRange {
+            CFRange { location, length }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Stand-ins for the `core-graphics` types this crate uses, for platforms without Core Graphics
+//! (i.e, GNUstep). These match the layout of the real types - and GNUstep's `NSPoint`, `NSSize`
+//! and `NSRect` - so they can be passed through `msg_send!` unchanged.
+//!
+//! On Apple platforms, `cacao::core_graphics` is the `core-graphics` crate itself.
+
+/// Core Graphics' base types.
+pub mod base {
+    /// The floating point type used for geometry.
+    #[cfg(target_pointer_width = "64")]
+    pub type CGFloat = f64;
+
+    /// The floating point type used for geometry.
+    #[cfg(not(target_pointer_width = "64"))]
+    pub type CGFloat = f32;
+}
+
+/// Points, sizes and rects.
+pub mod geometry {
+    use super::base::CGFloat;
+
+    /// A width and height.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct CGSize {
+        pub width: CGFloat,
+        pub height: CGFloat
+    }
+
+    impl CGSize {
+        /// Creates a new size.
+        pub fn new(width: CGFloat, height: CGFloat) -> CGSize {
+            CGSize { width, height }
+        }
+    }
+
+    /// An `x`, `y` coordinate.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct CGPoint {
+        pub x: CGFloat,
+        pub y: CGFloat
+    }
+
+    impl CGPoint {
+        /// Creates a new point.
+        pub fn new(x: CGFloat, y: CGFloat) -> CGPoint {
+            CGPoint { x, y }
+        }
+    }
+
+    /// A rectangle, as an origin and a size.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct CGRect {
+        pub origin: CGPoint,
+        pub size: CGSize
+    }
+
+    impl CGRect {
+        /// Creates a new rect.
+        pub fn new(origin: &CGPoint, size: &CGSize) -> CGRect {
+            CGRect {
+                origin: *origin,
+                size: *size
+            }
+        }
+    }
+}
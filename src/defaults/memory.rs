@@ -0,0 +1,405 @@
+//! An in-memory `DefaultsStore`. This never touches Foundation, which makes it handy for tests
+//! (no polluting the real defaults domain) and for platforms where `NSUserDefaults` isn't around.
+//!
+//! It mimics the parts of `NSUserDefaults` that tend to matter in practice:
+//!
+//! - Registered values are per-instance fallbacks, and are never persisted.
+//! - Stores for the same domain share their values, while different suites are isolated from
+//!   one another (and from the standard domain).
+//! - Values can be forced for a key, simulating a managed (administrator-set) preference.
+//! - Observers see changes made through any handle to their domain. Delivery is deferred until
+//!   `deliver_changes` is called, which stands in for the main run loop turning over.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::{ChangeHandler, DefaultsObserver, DefaultsStore, Scheduler, Value};
+
+/// The name we use internally for the standard (non-suite) domain.
+const STANDARD_DOMAIN: &str = "";
+
+/// The backing data for every domain created from the same root `MemoryStore`.
+#[derive(Default)]
+struct Domains {
+    persistent: HashMap<String, HashMap<String, Value>>,
+    forced: HashMap<String, HashMap<String, Value>>,
+    observers: Vec<Observation>,
+    next_observer_id: usize
+}
+
+impl Domains {
+    /// The stored value for a key - forced, or else persistent. Registered values are
+    /// per-instance, so they're not taken into account.
+    fn stored(&self, domain: &str, key: &str) -> Option<Value> {
+        let forced = self.forced.get(domain).and_then(|forced| forced.get(key));
+        let persistent = self.persistent.get(domain).and_then(|persistent| persistent.get(key));
+        forced.or(persistent).cloned()
+    }
+
+    /// Returns the handlers observing the given key.
+    fn observers(&self, domain: &str, key: &str) -> Vec<ChangeHandler> {
+        self.observers
+            .iter()
+            .filter(|observation| observation.domain == domain && observation.key == key)
+            .map(|observation| observation.handler.clone())
+            .collect()
+    }
+}
+
+impl fmt::Debug for Domains {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Domains")
+            .field("persistent", &self.persistent)
+            .field("forced", &self.forced)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+/// A registered observer.
+struct Observation {
+    id: usize,
+    domain: String,
+    key: String,
+    handler: ChangeHandler
+}
+
+/// Tasks waiting on `deliver_changes`.
+#[derive(Default)]
+struct Pending(Vec<Box<dyn FnOnce() + Send>>);
+
+impl fmt::Debug for Pending {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pending").field(&self.0.len()).finish()
+    }
+}
+
+/// An in-memory `DefaultsStore`.
+///
+/// Cloning a `MemoryStore` gives you another handle to the same domain, much like asking for
+/// `standardUserDefaults` twice. Registered values are not shared between clones.
+///
+/// ```rust
+/// use cacao::defaults::{MemoryStore, UserDefaults, Value};
+///
+/// let store = MemoryStore::new();
+/// let mut defaults = UserDefaults::with_store(store.clone());
+/// let suite = UserDefaults::with_store(store.suite("com.myapp.shared"));
+///
+/// defaults.insert("test", Value::Bool(true));
+/// assert_eq!(suite.get("test"), None);
+///
+/// store.force("locked", Value::Bool(false));
+/// assert!(defaults.is_forced_for_key("locked"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MemoryStore {
+    domains: Arc<Mutex<Domains>>,
+    pending: Arc<Mutex<Pending>>,
+    domain: String,
+    registered: HashMap<String, Value>
+}
+
+impl Default for MemoryStore {
+    /// Equivalent to calling `MemoryStore::new()`.
+    fn default() -> Self {
+        MemoryStore::new()
+    }
+}
+
+impl MemoryStore {
+    /// Returns a new, empty store for the standard domain. Each call returns a store that's
+    /// isolated from every other `MemoryStore::new()` call.
+    pub fn new() -> Self {
+        MemoryStore {
+            domains: Arc::new(Mutex::new(Domains::default())),
+            pending: Arc::new(Mutex::new(Pending::default())),
+            domain: STANDARD_DOMAIN.to_string(),
+            registered: HashMap::new()
+        }
+    }
+
+    /// Returns a store for the given suite, sharing its backing data with this store. Values
+    /// stored in one suite are not visible in another.
+    pub fn suite(&self, named: &str) -> Self {
+        MemoryStore {
+            domains: self.domains.clone(),
+            pending: self.pending.clone(),
+            domain: named.to_string(),
+            registered: HashMap::new()
+        }
+    }
+
+    /// Forces a value for the given key in this store's domain, simulating a preference managed
+    /// by an administrator. Forced values take precedence over stored and registered values, and
+    /// `is_forced_for_key` returns `true` for them.
+    pub fn force<K: AsRef<str>>(&self, key: K, value: Value) {
+        self.change(key.as_ref(), |domains, domain, key| {
+            domains
+                .forced
+                .entry(domain.to_string())
+                .or_default()
+                .insert(key.to_string(), value);
+        });
+    }
+
+    /// Removes a forced value for the given key.
+    pub fn unforce<K: AsRef<str>>(&self, key: K) {
+        self.change(key.as_ref(), |domains, domain, key| {
+            if let Some(forced) = domains.forced.get_mut(domain) {
+                forced.remove(key);
+            }
+        });
+    }
+
+    /// Delivers any pending change notifications to observers. On a real system this happens
+    /// when the main run loop turns over; here it's up to you, which makes it possible to test
+    /// how bursts of changes are coalesced.
+    pub fn deliver_changes(&self) {
+        loop {
+            // Handlers may well change values themselves, queueing more work - so keep going
+            // until things settle, and never hold the lock while running a task.
+            let tasks = std::mem::take(&mut self.pending.lock().unwrap().0);
+
+            if tasks.is_empty() {
+                return;
+            }
+
+            for task in tasks {
+                task();
+            }
+        }
+    }
+
+    /// Applies `mutation` to the shared domains, then notifies anyone observing `key` if the
+    /// stored value changed.
+    fn change<F>(&self, key: &str, mutation: F)
+    where
+        F: FnOnce(&mut Domains, &str, &str)
+    {
+        let (old, new, observers) = {
+            let mut domains = self.domains.lock().unwrap();
+            let old = domains.stored(&self.domain, key);
+            mutation(&mut domains, &self.domain, key);
+            let new = domains.stored(&self.domain, key);
+            let observers = domains.observers(&self.domain, key);
+            (old, new, observers)
+        };
+
+        if old != new {
+            for observer in observers {
+                observer(old.clone(), new.clone());
+            }
+        }
+    }
+}
+
+impl DefaultsStore for MemoryStore {
+    fn register(&mut self, values: HashMap<String, Value>) {
+        self.registered.extend(values);
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        self.change(key, |domains, domain, key| {
+            domains
+                .persistent
+                .entry(domain.to_string())
+                .or_default()
+                .insert(key.to_string(), value);
+        });
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.change(key, |domains, domain, key| {
+            if let Some(persistent) = domains.persistent.get_mut(domain) {
+                persistent.remove(key);
+            }
+        });
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        let domains = self.domains.lock().unwrap();
+
+        domains
+            .stored(&self.domain, key)
+            .or_else(|| self.registered.get(key).cloned())
+    }
+
+    fn is_forced_for_key(&self, key: &str) -> bool {
+        let domains = self.domains.lock().unwrap();
+
+        domains
+            .forced
+            .get(&self.domain)
+            .map(|forced| forced.contains_key(key))
+            .unwrap_or(false)
+    }
+
+    fn persistent_domain(&self) -> HashMap<String, Value> {
+        let domains = self.domains.lock().unwrap();
+        domains.persistent.get(&self.domain).cloned().unwrap_or_default()
+    }
+
+    fn observe(&self, key: &str, on_change: ChangeHandler) -> DefaultsObserver {
+        let id = {
+            let mut domains = self.domains.lock().unwrap();
+            let id = domains.next_observer_id;
+            domains.next_observer_id += 1;
+
+            domains.observers.push(Observation {
+                id,
+                domain: self.domain.clone(),
+                key: key.to_string(),
+                handler: on_change
+            });
+
+            id
+        };
+
+        let domains = Arc::downgrade(&self.domains);
+
+        DefaultsObserver::new(move || {
+            if let Some(domains) = domains.upgrade() {
+                // Pulled out before the lock is released, so the handler is dropped unlocked.
+                let removed: Vec<Observation> = {
+                    let mut domains = domains.lock().unwrap();
+                    let (removed, kept) = std::mem::take(&mut domains.observers)
+                        .into_iter()
+                        .partition(|observation| observation.id == id);
+                    domains.observers = kept;
+                    removed
+                };
+
+                drop(removed);
+            }
+        })
+    }
+
+    fn scheduler(&self) -> Scheduler {
+        let pending = self.pending.clone();
+
+        Arc::new(move |task| {
+            pending.lock().unwrap().0.push(task);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::MemoryStore;
+    use crate::defaults::{DefaultsStore, UserDefaults, Value, ValueChange};
+
+    #[test]
+    fn test_register_fallback() {
+        let mut store = MemoryStore::new();
+        let handle = store.clone();
+
+        store.register({
+            let mut map = HashMap::new();
+            map.insert("volume".to_string(), Value::Integer(5));
+            map
+        });
+
+        assert_eq!(store.get("volume"), Some(Value::Integer(5)));
+
+        store.insert("volume", Value::Integer(11));
+        assert_eq!(store.get("volume"), Some(Value::Integer(11)));
+
+        // Removing the stored value falls back to the registered one again.
+        store.remove("volume");
+        assert_eq!(store.get("volume"), Some(Value::Integer(5)));
+
+        // Registered values aren't shared with other handles to the same domain, but stored ones are.
+        assert_eq!(handle.get("volume"), None);
+        store.insert("volume", Value::Integer(7));
+        assert_eq!(handle.get("volume"), Some(Value::Integer(7)));
+    }
+
+    #[test]
+    fn test_forced_values() {
+        let mut store = MemoryStore::new();
+        store.insert("theme", Value::string("dark"));
+        assert!(!store.is_forced_for_key("theme"));
+
+        store.force("theme", Value::string("light"));
+        store.insert("theme", Value::string("sepia"));
+        assert!(store.is_forced_for_key("theme"));
+        assert_eq!(store.get("theme"), Some(Value::string("light")));
+
+        store.unforce("theme");
+        assert!(!store.is_forced_for_key("theme"));
+        assert_eq!(store.get("theme"), Some(Value::string("sepia")));
+    }
+
+    #[test]
+    fn test_suite_isolation() {
+        let mut standard = MemoryStore::new();
+        let mut suite = standard.suite("com.cacao.shared");
+        let same_suite = standard.suite("com.cacao.shared");
+        let other_suite = standard.suite("com.cacao.other");
+
+        standard.insert("key", Value::Integer(1));
+        suite.insert("key", Value::Integer(2));
+
+        assert_eq!(standard.get("key"), Some(Value::Integer(1)));
+        assert_eq!(suite.get("key"), Some(Value::Integer(2)));
+        assert_eq!(same_suite.get("key"), Some(Value::Integer(2)));
+        assert_eq!(other_suite.get("key"), None);
+
+        standard.force("key", Value::Integer(3));
+        assert!(!suite.is_forced_for_key("key"));
+
+        // Separate roots never see each other.
+        assert_eq!(MemoryStore::new().get("key"), None);
+    }
+
+    #[test]
+    fn test_observe() {
+        let store = MemoryStore::new();
+        let mut defaults = UserDefaults::with_store(store.clone());
+        let mut other_window = UserDefaults::with_store(store.clone());
+        let mut suite = UserDefaults::with_store(store.suite("com.cacao.shared"));
+
+        let changes = Arc::new(Mutex::new(vec![]));
+        let observer = defaults.observe("volume", {
+            let changes = changes.clone();
+            move |change: &ValueChange| changes.lock().unwrap().push(change.clone())
+        });
+
+        // A burst of changes - from any handle to the domain - is delivered as one.
+        defaults.insert("volume", Value::Integer(1));
+        other_window.insert("volume", Value::Integer(2));
+        defaults.insert("volume", Value::Integer(3));
+        defaults.insert("other", Value::Integer(4));
+        suite.insert("volume", Value::Integer(5));
+        assert!(changes.lock().unwrap().is_empty());
+
+        store.deliver_changes();
+        assert_eq!(*changes.lock().unwrap(), vec![ValueChange {
+            key: "volume".to_string(),
+            old: None,
+            new: Some(Value::Integer(3))
+        }]);
+
+        // Bursts that end where they started aren't reported.
+        defaults.insert("volume", Value::Integer(4));
+        defaults.insert("volume", Value::Integer(3));
+        store.deliver_changes();
+        assert_eq!(changes.lock().unwrap().len(), 1);
+
+        store.force("volume", Value::Integer(11));
+        defaults.remove("volume");
+        store.deliver_changes();
+        assert_eq!(changes.lock().unwrap()[1].new, Some(Value::Integer(11)));
+
+        // Dropping the observer stops delivery, including anything already queued.
+        store.unforce("volume");
+        drop(observer);
+        store.deliver_changes();
+        assert_eq!(changes.lock().unwrap().len(), 2);
+    }
+}
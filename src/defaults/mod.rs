@@ -4,8 +4,17 @@
 //! this case, `Value` handles wrapping types for insertion/retrieval, shepherding between
 //! the Objective-C runtime and your Rust code.
 //!
-//! It currently supports a number of primitive types, as well as a generic `Data` type for custom
-//! usage. Note that the `Data` type is stored internally as an `NSData` instance.
+//! It currently supports a number of primitive types, arrays, dictionaries and dates, as well as a
+//! generic `Data` type for custom usage. Note that the `Data` type is stored internally as an
+//! `NSData` instance.
+//!
+//! Changes to individual keys can be observed via `UserDefaults::observe`.
+//!
+//! A domain can be exported to (and imported from) a property list, in either the XML or binary
+//! format - see `UserDefaults::export_domain` and the `plist` module.
+//!
+//! With the `serde` feature enabled, you can also store and retrieve any type implementing
+//! `Serialize`/`Deserialize` via `UserDefaults::insert_typed` and `UserDefaults::get_typed`.
 //!
 //! Do not use this for storing sensitive data - you want the Keychain for that.
 //!
@@ -35,20 +44,44 @@
 use std::collections::HashMap;
 
 use objc::runtime::Object;
-use objc::{class, msg_send, sel, sel_impl};
-use objc_id::Id;
 
-use crate::foundation::{id, nil, to_bool, NSData, NSMutableDictionary, NSNumber, NSString, BOOL, NO, YES};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
 
 mod value;
 pub use value::Value;
 
+mod store;
+pub use store::{DefaultsStore, FoundationStore};
+
+mod memory;
+pub use memory::MemoryStore;
+
+mod observer;
+pub use observer::{ChangeHandler, DefaultsObserver, Scheduler, ValueChange};
+
+pub mod plist;
+use plist::{PlistError, PlistFormat};
+
+#[cfg(feature = "serde")]
+mod serialization;
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub use serialization::{date, from_value, to_value, ConversionError};
+
+#[cfg(feature = "serde")]
+pub(crate) use serialization::{serialize_into, EnumDeserializer, SerializedValue, VariantValue, DATE_TOKEN};
+
 /// Wraps and provides methods for interacting with `NSUserDefaults`, which can be used for storing
 /// pieces of information (preferences, or _defaults_) to persist across application launches.
 ///
+/// By default this is backed by `NSUserDefaults`, but you can swap in any `DefaultsStore` via
+/// `with_store` - e.g, a `MemoryStore` for tests.
+///
 /// This should not be used for sensitive data - use the Keychain for that.
 #[derive(Debug)]
-pub struct UserDefaults(pub Id<Object>);
+pub struct UserDefaults(Box<dyn DefaultsStore>);
 
 impl Default for UserDefaults {
     /// Equivalent to calling `UserDefaults::standard()`.
@@ -71,7 +104,7 @@ impl UserDefaults {
     /// let _ = defaults.get("test");
     /// ```
     pub fn standard() -> Self {
-        UserDefaults(unsafe { Id::from_ptr(msg_send![class!(NSUserDefaults), standardUserDefaults]) })
+        UserDefaults::with_store(FoundationStore::standard())
     }
 
     /// Returns a user defaults instance for the given suite name. You typically use this to share
@@ -86,12 +119,26 @@ impl UserDefaults {
     /// let _ = defaults.get("test");
     /// ```
     pub fn suite(named: &str) -> Self {
-        let name = NSString::new(named);
+        UserDefaults::with_store(FoundationStore::suite(named))
+    }
+
+    /// Returns a user defaults instance backed by the given store.
+    ///
+    /// ```rust
+    /// use cacao::defaults::{MemoryStore, UserDefaults, Value};
+    ///
+    /// let mut defaults = UserDefaults::with_store(MemoryStore::new());
+    /// defaults.insert("test", Value::Bool(true));
+    /// assert_eq!(defaults.get("test"), Some(Value::Bool(true)));
+    /// ```
+    pub fn with_store<S: DefaultsStore + 'static>(store: S) -> Self {
+        UserDefaults(Box::new(store))
+    }
 
-        UserDefaults(unsafe {
-            let alloc: id = msg_send![class!(NSUserDefaults), alloc];
-            Id::from_ptr(msg_send![alloc, initWithSuiteName:&*name])
-        })
+    /// Returns a user defaults instance backed by a fresh, empty `MemoryStore`. Shorthand for
+    /// `UserDefaults::with_store(MemoryStore::new())`.
+    pub fn in_memory() -> Self {
+        UserDefaults::with_store(MemoryStore::new())
     }
 
     /// You can use this to register defaults at the beginning of your program. Note that these are
@@ -112,11 +159,11 @@ impl UserDefaults {
     /// });
     /// ```
     pub fn register<K: AsRef<str>>(&mut self, values: HashMap<K, Value>) {
-        let dictionary = NSMutableDictionary::from(values);
-
-        unsafe {
-            let _: () = msg_send![&*self.0, registerDefaults:&*dictionary];
-        }
+        let values = values
+            .into_iter()
+            .map(|(key, value)| (key.as_ref().to_string(), value))
+            .collect();
+        self.0.register(values);
     }
 
     /// Inserts a value for the specified key. This synchronously updates the backing
@@ -129,12 +176,7 @@ impl UserDefaults {
     /// defaults.insert("test", Value::Bool(true));
     /// ```
     pub fn insert<K: AsRef<str>>(&mut self, key: K, value: Value) {
-        let key = NSString::new(key.as_ref());
-        let value: id = value.into();
-
-        unsafe {
-            let _: () = msg_send![&*self.0, setObject:value forKey:key];
-        }
+        self.0.insert(key.as_ref(), value);
     }
 
     /// Remove the default associated with the key. If the key doesn't exist, this is a noop.
@@ -146,11 +188,7 @@ impl UserDefaults {
     /// defaults.remove("test");
     /// ```
     pub fn remove<K: AsRef<str>>(&mut self, key: K) {
-        let key = NSString::new(key.as_ref());
-
-        unsafe {
-            let _: () = msg_send![&*self.0, removeObjectForKey:&*key];
-        }
+        self.0.remove(key.as_ref());
     }
 
     /// Returns a `Value` for the given key, from which you can further extract the data you
@@ -172,52 +210,49 @@ impl UserDefaults {
     /// assert_eq!(value, "value");
     /// ```
     pub fn get<K: AsRef<str>>(&self, key: K) -> Option<Value> {
-        let key = NSString::new(key.as_ref());
-
-        let result: id = unsafe { msg_send![&*self.0, objectForKey:&*key] };
-
-        if result == nil {
-            return None;
-        }
-
-        if NSData::is(result) {
-            let data = NSData::retain(result);
-            return Some(Value::Data(data.into_vec()));
-        }
-
-        if NSString::is(result) {
-            let s = NSString::retain(result).to_string();
-            return Some(Value::String(s));
-        }
-
-        // This works, but might not be the best approach. We basically need to inspect the
-        // `NSNumber` returned and see what the wrapped encoding type is. `q` and `d` represent
-        // `NSInteger` (platform specific) and `double` (f64) respectively, but conceivably we
-        // might need others.
-        //
-        // BOOL returns as "c", which... something makes me feel weird there, but testing it seems
-        // reliable.
-        //
-        // For context: https://nshipster.com/type-encodings/
-        if NSNumber::is(result) {
-            let number = NSNumber::wrap(result);
-
-            return match number.objc_type() {
-                "c" => Some(Value::Bool(number.as_bool())),
-                "d" => Some(Value::Float(number.as_f64())),
-                "q" => Some(Value::Integer(number.as_i64())),
+        self.0.get(key.as_ref())
+    }
 
-                _x => {
-                    // Debugging code that should be removed at some point.
-                    #[cfg(debug_assertions)]
-                    println!("Unexpected code type found: {}", _x);
+    /// Inserts any `Serialize` type for the specified key, by first converting it into a `Value`.
+    /// See `to_value` for how types map onto what `NSUserDefaults` can store.
+    ///
+    /// ```rust,no_run
+    /// use serde::{Deserialize, Serialize};
+    /// use cacao::defaults::UserDefaults;
+    ///
+    /// #[derive(Serialize, Deserialize)]
+    /// struct Preferences {
+    ///     show_sidebar: bool,
+    ///     recent_files: Vec<String>
+    /// }
+    ///
+    /// let mut defaults = UserDefaults::standard();
+    /// defaults.insert_typed("preferences", &Preferences {
+    ///     show_sidebar: true,
+    ///     recent_files: vec![]
+    /// }).unwrap();
+    ///
+    /// let preferences: Option<Preferences> = defaults.get_typed("preferences").unwrap();
+    /// assert!(preferences.unwrap().show_sidebar);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn insert_typed<K: AsRef<str>, T: Serialize + ?Sized>(&mut self, key: K, value: &T) -> Result<(), ConversionError> {
+        let value = to_value(value)?;
+        self.insert(key, value);
+        Ok(())
+    }
 
-                    None
-                }
-            };
+    /// Returns the value for the given key, deserialized into any `Deserialize` type. Returns
+    /// `Ok(None)` if there's nothing stored for the key, and an error if what's stored doesn't
+    /// match `T`.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn get_typed<T: DeserializeOwned, K: AsRef<str>>(&self, key: K) -> Result<Option<T>, ConversionError> {
+        match self.get(key) {
+            Some(value) => from_value(value).map(Some),
+            None => Ok(None)
         }
-
-        None
     }
 
     /// Returns a boolean value if the object stored for the specified key is managed by an
@@ -236,12 +271,83 @@ impl UserDefaults {
     /// assert_eq!(value, false);
     /// ```
     pub fn is_forced_for_key<K: AsRef<str>>(&self, key: K) -> bool {
-        let result: BOOL = unsafe {
-            let key = NSString::new(key.as_ref());
-            msg_send![&*self.0, objectIsForcedForKey:&*key]
+        self.0.is_forced_for_key(key.as_ref())
+    }
+
+    /// Calls `handler` whenever the value for `key` changes - whether through this instance,
+    /// another one for the same domain, or (for `NSUserDefaults`) outside the app entirely, e.g
+    /// via `defaults write`. The handler receives both the old and the new value.
+    ///
+    /// Changes are coalesced: a burst of writes results in a single call, with the value from
+    /// before the burst and the value after it, delivered on the main thread once the current
+    /// run loop iteration is over.
+    ///
+    /// Observation stops when the returned `DefaultsObserver` is dropped.
+    ///
+    /// ```rust
+    /// use cacao::defaults::{MemoryStore, UserDefaults, Value};
+    ///
+    /// let store = MemoryStore::new();
+    /// let mut defaults = UserDefaults::with_store(store.clone());
+    ///
+    /// let _observer = defaults.observe("volume", |change| {
+    ///     assert_eq!(change.old, None);
+    ///     assert_eq!(change.new, Some(Value::Integer(11)));
+    /// });
+    ///
+    /// defaults.insert("volume", Value::Integer(10));
+    /// defaults.insert("volume", Value::Integer(11));
+    ///
+    /// // Stands in for the run loop turning over.
+    /// store.deliver_changes();
+    /// ```
+    pub fn observe<K, F>(&self, key: K, handler: F) -> DefaultsObserver
+    where
+        K: AsRef<str>,
+        F: Fn(&ValueChange) + Send + Sync + 'static
+    {
+        let key = key.as_ref();
+        let on_change = observer::coalesce(key, handler, self.0.scheduler());
+        self.0.observe(key, on_change)
+    }
+
+    /// Exports every value stored in this domain as a property list, with a dictionary at its
+    /// root. Registered values are not included, so the result is exactly what the user (or your
+    /// app) has changed.
+    ///
+    /// ```rust
+    /// use cacao::defaults::{UserDefaults, Value};
+    /// use cacao::defaults::plist::PlistFormat;
+    ///
+    /// let mut defaults = UserDefaults::in_memory();
+    /// defaults.insert("test", Value::Bool(true));
+    ///
+    /// let exported = defaults.export_domain(PlistFormat::Xml);
+    ///
+    /// let mut restored = UserDefaults::in_memory();
+    /// restored.import_domain(&exported).unwrap();
+    /// assert_eq!(restored.get("test"), Some(Value::Bool(true)));
+    /// ```
+    pub fn export_domain(&self, format: PlistFormat) -> Vec<u8> {
+        plist::to_bytes(&Value::Dictionary(self.0.persistent_domain()), format)
+    }
+
+    /// Imports values from a property list (XML or binary, detected automatically) into this
+    /// domain. The property list must have a dictionary at its root; each entry is inserted as if
+    /// by `insert`, so keys not present in the property list are left untouched.
+    ///
+    /// Nothing is imported if the property list can't be read.
+    pub fn import_domain(&mut self, bytes: &[u8]) -> Result<(), PlistError> {
+        let values = match plist::from_bytes(bytes)? {
+            Value::Dictionary(values) => values,
+            _ => return Err(PlistError::ExpectedDictionary)
         };
 
-        to_bool(result)
+        for (key, value) in values {
+            self.0.insert(&key, value);
+        }
+
+        Ok(())
     }
 
     /// Blocks for any asynchronous updates to the defaults database and returns.
@@ -256,8 +362,22 @@ impl UserDefaults {
     /// defaults.synchronize();
     /// ```
     pub fn synchronize(&self) {
-        unsafe {
-            let _: () = msg_send![&*self.0, synchronize];
-        }
+        self.0.synchronize();
+    }
+
+    /// Returns the underlying `NSUserDefaults` instance, for anything these methods don't cover.
+    /// This is `None` for stores that aren't backed by Foundation (e.g, `MemoryStore`).
+    ///
+    /// _This replaces the public `.0` field from before `UserDefaults` was backed by a
+    /// `DefaultsStore`._
+    ///
+    /// ```rust
+    /// use cacao::defaults::UserDefaults;
+    ///
+    /// assert!(UserDefaults::standard().objc().is_some());
+    /// assert!(UserDefaults::in_memory().objc().is_none());
+    /// ```
+    pub fn objc(&self) -> Option<&Object> {
+        self.0.objc()
     }
 }
@@ -0,0 +1,109 @@
+//! Observing changes to individual keys in a `UserDefaults` domain.
+//!
+//! Stores report every change as it happens; `UserDefaults::observe` then coalesces them, so that
+//! a burst of writes (e.g, dragging a slider bound to a preference) results in a single callback
+//! carrying the value from before the burst and the value after it.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use super::Value;
+
+/// Called by a `DefaultsStore` with the old and new value for an observed key, each time it
+/// changes.
+pub type ChangeHandler = Arc<dyn Fn(Option<Value>, Option<Value>) + Send + Sync>;
+
+/// Runs a task at some later point - for `NSUserDefaults`, the next turn of the main run loop.
+/// Changes that arrive before the task runs are coalesced into one.
+pub type Scheduler = Arc<dyn Fn(Box<dyn FnOnce() + Send>) + Send + Sync>;
+
+/// Describes a change to an observed key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValueChange {
+    /// The key that changed.
+    pub key: String,
+
+    /// The value before the change, or `None` if there wasn't one.
+    pub old: Option<Value>,
+
+    /// The value after the change, or `None` if it was removed.
+    pub new: Option<Value>
+}
+
+/// Returned from `UserDefaults::observe`. Observation stops when this is dropped, so hold on to
+/// it for as long as you want to receive changes.
+#[must_use = "observation stops as soon as the observer is dropped"]
+pub struct DefaultsObserver {
+    cancel: Option<Box<dyn FnOnce()>>
+}
+
+impl DefaultsObserver {
+    /// Creates a new observer, which calls `cancel` when dropped. This is mostly of interest to
+    /// `DefaultsStore` implementations.
+    pub fn new<F: FnOnce() + 'static>(cancel: F) -> Self {
+        DefaultsObserver {
+            cancel: Some(Box::new(cancel))
+        }
+    }
+}
+
+impl fmt::Debug for DefaultsObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DefaultsObserver").finish()
+    }
+}
+
+impl Drop for DefaultsObserver {
+    /// Stops observing.
+    fn drop(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel();
+        }
+    }
+}
+
+/// The old and new values for a burst of changes that hasn't been delivered yet.
+type Burst = Option<(Option<Value>, Option<Value>)>;
+
+/// Wraps `handler` in a `ChangeHandler` that coalesces changes until `scheduler` gets around to
+/// running. Bursts that end up back where they started (e.g, a toggle flipped twice) aren't
+/// reported at all.
+///
+/// The returned handler is the only strong reference to `handler`; once the store drops it (on
+/// unsubscribe), any delivery still in flight is discarded.
+pub(crate) fn coalesce<F>(key: &str, handler: F, scheduler: Scheduler) -> ChangeHandler
+where
+    F: Fn(&ValueChange) + Send + Sync + 'static
+{
+    let key = key.to_string();
+    let handler = Arc::new(handler);
+    let pending: Arc<Mutex<Burst>> = Arc::new(Mutex::new(None));
+
+    Arc::new(move |old, new| {
+        {
+            let mut burst = pending.lock().unwrap();
+
+            if let Some((_, latest)) = burst.as_mut() {
+                *latest = new;
+                return;
+            }
+
+            *burst = Some((old, new));
+        }
+
+        // The lock is released first, in case the scheduler runs the task right away.
+        let key = key.clone();
+        let pending = pending.clone();
+        let handler = Arc::downgrade(&handler);
+
+        scheduler(Box::new(move || {
+            let burst = pending.lock().unwrap().take();
+
+            if let (Some((old, new)), Some(handler)) = (burst, handler.upgrade()) {
+                if old != new {
+                    handler(&ValueChange { key, old, new });
+                }
+            }
+        }));
+    })
+}
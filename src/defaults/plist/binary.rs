@@ -0,0 +1,476 @@
+//! Reading and writing binary (`bplist00`) property lists.
+//!
+//! A binary property list is a header, a flat run of encoded objects, a table of offsets to each
+//! object, and a fixed-size trailer describing the table. Containers refer to their contents by
+//! index into the offset table.
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use super::{date_from_reference_timestamp, date_to_reference_timestamp, PlistError};
+use crate::defaults::Value;
+
+pub(super) const MAGIC: &[u8] = b"bplist00";
+
+const TRAILER_LENGTH: usize = 32;
+
+/// Guards against stack exhaustion from maliciously deep (but acyclic) nesting.
+const MAX_DEPTH: usize = 512;
+
+/// An object that's referenced more than once is decoded once per reference, so a tiny file of
+/// containers that share their contents can describe an exponentially large tree. Decoding stops
+/// after this many objects for every object in the file (or `MIN_DECODED_OBJECTS`, for small
+/// files), which leaves room for the sharing real writers do (e.g, of repeated strings).
+const MAX_EXPANSION: usize = 64;
+const MIN_DECODED_OBJECTS: usize = 1 << 16;
+
+fn error(offset: usize, message: &str) -> PlistError {
+    PlistError::InvalidBinary {
+        offset,
+        message: message.to_string()
+    }
+}
+
+/// Reads a big-endian unsigned integer of up to 8 bytes.
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |value, byte| value << 8 | *byte as u64)
+}
+
+/// Reads a binary property list.
+pub(super) fn read(bytes: &[u8]) -> Result<Value, PlistError> {
+    if !bytes.starts_with(MAGIC) {
+        return Err(error(0, "Missing bplist00 header"));
+    }
+
+    if bytes.len() < MAGIC.len() + TRAILER_LENGTH {
+        return Err(error(bytes.len(), "File is too short to contain a trailer"));
+    }
+
+    let trailer_start = bytes.len() - TRAILER_LENGTH;
+    let trailer = &bytes[trailer_start..];
+    let offset_size = trailer[6] as usize;
+    let ref_size = trailer[7] as usize;
+    let object_count = read_uint(&trailer[8..16]);
+    let top_object = read_uint(&trailer[16..24]);
+    let table_start = read_uint(&trailer[24..32]);
+
+    if !(1..=8).contains(&offset_size) || !(1..=8).contains(&ref_size) {
+        return Err(error(trailer_start + 6, "Invalid offset or reference size"));
+    }
+
+    let table_end = object_count
+        .checked_mul(offset_size as u64)
+        .and_then(|length| length.checked_add(table_start));
+
+    match table_end {
+        Some(end) if table_start >= MAGIC.len() as u64 && end <= trailer_start as u64 => {},
+        _ => return Err(error(trailer_start + 8, "Offset table is out of bounds"))
+    }
+
+    if top_object >= object_count {
+        return Err(error(trailer_start + 16, "Top object is out of bounds"));
+    }
+
+    let reader = Reader {
+        bytes,
+        objects_end: table_start as usize,
+        offset_size,
+        ref_size,
+        table_start: table_start as usize,
+        object_count: object_count as usize,
+        decoded: Cell::new(0)
+    };
+
+    reader.read_object(top_object as usize, &mut vec![])
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    objects_end: usize,
+    offset_size: usize,
+    ref_size: usize,
+    table_start: usize,
+    object_count: usize,
+
+    /// How many objects have been decoded so far; see `MAX_EXPANSION`.
+    decoded: Cell<usize>
+}
+
+impl<'a> Reader<'a> {
+    /// Returns `length` bytes at `offset`, provided they lie within the object area.
+    fn slice(&self, offset: usize, length: usize) -> Result<&'a [u8], PlistError> {
+        match offset.checked_add(length) {
+            Some(end) if end <= self.objects_end => Ok(&self.bytes[offset..end]),
+            _ => Err(error(offset, "Object data is out of bounds"))
+        }
+    }
+
+    fn object_offset(&self, index: usize) -> Result<usize, PlistError> {
+        if index >= self.object_count {
+            return Err(error(self.table_start, "Object reference is out of bounds"));
+        }
+
+        let entry = self.table_start + index * self.offset_size;
+        let offset = read_uint(&self.bytes[entry..entry + self.offset_size]);
+
+        match offset >= MAGIC.len() as u64 && offset < self.objects_end as u64 {
+            true => Ok(offset as usize),
+            false => Err(error(entry, "Object offset is out of bounds"))
+        }
+    }
+
+    /// Reads the length for the object whose marker is at `offset`, returning it along with where
+    /// the object's payload starts. Lengths of 15 or more are stored as a trailing integer object.
+    fn read_length(&self, offset: usize, marker: u8) -> Result<(usize, usize), PlistError> {
+        if marker & 0x0f != 0x0f {
+            return Ok(((marker & 0x0f) as usize, offset + 1));
+        }
+
+        let int_marker = self.slice(offset + 1, 1)?[0];
+
+        if int_marker & 0xf0 != 0x10 || int_marker & 0x0f > 3 {
+            return Err(error(offset + 1, "Invalid length marker"));
+        }
+
+        let size = 1 << (int_marker & 0x0f);
+        let length = read_uint(self.slice(offset + 2, size)?);
+
+        match length.try_into() {
+            Ok(length) => Ok((length, offset + 2 + size)),
+            Err(_) => Err(error(offset + 2, "Length is too large"))
+        }
+    }
+
+    /// Reads `count` object references starting at `offset`.
+    fn read_refs(&self, offset: usize, count: usize) -> Result<Vec<usize>, PlistError> {
+        let length = count
+            .checked_mul(self.ref_size)
+            .ok_or_else(|| error(offset, "Container is too large"))?;
+
+        Ok(self
+            .slice(offset, length)?
+            .chunks(self.ref_size)
+            .map(|chunk| read_uint(chunk) as usize)
+            .collect())
+    }
+
+    /// Reads the object at `index`. `path` holds the containers we're currently inside of, so
+    /// that reference cycles can be rejected.
+    fn read_object(&self, index: usize, path: &mut Vec<usize>) -> Result<Value, PlistError> {
+        let offset = self.object_offset(index)?;
+
+        if path.contains(&index) {
+            return Err(error(offset, "Containers form a reference cycle"));
+        }
+
+        if path.len() >= MAX_DEPTH {
+            return Err(error(offset, "Containers are nested too deeply"));
+        }
+
+        let decoded = self.decoded.get() + 1;
+        if decoded > self.object_count.saturating_mul(MAX_EXPANSION).max(MIN_DECODED_OBJECTS) {
+            return Err(error(offset, "Shared references expand to too many objects"));
+        }
+
+        self.decoded.set(decoded);
+
+        let marker = self.bytes[offset];
+
+        match marker >> 4 {
+            0x0 => match marker {
+                0x08 => Ok(Value::Bool(false)),
+                0x09 => Ok(Value::Bool(true)),
+                _ => Err(PlistError::Unsupported(format!("Object marker 0x{:02x}", marker)))
+            },
+
+            0x1 => {
+                let size = 1usize << (marker & 0x0f);
+                let bytes = self.slice(offset + 1, size)?;
+
+                match size {
+                    // 1, 2 and 4 byte integers are unsigned, 8 byte integers are signed.
+                    1 | 2 | 4 | 8 => Ok(Value::Integer(read_uint(bytes) as i64)),
+
+                    // 16 byte integers only show up for values that don't fit in an i64.
+                    16 => {
+                        let value = i128::from_be_bytes(bytes.try_into().unwrap());
+
+                        match value.try_into() {
+                            Ok(value) => Ok(Value::Integer(value)),
+                            Err(_) => Err(PlistError::Unsupported(format!("Integer {} is out of range", value)))
+                        }
+                    },
+
+                    _ => Err(error(offset, "Invalid integer size"))
+                }
+            },
+
+            0x2 => match marker & 0x0f {
+                2 => Ok(Value::Float(
+                    f32::from_be_bytes(self.slice(offset + 1, 4)?.try_into().unwrap()) as f64
+                )),
+                3 => Ok(Value::Float(f64::from_be_bytes(
+                    self.slice(offset + 1, 8)?.try_into().unwrap()
+                ))),
+                _ => Err(error(offset, "Invalid real size"))
+            },
+
+            0x3 if marker == 0x33 => {
+                let timestamp = f64::from_be_bytes(self.slice(offset + 1, 8)?.try_into().unwrap());
+
+                match timestamp.is_finite() {
+                    true => Ok(Value::Date(date_from_reference_timestamp(timestamp))),
+                    false => Err(error(offset, "Invalid date"))
+                }
+            },
+
+            0x4 => {
+                let (length, start) = self.read_length(offset, marker)?;
+                Ok(Value::Data(self.slice(start, length)?.to_vec()))
+            },
+
+            // ASCII, and (in newer files) UTF-8 strings. Lengths are in bytes.
+            0x5 | 0x7 => {
+                let (length, start) = self.read_length(offset, marker)?;
+
+                match String::from_utf8(self.slice(start, length)?.to_vec()) {
+                    Ok(string) => Ok(Value::String(string)),
+                    Err(_) => Err(error(start, "String is not valid UTF-8"))
+                }
+            },
+
+            // UTF-16 strings. Lengths are in code units.
+            0x6 => {
+                let (length, start) = self.read_length(offset, marker)?;
+                let byte_length = length.checked_mul(2).ok_or_else(|| error(offset, "String is too large"))?;
+
+                let units: Vec<u16> = self
+                    .slice(start, byte_length)?
+                    .chunks(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+
+                match String::from_utf16(&units) {
+                    Ok(string) => Ok(Value::String(string)),
+                    Err(_) => Err(error(start, "String is not valid UTF-16"))
+                }
+            },
+
+            0xa => {
+                let (count, start) = self.read_length(offset, marker)?;
+                let refs = self.read_refs(start, count)?;
+
+                path.push(index);
+                let values = refs
+                    .into_iter()
+                    .map(|child| self.read_object(child, path))
+                    .collect::<Result<Vec<_>, _>>()?;
+                path.pop();
+
+                Ok(Value::Array(values))
+            },
+
+            0xd => {
+                let (count, start) = self.read_length(offset, marker)?;
+                let refs = self.read_refs(start, count.saturating_mul(2))?;
+                let (keys, values) = refs.split_at(count);
+                let mut map = HashMap::with_capacity(count);
+
+                path.push(index);
+
+                for (key, value) in keys.iter().zip(values) {
+                    let key = match self.read_object(*key, path)? {
+                        Value::String(key) => key,
+                        _ => return Err(error(offset, "Dictionary keys must be strings"))
+                    };
+
+                    map.insert(key, self.read_object(*value, path)?);
+                }
+
+                path.pop();
+
+                Ok(Value::Dictionary(map))
+            },
+
+            0x8 => Err(PlistError::Unsupported("UIDs (as used by keyed archives)".to_string())),
+            0xc => Err(PlistError::Unsupported("Sets".to_string())),
+            _ => Err(error(offset, &format!("Unknown object marker 0x{:02x}", marker)))
+        }
+    }
+}
+
+/// Writes a binary property list. Objects are written depth-first, without deduplication.
+pub(super) fn write(value: &Value) -> Vec<u8> {
+    let ref_size = minimum_size(count_objects(value) as u64 - 1);
+
+    let mut writer = Writer {
+        objects: vec![],
+        ref_size
+    };
+
+    writer.add(value);
+
+    let mut output = MAGIC.to_vec();
+    let mut offsets = Vec::with_capacity(writer.objects.len());
+
+    for object in &writer.objects {
+        offsets.push(output.len() as u64);
+        output.extend_from_slice(object);
+    }
+
+    let table_start = output.len() as u64;
+    let offset_size = minimum_size(table_start);
+
+    for offset in offsets {
+        output.extend_from_slice(&offset.to_be_bytes()[8 - offset_size..]);
+    }
+
+    output.extend_from_slice(&[0; 6]);
+    output.push(offset_size as u8);
+    output.push(ref_size as u8);
+    output.extend_from_slice(&(writer.objects.len() as u64).to_be_bytes());
+    output.extend_from_slice(&0u64.to_be_bytes());
+    output.extend_from_slice(&table_start.to_be_bytes());
+    output
+}
+
+/// The number of objects `value` will be flattened into.
+fn count_objects(value: &Value) -> usize {
+    match value {
+        Value::Array(values) => 1 + values.iter().map(count_objects).sum::<usize>(),
+        Value::Dictionary(map) => 1 + map.len() + map.values().map(count_objects).sum::<usize>(),
+        _ => 1
+    }
+}
+
+/// The smallest number of bytes (1, 2, 4 or 8) that can hold `value`.
+fn minimum_size(value: u64) -> usize {
+    match value {
+        0..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xffff_ffff => 4,
+        _ => 8
+    }
+}
+
+struct Writer {
+    objects: Vec<Vec<u8>>,
+    ref_size: usize
+}
+
+impl Writer {
+    /// Flattens `value` (and anything it contains) into `objects`, returning its index.
+    fn add(&mut self, value: &Value) -> usize {
+        let index = self.objects.len();
+        self.objects.push(vec![]);
+
+        let encoded = match value {
+            Value::Bool(false) => vec![0x08],
+            Value::Bool(true) => vec![0x09],
+            Value::Integer(integer) => encode_integer(*integer),
+
+            Value::Float(float) => {
+                let mut encoded = vec![0x23];
+                encoded.extend_from_slice(&float.to_be_bytes());
+                encoded
+            },
+
+            Value::Date(date) => {
+                let mut encoded = vec![0x33];
+                encoded.extend_from_slice(&date_to_reference_timestamp(date).to_be_bytes());
+                encoded
+            },
+
+            Value::Data(data) => {
+                let mut encoded = encode_marker(0x40, data.len());
+                encoded.extend_from_slice(data);
+                encoded
+            },
+
+            Value::String(string) => encode_string(string),
+
+            Value::Array(values) => {
+                let refs: Vec<usize> = values.iter().map(|value| self.add(value)).collect();
+                let mut encoded = encode_marker(0xa0, values.len());
+                self.push_refs(&mut encoded, &refs);
+                encoded
+            },
+
+            Value::Dictionary(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+
+                let key_refs: Vec<usize> = keys.iter().map(|key| self.add_string(key)).collect();
+                let value_refs: Vec<usize> = keys.iter().map(|key| self.add(&map[*key])).collect();
+
+                let mut encoded = encode_marker(0xd0, map.len());
+                self.push_refs(&mut encoded, &key_refs);
+                self.push_refs(&mut encoded, &value_refs);
+                encoded
+            }
+        };
+
+        self.objects[index] = encoded;
+        index
+    }
+
+    fn add_string(&mut self, string: &str) -> usize {
+        self.objects.push(encode_string(string));
+        self.objects.len() - 1
+    }
+
+    fn push_refs(&self, encoded: &mut Vec<u8>, refs: &[usize]) {
+        for index in refs {
+            encoded.extend_from_slice(&(*index as u64).to_be_bytes()[8 - self.ref_size..]);
+        }
+    }
+}
+
+/// Encodes an integer in as few bytes as possible. Negative numbers always take 8 bytes, as
+/// smaller integers are read back as unsigned.
+fn encode_integer(value: i64) -> Vec<u8> {
+    let size = match value < 0 {
+        true => 8,
+        false => minimum_size(value as u64)
+    };
+
+    let mut encoded = vec![0x10 | size.trailing_zeros() as u8];
+    encoded.extend_from_slice(&value.to_be_bytes()[8 - size..]);
+    encoded
+}
+
+/// Encodes a marker with a length, spilling into a trailing integer for lengths of 15 or more.
+fn encode_marker(marker: u8, length: usize) -> Vec<u8> {
+    match length < 0x0f {
+        true => vec![marker | length as u8],
+
+        false => {
+            let mut encoded = vec![marker | 0x0f];
+            encoded.extend(encode_integer(length as i64));
+            encoded
+        }
+    }
+}
+
+/// ASCII strings are stored as-is; anything else is stored as UTF-16.
+fn encode_string(string: &str) -> Vec<u8> {
+    match string.is_ascii() {
+        true => {
+            let mut encoded = encode_marker(0x50, string.len());
+            encoded.extend_from_slice(string.as_bytes());
+            encoded
+        },
+
+        false => {
+            let units: Vec<u16> = string.encode_utf16().collect();
+            let mut encoded = encode_marker(0x60, units.len());
+
+            for unit in units {
+                encoded.extend_from_slice(&unit.to_be_bytes());
+            }
+
+            encoded
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! A pure-Rust reader and writer for property lists, in both the XML and binary (`bplist00`)
+//! formats. Property lists map directly onto `Value`, so this is what powers
+//! `UserDefaults::export_domain` and `UserDefaults::import_domain` - but it's equally useful on
+//! its own for things like generating an `Info.plist`.
+//!
+//! ```rust
+//! use cacao::defaults::Value;
+//! use cacao::defaults::plist::{self, PlistFormat};
+//!
+//! let value = Value::Array(vec![Value::Bool(true), Value::string("hello")]);
+//!
+//! let xml = plist::to_bytes(&value, PlistFormat::Xml);
+//! let binary = plist::to_bytes(&value, PlistFormat::Binary);
+//!
+//! assert_eq!(plist::from_bytes(&xml).unwrap(), value);
+//! assert_eq!(plist::from_bytes(&binary).unwrap(), value);
+//! ```
+//!
+//! Note that `Value` has no representation for the handful of plist types that never appear in
+//! preferences (`UID`s from keyed archives, and binary `null`s); reading those is an error.
+
+use std::error;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Value;
+
+mod binary;
+mod xml;
+
+/// The on-disk formats a property list can be written in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlistFormat {
+    /// The XML format - human readable, and diff-friendly.
+    Xml,
+
+    /// The binary `bplist00` format - compact, and what the system typically writes.
+    Binary
+}
+
+/// Errors that can occur when reading a property list.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlistError {
+    /// The XML was malformed, or didn't describe a valid property list. `position` is the byte
+    /// offset into the input where the problem was found.
+    InvalidXml {
+        /// The byte offset into the input.
+        position: usize,
+
+        /// What went wrong.
+        message: String
+    },
+
+    /// The binary property list was malformed. `offset` is the byte offset into the input where
+    /// the problem was found.
+    InvalidBinary {
+        /// The byte offset into the input.
+        offset: usize,
+
+        /// What went wrong.
+        message: String
+    },
+
+    /// The property list was valid, but contained something `Value` can't represent.
+    Unsupported(String),
+
+    /// The property list was expected to have a dictionary at its root, but didn't.
+    ExpectedDictionary
+}
+
+impl fmt::Display for PlistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlistError::InvalidXml { position, message } => {
+                write!(f, "Invalid XML property list at byte {}: {}", position, message)
+            },
+
+            PlistError::InvalidBinary { offset, message } => {
+                write!(f, "Invalid binary property list at byte {}: {}", offset, message)
+            },
+
+            PlistError::Unsupported(message) => write!(f, "Unsupported property list contents: {}", message),
+            PlistError::ExpectedDictionary => write!(f, "Expected a dictionary at the root of the property list")
+        }
+    }
+}
+
+impl error::Error for PlistError {}
+
+/// Reads a property list, detecting whether it's in the XML or binary format.
+pub fn from_bytes(bytes: &[u8]) -> Result<Value, PlistError> {
+    match detect_format(bytes) {
+        PlistFormat::Binary => binary::read(bytes),
+        PlistFormat::Xml => xml::read(bytes)
+    }
+}
+
+/// Writes a property list in the given format.
+pub fn to_bytes(value: &Value, format: PlistFormat) -> Vec<u8> {
+    match format {
+        PlistFormat::Binary => binary::write(value),
+        PlistFormat::Xml => xml::write(value).into_bytes()
+    }
+}
+
+/// Returns the format of the given property list data. Anything that isn't binary is assumed to
+/// be XML.
+pub fn detect_format(bytes: &[u8]) -> PlistFormat {
+    match bytes.starts_with(binary::MAGIC) {
+        true => PlistFormat::Binary,
+        false => PlistFormat::Xml
+    }
+}
+
+impl Value {
+    /// Reads a `Value` from property list data, in either the XML or binary format.
+    pub fn from_plist(bytes: &[u8]) -> Result<Value, PlistError> {
+        from_bytes(bytes)
+    }
+
+    /// Writes this `Value` as a property list in the given format.
+    pub fn to_plist(&self, format: PlistFormat) -> Vec<u8> {
+        to_bytes(self, format)
+    }
+}
+
+/// The number of seconds between the Unix epoch and the Cocoa reference date (2001-01-01), which
+/// is what binary property lists measure dates from.
+const REFERENCE_DATE_OFFSET: f64 = 978_307_200.;
+
+/// Converts a date into seconds since the Cocoa reference date.
+fn date_to_reference_timestamp(date: &SystemTime) -> f64 {
+    super::value::date_to_unix_timestamp(date) - REFERENCE_DATE_OFFSET
+}
+
+/// Converts seconds since the Cocoa reference date into a date.
+fn date_from_reference_timestamp(timestamp: f64) -> SystemTime {
+    super::value::date_from_unix_timestamp(timestamp + REFERENCE_DATE_OFFSET)
+}
+
+/// Formats a date as ISO 8601 in UTC (e.g, `2020-09-13T12:26:40Z`), which is what XML property
+/// lists use. Sub-second precision is dropped, as it is by Foundation.
+fn format_iso8601(date: &SystemTime) -> String {
+    let seconds = super::value::date_to_unix_timestamp(date).floor() as i64;
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Parses an ISO 8601 date in the form XML property lists use (`YYYY-MM-DDTHH:MM:SSZ`).
+fn parse_iso8601(input: &str) -> Option<SystemTime> {
+    let bytes = input.as_bytes();
+
+    if bytes.len() != 20 || bytes[4] != b'-' || bytes[7] != b'-' || bytes[10] != b'T' || bytes[19] != b'Z' {
+        return None;
+    }
+
+    if bytes[13] != b':' || bytes[16] != b':' {
+        return None;
+    }
+
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = input.get(range)?;
+
+        match digits.bytes().all(|byte| byte.is_ascii_digit()) {
+            true => digits.parse().ok(),
+            false => None
+        }
+    };
+
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let seconds = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second;
+
+    Some(match seconds >= 0 {
+        true => UNIX_EPOCH + Duration::from_secs(seconds as u64),
+        false => UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    })
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date. See
+/// <http://howardhinnant.github.io/date_algorithms.html>.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard, padded base64 - what `<data>` elements hold.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut output = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let buffer = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (buffer[0] as u32) << 16 | (buffer[1] as u32) << 8 | buffer[2] as u32;
+
+        for index in 0..4 {
+            match index <= chunk.len() {
+                true => output.push(BASE64_ALPHABET[(triple >> (18 - 6 * index) & 0x3f) as usize] as char),
+                false => output.push('=')
+            }
+        }
+    }
+
+    output
+}
+
+/// Decodes standard base64, ignoring whitespace (Foundation wraps long `<data>` lines).
+fn base64_decode(input: &str) -> Option<Vec<u8>> {
+    let mut output = Vec::with_capacity(input.len() / 4 * 3);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    let mut padding = 0;
+
+    for byte in input.bytes().filter(|byte| !byte.is_ascii_whitespace()) {
+        let sextet = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => {
+                padding += 1;
+                continue;
+            },
+            _ => return None
+        };
+
+        // Nothing but padding is allowed after padding.
+        if padding > 0 {
+            return None;
+        }
+
+        buffer = buffer << 6 | sextet as u32;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    match padding <= 2 {
+        true => Some(output),
+        false => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    /// The same preferences in both formats, written by Python's `plistlib` rather than by this
+    /// crate, so the reader is checked against an independent writer. As with Foundation's own
+    /// output, the dictionary keys aren't sorted.
+    const XML_FIXTURE: &[u8] = include_bytes!("../../../test-data/plist/preferences.plist");
+    const BINARY_FIXTURE: &[u8] = include_bytes!("../../../test-data/plist/preferences.bplist");
+
+    fn expected_fixture() -> Value {
+        let mut window = HashMap::new();
+        window.insert("width".to_string(), Value::Float(800.5));
+        window.insert("height".to_string(), Value::Integer(600));
+
+        let mut map = HashMap::new();
+        map.insert("LaunchCount".to_string(), Value::Integer(42));
+        map.insert("NegativeOffset".to_string(), Value::Integer(-17));
+        map.insert("LargeNumber".to_string(), Value::Integer(9_007_199_254_740_993));
+        map.insert("ShowSidebar".to_string(), Value::Bool(true));
+        map.insert("CompactMode".to_string(), Value::Bool(false));
+        map.insert("Username".to_string(), Value::string("Ada & <Friends>"));
+        map.insert("Greeting".to_string(), Value::string("Grüße, 世界 🌍"));
+        map.insert("EmptyString".to_string(), Value::string(""));
+        map.insert("Token".to_string(), Value::Data(vec![0, 1, 2, 253, 254, 255]));
+        map.insert(
+            "LastOpened".to_string(),
+            Value::Date(UNIX_EPOCH + Duration::from_secs(1_600_000_000))
+        );
+        map.insert(
+            "Ancient".to_string(),
+            Value::Date(UNIX_EPOCH - Duration::from_secs(86_400 * 365))
+        );
+        map.insert(
+            "RecentFiles".to_string(),
+            Value::Array(vec![Value::string("a.txt"), Value::string("b.txt")])
+        );
+        map.insert("EmptyArray".to_string(), Value::Array(vec![]));
+        map.insert("Window".to_string(), Value::Dictionary(window));
+
+        Value::Dictionary(map)
+    }
+
+    #[test]
+    fn test_read_fixtures() {
+        assert_eq!(detect_format(XML_FIXTURE), PlistFormat::Xml);
+        assert_eq!(detect_format(BINARY_FIXTURE), PlistFormat::Binary);
+
+        assert_eq!(from_bytes(XML_FIXTURE).unwrap(), expected_fixture());
+        assert_eq!(from_bytes(BINARY_FIXTURE).unwrap(), expected_fixture());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = expected_fixture();
+
+        for format in [PlistFormat::Xml, PlistFormat::Binary] {
+            let bytes = to_bytes(&value, format);
+            assert_eq!(detect_format(&bytes), format);
+            assert_eq!(from_bytes(&bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_xml_output() {
+        let mut map = HashMap::new();
+        map.insert("b".to_string(), Value::Data(vec![1, 2, 3]));
+        map.insert("a".to_string(), Value::Array(vec![Value::Float(1.5), Value::Bool(true)]));
+
+        let xml = String::from_utf8(to_bytes(&Value::Dictionary(map), PlistFormat::Xml)).unwrap();
+
+        assert_eq!(
+            xml,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n\
+             <dict>\n\
+             \t<key>a</key>\n\
+             \t<array>\n\
+             \t\t<real>1.5</real>\n\
+             \t\t<true/>\n\
+             \t</array>\n\
+             \t<key>b</key>\n\
+             \t<data>AQID</data>\n\
+             </dict>\n\
+             </plist>\n"
+        );
+    }
+
+    #[test]
+    fn test_xml_errors() {
+        let error = from_bytes(b"<plist><dict><key>a</key></dict></plist>").unwrap_err();
+        assert_eq!(error, PlistError::InvalidXml {
+            position: 25,
+            message: "Expected a value for key \"a\"".to_string()
+        });
+
+        assert!(from_bytes(b"<plist><integer>12x</integer></plist>").is_err());
+        assert!(from_bytes(b"<plist><date>yesterday</date></plist>").is_err());
+        assert!(from_bytes(b"<plist><string>open</plist>").is_err());
+        assert!(from_bytes(b"<plist><true/><true/></plist>").is_err());
+        assert!(from_bytes(b"").is_err());
+    }
+
+    #[test]
+    fn test_binary_errors() {
+        assert!(from_bytes(b"bplist00").is_err());
+
+        // Truncating a valid file anywhere should fail cleanly rather than panic.
+        for length in 0..BINARY_FIXTURE.len() {
+            assert!(from_bytes(&BINARY_FIXTURE[..length]).is_err());
+        }
+    }
+
+    /// Builds a binary property list of `depth` nested arrays, each holding the next one twice,
+    /// around a `true`.
+    fn shared_arrays(depth: usize) -> Vec<u8> {
+        let mut bytes = b"bplist00".to_vec();
+        let mut offsets = vec![];
+
+        for index in 0..depth {
+            offsets.push(bytes.len() as u8);
+            bytes.extend_from_slice(&[0xa2, index as u8 + 1, index as u8 + 1]);
+        }
+
+        offsets.push(bytes.len() as u8);
+        bytes.push(0x09);
+
+        let table_start = bytes.len() as u64;
+        bytes.extend_from_slice(&offsets);
+        bytes.extend_from_slice(&[0, 0, 0, 0, 0, 0, 1, 1]);
+        bytes.extend_from_slice(&(offsets.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(&0u64.to_be_bytes());
+        bytes.extend_from_slice(&table_start.to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_binary_shared_references() {
+        let leaf = Value::Array(vec![Value::Bool(true), Value::Bool(true)]);
+        assert_eq!(from_bytes(&shared_arrays(2)).unwrap(), Value::Array(vec![leaf.clone(), leaf]));
+
+        // 40 levels would expand to 2^40 objects; this has to fail, rather than hang.
+        match from_bytes(&shared_arrays(40)) {
+            Err(PlistError::InvalidBinary { .. }) => {},
+            other => panic!("Expected an error, got {:?}", other.map(|_| ()))
+        }
+    }
+
+    #[test]
+    fn test_dates_and_base64() {
+        let date = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        assert_eq!(format_iso8601(&date), "2020-09-13T12:26:40Z");
+        assert_eq!(parse_iso8601("2020-09-13T12:26:40Z"), Some(date));
+        assert_eq!(format_iso8601(&UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            parse_iso8601("1969-12-31T23:59:59Z"),
+            Some(UNIX_EPOCH - Duration::from_secs(1))
+        );
+        assert_eq!(parse_iso8601("2020-13-01T00:00:00Z"), None);
+
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            assert_eq!(base64_decode(&base64_encode(input)).unwrap(), input);
+        }
+
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_decode("Zm9v\n\tYg=="), Some(b"foob".to_vec()));
+        assert_eq!(base64_decode("Zm9v!"), None);
+    }
+}
@@ -0,0 +1,427 @@
+//! Reading and writing XML property lists. This is a deliberately small, non-validating XML
+//! parser that understands exactly what property lists need: elements, character data,
+//! entities, `CDATA` sections, comments and the usual prolog.
+
+use std::collections::HashMap;
+use std::str;
+
+use super::{base64_decode, base64_encode, format_iso8601, parse_iso8601, PlistError};
+use crate::defaults::Value;
+
+const HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+    <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+    <plist version=\"1.0\">\n";
+
+/// A tag, as found in the input.
+#[derive(Debug, PartialEq)]
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Empty(&'a str)
+}
+
+/// Reads an XML property list.
+pub(super) fn read(bytes: &[u8]) -> Result<Value, PlistError> {
+    let input = str::from_utf8(bytes).map_err(|error| PlistError::InvalidXml {
+        position: error.valid_up_to(),
+        message: "Input is not valid UTF-8".to_string()
+    })?;
+
+    let mut reader = Reader { input, position: 0 };
+
+    reader.skip_misc()?;
+    let (position, tag) = reader.read_tag()?;
+
+    if tag != Tag::Open("plist") {
+        return Err(reader.error_at(position, "Expected a <plist> element"));
+    }
+
+    let value = reader.read_value()?;
+
+    reader.skip_misc()?;
+    let (position, tag) = reader.read_tag()?;
+
+    if tag != Tag::Close("plist") {
+        return Err(reader.error_at(position, "Expected </plist> - a property list holds exactly one value"));
+    }
+
+    reader.skip_misc()?;
+
+    match reader.position == input.len() {
+        true => Ok(value),
+        false => Err(reader.error("Unexpected content after </plist>"))
+    }
+}
+
+/// A cursor over the input.
+struct Reader<'a> {
+    input: &'a str,
+    position: usize
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn error(&self, message: &str) -> PlistError {
+        self.error_at(self.position, message)
+    }
+
+    fn error_at(&self, position: usize, message: &str) -> PlistError {
+        PlistError::InvalidXml {
+            position,
+            message: message.to_string()
+        }
+    }
+
+    /// Advances past `terminator`, returning everything before it.
+    fn take_until(&mut self, terminator: &str) -> Result<&'a str, PlistError> {
+        match self.rest().find(terminator) {
+            Some(index) => {
+                let taken = &self.rest()[..index];
+                self.position += index + terminator.len();
+                Ok(taken)
+            },
+
+            None => Err(self.error(&format!("Expected \"{}\" before the end of input", terminator)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.position = self.input.len() - trimmed.len();
+    }
+
+    /// Skips whitespace, comments, processing instructions and doctype declarations.
+    fn skip_misc(&mut self) -> Result<(), PlistError> {
+        loop {
+            self.skip_whitespace();
+
+            let rest = self.rest();
+
+            if rest.starts_with("<?") {
+                self.take_until("?>")?;
+            } else if rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.take_until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Reads the tag at the current position, returning where it started.
+    fn read_tag(&mut self) -> Result<(usize, Tag<'a>), PlistError> {
+        let start = self.position;
+
+        if !self.rest().starts_with('<') {
+            return Err(match self.rest().is_empty() {
+                true => self.error("Unexpected end of input"),
+                false => self.error("Expected a tag")
+            });
+        }
+
+        self.position += 1;
+        let closing = self.rest().starts_with('/');
+
+        if closing {
+            self.position += 1;
+        }
+
+        let name_length = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .unwrap_or_else(|| self.rest().len());
+        let name = &self.rest()[..name_length];
+        self.position += name_length;
+
+        if name.is_empty() {
+            return Err(self.error_at(start, "Expected a tag name"));
+        }
+
+        // Skip over any attributes, minding quoted values that might contain a '>'.
+        let mut quote = None;
+        let mut previous = None;
+
+        for (index, c) in self.rest().char_indices() {
+            match (quote, c) {
+                (Some(open), c) if c == open => quote = None,
+                (Some(_), _) => {},
+                (None, '"') | (None, '\'') => quote = Some(c),
+
+                (None, '>') => {
+                    self.position += index + 1;
+                    let empty = previous == Some('/');
+
+                    return match (closing, empty) {
+                        (true, true) => Err(self.error_at(start, "Malformed closing tag")),
+                        (true, false) => Ok((start, Tag::Close(name))),
+                        (false, true) => Ok((start, Tag::Empty(name))),
+                        (false, false) => Ok((start, Tag::Open(name)))
+                    };
+                },
+
+                _ => {}
+            }
+
+            previous = Some(c);
+        }
+
+        Err(self.error_at(start, "Unterminated tag"))
+    }
+
+    /// Reads character data up to (and including) the closing tag for `name`, decoding entities
+    /// and `CDATA` sections and dropping comments.
+    fn read_text(&mut self, name: &str) -> Result<String, PlistError> {
+        let mut text = String::new();
+
+        loop {
+            let rest = self.rest();
+
+            if rest.starts_with("<![CDATA[") {
+                self.position += "<![CDATA[".len();
+                text.push_str(self.take_until("]]>")?);
+            } else if rest.starts_with("<!--") {
+                self.take_until("-->")?;
+            } else if rest.starts_with('<') {
+                let (position, tag) = self.read_tag()?;
+
+                return match tag {
+                    Tag::Close(closing) if closing == name => Ok(text),
+                    _ => Err(self.error_at(position, &format!("Expected </{}>", name)))
+                };
+            } else if rest.starts_with('&') {
+                let start = self.position;
+                self.position += 1;
+                let entity = self.take_until(";")?;
+                text.push(decode_entity(entity).ok_or_else(|| self.error_at(start, "Unknown entity"))?);
+            } else if rest.is_empty() {
+                return Err(self.error(&format!("Expected </{}> before the end of input", name)));
+            } else {
+                let length = rest.find(['<', '&']).unwrap_or(rest.len());
+                text.push_str(&rest[..length]);
+                self.position += length;
+            }
+        }
+    }
+
+    /// Reads the next value, erroring on anything that isn't one.
+    fn read_value(&mut self) -> Result<Value, PlistError> {
+        self.skip_misc()?;
+        let (position, tag) = self.read_tag()?;
+
+        match tag {
+            Tag::Open("dict") => self.read_dictionary(),
+            Tag::Empty("dict") => Ok(Value::Dictionary(HashMap::new())),
+            Tag::Open("array") => self.read_array(),
+            Tag::Empty("array") => Ok(Value::Array(vec![])),
+            Tag::Open("string") => self.read_text("string").map(Value::String),
+            Tag::Empty("string") => Ok(Value::string("")),
+            Tag::Empty("true") => Ok(Value::Bool(true)),
+            Tag::Empty("false") => Ok(Value::Bool(false)),
+
+            Tag::Open("integer") => {
+                let text = self.read_text("integer")?;
+
+                parse_integer(text.trim())
+                    .map(Value::Integer)
+                    .ok_or_else(|| self.error_at(position, &format!("Invalid integer \"{}\"", text)))
+            },
+
+            Tag::Open("real") => {
+                let text = self.read_text("real")?;
+
+                parse_real(text.trim())
+                    .map(Value::Float)
+                    .ok_or_else(|| self.error_at(position, &format!("Invalid real \"{}\"", text)))
+            },
+
+            Tag::Open("date") => {
+                let text = self.read_text("date")?;
+
+                parse_iso8601(text.trim())
+                    .map(Value::Date)
+                    .ok_or_else(|| self.error_at(position, &format!("Invalid date \"{}\"", text)))
+            },
+
+            Tag::Open("data") => {
+                let text = self.read_text("data")?;
+
+                base64_decode(&text)
+                    .map(Value::Data)
+                    .ok_or_else(|| self.error_at(position, "Invalid base64 in <data>"))
+            },
+
+            Tag::Empty("data") => Ok(Value::Data(vec![])),
+
+            Tag::Open(name) | Tag::Empty(name) => Err(self.error_at(position, &format!("Unexpected element <{}>", name))),
+            Tag::Close(name) => Err(self.error_at(position, &format!("Expected a value, found </{}>", name)))
+        }
+    }
+
+    fn read_array(&mut self) -> Result<Value, PlistError> {
+        let mut values = vec![];
+
+        loop {
+            self.skip_misc()?;
+
+            if self.rest().starts_with("</") {
+                let (position, tag) = self.read_tag()?;
+
+                return match tag {
+                    Tag::Close("array") => Ok(Value::Array(values)),
+                    _ => Err(self.error_at(position, "Expected </array>"))
+                };
+            }
+
+            values.push(self.read_value()?);
+        }
+    }
+
+    fn read_dictionary(&mut self) -> Result<Value, PlistError> {
+        let mut map = HashMap::new();
+
+        loop {
+            self.skip_misc()?;
+            let (position, tag) = self.read_tag()?;
+
+            let key = match tag {
+                Tag::Close("dict") => return Ok(Value::Dictionary(map)),
+                Tag::Open("key") => self.read_text("key")?,
+                Tag::Empty("key") => String::new(),
+                _ => return Err(self.error_at(position, "Expected a <key>"))
+            };
+
+            self.skip_misc()?;
+
+            if self.rest().starts_with("</") {
+                return Err(self.error(&format!("Expected a value for key \"{}\"", key)));
+            }
+
+            let value = self.read_value()?;
+            map.insert(key, value);
+        }
+    }
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+
+        _ => {
+            let code = match entity.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?
+            };
+
+            std::char::from_u32(code)
+        }
+    }
+}
+
+/// Integers are decimal, but Foundation also accepts a `0x` prefix.
+fn parse_integer(text: &str) -> Option<i64> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text))
+    };
+
+    if digits.starts_with('+') || digits.starts_with('-') {
+        return None;
+    }
+
+    let magnitude = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i128>().ok()?
+    };
+
+    let value = if negative { -magnitude } else { magnitude };
+
+    match value >= i64::MIN as i128 && value <= i64::MAX as i128 {
+        true => Some(value as i64),
+        false => None
+    }
+}
+
+fn parse_real(text: &str) -> Option<f64> {
+    match text.to_ascii_lowercase().as_str() {
+        "nan" => Some(f64::NAN),
+        "inf" | "+inf" | "infinity" | "+infinity" => Some(f64::INFINITY),
+        "-inf" | "-infinity" => Some(f64::NEG_INFINITY),
+        lowercased => lowercased.parse().ok()
+    }
+}
+
+/// Writes an XML property list in the same layout Foundation uses: tab-indented, with dictionary
+/// keys sorted.
+pub(super) fn write(value: &Value) -> String {
+    let mut output = HEADER.to_string();
+    write_value(&mut output, value, 0);
+    output.push_str("</plist>\n");
+    output
+}
+
+fn write_value(output: &mut String, value: &Value, depth: usize) {
+    let indent = "\t".repeat(depth);
+
+    match value {
+        Value::Bool(true) => output.push_str(&format!("{}<true/>\n", indent)),
+        Value::Bool(false) => output.push_str(&format!("{}<false/>\n", indent)),
+        Value::String(string) => output.push_str(&format!("{}<string>{}</string>\n", indent, escape(string))),
+        Value::Integer(integer) => output.push_str(&format!("{}<integer>{}</integer>\n", indent, integer)),
+        Value::Float(float) => output.push_str(&format!("{}<real>{}</real>\n", indent, format_real(*float))),
+        Value::Data(data) => output.push_str(&format!("{}<data>{}</data>\n", indent, base64_encode(data))),
+        Value::Date(date) => output.push_str(&format!("{}<date>{}</date>\n", indent, format_iso8601(date))),
+
+        Value::Array(values) if values.is_empty() => output.push_str(&format!("{}<array/>\n", indent)),
+
+        Value::Array(values) => {
+            output.push_str(&format!("{}<array>\n", indent));
+
+            for value in values {
+                write_value(output, value, depth + 1);
+            }
+
+            output.push_str(&format!("{}</array>\n", indent));
+        },
+
+        Value::Dictionary(map) if map.is_empty() => output.push_str(&format!("{}<dict/>\n", indent)),
+
+        Value::Dictionary(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+
+            output.push_str(&format!("{}<dict>\n", indent));
+
+            for key in keys {
+                output.push_str(&format!("{}\t<key>{}</key>\n", indent, escape(key)));
+                write_value(output, &map[key], depth + 1);
+            }
+
+            output.push_str(&format!("{}</dict>\n", indent));
+        }
+    }
+}
+
+fn format_real(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else if value.is_infinite() {
+        match value > 0. {
+            true => "+infinity".to_string(),
+            false => "-infinity".to_string()
+        }
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
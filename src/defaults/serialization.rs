@@ -0,0 +1,963 @@
+//! Serde support for `Value`. This maps `Value` onto the serde data model (and back), which is
+//! what powers `UserDefaults::get_typed` and `UserDefaults::insert_typed`.
+//!
+//! The same machinery backs `ObjcValue`'s serde support, which follows these rules too - except
+//! that `ObjcValue` can hold `NSNull`, so nulls are kept wherever they appear rather than skipped.
+//!
+//! A few notes on how things map, since `NSUserDefaults` is a property list store and can't hold
+//! everything serde can describe:
+//!
+//! - There's no `null`. `None` (and `()`) fields in structs and maps are skipped, and are treated
+//!   as missing when reading back. A `None` anywhere else (top-level, or inside a sequence) is an
+//!   error.
+//! - Map keys must be strings (or integers, which are stored as their string form).
+//! - Enums follow the externally tagged representation `serde_json` uses: unit variants are
+//!   stored as a string, and everything else as a single-entry dictionary.
+//! - Serde has no date type. Use the [`date`] module with `#[serde(with = "...")]` on a
+//!   `SystemTime` field to have it stored as a `Value::Date`. Other formats will see the date as
+//!   a floating point Unix timestamp.
+//!
+//! None of this touches the Objective-C runtime.
+
+use std::collections::{BTreeMap, HashMap};
+use std::error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::time::SystemTime;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor
+};
+use serde::ser::{
+    self, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple, SerializeTupleStruct,
+    SerializeTupleVariant
+};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::value::{date_from_unix_timestamp, date_to_unix_timestamp};
+use super::Value;
+
+/// A marker name used to smuggle dates through the serde data model, which has no date type.
+pub(crate) const DATE_TOKEN: &str = "$__cacao_defaults_date";
+
+/// An error that occurred while converting between a `Value` and a Rust type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConversionError {
+    message: String
+}
+
+impl ConversionError {
+    pub(crate) fn new<T: fmt::Display>(message: T) -> Self {
+        ConversionError {
+            message: message.to_string()
+        }
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for ConversionError {}
+
+impl ser::Error for ConversionError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        ConversionError::new(message)
+    }
+}
+
+impl de::Error for ConversionError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        ConversionError::new(message)
+    }
+}
+
+/// Converts any `Serialize` type into a `Value`.
+///
+/// ```rust
+/// use cacao::defaults::{to_value, Value};
+///
+/// let value = to_value(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(value, Value::Array(vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)]));
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, ConversionError> {
+    serialize_into(value)
+}
+
+/// Converts a `Value` into any `Deserialize` type.
+///
+/// ```rust
+/// use cacao::defaults::{from_value, Value};
+///
+/// let numbers: Vec<u8> = from_value(Value::Array(vec![Value::Integer(1), Value::Integer(2)])).unwrap();
+/// assert_eq!(numbers, vec![1, 2]);
+/// ```
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, ConversionError> {
+    T::deserialize(value)
+}
+
+/// Serializes and deserializes a `SystemTime` as a `Value::Date`. Use it on fields with
+/// `#[serde(with = "cacao::defaults::date")]`.
+///
+/// ```rust
+/// use std::time::SystemTime;
+///
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Preferences {
+///     #[serde(with = "cacao::defaults::date")]
+///     last_opened: SystemTime
+/// }
+/// ```
+pub mod date {
+    use std::fmt;
+    use std::time::SystemTime;
+
+    use serde::de::{self, Visitor};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::DATE_TOKEN;
+    use crate::defaults::value::{date_from_unix_timestamp, date_to_unix_timestamp};
+
+    /// Serializes a `SystemTime` as a date.
+    pub fn serialize<S: Serializer>(date: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct(DATE_TOKEN, &date_to_unix_timestamp(date))
+    }
+
+    /// Deserializes a `SystemTime` from a date (or a Unix timestamp).
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        deserializer.deserialize_newtype_struct(DATE_TOKEN, DateVisitor)
+    }
+
+    struct DateVisitor;
+
+    impl<'de> Visitor<'de> for DateVisitor {
+        type Value = SystemTime;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a date")
+        }
+
+        fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<SystemTime, D::Error> {
+            f64::deserialize(deserializer).map(date_from_unix_timestamp)
+        }
+
+        fn visit_f64<E: de::Error>(self, timestamp: f64) -> Result<SystemTime, E> {
+            Ok(date_from_unix_timestamp(timestamp))
+        }
+
+        fn visit_i64<E: de::Error>(self, timestamp: i64) -> Result<SystemTime, E> {
+            Ok(date_from_unix_timestamp(timestamp as f64))
+        }
+
+        fn visit_u64<E: de::Error>(self, timestamp: u64) -> Result<SystemTime, E> {
+            Ok(date_from_unix_timestamp(timestamp as f64))
+        }
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Float(f) => serializer.serialize_f64(*f),
+            Value::Integer(i) => serializer.serialize_i64(*i),
+            Value::Data(data) => serializer.serialize_bytes(data),
+            Value::Array(values) => values.serialize(serializer),
+
+            // Sorted, so that output is stable across runs.
+            Value::Dictionary(map) => map.iter().collect::<BTreeMap<_, _>>().serialize(serializer),
+
+            Value::Date(date) => date::serialize(date, serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value that can be stored in UserDefaults")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Integer(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+        match value <= i64::MAX as u64 {
+            true => Ok(Value::Integer(value as i64)),
+            false => Err(E::custom(format!("{} is too large to be stored as an Integer", value)))
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::Float(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Value, E> {
+        Ok(Value::Data(value.to_vec()))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Value, E> {
+        Ok(Value::Data(value))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    /// Only our own deserializer hands us a newtype, and only for dates.
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        f64::deserialize(deserializer).map(|timestamp| Value::Date(date_from_unix_timestamp(timestamp)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let mut map = HashMap::with_capacity(access.size_hint().unwrap_or(0));
+
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value);
+        }
+
+        Ok(Value::Dictionary(map))
+    }
+}
+
+/// What a Rust value can be serialized into - a `Value`, or an `ObjcValue`.
+pub(crate) trait SerializedValue: Sized {
+    /// What a null (`None`, or `()`) becomes, if this can hold one. If it can't, nulls in
+    /// dictionaries are skipped, and nulls anywhere else are an error.
+    fn null() -> Option<Self>;
+
+    fn bool(value: bool) -> Self;
+
+    fn integer(value: i64) -> Self;
+
+    fn unsigned_integer(value: u64) -> Result<Self, ConversionError>;
+
+    fn float(value: f64) -> Self;
+
+    fn string(value: String) -> Self;
+
+    fn data(value: Vec<u8>) -> Self;
+
+    fn date(value: SystemTime) -> Self;
+
+    fn array(values: Vec<Self>) -> Self;
+
+    fn dictionary(map: HashMap<String, Self>) -> Self;
+
+    /// Returns the number this holds, if it's a float - which is how dates are serialized.
+    fn timestamp(&self) -> Option<f64>;
+
+    /// Returns the dictionary key this can be used as, if any.
+    fn into_key(self) -> Option<String>;
+}
+
+impl SerializedValue for Value {
+    fn null() -> Option<Self> {
+        None
+    }
+
+    fn bool(value: bool) -> Self {
+        Value::Bool(value)
+    }
+
+    fn integer(value: i64) -> Self {
+        Value::Integer(value)
+    }
+
+    fn unsigned_integer(value: u64) -> Result<Self, ConversionError> {
+        ValueVisitor.visit_u64(value)
+    }
+
+    fn float(value: f64) -> Self {
+        Value::Float(value)
+    }
+
+    fn string(value: String) -> Self {
+        Value::String(value)
+    }
+
+    fn data(value: Vec<u8>) -> Self {
+        Value::Data(value)
+    }
+
+    fn date(value: SystemTime) -> Self {
+        Value::Date(value)
+    }
+
+    fn array(values: Vec<Self>) -> Self {
+        Value::Array(values)
+    }
+
+    fn dictionary(map: HashMap<String, Self>) -> Self {
+        Value::Dictionary(map)
+    }
+
+    fn timestamp(&self) -> Option<f64> {
+        match self {
+            Value::Float(timestamp) => Some(*timestamp),
+            _ => None
+        }
+    }
+
+    fn into_key(self) -> Option<String> {
+        match self {
+            Value::String(key) => Some(key),
+            Value::Integer(key) => Some(key.to_string()),
+            _ => None
+        }
+    }
+}
+
+/// Serializes `value` into a `T`, failing if it's a null that `T` can't hold.
+pub(crate) fn serialize_into<T: SerializedValue, S: Serialize + ?Sized>(value: &S) -> Result<T, ConversionError> {
+    value
+        .serialize(ValueSerializer(PhantomData))?
+        .ok_or_else(|| ConversionError::new("A null value cannot be stored as a Value"))
+}
+
+/// Serializes into an `Option<T>`, where `None` represents a null that `T` can't hold, and that
+/// the caller has to decide what to do with.
+struct ValueSerializer<T>(PhantomData<T>);
+
+impl<T: SerializedValue> Serializer for ValueSerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    type SerializeSeq = ArraySerializer<T>;
+    type SerializeTuple = ArraySerializer<T>;
+    type SerializeTupleStruct = ArraySerializer<T>;
+    type SerializeTupleVariant = ArraySerializer<T>;
+    type SerializeMap = DictionarySerializer<T>;
+    type SerializeStruct = DictionarySerializer<T>;
+    type SerializeStructVariant = DictionarySerializer<T>;
+
+    fn serialize_bool(self, value: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(T::bool(value)))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(T::integer(value)))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_i64(value as i64)
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Self::Ok, Self::Error> {
+        T::unsigned_integer(value).map(Some)
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Self::Ok, Self::Error> {
+        self.serialize_f64(value as f64)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(T::float(value)))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(T::string(value.to_string())))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(T::string(value.to_string())))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(T::data(value.to_vec())))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(T::null())
+    }
+
+    fn serialize_some<S: Serialize + ?Sized>(self, value: &S) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(T::null())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(T::null())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<S: Serialize + ?Sized>(self, name: &'static str, value: &S) -> Result<Self::Ok, Self::Error> {
+        if name != DATE_TOKEN {
+            return value.serialize(self);
+        }
+
+        match value.serialize(self)?.as_ref().and_then(T::timestamp) {
+            Some(timestamp) => Ok(Some(T::date(date_from_unix_timestamp(timestamp)))),
+            None => Err(ConversionError::new("Dates must be serialized as a timestamp"))
+        }
+    }
+
+    fn serialize_newtype_variant<S: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &S
+    ) -> Result<Self::Ok, Self::Error> {
+        let mut map = HashMap::new();
+        map.insert(variant.to_string(), serialize_into(value)?);
+        Ok(Some(T::dictionary(map)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(ArraySerializer {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(ArraySerializer {
+            values: Vec::with_capacity(len),
+            variant: Some(variant)
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(DictionarySerializer {
+            map: HashMap::with_capacity(len.unwrap_or(0)),
+            next_key: None,
+            variant: None
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(DictionarySerializer {
+            map: HashMap::with_capacity(len),
+            next_key: None,
+            variant: Some(variant)
+        })
+    }
+}
+
+/// Wraps a serialized enum variant's contents in a single-entry dictionary, if need be.
+fn wrap_variant<T: SerializedValue>(variant: Option<&'static str>, value: T) -> Option<T> {
+    Some(match variant {
+        Some(variant) => {
+            let mut map = HashMap::new();
+            map.insert(variant.to_string(), value);
+            T::dictionary(map)
+        },
+
+        None => value
+    })
+}
+
+struct ArraySerializer<T> {
+    values: Vec<T>,
+    variant: Option<&'static str>
+}
+
+impl<T: SerializedValue> SerializeSeq for ArraySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_element<S: Serialize + ?Sized>(&mut self, value: &S) -> Result<(), Self::Error> {
+        self.values.push(serialize_into(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(wrap_variant(self.variant, T::array(self.values)))
+    }
+}
+
+impl<T: SerializedValue> SerializeTuple for ArraySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_element<S: Serialize + ?Sized>(&mut self, value: &S) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<T: SerializedValue> SerializeTupleStruct for ArraySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_field<S: Serialize + ?Sized>(&mut self, value: &S) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+impl<T: SerializedValue> SerializeTupleVariant for ArraySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_field<S: Serialize + ?Sized>(&mut self, value: &S) -> Result<(), Self::Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+struct DictionarySerializer<T> {
+    map: HashMap<String, T>,
+    next_key: Option<String>,
+    variant: Option<&'static str>
+}
+
+impl<T: SerializedValue> DictionarySerializer<T> {
+    fn insert<S: Serialize + ?Sized>(&mut self, key: String, value: &S) -> Result<(), ConversionError> {
+        // Nulls that can't be stored are skipped - see the module docs.
+        if let Some(value) = value.serialize(ValueSerializer(PhantomData))? {
+            self.map.insert(key, value);
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: SerializedValue> SerializeMap for DictionarySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_key<S: Serialize + ?Sized>(&mut self, key: &S) -> Result<(), Self::Error> {
+        let key: T = serialize_into(key)?;
+        self.next_key = Some(
+            key.into_key()
+                .ok_or_else(|| ConversionError::new("Dictionary keys must be strings"))?
+        );
+
+        Ok(())
+    }
+
+    fn serialize_value<S: Serialize + ?Sized>(&mut self, value: &S) -> Result<(), Self::Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ConversionError::new("serialize_value called before serialize_key"))?;
+
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(wrap_variant(self.variant, T::dictionary(self.map)))
+    }
+}
+
+impl<T: SerializedValue> SerializeStruct for DictionarySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_field<S: Serialize + ?Sized>(&mut self, key: &'static str, value: &S) -> Result<(), Self::Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeMap::end(self)
+    }
+}
+
+impl<T: SerializedValue> SerializeStructVariant for DictionarySerializer<T> {
+    type Ok = Option<T>;
+    type Error = ConversionError;
+
+    fn serialize_field<S: Serialize + ?Sized>(&mut self, key: &'static str, value: &S) -> Result<(), Self::Error> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeMap::end(self)
+    }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = ConversionError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Float(f) => visitor.visit_f64(f),
+            Value::Integer(i) => visitor.visit_i64(i),
+            Value::Data(data) => visitor.visit_byte_buf(data),
+
+            Value::Array(values) => {
+                let mut deserializer = de::value::SeqDeserializer::new(values.into_iter());
+                let value = visitor.visit_seq(&mut deserializer)?;
+                deserializer.end()?;
+                Ok(value)
+            },
+
+            Value::Dictionary(map) => {
+                let mut deserializer = de::value::MapDeserializer::new(map.into_iter());
+                let value = visitor.visit_map(&mut deserializer)?;
+                deserializer.end()?;
+                Ok(value)
+            },
+
+            Value::Date(date) => visitor.visit_newtype_struct(Value::Float(date_to_unix_timestamp(&date)))
+        }
+    }
+
+    /// There are no nulls in a `Value`, so anything we're asked to deserialize as an `Option` is
+    /// present. Missing struct fields are handled by serde itself.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        match (name == DATE_TOKEN, self) {
+            (true, Value::Date(date)) => visitor.visit_newtype_struct(Value::Float(date_to_unix_timestamp(&date))),
+            (true, value) => value.deserialize_any(visitor),
+            (false, value) => visitor.visit_newtype_struct(value)
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Self::Error> {
+        let (variant, value) = match self {
+            Value::String(variant) => (variant, None),
+
+            Value::Dictionary(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("checked length above");
+                (variant, Some(value))
+            },
+
+            _ => {
+                return Err(ConversionError::new(
+                    "Expected a string or a single-entry dictionary for an enum"
+                ))
+            },
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf seq tuple tuple_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ConversionError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// What an enum variant's contents can be deserialized from - a `Value` or an `ObjcValue`.
+pub(crate) trait VariantValue<'de>: Deserializer<'de, Error = ConversionError> {
+    /// Wraps a variant name.
+    fn variant(name: String) -> Self;
+
+    fn is_array(&self) -> bool;
+
+    fn is_dictionary(&self) -> bool;
+}
+
+impl<'de> VariantValue<'de> for Value {
+    fn variant(name: String) -> Self {
+        Value::String(name)
+    }
+
+    fn is_array(&self) -> bool {
+        matches!(self, Value::Array(_))
+    }
+
+    fn is_dictionary(&self) -> bool {
+        matches!(self, Value::Dictionary(_))
+    }
+}
+
+pub(crate) struct EnumDeserializer<T> {
+    pub(crate) variant: String,
+    pub(crate) value: Option<T>
+}
+
+impl<'de, T: VariantValue<'de>> EnumAccess<'de> for EnumDeserializer<T> {
+    type Error = ConversionError;
+    type Variant = VariantDeserializer<T>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error> {
+        let variant = seed.deserialize(T::variant(self.variant))?;
+        Ok((variant, VariantDeserializer(self.value)))
+    }
+}
+
+pub(crate) struct VariantDeserializer<T>(Option<T>);
+
+impl<'de, T: VariantValue<'de>> VariantAccess<'de> for VariantDeserializer<T> {
+    type Error = ConversionError;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.0 {
+            None => Ok(()),
+            Some(_) => Err(ConversionError::new("Expected a unit variant"))
+        }
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, Self::Error> {
+        match self.0 {
+            Some(value) => seed.deserialize(value),
+            None => Err(ConversionError::new("Expected a newtype variant"))
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Some(value) if value.is_array() => value.deserialize_any(visitor),
+            _ => Err(ConversionError::new("Expected a tuple variant"))
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Some(value) if value.is_dictionary() => value.deserialize_any(visitor),
+            _ => Err(ConversionError::new("Expected a struct variant"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Serialize};
+
+    use super::{from_value, to_value};
+    use crate::defaults::Value;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    enum Theme {
+        Light,
+        Dark,
+        Custom(String),
+        Accent { red: u8, green: u8, blue: u8 }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Preferences {
+        launch_count: u32,
+        zoom: f64,
+        show_sidebar: bool,
+        recent_files: Vec<String>,
+        window_sizes: HashMap<String, (f64, f64)>,
+        theme: Theme,
+        nickname: Option<String>,
+
+        #[serde(with = "crate::defaults::date")]
+        last_opened: SystemTime,
+
+        #[serde(with = "serde_bytes_compat")]
+        token: Vec<u8>
+    }
+
+    /// Serde serializes `Vec<u8>` as a sequence by default; this stores it as `Data` instead.
+    mod serde_bytes_compat {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(bytes)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+            crate::defaults::Value::deserialize(deserializer).map(|value| value.as_data().unwrap_or_default().to_vec())
+        }
+    }
+
+    fn preferences() -> Preferences {
+        Preferences {
+            launch_count: 3,
+            zoom: 1.25,
+            show_sidebar: true,
+            recent_files: vec!["a.txt".to_string(), "b.txt".to_string()],
+            window_sizes: {
+                let mut map = HashMap::new();
+                map.insert("main".to_string(), (800., 600.));
+                map
+            },
+            theme: Theme::Accent {
+                red: 1,
+                green: 2,
+                blue: 3
+            },
+            nickname: None,
+            last_opened: UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+            token: vec![0xde, 0xad]
+        }
+    }
+
+    #[test]
+    fn test_struct_round_trip() {
+        let preferences = preferences();
+        let value = to_value(&preferences).unwrap();
+        let map = value.as_dictionary().unwrap();
+
+        assert_eq!(map.get("launch_count"), Some(&Value::Integer(3)));
+        assert_eq!(map.get("show_sidebar"), Some(&Value::Bool(true)));
+        assert_eq!(map.get("token"), Some(&Value::Data(vec![0xde, 0xad])));
+        assert_eq!(map.get("last_opened").and_then(Value::as_date), Some(preferences.last_opened));
+        assert!(!map.contains_key("nickname"));
+
+        assert_eq!(from_value::<Preferences>(value).unwrap(), preferences);
+    }
+
+    #[test]
+    fn test_enums() {
+        assert_eq!(to_value(&Theme::Dark).unwrap(), Value::string("Dark"));
+
+        let custom = to_value(&Theme::Custom("Solarized".to_string())).unwrap();
+        assert_eq!(
+            custom.as_dictionary().unwrap().get("Custom"),
+            Some(&Value::string("Solarized"))
+        );
+
+        for theme in [Theme::Light, Theme::Custom("x".to_string()), preferences().theme] {
+            assert_eq!(from_value::<Theme>(to_value(&theme).unwrap()).unwrap(), theme);
+        }
+
+        assert!(from_value::<Theme>(Value::string("Sepia")).is_err());
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let date = UNIX_EPOCH - Duration::from_millis(1500);
+        let value = Value::Array(vec![
+            Value::Bool(false),
+            Value::Integer(-7),
+            Value::Float(0.5),
+            Value::Data(vec![1, 2, 3]),
+            Value::Date(date),
+            Value::Dictionary(HashMap::new()),
+        ]);
+
+        assert_eq!(to_value(&value).unwrap(), value);
+        assert_eq!(from_value::<Value>(value.clone()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(to_value(&None::<u8>).is_err());
+        assert!(to_value(&vec![Some(1), None]).is_err());
+        assert!(to_value(&u64::MAX).is_err());
+        assert!(from_value::<u8>(Value::Integer(300)).is_err());
+        assert!(from_value::<String>(Value::Integer(1)).is_err());
+
+        let mut map = HashMap::new();
+        map.insert(vec![1], 1);
+        assert!(to_value(&map).is_err());
+    }
+}
@@ -0,0 +1,251 @@
+//! The storage layer behind `UserDefaults`. By default this is `NSUserDefaults`, but anything
+//! implementing `DefaultsStore` can be plugged in - notably `MemoryStore`, which is useful for
+//! tests and for platforms without Foundation.
+
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::fmt;
+use std::sync::Arc;
+
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::{Id, ShareId};
+
+use crate::foundation::{id, load_or_register_class, nil, to_bool, NSMutableDictionary, NSString, NSUInteger, BOOL};
+use crate::utils::load;
+
+use super::{ChangeHandler, DefaultsObserver, Scheduler, Value};
+
+extern "C" {
+    static NSKeyValueChangeOldKey: id;
+    static NSKeyValueChangeNewKey: id;
+}
+
+/// `NSKeyValueObservingOptionNew | NSKeyValueObservingOptionOld`.
+const OBSERVE_NEW_AND_OLD: NSUInteger = 0x01 | 0x02;
+
+static CHANGE_HANDLER_PTR: &str = "rstDefaultsChangeHandlerPtr";
+
+/// A backing store for `UserDefaults`. Implementations are expected to mirror the semantics of
+/// `NSUserDefaults`: registered values act as fallbacks, and forced (managed) values take
+/// precedence over anything the app has stored.
+pub trait DefaultsStore: fmt::Debug {
+    /// Registers fallback values, returned for keys that have no stored value.
+    fn register(&mut self, values: HashMap<String, Value>);
+
+    /// Stores a value for the given key.
+    fn insert(&mut self, key: &str, value: Value);
+
+    /// Removes the stored value for the given key. Registered values are unaffected.
+    fn remove(&mut self, key: &str);
+
+    /// Returns the effective value for the given key, if any.
+    fn get(&self, key: &str) -> Option<Value>;
+
+    /// Returns whether the value for the given key is managed by an administrator.
+    fn is_forced_for_key(&self, key: &str) -> bool;
+
+    /// Returns every value stored in this store's domain. Registered and forced values are not
+    /// included.
+    fn persistent_domain(&self) -> HashMap<String, Value>;
+
+    /// Starts observing `key`, calling `on_change` with the old and new value each time the stored
+    /// value changes - including changes made through other handles to the same domain (and,
+    /// where the store supports it, outside of the process entirely). Observation stops when the
+    /// returned `DefaultsObserver` is dropped.
+    fn observe(&self, key: &str, on_change: ChangeHandler) -> DefaultsObserver;
+
+    /// Returns the `Scheduler` used to deliver coalesced changes to observers.
+    fn scheduler(&self) -> Scheduler;
+
+    /// Blocks until any pending writes have been persisted. Stores that write synchronously can
+    /// leave this as a no-op.
+    fn synchronize(&self) {}
+
+    /// Returns the underlying `NSUserDefaults` instance, if this store wraps one.
+    fn objc(&self) -> Option<&Object> {
+        None
+    }
+}
+
+/// A `DefaultsStore` backed by `NSUserDefaults`.
+#[derive(Debug)]
+pub struct FoundationStore {
+    /// The underlying `NSUserDefaults` instance.
+    pub objc: Id<Object>,
+
+    /// The suite name, if this isn't the standard store.
+    suite_name: Option<String>
+}
+
+impl FoundationStore {
+    /// Wraps `[NSUserDefaults standardUserDefaults]`.
+    pub fn standard() -> Self {
+        FoundationStore {
+            objc: unsafe { Id::from_ptr(msg_send![class!(NSUserDefaults), standardUserDefaults]) },
+            suite_name: None
+        }
+    }
+
+    /// Wraps an `NSUserDefaults` instance for the given suite name.
+    pub fn suite(named: &str) -> Self {
+        let name = NSString::new(named);
+
+        FoundationStore {
+            objc: unsafe {
+                let alloc: id = msg_send![class!(NSUserDefaults), alloc];
+                Id::from_ptr(msg_send![alloc, initWithSuiteName:&*name])
+            },
+            suite_name: Some(named.to_string())
+        }
+    }
+
+    /// Returns the name of the persistent domain backing this store. For the standard store
+    /// that's the bundle identifier - or, for unbundled executables, the process name.
+    fn domain_name(&self) -> NSString<'static> {
+        if let Some(name) = &self.suite_name {
+            return NSString::new(name);
+        }
+
+        unsafe {
+            let bundle: id = msg_send![class!(NSBundle), mainBundle];
+            let identifier: id = msg_send![bundle, bundleIdentifier];
+
+            match identifier.is_null() {
+                false => NSString::retain(identifier),
+
+                true => {
+                    let info: id = msg_send![class!(NSProcessInfo), processInfo];
+                    NSString::retain(msg_send![info, processName])
+                }
+            }
+        }
+    }
+}
+
+impl DefaultsStore for FoundationStore {
+    fn register(&mut self, values: HashMap<String, Value>) {
+        let dictionary = NSMutableDictionary::from(values);
+
+        unsafe {
+            let _: () = msg_send![&*self.objc, registerDefaults:&*dictionary];
+        }
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        let key = NSString::new(key);
+        let value: id = value.into();
+
+        unsafe {
+            let _: () = msg_send![&*self.objc, setObject:value forKey:key];
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        let key = NSString::new(key);
+
+        unsafe {
+            let _: () = msg_send![&*self.objc, removeObjectForKey:&*key];
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<Value> {
+        let key = NSString::new(key);
+        let result: id = unsafe { msg_send![&*self.objc, objectForKey:&*key] };
+
+        Value::from_object(result)
+    }
+
+    fn is_forced_for_key(&self, key: &str) -> bool {
+        let result: BOOL = unsafe {
+            let key = NSString::new(key);
+            msg_send![&*self.objc, objectIsForcedForKey:&*key]
+        };
+
+        to_bool(result)
+    }
+
+    fn persistent_domain(&self) -> HashMap<String, Value> {
+        let name = self.domain_name();
+        let domain: id = unsafe { msg_send![&*self.objc, persistentDomainForName:&*name] };
+
+        match Value::from_object(domain) {
+            Some(Value::Dictionary(map)) => map,
+            _ => HashMap::new()
+        }
+    }
+
+    /// Observes the key via Key-Value Observing, which `NSUserDefaults` supports for changes made
+    /// anywhere - this process, other processes, or `defaults write`. Note that KVO treats the
+    /// key as a key path, so keys containing a `.` can't be observed.
+    fn observe(&self, key: &str, on_change: ChangeHandler) -> DefaultsObserver {
+        let handler = Box::into_raw(Box::new(on_change));
+        let key = NSString::new(key);
+
+        let (defaults, observer) = unsafe {
+            let defaults: ShareId<Object> = ShareId::from_ptr(&*self.objc as *const Object as id);
+
+            let observer: id = msg_send![register_observer_class(), new];
+            (&mut *observer).set_ivar(CHANGE_HANDLER_PTR, handler as usize);
+            let observer: Id<Object> = Id::from_retained_ptr(observer);
+
+            let _: () = msg_send![&*defaults, addObserver:&*observer
+                forKeyPath:&*key
+                options:OBSERVE_NEW_AND_OLD
+                context:nil];
+
+            (defaults, observer)
+        };
+
+        DefaultsObserver::new(move || unsafe {
+            let _: () = msg_send![&*defaults, removeObserver:&*observer forKeyPath:&*key];
+            drop(observer);
+            drop(Box::from_raw(handler));
+        })
+    }
+
+    /// KVO notifications arrive on whichever thread made the change; they're delivered to
+    /// observers on the main thread.
+    fn scheduler(&self) -> Scheduler {
+        Arc::new(|task| {
+            dispatch::Queue::main().exec_async(task);
+        })
+    }
+
+    fn synchronize(&self) {
+        unsafe {
+            let _: () = msg_send![&*self.objc, synchronize];
+        }
+    }
+
+    fn objc(&self) -> Option<&Object> {
+        Some(&self.objc)
+    }
+}
+
+/// Called by KVO whenever an observed key changes. Missing values come through as `NSNull`,
+/// which `Value::from_object` maps to `None`.
+extern "C" fn observe_value(this: &Object, _: Sel, _key_path: id, _object: id, change: id, _context: *mut c_void) {
+    let handler = load::<ChangeHandler>(this, CHANGE_HANDLER_PTR);
+
+    let (old, new) = unsafe {
+        let old: id = msg_send![change, objectForKey: NSKeyValueChangeOldKey];
+        let new: id = msg_send![change, objectForKey: NSKeyValueChangeNewKey];
+        (Value::from_object(old), Value::from_object(new))
+    };
+
+    if old != new {
+        handler(old, new);
+    }
+}
+
+/// Registers an `NSObject` subclass that forwards KVO notifications to a `ChangeHandler`.
+fn register_observer_class() -> *const Class {
+    load_or_register_class("NSObject", "RSTDefaultsObserver", |decl| unsafe {
+        decl.add_ivar::<usize>(CHANGE_HANDLER_PTR);
+        decl.add_method(
+            sel!(observeValueForKeyPath:ofObject:change:context:),
+            observe_value as extern "C" fn(&Object, _, id, id, id, *mut c_void)
+        );
+    })
+}
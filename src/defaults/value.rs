@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::time::SystemTime;
 
-use crate::foundation::{id, NSData, NSMutableDictionary, NSNumber, NSString};
+use crate::foundation::{id, NSMutableDictionary, NSString, ObjcValue};
+
+pub(crate) use crate::foundation::{date_from_unix_timestamp, date_to_unix_timestamp};
 
 /// Represents a Value that can be stored or queried with `UserDefaults`.
 ///
@@ -23,7 +26,17 @@ pub enum Value {
 
     /// Represents Data (bytes). You can use this to store arbitrary things that aren't supported
     /// above. You're responsible for moving things back and forth to the necessary types.
-    Data(Vec<u8>)
+    Data(Vec<u8>),
+
+    /// Represents an Array of `Value`s. This is stored internally as an `NSArray`.
+    Array(Vec<Value>),
+
+    /// Represents a Dictionary of `Value`s, keyed by `String`. This is stored internally as an
+    /// `NSDictionary`.
+    Dictionary(HashMap<String, Value>),
+
+    /// Represents a Date. This is stored internally as an `NSDate`.
+    Date(SystemTime)
 }
 
 impl Value {
@@ -128,23 +141,109 @@ impl Value {
             _ => None
         }
     }
+
+    /// Returns `true` if the value is an array. Returns `false` otherwise.
+    pub fn is_array(&self) -> bool {
+        match self {
+            Value::Array(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is an array, returns it (`&[Value]`). Returns `None` otherwise.
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None
+        }
+    }
+
+    /// Returns `true` if the value is a dictionary. Returns `false` otherwise.
+    pub fn is_dictionary(&self) -> bool {
+        match self {
+            Value::Dictionary(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is a dictionary, returns it (`&HashMap<String, Value>`). Returns `None` otherwise.
+    pub fn as_dictionary(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Dictionary(map) => Some(map),
+            _ => None
+        }
+    }
+
+    /// Returns `true` if the value is a date. Returns `false` otherwise.
+    pub fn is_date(&self) -> bool {
+        match self {
+            Value::Date(_) => true,
+            _ => false
+        }
+    }
+
+    /// If this is a date, returns it (`SystemTime`). Returns `None` otherwise.
+    pub fn as_date(&self) -> Option<SystemTime> {
+        match self {
+            Value::Date(date) => Some(*date),
+            _ => None
+        }
+    }
+
+    /// Converts an `ObjcValue` into a `Value`. `NSNull` has no equivalent, so it (and any array or
+    /// dictionary entry holding it) is dropped; unsigned integers that don't fit in an `i64` become
+    /// floats, and `float`s widen to `f64`.
+    pub fn from_objc(value: ObjcValue) -> Option<Value> {
+        Some(match value {
+            ObjcValue::Null => return None,
+            ObjcValue::Bool(b) => Value::Bool(b),
+            ObjcValue::Integer(i) => Value::Integer(i),
+            ObjcValue::UnsignedInteger(u) if u <= i64::MAX as u64 => Value::Integer(u as i64),
+            ObjcValue::UnsignedInteger(u) => Value::Float(u as f64),
+            ObjcValue::Float(f) => Value::Float(f as f64),
+            ObjcValue::Double(d) => Value::Float(d),
+            ObjcValue::String(s) => Value::String(s),
+            ObjcValue::Data(data) => Value::Data(data),
+            ObjcValue::Date(date) => Value::Date(date),
+            ObjcValue::Array(values) => Value::Array(values.into_iter().filter_map(Value::from_objc).collect()),
+
+            ObjcValue::Dictionary(map) => Value::Dictionary(
+                map.into_iter()
+                    .filter_map(|(key, value)| Some((key, Value::from_objc(value)?)))
+                    .collect()
+            )
+        })
+    }
+
+    /// Attempts to convert an `NSObject` into a `Value`, recursing into arrays and dictionaries.
+    /// Returns `None` for `nil` and for any type we don't support.
+    pub(crate) fn from_object(object: id) -> Option<Value> {
+        ObjcValue::from_object(object).and_then(Value::from_objc)
+    }
 }
 
-impl From<Value> for id {
-    /// Shepherds `Value` types into `NSObject`s that can be stored in `NSUserDefaults`.
-    // These currently work, but may not be exhaustive and should be looked over past the preview
-    // period.
+impl From<Value> for ObjcValue {
     fn from(value: Value) -> Self {
         match value {
-            Value::Bool(b) => NSNumber::bool(b).into(),
-            Value::String(s) => NSString::new(&s).into(),
-            Value::Float(f) => NSNumber::float(f).into(),
-            Value::Integer(i) => NSNumber::integer(i).into(),
-            Value::Data(data) => NSData::new(data).into()
+            Value::Bool(b) => ObjcValue::Bool(b),
+            Value::String(s) => ObjcValue::String(s),
+            Value::Float(f) => ObjcValue::Double(f),
+            Value::Integer(i) => ObjcValue::Integer(i),
+            Value::Data(data) => ObjcValue::Data(data),
+            Value::Array(values) => ObjcValue::Array(values.into_iter().map(ObjcValue::from).collect()),
+            Value::Dictionary(map) => ObjcValue::Dictionary(map.into_iter().map(|(key, value)| (key, value.into())).collect()),
+            Value::Date(date) => ObjcValue::Date(date)
         }
     }
 }
 
+impl From<Value> for id {
+    /// Shepherds `Value` types into `NSObject`s that can be stored in `NSUserDefaults`.
+    fn from(value: Value) -> Self {
+        ObjcValue::from(value).into()
+    }
+}
+
 impl<K> From<HashMap<K, Value>> for NSMutableDictionary
 where
     K: AsRef<str>
@@ -161,3 +260,39 @@ where
         dictionary
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::Value;
+    use crate::foundation::ObjcValue;
+
+    #[test]
+    fn test_from_objc() {
+        let mut map = HashMap::new();
+        map.insert("null".to_string(), ObjcValue::Null);
+        map.insert("small".to_string(), ObjcValue::UnsignedInteger(7));
+        map.insert("large".to_string(), ObjcValue::UnsignedInteger(u64::MAX));
+        map.insert("float".to_string(), ObjcValue::Float(0.5));
+        map.insert(
+            "array".to_string(),
+            ObjcValue::Array(vec![ObjcValue::Null, ObjcValue::Bool(true)])
+        );
+
+        let mut expected = HashMap::new();
+        expected.insert("small".to_string(), Value::Integer(7));
+        expected.insert("large".to_string(), Value::Float(u64::MAX as f64));
+        expected.insert("float".to_string(), Value::Float(0.5));
+        expected.insert("array".to_string(), Value::Array(vec![Value::Bool(true)]));
+
+        assert_eq!(
+            Value::from_objc(ObjcValue::Dictionary(map)),
+            Some(Value::Dictionary(expected.clone()))
+        );
+        assert_eq!(Value::from_objc(ObjcValue::Null), None);
+
+        let round_trip = Value::from_objc(ObjcValue::from(Value::Dictionary(expected.clone())));
+        assert_eq!(round_trip, Some(Value::Dictionary(expected)));
+    }
+}
@@ -0,0 +1,257 @@
+//! Known `NSError` domains, and the codes within them that are worth matching on.
+
+use crate::foundation::NSInteger;
+
+/// `NSCocoaErrorDomain`.
+pub const COCOA_ERROR_DOMAIN: &str = "NSCocoaErrorDomain";
+
+/// `NSPOSIXErrorDomain`. Codes in this domain are `errno` values.
+pub const POSIX_ERROR_DOMAIN: &str = "NSPOSIXErrorDomain";
+
+/// `NSOSStatusErrorDomain`. Codes in this domain are Carbon-era `OSStatus` values.
+pub const OSSTATUS_ERROR_DOMAIN: &str = "NSOSStatusErrorDomain";
+
+/// `NSURLErrorDomain`.
+pub const URL_ERROR_DOMAIN: &str = "NSURLErrorDomain";
+
+/// Generates a code enum with a catch-all `Other` variant, and conversions to and from
+/// `NSInteger`.
+macro_rules! error_codes {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident = $value:expr
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant,
+            )*
+
+            /// A code that isn't covered above.
+            Other(NSInteger)
+        }
+
+        impl From<NSInteger> for $name {
+            fn from(code: NSInteger) -> Self {
+                match code {
+                    $($value => $name::$variant,)*
+                    code => $name::Other(code)
+                }
+            }
+        }
+
+        impl From<$name> for NSInteger {
+            fn from(code: $name) -> Self {
+                match code {
+                    $($name::$variant => $value,)*
+                    $name::Other(code) => code
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// Codes in `NSCocoaErrorDomain`. These are mostly file system errors, which usually carry an
+    /// underlying `NSPOSIXErrorDomain` error with the actual `errno`.
+    pub enum CocoaErrorCode {
+        /// `NSFileNoSuchFileError`.
+        FileNoSuchFile = 4,
+
+        /// `NSFileLockingError`.
+        FileLocking = 255,
+
+        /// `NSFileReadUnknownError`.
+        FileReadUnknown = 256,
+
+        /// `NSFileReadNoPermissionError`.
+        FileReadNoPermission = 257,
+
+        /// `NSFileReadInvalidFileNameError`.
+        FileReadInvalidFileName = 258,
+
+        /// `NSFileReadCorruptFileError`.
+        FileReadCorruptFile = 259,
+
+        /// `NSFileReadNoSuchFileError`.
+        FileReadNoSuchFile = 260,
+
+        /// `NSFileReadInapplicableStringEncodingError`.
+        FileReadInapplicableStringEncoding = 261,
+
+        /// `NSFileReadUnsupportedSchemeError`.
+        FileReadUnsupportedScheme = 262,
+
+        /// `NSFileReadTooLargeError`.
+        FileReadTooLarge = 263,
+
+        /// `NSFileReadUnknownStringEncodingError`.
+        FileReadUnknownStringEncoding = 264,
+
+        /// `NSFileWriteUnknownError`.
+        FileWriteUnknown = 512,
+
+        /// `NSFileWriteNoPermissionError`.
+        FileWriteNoPermission = 513,
+
+        /// `NSFileWriteInvalidFileNameError`.
+        FileWriteInvalidFileName = 514,
+
+        /// `NSFileWriteFileExistsError`.
+        FileWriteFileExists = 516,
+
+        /// `NSFileWriteInapplicableStringEncodingError`.
+        FileWriteInapplicableStringEncoding = 517,
+
+        /// `NSFileWriteUnsupportedSchemeError`.
+        FileWriteUnsupportedScheme = 518,
+
+        /// `NSFileWriteOutOfSpaceError`.
+        FileWriteOutOfSpace = 640,
+
+        /// `NSFileWriteVolumeReadOnlyError`.
+        FileWriteVolumeReadOnly = 642,
+
+        /// `NSUserCancelledError`.
+        UserCancelled = 3072,
+
+        /// `NSPropertyListReadCorruptError`.
+        PropertyListReadCorrupt = 3840,
+
+        /// `NSPropertyListWriteInvalidError`.
+        PropertyListWriteInvalid = 3852,
+
+        /// `NSCoderReadCorruptError`.
+        CoderReadCorrupt = 4864,
+
+        /// `NSCoderValueNotFoundError`.
+        CoderValueNotFound = 4865
+    }
+}
+
+error_codes! {
+    /// Codes in `NSURLErrorDomain`.
+    pub enum UrlErrorCode {
+        /// `NSURLErrorUnknown`.
+        Unknown = -1,
+
+        /// `NSURLErrorCancelled`.
+        Cancelled = -999,
+
+        /// `NSURLErrorBadURL`.
+        BadUrl = -1000,
+
+        /// `NSURLErrorTimedOut`.
+        TimedOut = -1001,
+
+        /// `NSURLErrorUnsupportedURL`.
+        UnsupportedUrl = -1002,
+
+        /// `NSURLErrorCannotFindHost`.
+        CannotFindHost = -1003,
+
+        /// `NSURLErrorCannotConnectToHost`.
+        CannotConnectToHost = -1004,
+
+        /// `NSURLErrorNetworkConnectionLost`.
+        NetworkConnectionLost = -1005,
+
+        /// `NSURLErrorDNSLookupFailed`.
+        DnsLookupFailed = -1006,
+
+        /// `NSURLErrorHTTPTooManyRedirects`.
+        HttpTooManyRedirects = -1007,
+
+        /// `NSURLErrorResourceUnavailable`.
+        ResourceUnavailable = -1008,
+
+        /// `NSURLErrorNotConnectedToInternet`.
+        NotConnectedToInternet = -1009,
+
+        /// `NSURLErrorBadServerResponse`.
+        BadServerResponse = -1011,
+
+        /// `NSURLErrorUserCancelledAuthentication`.
+        UserCancelledAuthentication = -1012,
+
+        /// `NSURLErrorUserAuthenticationRequired`.
+        UserAuthenticationRequired = -1013,
+
+        /// `NSURLErrorFileDoesNotExist`.
+        FileDoesNotExist = -1100,
+
+        /// `NSURLErrorFileIsDirectory`.
+        FileIsDirectory = -1101,
+
+        /// `NSURLErrorNoPermissionsToReadFile`.
+        NoPermissionsToReadFile = -1102,
+
+        /// `NSURLErrorDataLengthExceedsMaximum`.
+        DataLengthExceedsMaximum = -1103,
+
+        /// `NSURLErrorSecureConnectionFailed`.
+        SecureConnectionFailed = -1200
+    }
+}
+
+/// An error's domain and code, for the domains we know about. Use `Error::kind()` to get one of
+/// these and match on it:
+///
+/// ```rust,no_run
+/// use cacao::error::{CocoaErrorCode, Error, ErrorKind};
+///
+/// fn is_missing(error: &Error) -> bool {
+///     match error.kind() {
+///         ErrorKind::Cocoa(CocoaErrorCode::FileNoSuchFile | CocoaErrorCode::FileReadNoSuchFile) => true,
+///         ErrorKind::Posix(code) => code == libc::ENOENT,
+///         _ => false
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// `NSCocoaErrorDomain`.
+    Cocoa(CocoaErrorCode),
+
+    /// `NSPOSIXErrorDomain`, holding the `errno`.
+    Posix(i32),
+
+    /// `NSOSStatusErrorDomain`, holding the `OSStatus`.
+    OSStatus(i32),
+
+    /// `NSURLErrorDomain`.
+    Url(UrlErrorCode),
+
+    /// Any other domain.
+    Other {
+        /// The error domain.
+        domain: String,
+
+        /// The error code.
+        code: NSInteger
+    }
+}
+
+impl ErrorKind {
+    /// Classifies a domain and code.
+    pub fn new(domain: &str, code: NSInteger) -> Self {
+        match domain {
+            COCOA_ERROR_DOMAIN => ErrorKind::Cocoa(code.into()),
+            POSIX_ERROR_DOMAIN => ErrorKind::Posix(code as i32),
+            OSSTATUS_ERROR_DOMAIN => ErrorKind::OSStatus(code as i32),
+            URL_ERROR_DOMAIN => ErrorKind::Url(code.into()),
+
+            domain => ErrorKind::Other {
+                domain: domain.to_string(),
+                code
+            }
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! A wrapper for `NSError`.
+//!
+//! It attempts to be thread safe where possible, and extract the usable information out of an
+//! `NSError`: the code and domain, the localized description, failure reason and recovery
+//! suggestion, the property list parts of `userInfo`, and the chain of underlying errors. The
+//! latter is exposed through `std::error::Error::source()`, so the usual error reporting tools
+//! can walk it.
+//!
+//! For matching on codes, `Error::kind()` classifies the domains that come up most often (Cocoa,
+//! POSIX, `OSStatus` and URL loading). File operations in particular tend to report a Cocoa error
+//! that wraps a POSIX one; `Error::posix_code()` digs the `errno` out for you.
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{id, nil, to_bool, NSDictionary, NSInteger, NSMutableDictionary, NSString, ObjcValue, BOOL};
+
+mod domain;
+pub use domain::{
+    CocoaErrorCode, ErrorKind, UrlErrorCode, COCOA_ERROR_DOMAIN, OSSTATUS_ERROR_DOMAIN, POSIX_ERROR_DOMAIN, URL_ERROR_DOMAIN
+};
+
+/// `NSLocalizedDescriptionKey`.
+const DESCRIPTION_KEY: &str = "NSLocalizedDescription";
+
+/// `NSLocalizedFailureReasonErrorKey`.
+const FAILURE_REASON_KEY: &str = "NSLocalizedFailureReason";
+
+/// `NSLocalizedRecoverySuggestionErrorKey`.
+const RECOVERY_SUGGESTION_KEY: &str = "NSLocalizedRecoverySuggestion";
+
+/// `NSUnderlyingErrorKey`.
+const UNDERLYING_ERROR_KEY: &str = "NSUnderlyingError";
+
+/// A wrapper around pieces of data extracted from `NSError`.
+///
+/// Converting back with `into_nserror()` produces an equivalent `NSError`. The one lossy part is
+/// `user_info`: only property list values survive, with the exception of `NSURL`s, which are
+/// kept as their absolute string (e.g, `NSURLErrorFailingURLErrorKey`).
+///
+/// This is `#[non_exhaustive]`, so that more of `NSError` can be carried over without breaking
+/// anyone: create one with `Error::with_description()` and set the fields you need from there.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Error {
+    /// Represents the code. Some of these can be... archaic, and some (e.g, `OSStatus` and URL
+    /// errors) are negative.
+    pub code: NSInteger,
+
+    /// Represents the domain of the error.
+    pub domain: String,
+
+    /// Maps over to `[NSError localizedDescription]`.
+    pub description: String,
+
+    /// Maps over to `[NSError localizedFailureReason]`.
+    pub failure_reason: Option<String>,
+
+    /// Maps over to `[NSError localizedRecoverySuggestion]`.
+    pub recovery_suggestion: Option<String>,
+
+    /// Whatever else was in `userInfo`. The keys above (and `NSUnderlyingErrorKey`) are pulled
+    /// out into their own fields, and aren't duplicated here.
+    pub user_info: HashMap<String, ObjcValue>,
+
+    /// The error stored under `NSUnderlyingErrorKey`, if any.
+    pub underlying: Option<Box<Error>>
+}
+
+impl Error {
+    /// Given an `NSError` (i.e, an id reference) we'll pull out the relevant information and
+    /// configure this. We pull out the information as it makes the error thread safe this way,
+    /// which is... easier, in some cases.
+    pub fn new(error: id) -> Self {
+        unsafe {
+            let code: NSInteger = msg_send![error, code];
+            let domain = NSString::retain(msg_send![error, domain]);
+            let description = NSString::retain(msg_send![error, localizedDescription]);
+            let failure_reason: id = msg_send![error, localizedFailureReason];
+            let recovery_suggestion: id = msg_send![error, localizedRecoverySuggestion];
+            let user_info: id = msg_send![error, userInfo];
+
+            let mut underlying = None;
+            let mut info = HashMap::new();
+
+            if user_info != nil {
+                let user_info = NSDictionary::retain(user_info);
+
+                for (key, value) in HashMap::from(&user_info) {
+                    match key.as_str() {
+                        DESCRIPTION_KEY | FAILURE_REASON_KEY | RECOVERY_SUGGESTION_KEY => {},
+
+                        UNDERLYING_ERROR_KEY if is_error(value) => {
+                            underlying = Some(Box::new(Error::new(value)));
+                        },
+
+                        _ => {
+                            if let Some(value) = user_info_value(value) {
+                                info.insert(key, value);
+                            }
+                        },
+                    }
+                }
+            }
+
+            Error {
+                code,
+                domain: domain.to_string(),
+                description: description.to_string(),
+                failure_reason: optional_string(failure_reason),
+                recovery_suggestion: optional_string(recovery_suggestion),
+                user_info: info,
+                underlying
+            }
+        }
+    }
+
+    /// Creates an error on the Rust side, for cases where we need to report something that didn't
+    /// come from the system.
+    pub fn with_description<D: Into<String>, S: Into<String>>(domain: D, code: NSInteger, description: S) -> Self {
+        Error {
+            code,
+            domain: domain.into(),
+            description: description.into(),
+            failure_reason: None,
+            recovery_suggestion: None,
+            user_info: HashMap::new(),
+            underlying: None
+        }
+    }
+
+    /// Returns a boxed `Error`.
+    pub fn boxed(error: id) -> Box<Self> {
+        Box::new(Error::new(error))
+    }
+
+    /// Classifies the domain and code of this error, for matching on.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::new(&self.domain, self.code)
+    }
+
+    /// Returns an iterator over this error and the chain of underlying errors beneath it.
+    pub fn chain(&self) -> impl Iterator<Item = &Error> {
+        let mut next = Some(self);
+
+        std::iter::from_fn(move || {
+            let error = next?;
+            next = error.underlying.as_deref();
+            Some(error)
+        })
+    }
+
+    /// Returns the `errno` for this error, if it - or any error underlying it - is in the POSIX
+    /// domain. File operations usually report a Cocoa error with one of these beneath it.
+    pub fn posix_code(&self) -> Option<i32> {
+        self.chain().find_map(|error| match error.kind() {
+            ErrorKind::Posix(code) => Some(code),
+            _ => None
+        })
+    }
+
+    /// Used for cases where we need to return an `NSError` back to the system (e.g, top-level
+    /// error handling). We just create a new `NSError` so the `Error` crate can be mostly
+    /// thread safe.
+    pub fn into_nserror(self) -> id {
+        let mut user_info: NSMutableDictionary = self
+            .user_info
+            .into_iter()
+            .map(|(key, value)| (key, id::from(value)))
+            .collect();
+
+        let strings = [
+            (DESCRIPTION_KEY, Some(self.description)),
+            (FAILURE_REASON_KEY, self.failure_reason),
+            (RECOVERY_SUGGESTION_KEY, self.recovery_suggestion)
+        ];
+
+        for (key, value) in strings.iter() {
+            if let Some(value) = value {
+                user_info.insert(NSString::new(key), NSString::new(value).into());
+            }
+        }
+
+        if let Some(underlying) = self.underlying {
+            user_info.insert(NSString::new(UNDERLYING_ERROR_KEY), underlying.into_nserror());
+        }
+
+        unsafe {
+            let domain = NSString::new(&self.domain);
+            let user_info = user_info.into_inner();
+            msg_send![class!(NSError), errorWithDomain:&*domain code:self.code userInfo:user_info]
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.description)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.underlying.as_deref().map(|error| error as &(dyn error::Error + 'static))
+    }
+}
+
+fn is_error(object: id) -> bool {
+    let result: BOOL = unsafe { msg_send![object, isKindOfClass: class!(NSError)] };
+    to_bool(result)
+}
+
+fn optional_string(string: id) -> Option<String> {
+    match string {
+        s if s == nil => None,
+        s => Some(NSString::retain(s).to_string())
+    }
+}
+
+/// Converts a `userInfo` value, falling back to the absolute string for `NSURL`s.
+fn user_info_value(value: id) -> Option<ObjcValue> {
+    ObjcValue::from_object(value).or_else(|| unsafe {
+        let result: BOOL = msg_send![value, isKindOfClass: class!(NSURL)];
+
+        match to_bool(result) {
+            true => optional_string(msg_send![value, absoluteString]).map(ObjcValue::String),
+            false => None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as StdError;
+
+    use super::{CocoaErrorCode, Error, ErrorKind, COCOA_ERROR_DOMAIN, POSIX_ERROR_DOMAIN};
+    use crate::foundation::ObjcValue;
+
+    fn file_error() -> Error {
+        let mut error = Error::with_description(COCOA_ERROR_DOMAIN, 4, "The file doesn’t exist.");
+        error.failure_reason = Some("No such file.".into());
+        error
+            .user_info
+            .insert("NSFilePath".into(), ObjcValue::String("/tmp/missing".into()));
+        error.underlying = Some(Box::new(Error::with_description(
+            POSIX_ERROR_DOMAIN,
+            2,
+            "No such file or directory"
+        )));
+        error
+    }
+
+    #[test]
+    fn test_kind_and_chain() {
+        let error = file_error();
+        assert_eq!(error.kind(), ErrorKind::Cocoa(CocoaErrorCode::FileNoSuchFile));
+        assert_eq!(error.posix_code(), Some(2));
+        assert_eq!(error.chain().count(), 2);
+        assert_eq!(
+            error.source().map(|e| e.to_string()).as_deref(),
+            Some("No such file or directory")
+        );
+
+        let other = Error::with_description("com.example", -7, "Nope");
+        assert_eq!(other.kind(), ErrorKind::Other {
+            domain: "com.example".into(),
+            code: -7
+        });
+        assert_eq!(other.posix_code(), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let error = file_error();
+        assert_eq!(Error::new(error.clone().into_nserror()), error);
+    }
+}
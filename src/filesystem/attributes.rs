@@ -0,0 +1,99 @@
+//! A typed view over the attributes dictionary that `NSFileManager` vends for an item.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use crate::foundation::{NSURLFileResource, ObjcValue};
+
+/// The attributes of a file system item, as returned by `FileManager::attributes()`.
+///
+/// The common attributes are pulled out into fields; everything else is left in `raw` (keyed by
+/// the `NSFileAttributeKey` name, e.g. `NSFileExtensionHidden`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileAttributes {
+    /// The size of the item, in bytes.
+    pub size: u64,
+
+    /// The type of the item (regular file, directory, symbolic link...).
+    pub file_type: NSURLFileResource,
+
+    /// When the item was created, if the file system records it.
+    pub creation_date: Option<SystemTime>,
+
+    /// When the item was last modified.
+    pub modification_date: Option<SystemTime>,
+
+    /// The POSIX permission bits (e.g, `0o644`).
+    pub posix_permissions: Option<u32>,
+
+    /// The name of the item's owner.
+    pub owner_account_name: Option<String>,
+
+    /// The name of the item's group.
+    pub group_owner_account_name: Option<String>,
+
+    /// The attributes dictionary, as vended by the system.
+    pub raw: HashMap<String, ObjcValue>
+}
+
+impl From<HashMap<String, ObjcValue>> for FileAttributes {
+    fn from(raw: HashMap<String, ObjcValue>) -> Self {
+        let integer = |key: &str| match raw.get(key) {
+            Some(ObjcValue::Integer(i)) => Some(*i as u64),
+            Some(ObjcValue::UnsignedInteger(u)) => Some(*u),
+            _ => None
+        };
+
+        let date = |key: &str| match raw.get(key) {
+            Some(ObjcValue::Date(date)) => Some(*date),
+            _ => None
+        };
+
+        let string = |key: &str| match raw.get(key) {
+            Some(ObjcValue::String(s)) => Some(s.clone()),
+            _ => None
+        };
+
+        FileAttributes {
+            size: integer("NSFileSize").unwrap_or(0),
+            file_type: match string("NSFileType").as_deref() {
+                Some("NSFileTypeDirectory") => NSURLFileResource::Directory,
+                Some("NSFileTypeRegular") => NSURLFileResource::Regular,
+                Some("NSFileTypeSymbolicLink") => NSURLFileResource::SymbolicLink,
+                Some("NSFileTypeSocket") => NSURLFileResource::Socket,
+                Some("NSFileTypeCharacterSpecial") => NSURLFileResource::CharacterSpecial,
+                Some("NSFileTypeBlockSpecial") => NSURLFileResource::BlockSpecial,
+                Some("NSFileTypeFIFO") => NSURLFileResource::NamedPipe,
+                _ => NSURLFileResource::Unknown
+            },
+            creation_date: date("NSFileCreationDate"),
+            modification_date: date("NSFileModificationDate"),
+            posix_permissions: integer("NSFilePosixPermissions").map(|p| p as u32),
+            owner_account_name: string("NSFileOwnerAccountName"),
+            group_owner_account_name: string("NSFileGroupOwnerAccountName"),
+            raw
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::FileAttributes;
+    use crate::foundation::{NSURLFileResource, ObjcValue};
+
+    #[test]
+    fn test_from_raw() {
+        let mut raw = HashMap::new();
+        raw.insert("NSFileSize".to_string(), ObjcValue::UnsignedInteger(42));
+        raw.insert("NSFileType".to_string(), ObjcValue::String("NSFileTypeDirectory".into()));
+        raw.insert("NSFilePosixPermissions".to_string(), ObjcValue::Integer(0o755));
+
+        let attributes = FileAttributes::from(raw);
+        assert_eq!(attributes.size, 42);
+        assert_eq!(attributes.file_type, NSURLFileResource::Directory);
+        assert_eq!(attributes.posix_permissions, Some(0o755));
+        assert_eq!(attributes.modification_date, None);
+    }
+}
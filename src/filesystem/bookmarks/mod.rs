@@ -0,0 +1,234 @@
+//! Persists bookmarks for security-scoped `NSURL`s, so sandboxed apps can get back to files and
+//! folders the user granted access to in a previous launch.
+//!
+//! A `BookmarkStore` saves bookmark data keyed by an ID of your choosing, either in
+//! `UserDefaults` or in a file. When you ask for one back, it resolves the bookmark, hands out a
+//! `SecurityScopedAccess` guard that keeps the resource accessible until it's dropped, and - if
+//! the system reports the bookmark as stale (e.g, the file moved) - quietly replaces it with a
+//! fresh one.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cacao::defaults::UserDefaults;
+//! use cacao::filesystem::BookmarkStore;
+//! use cacao::foundation::NSURL;
+//!
+//! let mut store = BookmarkStore::in_defaults(UserDefaults::standard(), "bookmarks").unwrap();
+//!
+//! // e.g, a folder the user picked through a `FileSelectPanel`.
+//! let url = NSURL::with_path("/Users/me/Projects");
+//! store.insert("project", &url).unwrap();
+//!
+//! // ...and in a later launch:
+//! let project = store.access("project").unwrap();
+//! println!("{}", project.pathbuf().display());
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::defaults::UserDefaults;
+use crate::foundation::{NSData, NSURLBookmarkCreationOption, NSURLBookmarkResolutionOption, SecurityScopedAccess, NSURL};
+
+mod storage;
+pub use storage::{BookmarkStorage, DefaultsStorage, FileStorage, MemoryStorage};
+
+/// Security scope only applies to sandboxed macOS apps.
+#[cfg(target_os = "macos")]
+const CREATION_OPTIONS: &[NSURLBookmarkCreationOption] = &[NSURLBookmarkCreationOption::SecurityScoped];
+
+#[cfg(not(target_os = "macos"))]
+const CREATION_OPTIONS: &[NSURLBookmarkCreationOption] = &[];
+
+#[cfg(target_os = "macos")]
+const RESOLUTION_OPTIONS: &[NSURLBookmarkResolutionOption] = &[
+    NSURLBookmarkResolutionOption::SecurityScoped,
+    NSURLBookmarkResolutionOption::WithoutUI
+];
+
+#[cfg(not(target_os = "macos"))]
+const RESOLUTION_OPTIONS: &[NSURLBookmarkResolutionOption] = &[NSURLBookmarkResolutionOption::WithoutUI];
+
+/// Creates and resolves bookmark data. `BookmarkStore` uses `FoundationResolver` unless told
+/// otherwise; swapping in your own is mostly useful for testing staleness handling.
+pub trait BookmarkResolver: fmt::Debug {
+    /// Creates bookmark data for `url`.
+    fn create(&self, url: &NSURL) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// Resolves bookmark data into a URL, along with whether the data is stale and should be
+    /// recreated.
+    fn resolve(&self, data: &[u8]) -> Result<(NSURL<'static>, bool), Box<dyn Error>>;
+}
+
+/// Creates and resolves bookmarks with `NSURL`. On macOS, these are security-scoped.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FoundationResolver;
+
+impl BookmarkResolver for FoundationResolver {
+    fn create(&self, url: &NSURL) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(url.bookmark_data(CREATION_OPTIONS, &[], None)?.into_vec())
+    }
+
+    fn resolve(&self, data: &[u8]) -> Result<(NSURL<'static>, bool), Box<dyn Error>> {
+        NSURL::from_bookmark_data(NSData::with_slice(data), RESOLUTION_OPTIONS, None)
+    }
+}
+
+/// Saves bookmarks keyed by ID, refreshes them when they go stale, and hands out access guards
+/// for them.
+#[derive(Debug)]
+pub struct BookmarkStore {
+    storage: Box<dyn BookmarkStorage>,
+    resolver: Box<dyn BookmarkResolver>,
+    bookmarks: HashMap<String, Vec<u8>>
+}
+
+impl BookmarkStore {
+    /// Creates a store backed by `storage`, loading whatever's already in it.
+    pub fn new<S: BookmarkStorage + 'static>(storage: S) -> Result<Self, Box<dyn Error>> {
+        Ok(BookmarkStore {
+            bookmarks: storage.load()?,
+            storage: Box::new(storage),
+            resolver: Box::new(FoundationResolver)
+        })
+    }
+
+    /// Creates a store that keeps its bookmarks under `key` in `defaults`.
+    pub fn in_defaults<K: Into<String>>(defaults: UserDefaults, key: K) -> Result<Self, Box<dyn Error>> {
+        BookmarkStore::new(DefaultsStorage::new(defaults, key))
+    }
+
+    /// Creates a store that keeps its bookmarks in the file at `path`.
+    pub fn in_file<P: Into<PathBuf>>(path: P) -> Result<Self, Box<dyn Error>> {
+        BookmarkStore::new(FileStorage::new(path))
+    }
+
+    /// Creates a store that only keeps its bookmarks in memory.
+    pub fn in_memory() -> Self {
+        BookmarkStore {
+            storage: Box::new(MemoryStorage::new()),
+            resolver: Box::new(FoundationResolver),
+            bookmarks: HashMap::new()
+        }
+    }
+
+    /// Uses `resolver` to create and resolve bookmarks, rather than `FoundationResolver`.
+    pub fn with_resolver<R: BookmarkResolver + 'static>(mut self, resolver: R) -> Self {
+        self.resolver = Box::new(resolver);
+        self
+    }
+
+    /// Creates a bookmark for `url` and saves it under `id`, replacing anything already there.
+    /// For security-scoped URLs, this needs to happen while the URL is accessible - e.g, right
+    /// after the user picked it.
+    pub fn insert<I: Into<String>>(&mut self, id: I, url: &NSURL) -> Result<(), Box<dyn Error>> {
+        let data = self.resolver.create(url)?;
+        self.bookmarks.insert(id.into(), data);
+        self.storage.save(&self.bookmarks)
+    }
+
+    /// Removes the bookmark saved under `id`, returning whether there was one.
+    pub fn remove(&mut self, id: &str) -> Result<bool, Box<dyn Error>> {
+        match self.bookmarks.remove(id) {
+            Some(_) => self.storage.save(&self.bookmarks).map(|_| true),
+            None => Ok(false)
+        }
+    }
+
+    /// Returns whether a bookmark is saved under `id`.
+    pub fn contains(&self, id: &str) -> bool {
+        self.bookmarks.contains_key(id)
+    }
+
+    /// Returns the IDs of every saved bookmark, sorted.
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.bookmarks.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Resolves the bookmark saved under `id` and starts accessing it. Access lasts until the
+    /// returned guard is dropped.
+    ///
+    /// If the bookmark is stale, it's recreated and saved. Failing to recreate it isn't an
+    /// error - the resolved URL is still good for now, and the refresh is tried again next time.
+    pub fn access(&mut self, id: &str) -> Result<SecurityScopedAccess<'static>, Box<dyn Error>> {
+        let data = match self.bookmarks.get(id) {
+            Some(data) => data,
+            None => return Err(format!("No bookmark is saved for '{}'", id).into())
+        };
+
+        let (url, is_stale) = self.resolver.resolve(data)?;
+        let access = url.security_scoped_access();
+
+        if is_stale {
+            if let Ok(data) = self.resolver.create(&url) {
+                self.bookmarks.insert(id.to_string(), data);
+                self.storage.save(&self.bookmarks)?;
+            }
+        }
+
+        Ok(access)
+    }
+
+    /// Resolves the bookmark saved under `id`, refreshing it if it's stale. Note that for
+    /// security-scoped bookmarks, you'll want `access()` instead - the URL this returns isn't
+    /// accessible on its own.
+    pub fn resolve(&mut self, id: &str) -> Result<NSURL<'static>, Box<dyn Error>> {
+        self.access(id).map(|access| access.url().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::{BookmarkResolver, BookmarkStorage, BookmarkStore, MemoryStorage};
+    use crate::foundation::NSURL;
+
+    /// Bookmarks are just paths; anything prefixed with `stale:` resolves as stale.
+    #[derive(Debug)]
+    struct PathResolver;
+
+    impl BookmarkResolver for PathResolver {
+        fn create(&self, url: &NSURL) -> Result<Vec<u8>, Box<dyn Error>> {
+            Ok(url.pathbuf().to_string_lossy().as_bytes().to_vec())
+        }
+
+        fn resolve(&self, data: &[u8]) -> Result<(NSURL<'static>, bool), Box<dyn Error>> {
+            let path = String::from_utf8(data.to_vec())?;
+
+            Ok(match path.strip_prefix("stale:") {
+                Some(path) => (NSURL::with_path(path), true),
+                None => (NSURL::with_path(path), false)
+            })
+        }
+    }
+
+    #[test]
+    fn test_stale_bookmarks_are_refreshed() {
+        let storage = MemoryStorage::new();
+        let mut store = BookmarkStore::new(storage.clone()).unwrap().with_resolver(PathResolver);
+
+        store.insert("project", &NSURL::with_path("/tmp/project")).unwrap();
+        assert_eq!(storage.bookmarks()["project"], b"/tmp/project".to_vec());
+
+        let mut stale = storage.bookmarks();
+        stale.insert("project".into(), b"stale:/tmp/moved".to_vec());
+        storage.clone().save(&stale).unwrap();
+        let mut store = BookmarkStore::new(storage.clone()).unwrap().with_resolver(PathResolver);
+
+        let access = store.access("project").unwrap();
+        assert_eq!(access.pathbuf().to_str(), Some("/tmp/moved"));
+        assert!(!access.is_active());
+        drop(access);
+        assert_eq!(storage.bookmarks()["project"], b"/tmp/moved".to_vec());
+
+        assert_eq!(store.resolve("project").unwrap().pathbuf().to_str(), Some("/tmp/moved"));
+        assert!(store.access("missing").is_err());
+        assert!(store.remove("project").unwrap());
+        assert_eq!(store.ids(), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,180 @@
+//! Where a `BookmarkStore` persists its bookmarks.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::defaults::plist::{self, PlistFormat};
+use crate::defaults::{UserDefaults, Value};
+
+/// Persists bookmark data, keyed by ID. The store reads everything once when it's created, and
+/// writes everything back whenever something changes - bookmarks are small, and there usually
+/// aren't many of them.
+pub trait BookmarkStorage: fmt::Debug {
+    /// Returns every stored bookmark. Storage that doesn't exist yet should return an empty map,
+    /// rather than an error.
+    fn load(&self) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>>;
+
+    /// Replaces the stored bookmarks with `bookmarks`.
+    fn save(&mut self, bookmarks: &HashMap<String, Vec<u8>>) -> Result<(), Box<dyn Error>>;
+}
+
+/// Stores bookmarks as a dictionary of data under a single `UserDefaults` key.
+#[derive(Debug)]
+pub struct DefaultsStorage {
+    defaults: UserDefaults,
+    key: String
+}
+
+impl DefaultsStorage {
+    /// Stores bookmarks under `key` in `defaults`.
+    pub fn new<K: Into<String>>(defaults: UserDefaults, key: K) -> Self {
+        DefaultsStorage {
+            defaults,
+            key: key.into()
+        }
+    }
+}
+
+impl BookmarkStorage for DefaultsStorage {
+    fn load(&self) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+        match self.defaults.get(&self.key) {
+            Some(value) => from_value(value),
+            None => Ok(HashMap::new())
+        }
+    }
+
+    fn save(&mut self, bookmarks: &HashMap<String, Vec<u8>>) -> Result<(), Box<dyn Error>> {
+        self.defaults.insert(&self.key, to_value(bookmarks));
+        Ok(())
+    }
+}
+
+/// Stores bookmarks in a binary property list file. Writes go to a temporary file that's then
+/// moved into place, so a crash mid-write can't leave a truncated file behind.
+#[derive(Debug)]
+pub struct FileStorage {
+    path: PathBuf
+}
+
+impl FileStorage {
+    /// Stores bookmarks in the file at `path`. Missing parent directories are created on the
+    /// first write.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        FileStorage { path: path.into() }
+    }
+}
+
+impl BookmarkStorage for FileStorage {
+    fn load(&self) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        from_value(plist::from_bytes(&fs::read(&self.path)?)?)
+    }
+
+    fn save(&mut self, bookmarks: &HashMap<String, Vec<u8>>) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let temporary = self.path.with_extension("tmp");
+        fs::write(&temporary, plist::to_bytes(&to_value(bookmarks), PlistFormat::Binary))?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Keeps bookmarks in memory, for tests. Clones share the same bookmarks, so a test can hold on
+/// to one and inspect what the store wrote.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage(Arc<Mutex<HashMap<String, Vec<u8>>>>);
+
+impl MemoryStorage {
+    /// Creates an empty storage.
+    pub fn new() -> Self {
+        MemoryStorage::default()
+    }
+
+    /// Returns a copy of what's currently stored.
+    pub fn bookmarks(&self) -> HashMap<String, Vec<u8>> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl BookmarkStorage for MemoryStorage {
+    fn load(&self) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+        Ok(self.bookmarks())
+    }
+
+    fn save(&mut self, bookmarks: &HashMap<String, Vec<u8>>) -> Result<(), Box<dyn Error>> {
+        *self.0.lock().unwrap() = bookmarks.clone();
+        Ok(())
+    }
+}
+
+fn to_value(bookmarks: &HashMap<String, Vec<u8>>) -> Value {
+    Value::Dictionary(
+        bookmarks
+            .iter()
+            .map(|(id, data)| (id.clone(), Value::Data(data.clone())))
+            .collect()
+    )
+}
+
+fn from_value(value: Value) -> Result<HashMap<String, Vec<u8>>, Box<dyn Error>> {
+    match value {
+        Value::Dictionary(map) => Ok(map
+            .into_iter()
+            .filter_map(|(id, value)| match value {
+                Value::Data(data) => Some((id, data)),
+                _ => None
+            })
+            .collect()),
+
+        _ => Err("Stored bookmarks are not a dictionary".into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{BookmarkStorage, DefaultsStorage, FileStorage};
+    use crate::defaults::{UserDefaults, Value};
+    use crate::utils::tempdir::TempDir;
+
+    fn bookmarks() -> HashMap<String, Vec<u8>> {
+        let mut bookmarks = HashMap::new();
+        bookmarks.insert("project".to_string(), vec![1, 2, 3]);
+        bookmarks.insert("downloads".to_string(), vec![4, 5]);
+        bookmarks
+    }
+
+    #[test]
+    fn test_file_storage() {
+        let directory = TempDir::new("bookmarks");
+        let path = directory.join("nested/bookmarks.plist");
+        let mut storage = FileStorage::new(&path);
+        assert_eq!(storage.load().unwrap(), HashMap::new());
+
+        storage.save(&bookmarks()).unwrap();
+        assert_eq!(FileStorage::new(&path).load().unwrap(), bookmarks());
+    }
+
+    #[test]
+    fn test_defaults_storage() {
+        let mut defaults = UserDefaults::in_memory();
+        defaults.insert("bookmarks", Value::string("not a dictionary"));
+
+        let mut storage = DefaultsStorage::new(defaults, "bookmarks");
+        assert!(storage.load().is_err());
+
+        storage.save(&bookmarks()).unwrap();
+        assert_eq!(storage.load().unwrap(), bookmarks());
+    }
+}
@@ -0,0 +1,118 @@
+//! Registers the `NSObject` subclass that acts as an `NSOpenSavePanelDelegate`, looping delegate
+//! callbacks back around to an `OpenSaveController`.
+
+use std::fmt;
+
+use objc::declare::ClassDecl;
+use objc::runtime::{Class, Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::ShareId;
+
+use crate::filesystem::traits::OpenSaveController;
+use crate::foundation::{id, load_or_register_class, nil, to_bool, NSString, BOOL, NO, NSURL, YES};
+use crate::utils::load;
+
+pub(crate) static OPEN_SAVE_CONTROLLER_PTR: &str = "rstOpenSaveControllerPtr";
+
+/// Boxes the controller a second time, so that the ivar can hold a thin pointer (see
+/// `invoker::Action`).
+pub(crate) struct PanelController(Box<dyn OpenSaveController>);
+
+impl fmt::Debug for PanelController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanelController").finish()
+    }
+}
+
+/// Asks the controller whether `url` should be selectable.
+extern "C" fn should_enable_url(this: &Object, _: Sel, _panel: id, url: id) -> BOOL {
+    let controller = load::<PanelController>(this, OPEN_SAVE_CONTROLLER_PTR);
+
+    match controller.0.should_enable_url(&NSURL::retain(url)) {
+        true => YES,
+        false => NO
+    }
+}
+
+/// Notifies the controller that the user changed directories.
+extern "C" fn did_change_to_directory(this: &Object, _: Sel, _panel: id, url: id) {
+    if url == nil {
+        return;
+    }
+
+    let controller = load::<PanelController>(this, OPEN_SAVE_CONTROLLER_PTR);
+    controller.0.did_change_to_directory(&NSURL::retain(url));
+}
+
+/// Notifies the controller of the filename the user entered, and hands it back unchanged.
+extern "C" fn user_entered_filename(this: &Object, _: Sel, _panel: id, filename: id, confirmed: BOOL) -> id {
+    let controller = load::<PanelController>(this, OPEN_SAVE_CONTROLLER_PTR);
+    let name = NSString::retain(filename);
+    controller.0.user_entered_filename(name.to_str(), to_bool(confirmed));
+    filename
+}
+
+/// Notifies the controller that the selection changed.
+extern "C" fn selection_did_change(this: &Object, _: Sel, _panel: id) {
+    let controller = load::<PanelController>(this, OPEN_SAVE_CONTROLLER_PTR);
+    controller.0.panel_selection_did_change();
+}
+
+/// Notifies the controller that the panel is expanding or collapsing.
+extern "C" fn will_expand(this: &Object, _: Sel, _panel: id, expanding: BOOL) {
+    let controller = load::<PanelController>(this, OPEN_SAVE_CONTROLLER_PTR);
+    controller.0.will_expand(to_bool(expanding));
+}
+
+/// Drops the controller. Panels only hold a weak reference to their delegate, so this happens
+/// once both the Rust-side panel and any pending completion handler have let go of it.
+extern "C" fn dealloc(this: &Object, _: Sel) {
+    unsafe {
+        let ptr: usize = *this.get_ivar(OPEN_SAVE_CONTROLLER_PTR);
+        let obj = ptr as *mut PanelController;
+
+        if !obj.is_null() {
+            let _controller = Box::from_raw(obj);
+        }
+
+        let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+}
+
+/// Injects an `NSObject` subclass that implements `NSOpenSavePanelDelegate`.
+pub(crate) fn register_panel_delegate_class() -> *const Class {
+    load_or_register_class("NSObject", "RSTOpenSavePanelDelegate", |decl: &mut ClassDecl| unsafe {
+        decl.add_ivar::<usize>(OPEN_SAVE_CONTROLLER_PTR);
+
+        decl.add_method(
+            sel!(panel:shouldEnableURL:),
+            should_enable_url as extern "C" fn(&Object, _, _, _) -> BOOL
+        );
+        decl.add_method(
+            sel!(panel:didChangeToDirectoryURL:),
+            did_change_to_directory as extern "C" fn(&Object, _, _, _)
+        );
+        decl.add_method(
+            sel!(panel:userEnteredFilename:confirmed:),
+            user_entered_filename as extern "C" fn(&Object, _, _, _, _) -> id
+        );
+        decl.add_method(
+            sel!(panelSelectionDidChange:),
+            selection_did_change as extern "C" fn(&Object, _, _)
+        );
+        decl.add_method(sel!(panel:willExpand:), will_expand as extern "C" fn(&Object, _, _, _));
+        decl.add_method(sel!(dealloc), dealloc as extern "C" fn(&Object, _));
+    })
+}
+
+/// Creates a delegate instance that owns `controller`, and sets it as `panel`'s delegate.
+pub(crate) fn set_panel_delegate<T: OpenSaveController + 'static>(panel: &Object, controller: T) -> ShareId<Object> {
+    let ptr = Box::into_raw(Box::new(PanelController(Box::new(controller))));
+
+    unsafe {
+        let delegate: id = msg_send![register_panel_delegate_class(), new];
+        (&mut *delegate).set_ivar(OPEN_SAVE_CONTROLLER_PTR, ptr as usize);
+        let _: () = msg_send![panel, setDelegate: delegate];
+        ShareId::from_retained_ptr(delegate)
+    }
+}
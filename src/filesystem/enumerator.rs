@@ -0,0 +1,42 @@
+//! A wrapper for `NSDirectoryEnumerator`, for walking a directory tree.
+
+use objc::runtime::Object;
+use objc::{msg_send, sel, sel_impl};
+use objc_id::Id;
+
+use crate::foundation::{id, nil, NSURL};
+
+/// Walks the contents of a directory, recursively unless told otherwise. Returned from
+/// `FileManager::enumerate()`.
+///
+/// Any resource keys passed when creating this are prefetched, so reading them from the URLs this
+/// vends is cheap.
+#[derive(Debug)]
+pub struct DirectoryEnumerator(pub Id<Object>);
+
+impl DirectoryEnumerator {
+    /// Wraps and retains an `NSDirectoryEnumerator`.
+    pub(crate) fn retain(enumerator: id) -> Self {
+        DirectoryEnumerator(unsafe { Id::from_ptr(enumerator) })
+    }
+
+    /// Skips the contents of the directory that was most recently returned, if it was one.
+    pub fn skip_descendants(&self) {
+        unsafe {
+            let _: () = msg_send![&*self.0, skipDescendants];
+        }
+    }
+}
+
+impl Iterator for DirectoryEnumerator {
+    type Item = NSURL<'static>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let url: id = unsafe { msg_send![&*self.0, nextObject] };
+
+        match url {
+            url if url == nil => None,
+            url => Some(NSURL::retain(url))
+        }
+    }
+}
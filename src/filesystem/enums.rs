@@ -208,3 +208,53 @@ impl From<SearchPathDirectory> for NSUInteger {
         }
     }
 }
+
+/// Options for enumerating the contents of a directory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DirectoryEnumerationOption {
+    /// Perform a shallow enumeration; don't descend into directories.
+    SkipsSubdirectoryDescendants,
+
+    /// Don't descend into packages (e.g, `.app` bundles).
+    SkipsPackageDescendants,
+
+    /// Skip hidden files.
+    SkipsHiddenFiles,
+
+    /// Return directories again after their contents (macOS 10.15+).
+    IncludesDirectoriesPostOrder,
+
+    /// Return URLs relative to the directory being enumerated (macOS 10.15+).
+    ProducesRelativePathURLs
+}
+
+impl From<DirectoryEnumerationOption> for NSUInteger {
+    fn from(option: DirectoryEnumerationOption) -> Self {
+        match option {
+            DirectoryEnumerationOption::SkipsSubdirectoryDescendants => 1 << 0,
+            DirectoryEnumerationOption::SkipsPackageDescendants => 1 << 1,
+            DirectoryEnumerationOption::SkipsHiddenFiles => 1 << 2,
+            DirectoryEnumerationOption::IncludesDirectoriesPostOrder => 1 << 3,
+            DirectoryEnumerationOption::ProducesRelativePathURLs => 1 << 4
+        }
+    }
+}
+
+/// Options for replacing one item with another (e.g, for atomic saves).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ItemReplacementOption {
+    /// Only use metadata from the new item, rather than merging it with the original's.
+    UsingNewMetadataOnly,
+
+    /// Keep the backup item around after the replacement succeeds.
+    WithoutDeletingBackupItem
+}
+
+impl From<ItemReplacementOption> for NSUInteger {
+    fn from(option: ItemReplacementOption) -> Self {
+        match option {
+            ItemReplacementOption::UsingNewMetadataOnly => 1 << 0,
+            ItemReplacementOption::WithoutDeletingBackupItem => 1 << 1
+        }
+    }
+}
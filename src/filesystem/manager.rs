@@ -4,14 +4,18 @@
 use std::error::Error;
 use std::sync::{Arc, RwLock};
 
-use objc::runtime::{Object, BOOL};
+use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::Id;
 use url::Url;
 
-use crate::error::Error as AppKitError;
-use crate::filesystem::enums::{SearchPathDirectory, SearchPathDomainMask};
-use crate::foundation::{id, nil, NSString, NSUInteger, NO};
+use crate::error::{CocoaErrorCode, Error as AppKitError, COCOA_ERROR_DOMAIN, POSIX_ERROR_DOMAIN};
+use crate::filesystem::attributes::FileAttributes;
+use crate::filesystem::enumerator::DirectoryEnumerator;
+use crate::filesystem::enums::{DirectoryEnumerationOption, ItemReplacementOption, SearchPathDirectory, SearchPathDomainMask};
+use crate::foundation::{
+    id, nil, to_bool, NSArray, NSInteger, NSString, NSUInteger, NSURLResourceKey, ObjcValue, BOOL, NO, NSURL, YES
+};
 
 /// A FileManager can be used for file operations (moving files, etc).
 ///
@@ -87,4 +91,366 @@ impl FileManager {
 
         Ok(())
     }
+
+    /// Given a directory/domain combination, returns the matching directory as an `NSURL`. This
+    /// is the more general form of `get_directory()`, and surfaces errors from the system.
+    ///
+    /// `appropriate_for` is only used for `SearchPathDirectory::ItemReplacement` (where it
+    /// determines the volume the directory lives on); `create` creates the directory if it
+    /// doesn't exist yet.
+    pub fn url_for_directory(
+        &self,
+        directory: SearchPathDirectory,
+        in_domain: SearchPathDomainMask,
+        appropriate_for: Option<&NSURL>,
+        create: bool
+    ) -> Result<NSURL<'static>, Box<dyn Error>> {
+        let dir: NSUInteger = directory.into();
+        let mask: NSUInteger = in_domain.into();
+        let appropriate_for: id = match appropriate_for {
+            Some(url) => &*url.objc as *const Object as id,
+            None => nil
+        };
+
+        let create = match create {
+            true => YES,
+            false => NO
+        };
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let url: id = msg_send![&**manager, URLForDirectory:dir
+                inDomain:mask
+                appropriateForURL:appropriate_for
+                create:create
+                error:&mut error];
+
+            url_or_error(url, error)
+        }
+    }
+
+    /// Creates (and returns) a temporary directory on the same volume as `url`, suitable for
+    /// writing a replacement before swapping it in with `replace_item()`. You're responsible for
+    /// removing it afterwards.
+    pub fn item_replacement_directory(&self, url: &NSURL) -> Result<NSURL<'static>, Box<dyn Error>> {
+        self.url_for_directory(
+            SearchPathDirectory::ItemReplacement,
+            SearchPathDomainMask::User,
+            Some(url),
+            true
+        )
+    }
+
+    /// Returns whether an item exists at `url`. Note that this follows symbolic links.
+    pub fn exists(&self, url: &NSURL) -> bool {
+        self.item_exists(url).is_some()
+    }
+
+    /// Returns whether `url` points to an existing directory.
+    pub fn is_directory(&self, url: &NSURL) -> bool {
+        self.item_exists(url) == Some(true)
+    }
+
+    /// Returns the attributes (size, type, dates, permissions...) of the item at `url`. Symbolic
+    /// links are not followed.
+    pub fn attributes(&self, url: &NSURL) -> Result<FileAttributes, Box<dyn Error>> {
+        let path = NSString::new(&url.pathbuf().to_string_lossy());
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let attributes: id = msg_send![&**manager, attributesOfItemAtPath:&*path error:&mut error];
+
+            if attributes == nil {
+                return Err(AppKitError::new(error).into());
+            }
+
+            Ok(ObjcValue::dictionary_from_object(attributes).into())
+        }
+    }
+
+    /// Creates a directory at `url`. If `with_intermediates` is `true`, any missing parent
+    /// directories are created too, and it's not an error for the directory to exist already.
+    pub fn create_directory(&self, url: &NSURL, with_intermediates: bool) -> Result<(), Box<dyn Error>> {
+        let with_intermediates = match with_intermediates {
+            true => YES,
+            false => NO
+        };
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let result: BOOL = msg_send![&**manager, createDirectoryAtURL:&*url.objc
+                withIntermediateDirectories:with_intermediates
+                attributes:nil
+                error:&mut error];
+
+            check(result, error)
+        }
+    }
+
+    /// Copies the item at `from` to `to`. Directories are copied recursively; `to` must not exist.
+    pub fn copy_item(&self, from: &NSURL, to: &NSURL) -> Result<(), Box<dyn Error>> {
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let result: BOOL = msg_send![&**manager, copyItemAtURL:&*from.objc toURL:&*to.objc error:&mut error];
+            check(result, error)
+        }
+    }
+
+    /// Removes the item at `url`. Directories are removed recursively.
+    pub fn remove_item(&self, url: &NSURL) -> Result<(), Box<dyn Error>> {
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let result: BOOL = msg_send![&**manager, removeItemAtURL:&*url.objc error:&mut error];
+            check(result, error)
+        }
+    }
+
+    /// Moves the item at `url` to the Trash, returning where it ended up (which can differ from
+    /// the original name, if something by that name was already in the Trash).
+    pub fn trash_item(&self, url: &NSURL) -> Result<NSURL<'static>, Box<dyn Error>> {
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let mut resulting_url: id = nil;
+            let result: BOOL = msg_send![&**manager, trashItemAtURL:&*url.objc
+                resultingItemURL:&mut resulting_url
+                error:&mut error];
+
+            check(result, error)?;
+            url_or_error(resulting_url, nil)
+        }
+    }
+
+    /// Atomically replaces the item at `original` with the one at `replacement`, preserving the
+    /// original's metadata (unless told otherwise). This is the building block for safe saves:
+    /// write into `item_replacement_directory()`, then swap it in here.
+    ///
+    /// If `backup_name` is given, the original is kept under that name until the replacement
+    /// succeeds (and afterwards, with `ItemReplacementOption::WithoutDeletingBackupItem`).
+    /// Returns the URL of the replaced item, which may differ from `original`.
+    pub fn replace_item(
+        &self,
+        original: &NSURL,
+        replacement: &NSURL,
+        backup_name: Option<&str>,
+        options: &[ItemReplacementOption]
+    ) -> Result<NSURL<'static>, Box<dyn Error>> {
+        let options = options
+            .iter()
+            .fold(0 as NSUInteger, |options, option| options | NSUInteger::from(*option));
+
+        let backup_name = backup_name.map(NSString::new);
+        let backup_name: id = match &backup_name {
+            Some(name) => &**name as *const Object as id,
+            None => nil
+        };
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let mut resulting_url: id = nil;
+            let result: BOOL = msg_send![&**manager, replaceItemAtURL:&*original.objc
+                withItemAtURL:&*replacement.objc
+                backupItemName:backup_name
+                options:options
+                resultingItemURL:&mut resulting_url
+                error:&mut error];
+
+            check(result, error)?;
+
+            match resulting_url {
+                url if url == nil => Ok(NSURL::retain(&*original.objc as *const Object as id)),
+                url => Ok(NSURL::retain(url))
+            }
+        }
+    }
+
+    /// Returns the immediate contents of the directory at `url`. Any resource values for `keys`
+    /// are prefetched, so reading them from the returned URLs is cheap.
+    pub fn contents_of_directory(
+        &self,
+        url: &NSURL,
+        keys: &[NSURLResourceKey],
+        options: &[DirectoryEnumerationOption]
+    ) -> Result<Vec<NSURL<'static>>, Box<dyn Error>> {
+        let keys = resource_keys(keys);
+        let options = enumeration_options(options);
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut error: id = nil;
+            let contents: id = msg_send![&**manager, contentsOfDirectoryAtURL:&*url.objc
+                includingPropertiesForKeys:&*keys
+                options:options
+                error:&mut error];
+
+            if contents == nil {
+                return Err(AppKitError::new(error).into());
+            }
+
+            Ok(NSArray::retain(contents).iter().map(NSURL::retain).collect())
+        }
+    }
+
+    /// Returns an enumerator that walks the directory at `url`, recursively unless
+    /// `DirectoryEnumerationOption::SkipsSubdirectoryDescendants` is passed. Any resource values
+    /// for `keys` are prefetched.
+    ///
+    /// Errors for individual items are skipped over; if `url` itself can't be read, this returns
+    /// an error - `FileReadNoSuchFile` if nothing exists there, or `ENOTDIR` if it isn't a
+    /// directory.
+    pub fn enumerate(
+        &self,
+        url: &NSURL,
+        keys: &[NSURLResourceKey],
+        options: &[DirectoryEnumerationOption]
+    ) -> Result<DirectoryEnumerator, Box<dyn Error>> {
+        if !self.is_directory(url) {
+            let path = url.pathbuf();
+
+            let error = match self.exists(url) {
+                true => AppKitError::with_description(
+                    POSIX_ERROR_DOMAIN,
+                    libc::ENOTDIR as NSInteger,
+                    format!("{} is not a directory", path.display())
+                ),
+
+                false => AppKitError::with_description(
+                    COCOA_ERROR_DOMAIN,
+                    CocoaErrorCode::FileReadNoSuchFile.into(),
+                    format!("No directory exists at {}", path.display())
+                )
+            };
+
+            return Err(error.into());
+        }
+
+        let keys = resource_keys(keys);
+        let options = enumeration_options(options);
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let enumerator: id = msg_send![&**manager, enumeratorAtURL:&*url.objc
+                includingPropertiesForKeys:&*keys
+                options:options
+                errorHandler:nil];
+
+            Ok(DirectoryEnumerator::retain(enumerator))
+        }
+    }
+
+    /// Returns `None` if nothing exists at `url`, or whether it's a directory otherwise.
+    fn item_exists(&self, url: &NSURL) -> Option<bool> {
+        let path = NSString::new(&url.pathbuf().to_string_lossy());
+
+        unsafe {
+            let manager = self.0.read().unwrap();
+            let mut is_directory: BOOL = NO;
+            let exists: BOOL = msg_send![&**manager, fileExistsAtPath:&*path isDirectory:&mut is_directory];
+
+            match to_bool(exists) {
+                true => Some(to_bool(is_directory)),
+                false => None
+            }
+        }
+    }
+}
+
+/// Turns the result of an `NSError **` call into a `Result`.
+fn check(result: BOOL, error: id) -> Result<(), Box<dyn Error>> {
+    match to_bool(result) {
+        true => Ok(()),
+        false => Err(AppKitError::new(error).into())
+    }
+}
+
+/// Wraps a returned `NSURL`, or the error if it's `nil`.
+fn url_or_error(url: id, error: id) -> Result<NSURL<'static>, Box<dyn Error>> {
+    if url != nil {
+        return Ok(NSURL::retain(url));
+    }
+
+    match error {
+        error if error == nil => Err("The system did not return a URL".into()),
+        error => Err(AppKitError::new(error).into())
+    }
+}
+
+fn resource_keys(keys: &[NSURLResourceKey]) -> NSArray {
+    keys.iter().map(|key| NSString::from(key).into()).collect()
+}
+
+fn enumeration_options(options: &[DirectoryEnumerationOption]) -> NSUInteger {
+    options
+        .iter()
+        .fold(0 as NSUInteger, |options, option| options | NSUInteger::from(*option))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::FileManager;
+    use crate::filesystem::DirectoryEnumerationOption;
+    use crate::foundation::{NSURLFileResource, NSURLResourceKey, NSURL};
+    use crate::utils::tempdir::TempDir;
+
+    #[test]
+    fn test_file_operations() {
+        let root = TempDir::new("file-manager");
+        let manager = FileManager::default();
+
+        let nested = NSURL::with_path(root.join("a/b"));
+        manager.create_directory(&nested, true).unwrap();
+        assert!(manager.is_directory(&nested));
+
+        fs::write(root.join("a/b/file.txt"), b"hello").unwrap();
+        let file = NSURL::with_path(root.join("a/b/file.txt"));
+        assert!(manager.exists(&file));
+        assert!(!manager.is_directory(&file));
+
+        let attributes = manager.attributes(&file).unwrap();
+        assert_eq!(attributes.size, 5);
+        assert_eq!(attributes.file_type, NSURLFileResource::Regular);
+
+        let copy = NSURL::with_path(root.join("copy.txt"));
+        manager.copy_item(&file, &copy).unwrap();
+        assert_eq!(fs::read(root.join("copy.txt")).unwrap(), b"hello");
+
+        let contents = manager
+            .contents_of_directory(&NSURL::with_path(root.path()), &[NSURLResourceKey::IsDirectory], &[])
+            .unwrap();
+        assert_eq!(contents.len(), 2);
+
+        let options = [DirectoryEnumerationOption::SkipsHiddenFiles];
+        let walked = manager
+            .enumerate(&NSURL::with_path(root.path()), &[], &options)
+            .unwrap()
+            .count();
+        assert_eq!(walked, 4);
+
+        let error = manager.enumerate(&file, &[], &options).unwrap_err();
+        let error = error.downcast_ref::<crate::error::Error>().unwrap();
+        assert_eq!(error.posix_code(), Some(libc::ENOTDIR));
+
+        let replacement_directory = manager.item_replacement_directory(&copy).unwrap();
+        let replacement = replacement_directory.pathbuf().join("copy.txt");
+        fs::write(&replacement, b"goodbye").unwrap();
+        manager
+            .replace_item(&copy, &NSURL::with_path(&replacement), None, &[])
+            .unwrap();
+        assert_eq!(fs::read(root.join("copy.txt")).unwrap(), b"goodbye");
+        manager.remove_item(&replacement_directory).unwrap();
+
+        let error = manager.copy_item(&file, &copy).unwrap_err();
+        assert!(error.downcast_ref::<crate::error::Error>().is_some());
+
+        manager.remove_item(&NSURL::with_path(root.path())).unwrap();
+        assert!(!manager.exists(&NSURL::with_path(root.path())));
+    }
 }
@@ -1,6 +1,14 @@
+mod class;
+
 pub mod enums;
 pub use enums::*;
 
+pub mod attributes;
+pub use attributes::FileAttributes;
+
+pub mod enumerator;
+pub use enumerator::DirectoryEnumerator;
+
 pub mod manager;
 pub use manager::FileManager;
 
@@ -14,3 +22,9 @@ pub use save::FileSavePanel;
 pub mod select;
 #[cfg(feature = "appkit")]
 pub use select::FileSelectPanel;
+
+pub mod watcher;
+pub use watcher::{EventSource, WatchEvent, WatchEventKind, Watcher};
+
+pub mod bookmarks;
+pub use bookmarks::BookmarkStore;
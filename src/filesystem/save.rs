@@ -8,15 +8,21 @@ use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
-use crate::foundation::{id, nil, NSInteger, NSString, NO, YES};
+use crate::filesystem::class::set_panel_delegate;
+use crate::filesystem::enums::ModalResponse;
+use crate::filesystem::traits::{FilePanel, OpenSaveController};
+use crate::foundation::{id, nil, NSArray, NSInteger, NSString, NO, NSURL, YES};
+
+#[cfg(feature = "appkit")]
+use crate::appkit::window::Window;
 
 #[derive(Debug)]
 pub struct FileSavePanel {
-    /// The internal Objective C `NSOpenPanel` instance.
+    /// The internal Objective C `NSSavePanel` instance.
     pub panel: ShareId<Object>,
 
-    /// The internal `NSObject` that routes delegate callbacks around.
-    pub delegate: ShareId<Object>,
+    /// The internal `NSObject` that routes delegate callbacks around, if a delegate has been set.
+    pub delegate: Option<ShareId<Object>>,
 
     /// Whether the user can choose files. Defaults to `true`.
     pub can_create_directories: bool
@@ -39,14 +45,17 @@ impl FileSavePanel {
                 ShareId::from_ptr(x)
             },
 
-            delegate: unsafe { ShareId::from_ptr(msg_send![class!(NSObject), new]) },
+            delegate: None,
 
             can_create_directories: true
         }
     }
 
-    /// @TODO: Do we even need this?
-    pub fn set_delegate(&mut self) {}
+    /// Sets a controller to receive delegate callbacks from this panel - e.g, to be told about
+    /// the filename the user entered. The panel owns it from here on.
+    pub fn set_delegate<T: OpenSaveController + 'static>(&mut self, controller: T) {
+        self.delegate = Some(set_panel_delegate(&self.panel, controller));
+    }
 
     /// Sets a suggested filename for the save dialog. The user can still change this if they
     /// choose to, but it's generally best practice to call this.
@@ -57,11 +66,21 @@ impl FileSavePanel {
         }
     }
 
-    /// Set the message text displayed in the panel.
-    pub fn set_message<S: AsRef<str>>(&mut self, message: S) {
+    /// Sets the label shown next to the filename field (e.g, "Export As:").
+    pub fn set_name_field_label<S: AsRef<str>>(&mut self, label: S) {
         unsafe {
-            let message = NSString::new(message.as_ref());
-            let _: () = msg_send![&*self.panel, setMessage:&*message];
+            let label = NSString::new(label.as_ref());
+            let _: () = msg_send![&*self.panel, setNameFieldLabel:&*label];
+        }
+    }
+
+    /// Sets whether the user can save with an extension that isn't in the allowed content types.
+    pub fn set_allows_other_file_types(&mut self, allows: bool) {
+        unsafe {
+            let _: () = msg_send![&*self.panel, setAllowsOtherFileTypes:match allows {
+                true => YES,
+                false => NO
+            }];
         }
     }
 
@@ -77,41 +96,77 @@ impl FileSavePanel {
         self.can_create_directories = can_create;
     }
 
-    /// Shows the panel as a modal. Currently sheets are not supported, but you're free (and able
-    /// to) thread the Objective C calls yourself by using the panel field on this struct.
+    /// Shows the panel as a modal. `handler` is passed the URL the user chose, or `None` if they
+    /// cancelled.
     ///
-    /// Note that this clones the underlying `NSOpenPanel` pointer. This is theoretically safe as
+    /// Note that this clones the underlying `NSSavePanel` pointer. This is theoretically safe as
     /// the system runs and manages that in another process, and we're still abiding by the general
     /// retain/ownership rules here.
-    pub fn show<F: Fn(Option<String>) + 'static>(&self, handler: F) {
-        let panel = self.panel.clone();
-        let completion = ConcreteBlock::new(move |_result: NSInteger| {
-            //let response: ModalResponse = result.into();
-            handler(get_url(&panel));
-        });
-        let completion = completion.copy();
+    ///
+    /// In a sandboxed app, the URL handed to `handler` is security-scoped: use
+    /// `NSURL::security_scoped_access()` to write to it.
+    pub fn show<F>(&self, handler: F)
+    where
+        F: Fn(Option<NSURL>) + 'static
+    {
+        let completion = self.completion_handler(handler);
 
         unsafe {
-            let _: () = msg_send![&*self.panel, runModal];
-            completion.call((1,));
-            //beginWithCompletionHandler:completion.copy()];
-            //let _: () = msg_send![&*self.panel, beginWithCompletionHandler:completion.copy()];
+            let _: () = msg_send![&*self.panel, beginWithCompletionHandler:completion.copy()];
         }
     }
+
+    /// Shows the panel as a sheet on `window`. `handler` is passed the URL the user chose, or
+    /// `None` if they cancelled.
+    #[cfg(feature = "appkit")]
+    pub fn begin_sheet<T, F>(&self, window: &Window<T>, handler: F)
+    where
+        F: Fn(Option<NSURL>) + 'static
+    {
+        let completion = self.completion_handler(handler);
+
+        unsafe {
+            let _: () = msg_send![&*self.panel, beginSheetModalForWindow:&*window.objc completionHandler:completion.copy()];
+        }
+    }
+
+    /// Wraps `handler` in a block that the panel can call when it's done.
+    fn completion_handler<F>(&self, handler: F) -> ConcreteBlock<(NSInteger,), (), impl Fn(NSInteger)>
+    where
+        F: Fn(Option<NSURL>) + 'static
+    {
+        let panel = self.panel.clone();
+
+        // Keeps the delegate alive until the panel is done with it, even if this is dropped.
+        let delegate = self.delegate.clone();
+
+        ConcreteBlock::new(move |result: NSInteger| {
+            let _delegate = &delegate;
+            let response: ModalResponse = result.into();
+
+            handler(match response {
+                ModalResponse::Ok => get_url(&panel),
+                _ => None
+            });
+        })
+    }
+}
+
+impl FilePanel for FileSavePanel {
+    fn panel(&self) -> &Object {
+        &self.panel
+    }
 }
 
-/// Retrieves the selected URLs from the provided panel.
-/// This is currently a bit ugly, but it's also not something that needs to be the best thing in
-/// the world as it (ideally) shouldn't be called repeatedly in hot spots.
-pub fn get_url(panel: &Object) -> Option<String> {
+/// Retrieves the selected URL from the provided panel.
+pub fn get_url(panel: &Object) -> Option<NSURL<'static>> {
     unsafe {
         let url: id = msg_send![&*panel, URL];
 
         if url == nil {
             None
         } else {
-            let path: id = msg_send![url, path];
-            Some(NSString::retain(path).to_string())
+            Some(NSURL::retain(url))
         }
     }
 }
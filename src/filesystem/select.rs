@@ -10,8 +10,10 @@ use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
+use crate::filesystem::class::set_panel_delegate;
 use crate::filesystem::enums::ModalResponse;
-use crate::foundation::{id, nil, NSInteger, NSString, NO, NSURL, YES};
+use crate::filesystem::traits::{FilePanel, OpenSaveController};
+use crate::foundation::{id, nil, NSArray, NSInteger, NSString, NO, NSURL, YES};
 
 #[cfg(feature = "appkit")]
 use crate::appkit::window::{Window, WindowDelegate};
@@ -21,8 +23,8 @@ pub struct FileSelectPanel {
     /// The internal Objective C `NSOpenPanel` instance.
     pub panel: ShareId<Object>,
 
-    /// The internal `NSObject` that routes delegate callbacks around.
-    pub delegate: ShareId<Object>,
+    /// The internal `NSObject` that routes delegate callbacks around, if a delegate has been set.
+    pub delegate: Option<ShareId<Object>>,
 
     /// Whether the user can choose files. Defaults to `true`.
     pub can_choose_files: bool,
@@ -58,7 +60,7 @@ impl FileSelectPanel {
                 ShareId::from_ptr(x)
             },
 
-            delegate: unsafe { ShareId::from_ptr(msg_send![class!(NSObject), new]) },
+            delegate: None,
 
             can_choose_files: true,
             can_choose_directories: false,
@@ -67,7 +69,11 @@ impl FileSelectPanel {
         }
     }
 
-    pub fn set_delegate(&mut self) {}
+    /// Sets a controller to receive delegate callbacks from this panel - e.g, to decide which
+    /// files can be selected. The panel owns it from here on.
+    pub fn set_delegate<T: OpenSaveController + 'static>(&mut self, controller: T) {
+        self.delegate = Some(set_panel_delegate(&self.panel, controller));
+    }
 
     /// Sets whether files can be chosen by the user.
     pub fn set_can_choose_files(&mut self, can_choose: bool) {
@@ -81,11 +87,14 @@ impl FileSelectPanel {
         self.can_choose_files = can_choose;
     }
 
-    /// Set the message text displayed in the panel.
-    pub fn set_message<S: AsRef<str>>(&mut self, message: S) {
+    /// Sets whether the accessory view is shown expanded, rather than hidden behind an "Options"
+    /// button.
+    pub fn set_accessory_view_disclosed(&mut self, disclosed: bool) {
         unsafe {
-            let message = NSString::new(message.as_ref());
-            let _: () = msg_send![&*self.panel, setMessage:&*message];
+            let _: () = msg_send![&*self.panel, setAccessoryViewDisclosed:match disclosed {
+                true => YES,
+                false => NO
+            }];
         }
     }
 
@@ -131,6 +140,10 @@ impl FileSelectPanel {
     /// the system runs and manages that in another process, and we're still abiding by the general
     /// retain/ownership rules here.
     ///
+    /// In a sandboxed app, the URLs handed to `handler` are security-scoped: use
+    /// `NSURL::security_scoped_access()` to work with them, or `filesystem::BookmarkStore` to get
+    /// back to them in a later launch.
+    ///
     /// This is offered for scenarios where you don't necessarily have a Window (e.g, a shell
     /// script) or can't easily pass one to use as a sheet.
     pub fn show<F>(&self, handler: F)
@@ -138,7 +151,12 @@ impl FileSelectPanel {
         F: Fn(Vec<NSURL>) + 'static
     {
         let panel = self.panel.clone();
+
+        // Keeps the delegate alive until the panel is done with it, even if this is dropped.
+        let delegate = self.delegate.clone();
+
         let completion = ConcreteBlock::new(move |result: NSInteger| {
+            let _delegate = &delegate;
             let response: ModalResponse = result.into();
 
             handler(match response {
@@ -174,7 +192,12 @@ impl FileSelectPanel {
         F: Fn(Vec<NSURL>) + 'static
     {
         let panel = self.panel.clone();
+
+        // Keeps the delegate alive until the panel is done with it, even if this is dropped.
+        let delegate = self.delegate.clone();
+
         let completion = ConcreteBlock::new(move |result: NSInteger| {
+            let _delegate = &delegate;
             let response: ModalResponse = result.into();
 
             handler(match response {
@@ -189,13 +212,19 @@ impl FileSelectPanel {
     }
 }
 
+impl FilePanel for FileSelectPanel {
+    fn panel(&self) -> &Object {
+        &self.panel
+    }
+}
+
 /// Retrieves the selected URLs from the provided panel.
 /// This is currently a bit ugly, but it's also not something that needs to be the best thing in
 /// the world as it (ideally) shouldn't be called repeatedly in hot spots.
 ///
 /// (We mostly do this to find the sweet spot between Rust constructs and necessary Foundation
 /// interaction patterns)
-fn get_urls(panel: &Object) -> Vec<NSURL> {
+fn get_urls(panel: &Object) -> Vec<NSURL<'static>> {
     unsafe {
         let urls: id = msg_send![&*panel, URLs];
         let count: usize = msg_send![urls, count];
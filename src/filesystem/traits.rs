@@ -1,5 +1,14 @@
 //! A trait that you can implement to handle open and save file dialogs. This more or less maps
 //! over to `NSOpenPanel` and `NSSavePanel` handling.
+
+use objc::runtime::{Class, Object};
+use objc::{msg_send, sel, sel_impl};
+
+use crate::foundation::{id, nil, to_bool, NSArray, NSString, BOOL, NSURL};
+use crate::layout::Layout;
+
+/// Set one of these on a `FileSelectPanel` or `FileSavePanel` with `set_delegate()`. All methods
+/// are called on the main thread.
 pub trait OpenSaveController {
     /// Called when the user has entered a filename (typically, during saving). `confirmed`
     /// indicates whether or not they hit the save button.
@@ -9,18 +18,124 @@ pub trait OpenSaveController {
     fn panel_selection_did_change(&self) {}
 
     /// Notifies you that the user changed directories.
-    fn did_change_to_directory(&self, _url: &str) {}
+    fn did_change_to_directory(&self, _url: &NSURL) {}
 
     /// Notifies you that the Save panel is about to expand or collapse because the user
     /// clicked the disclosure triangle that displays or hides the file browser.
     fn will_expand(&self, _expanding: bool) {}
 
     /// Determine whether the specified URL should be enabled in the Open panel.
-    fn should_enable_url(&self, _url: &str) -> bool {
+    fn should_enable_url(&self, _url: &NSURL) -> bool {
         true
     }
 }
 
+/// Configuration shared by `FileSavePanel` and `FileSelectPanel` - `NSOpenPanel` is a subclass
+/// of `NSSavePanel`, so these work the same way on both.
+pub trait FilePanel {
+    /// Returns the underlying `NSSavePanel` (or `NSOpenPanel`).
+    fn panel(&self) -> &Object;
+
+    /// Set the message text displayed in the panel.
+    fn set_message<S: AsRef<str>>(&mut self, message: S) {
+        unsafe {
+            let message = NSString::new(message.as_ref());
+            let _: () = msg_send![self.panel(), setMessage:&*message];
+        }
+    }
+
+    /// Sets the title of the panel.
+    fn set_title<S: AsRef<str>>(&mut self, title: S) {
+        unsafe {
+            let title = NSString::new(title.as_ref());
+            let _: () = msg_send![self.panel(), setTitle:&*title];
+        }
+    }
+
+    /// Sets the text of the default button (e.g, "Save", or "Open").
+    fn set_prompt<S: AsRef<str>>(&mut self, prompt: S) {
+        unsafe {
+            let prompt = NSString::new(prompt.as_ref());
+            let _: () = msg_send![self.panel(), setPrompt:&*prompt];
+        }
+    }
+
+    /// Sets the directory the panel starts out showing.
+    fn set_directory(&mut self, url: &NSURL) {
+        unsafe {
+            let _: () = msg_send![self.panel(), setDirectoryURL:&*url.objc];
+        }
+    }
+
+    /// Restricts which files can be chosen (or what a file can be saved as). Each entry can be a
+    /// filename extension (e.g, `"png"`) or a uniform type identifier (e.g, `"public.image"`). An
+    /// empty slice allows anything.
+    ///
+    /// On macOS 11 and later, these are converted to `UTType`s and set as the panel's
+    /// `allowedContentTypes`; older systems fall back to `allowedFileTypes`.
+    fn set_allowed_content_types<S: AsRef<str>>(&mut self, types: &[S]) {
+        let panel = self.panel();
+
+        unsafe {
+            let responds: BOOL = msg_send![panel, respondsToSelector:sel!(setAllowedContentTypes:)];
+
+            match Class::get("UTType") {
+                Some(uttype) if to_bool(responds) => {
+                    let types: Vec<id> = types
+                        .iter()
+                        .map(|content_type| content_type_for(uttype, content_type.as_ref()))
+                        .collect();
+
+                    let types: id = NSArray::from(types).into();
+                    let _: () = msg_send![panel, setAllowedContentTypes: types];
+                },
+
+                _ => {
+                    let types: id = match types.is_empty() {
+                        true => nil,
+                        false => NSArray::from(
+                            types
+                                .iter()
+                                .map(|content_type| NSString::new(content_type.as_ref()).into())
+                                .collect::<Vec<id>>()
+                        )
+                        .into()
+                    };
+
+                    let _: () = msg_send![panel, setAllowedFileTypes: types];
+                }
+            }
+        }
+    }
+
+    /// Shows `view` at the bottom of the panel, for any extra options you want to offer (e.g, a
+    /// format picker). Keep a handle to the view around if you want to read those options back.
+    fn set_accessory_view<L: Layout>(&mut self, view: &L) {
+        let panel = self.panel();
+
+        view.with_backing_obj_mut(|obj| unsafe {
+            let _: () = msg_send![panel, setAccessoryView: obj];
+        });
+    }
+}
+
+/// Returns the `UTType` for `content_type`, which is either a type identifier or a filename
+/// extension. Unknown extensions get a dynamic type, so this always returns something.
+unsafe fn content_type_for(uttype: &Class, content_type: &str) -> id {
+    let string = NSString::new(content_type);
+
+    let identified: id = match content_type.contains('.') {
+        true => msg_send![uttype, typeWithIdentifier:&*string],
+        false => nil
+    };
+
+    if identified != nil {
+        return identified;
+    }
+
+    msg_send![uttype, typeWithFilenameExtension:&*string]
+}
+
 /// A trait you can implement for working with the underlying filesystem. This is important,
 /// notably, because sandboxed applications have different working restrictions surrounding what
 /// they can access.
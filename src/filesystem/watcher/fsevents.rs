@@ -0,0 +1,299 @@
+//! An `EventSource` backed by FSEvents, for macOS.
+
+use std::error::Error;
+use std::ffi::{c_void, CStr};
+use std::fmt;
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::sync::Arc;
+
+use dispatch::ffi::{dispatch_queue_create, dispatch_queue_t, dispatch_release, DISPATCH_QUEUE_SERIAL};
+
+use crate::foundation::{id, NSArray, NSString};
+
+use super::{EventSink, EventSource, WatchEvent, WatchEventKind};
+
+type FSEventStreamRef = *mut c_void;
+
+type FSEventStreamCallback = extern "C" fn(FSEventStreamRef, *mut c_void, usize, *mut c_void, *const u32, *const u64);
+
+#[repr(C)]
+struct FSEventStreamContext {
+    version: isize,
+    info: *mut c_void,
+    retain: Option<extern "C" fn(*const c_void) -> *const c_void>,
+    release: Option<extern "C" fn(*const c_void)>,
+    copy_description: *const c_void
+}
+
+#[link(name = "CoreServices", kind = "framework")]
+extern "C" {
+    fn FSEventStreamCreate(
+        allocator: *const c_void,
+        callback: FSEventStreamCallback,
+        context: *const FSEventStreamContext,
+        paths_to_watch: id,
+        since_when: u64,
+        latency: f64,
+        flags: u32
+    ) -> FSEventStreamRef;
+
+    fn FSEventStreamSetDispatchQueue(stream: FSEventStreamRef, queue: dispatch_queue_t);
+    fn FSEventStreamStart(stream: FSEventStreamRef) -> u8;
+    fn FSEventStreamStop(stream: FSEventStreamRef);
+    fn FSEventStreamInvalidate(stream: FSEventStreamRef);
+    fn FSEventStreamRelease(stream: FSEventStreamRef);
+}
+
+/// `kFSEventStreamEventIdSinceNow`.
+const SINCE_NOW: u64 = 0xFFFF_FFFF_FFFF_FFFF;
+
+/// How long FSEvents coalesces on its end, in seconds. The `Watcher` debounces on top of this.
+const LATENCY: f64 = 0.05;
+
+/// `kFSEventStreamCreateFlagNoDefer | kFSEventStreamCreateFlagWatchRoot |
+/// kFSEventStreamCreateFlagFileEvents`.
+const CREATE_FLAGS: u32 = 0x02 | 0x04 | 0x10;
+
+/// `kFSEventStreamEventFlagMustScanSubDirs`, `kFSEventStreamEventFlagUserDropped` and
+/// `kFSEventStreamEventFlagKernelDropped`.
+const MUST_SCAN_SUB_DIRS: u32 = 0x01 | 0x02 | 0x04;
+
+const ITEM_CREATED: u32 = 0x100;
+const ITEM_REMOVED: u32 = 0x200;
+const ITEM_INODE_META_MOD: u32 = 0x400;
+const ITEM_RENAMED: u32 = 0x800;
+const ITEM_MODIFIED: u32 = 0x1000;
+const ITEM_FINDER_INFO_MOD: u32 = 0x2000;
+const ITEM_CHANGE_OWNER: u32 = 0x4000;
+const ITEM_XATTR_MOD: u32 = 0x8000;
+const ROOT_CHANGED: u32 = 0x20;
+
+/// What the callback needs. This is reference counted, and the stream holds a reference (via the
+/// context's `retain` and `release` callbacks) for as long as it can still call us - which can be
+/// after it's been invalidated, if a callback is already running on the queue.
+struct Context {
+    sink: EventSink,
+    roots: Vec<(PathBuf, bool)>
+}
+
+/// Watches paths using FSEvents. A single stream covers every watched path, and is recreated as
+/// paths are added or removed.
+///
+/// FSEvents always watches recursively; for non-recursive watches, events below the immediate
+/// children are filtered out.
+pub struct FSEventsSource {
+    sink: Option<EventSink>,
+    roots: Vec<(PathBuf, bool)>,
+    queue: dispatch_queue_t,
+    stream: FSEventStreamRef
+}
+
+// The stream and queue are only touched from whichever thread owns the source; the callback only
+// reads its context, which the stream keeps alive.
+unsafe impl Send for FSEventsSource {}
+
+impl fmt::Debug for FSEventsSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FSEventsSource").field("roots", &self.roots).finish()
+    }
+}
+
+impl Default for FSEventsSource {
+    fn default() -> Self {
+        FSEventsSource::new()
+    }
+}
+
+impl FSEventsSource {
+    /// Creates a new source. Nothing happens until it's started by a `Watcher`.
+    pub fn new() -> Self {
+        FSEventsSource {
+            sink: None,
+            roots: Vec::new(),
+            queue: ptr::null_mut(),
+            stream: ptr::null_mut()
+        }
+    }
+
+    /// Tears down the current stream (if any), and starts a new one for the current roots.
+    fn restart(&mut self) -> Result<(), Box<dyn Error>> {
+        self.stop();
+
+        let sink = match (&self.sink, self.roots.is_empty()) {
+            (Some(sink), false) => sink.clone(),
+            _ => return Ok(())
+        };
+
+        let paths: NSArray = self
+            .roots
+            .iter()
+            .map(|(path, _)| NSString::new(&path.to_string_lossy()).into())
+            .collect();
+
+        let info = Arc::into_raw(Arc::new(Context {
+            sink,
+            roots: self.roots.clone()
+        }));
+
+        let context = FSEventStreamContext {
+            version: 0,
+            info: info as *mut c_void,
+            retain: Some(retain_context),
+            release: Some(release_context),
+            copy_description: ptr::null()
+        };
+
+        unsafe {
+            self.stream = FSEventStreamCreate(
+                ptr::null(),
+                callback,
+                &context,
+                &*paths as *const _ as id,
+                SINCE_NOW,
+                LATENCY,
+                CREATE_FLAGS
+            );
+
+            // The stream retains the context if it was created; either way, we're done with ours.
+            drop(Arc::from_raw(info));
+
+            if self.stream.is_null() {
+                self.stop();
+                return Err("Unable to create an FSEvents stream".into());
+            }
+
+            FSEventStreamSetDispatchQueue(self.stream, self.queue);
+
+            if FSEventStreamStart(self.stream) == 0 {
+                self.stop();
+                return Err("Unable to start an FSEvents stream".into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops and releases the current stream, if there is one. Its context is freed by FSEvents
+    /// once nothing can call back with it.
+    fn stop(&mut self) {
+        if self.stream.is_null() {
+            return;
+        }
+
+        unsafe {
+            FSEventStreamStop(self.stream);
+            FSEventStreamInvalidate(self.stream);
+            FSEventStreamRelease(self.stream);
+        }
+
+        self.stream = ptr::null_mut();
+    }
+}
+
+impl EventSource for FSEventsSource {
+    fn start(&mut self, sink: EventSink) -> Result<(), Box<dyn Error>> {
+        self.sink = Some(sink);
+        self.queue = unsafe {
+            dispatch_queue_create(
+                b"com.cacao-rs.filesystem.watcher\0".as_ptr() as *const c_char,
+                DISPATCH_QUEUE_SERIAL
+            )
+        };
+        Ok(())
+    }
+
+    fn watch(&mut self, path: &Path, recursive: bool) -> Result<(), Box<dyn Error>> {
+        // FSEvents reports resolved paths (e.g, `/private/var` rather than `/var`).
+        let path = path.canonicalize()?;
+        self.roots.retain(|(root, _)| root != &path);
+        self.roots.push((path, recursive));
+        self.restart()
+    }
+
+    fn unwatch(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.roots.retain(|(root, _)| root != &path);
+        self.restart()
+    }
+}
+
+impl Drop for FSEventsSource {
+    fn drop(&mut self) {
+        self.stop();
+
+        if !self.queue.is_null() {
+            unsafe {
+                dispatch_release(self.queue);
+            }
+        }
+    }
+}
+
+/// Takes a reference to a `Context` on the stream's behalf.
+extern "C" fn retain_context(info: *const c_void) -> *const c_void {
+    unsafe {
+        Arc::increment_strong_count(info as *const Context);
+    }
+
+    info
+}
+
+/// Drops a reference the stream took with `retain_context`.
+extern "C" fn release_context(info: *const c_void) {
+    unsafe {
+        Arc::decrement_strong_count(info as *const Context);
+    }
+}
+
+extern "C" fn callback(
+    _stream: FSEventStreamRef,
+    info: *mut c_void,
+    count: usize,
+    paths: *mut c_void,
+    flags: *const u32,
+    _ids: *const u64
+) {
+    let context = unsafe { &*(info as *const Context) };
+    let paths = paths as *const *const c_char;
+
+    for index in 0..count {
+        let (path, flags) = unsafe {
+            let path = CStr::from_ptr(*paths.add(index)).to_string_lossy().into_owned();
+            (PathBuf::from(path), *flags.add(index))
+        };
+
+        let watched = context.roots.iter().any(|(root, recursive)| {
+            path.starts_with(root) && (*recursive || path == *root || path.parent() == Some(root.as_path()))
+        });
+
+        if !watched {
+            continue;
+        }
+
+        if let Some(kind) = event_kind(flags, &path) {
+            (context.sink)(WatchEvent::new(kind, path));
+        }
+    }
+}
+
+/// Maps FSEvents flags to an event kind. FSEvents coalesces flags for a path, so a single event
+/// can say "created, modified, removed"; whether the path still exists settles it.
+fn event_kind(flags: u32, path: &Path) -> Option<WatchEventKind> {
+    let exists = path.exists();
+
+    if flags & MUST_SCAN_SUB_DIRS != 0 {
+        Some(WatchEventKind::Rescan)
+    } else if flags & ITEM_RENAMED != 0 {
+        Some(WatchEventKind::Renamed)
+    } else if flags & (ITEM_REMOVED | ROOT_CHANGED) != 0 && !exists {
+        Some(WatchEventKind::Removed)
+    } else if flags & ITEM_CREATED != 0 && exists {
+        Some(WatchEventKind::Created)
+    } else if flags & (ITEM_MODIFIED | ITEM_INODE_META_MOD | ITEM_FINDER_INFO_MOD | ITEM_CHANGE_OWNER | ITEM_XATTR_MOD) != 0 {
+        Some(WatchEventKind::Modified)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,381 @@
+//! An `EventSource` backed by inotify, for Linux (and so GNUstep).
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::io;
+use std::os::raw::c_char;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use super::{EventSink, EventSource, WatchEvent, WatchEventKind};
+
+/// The events we ask inotify for.
+const WATCH_MASK: u32 = libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MODIFY
+    | libc::IN_ATTRIB
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVE_SELF;
+
+/// How often (in milliseconds) the reader thread checks whether it should stop.
+const POLL_TIMEOUT: i32 = 100;
+
+/// A watch descriptor's bookkeeping.
+#[derive(Debug)]
+struct Watch {
+    path: PathBuf,
+
+    /// The paths passed to `watch()` that this watch is for, and whether each was recursive. A
+    /// directory can be beneath more than one of them, and inotify hands back the same
+    /// descriptor each time it's watched.
+    roots: Vec<(PathBuf, bool)>
+}
+
+impl Watch {
+    /// Whether this was passed to `watch()`, rather than found beneath something that was.
+    fn is_root(&self) -> bool {
+        self.roots.iter().any(|(root, _)| root == &self.path)
+    }
+}
+
+type Watches = Arc<Mutex<HashMap<i32, Watch>>>;
+
+/// Watches paths using inotify. Recursive watches add a watch per directory, including ones
+/// created after watching started.
+#[derive(Debug)]
+pub struct InotifySource {
+    fd: RawFd,
+    watches: Watches,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>
+}
+
+impl Default for InotifySource {
+    fn default() -> Self {
+        InotifySource::new()
+    }
+}
+
+impl InotifySource {
+    /// Creates a new source. Nothing happens until it's started by a `Watcher`.
+    pub fn new() -> Self {
+        InotifySource {
+            fd: -1,
+            watches: Arc::new(Mutex::new(HashMap::new())),
+            running: Arc::new(AtomicBool::new(false)),
+            thread: None
+        }
+    }
+}
+
+impl EventSource for InotifySource {
+    fn start(&mut self, sink: EventSink) -> Result<(), Box<dyn Error>> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        self.fd = fd;
+        self.running.store(true, Ordering::SeqCst);
+
+        let watches = self.watches.clone();
+        let running = self.running.clone();
+        self.thread = Some(thread::spawn(move || read_events(fd, watches, running, sink)));
+
+        Ok(())
+    }
+
+    fn watch(&mut self, path: &Path, recursive: bool) -> Result<(), Box<dyn Error>> {
+        let mut watches = self.watches.lock().unwrap();
+        add_watch(self.fd, &mut watches, path, recursive, path).map_err(|e| e.into())
+    }
+
+    /// Stops watching `path`, and the directories that were watched because they're beneath it -
+    /// unless they're also beneath something else that's still being watched.
+    fn unwatch(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut watches = self.watches.lock().unwrap();
+        let fd = self.fd;
+
+        watches.retain(|descriptor, watch| {
+            watch.roots.retain(|(root, _)| root != path);
+
+            if !watch.roots.is_empty() {
+                return true;
+            }
+
+            unsafe {
+                libc::inotify_rm_watch(fd, *descriptor);
+            }
+
+            false
+        });
+
+        Ok(())
+    }
+}
+
+impl Drop for InotifySource {
+    /// Stops the reader thread and closes the inotify instance.
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+
+        if self.fd >= 0 {
+            unsafe {
+                libc::close(self.fd);
+            }
+        }
+    }
+}
+
+/// Watches `path`, and (if `recursive`) every directory beneath it, on behalf of `root`.
+/// Directories beneath a recursive root are watched recursively.
+fn add_watch(fd: RawFd, watches: &mut HashMap<i32, Watch>, path: &Path, recursive: bool, root: &Path) -> io::Result<()> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let descriptor = unsafe { libc::inotify_add_watch(fd, c_path.as_ptr(), WATCH_MASK) };
+
+    if descriptor < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let watch = watches.entry(descriptor).or_insert_with(|| Watch {
+        path: path.to_path_buf(),
+        roots: Vec::new()
+    });
+
+    match watch.roots.iter_mut().find(|(existing, _)| existing == root) {
+        Some((_, existing)) => *existing |= recursive,
+        None => watch.roots.push((root.to_path_buf(), recursive))
+    }
+
+    if recursive && path.is_dir() {
+        for entry in path.read_dir()?.flatten() {
+            let is_directory = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+
+            // Subdirectories can disappear (or deny us) between listing and watching; that's
+            // not worth failing the whole watch over.
+            if is_directory {
+                let _ = add_watch(fd, watches, &entry.path(), true, root);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs on the reader thread until `running` is cleared.
+fn read_events(fd: RawFd, watches: Watches, running: Arc<AtomicBool>, sink: EventSink) {
+    // inotify_event is 4-byte aligned; reading into u32s keeps it that way.
+    let mut buffer = [0u32; 1024];
+    let header = std::mem::size_of::<libc::inotify_event>();
+
+    while running.load(Ordering::SeqCst) {
+        let mut poll = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0
+        };
+
+        if unsafe { libc::poll(&mut poll, 1, POLL_TIMEOUT) } <= 0 {
+            continue;
+        }
+
+        let read = unsafe { libc::read(fd, buffer.as_mut_ptr() as *mut _, std::mem::size_of_val(&buffer)) };
+
+        if read <= 0 {
+            continue;
+        }
+
+        let bytes = buffer.as_ptr() as *const u8;
+        let mut offset = 0;
+
+        while offset + header <= read as usize {
+            let event = unsafe { &*(bytes.add(offset) as *const libc::inotify_event) };
+
+            let name = match event.len {
+                0 => None,
+                _ => {
+                    let name = unsafe { CStr::from_ptr(bytes.add(offset + header) as *const c_char) };
+                    Some(std::ffi::OsStr::from_bytes(name.to_bytes()).to_owned())
+                }
+            };
+
+            offset += header + event.len as usize;
+
+            let mut watches = watches.lock().unwrap();
+
+            // The kernel's queue filled up and events were dropped, so every root needs a rescan.
+            if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                let roots = rescan_roots(&watches);
+                drop(watches);
+
+                for root in roots {
+                    sink(WatchEvent::new(WatchEventKind::Rescan, root));
+                }
+
+                continue;
+            }
+
+            if event.mask & libc::IN_IGNORED != 0 {
+                watches.remove(&event.wd);
+                continue;
+            }
+
+            let (path, root, roots) = match watches.get(&event.wd) {
+                Some(watch) => match &name {
+                    Some(name) => (watch.path.join(name), watch.is_root(), watch.roots.clone()),
+                    None => (watch.path.clone(), watch.is_root(), watch.roots.clone())
+                },
+
+                None => continue
+            };
+
+            // New directories beneath a recursive watch need watches of their own.
+            if event.mask & libc::IN_ISDIR != 0 && event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                for (root, _) in roots.iter().filter(|(_, recursive)| *recursive) {
+                    let _ = add_watch(fd, &mut watches, &path, true, root);
+                }
+            }
+
+            drop(watches);
+
+            if let Some(kind) = event_kind(event.mask, name.is_some() || root) {
+                sink(WatchEvent::new(kind, path));
+            }
+        }
+    }
+}
+
+/// Returns the paths that were passed to `watch()`, each once.
+fn rescan_roots(watches: &HashMap<i32, Watch>) -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+
+    for (root, _) in watches.values().flat_map(|watch| watch.roots.iter()) {
+        if !roots.contains(root) {
+            roots.push(root.clone());
+        }
+    }
+
+    roots.sort();
+    roots
+}
+
+/// Maps an inotify mask to an event kind. Self events (the watched item itself being deleted or
+/// moved) are only reported for roots; for everything else, the parent directory's watch has
+/// already reported it.
+fn event_kind(mask: u32, report_self: bool) -> Option<WatchEventKind> {
+    if mask & libc::IN_CREATE != 0 {
+        Some(WatchEventKind::Created)
+    } else if mask & libc::IN_DELETE != 0 {
+        Some(WatchEventKind::Removed)
+    } else if mask & (libc::IN_MOVED_FROM | libc::IN_MOVED_TO) != 0 {
+        Some(WatchEventKind::Renamed)
+    } else if mask & (libc::IN_MODIFY | libc::IN_ATTRIB) != 0 {
+        Some(WatchEventKind::Modified)
+    } else if report_self && mask & libc::IN_DELETE_SELF != 0 {
+        Some(WatchEventKind::Removed)
+    } else if report_self && mask & libc::IN_MOVE_SELF != 0 {
+        Some(WatchEventKind::Renamed)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::InotifySource;
+    use crate::filesystem::watcher::{EventSource, WatchEvent, WatchEventKind};
+    use crate::utils::tempdir::TempDir;
+
+    #[test]
+    fn test_recursive_watch() {
+        let root = TempDir::new("inotify");
+
+        let (sender, receiver) = mpsc::channel();
+        let sender = std::sync::Mutex::new(sender);
+
+        let mut source = InotifySource::new();
+        source
+            .start(Arc::new(move |event| sender.lock().unwrap().send(event).unwrap()))
+            .unwrap();
+        source.watch(root.path(), true).unwrap();
+
+        let next = || receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        fs::create_dir(root.join("nested")).unwrap();
+        assert_eq!(next(), WatchEvent::new(WatchEventKind::Created, root.join("nested")));
+
+        // Give the reader thread a moment to watch the new directory.
+        thread::sleep(Duration::from_millis(200));
+
+        fs::write(root.join("nested/file.txt"), b"hello").unwrap();
+        assert_eq!(next(), WatchEvent::new(WatchEventKind::Created, root.join("nested/file.txt")));
+        assert_eq!(
+            next(),
+            WatchEvent::new(WatchEventKind::Modified, root.join("nested/file.txt"))
+        );
+
+        fs::rename(root.join("nested/file.txt"), root.join("nested/renamed.txt")).unwrap();
+        assert_eq!(next(), WatchEvent::new(WatchEventKind::Renamed, root.join("nested/file.txt")));
+        assert_eq!(
+            next(),
+            WatchEvent::new(WatchEventKind::Renamed, root.join("nested/renamed.txt"))
+        );
+
+        fs::remove_file(root.join("nested/renamed.txt")).unwrap();
+        assert_eq!(
+            next(),
+            WatchEvent::new(WatchEventKind::Removed, root.join("nested/renamed.txt"))
+        );
+
+        source.unwatch(root.path()).unwrap();
+    }
+
+    #[test]
+    fn test_unwatch_nested_root() {
+        let root = TempDir::new("inotify-unwatch");
+        fs::create_dir_all(root.join("outer/inner")).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let sender = std::sync::Mutex::new(sender);
+
+        let mut source = InotifySource::new();
+        source
+            .start(Arc::new(move |event| sender.lock().unwrap().send(event).unwrap()))
+            .unwrap();
+        source.watch(&root.join("outer"), true).unwrap();
+        source.watch(&root.join("outer/inner"), true).unwrap();
+
+        // Unwatching the outer root shouldn't touch the inner one, even though it's beneath it.
+        source.unwatch(&root.join("outer")).unwrap();
+
+        fs::write(root.join("outer/ignored.txt"), b"hello").unwrap();
+        fs::write(root.join("outer/inner/file.txt"), b"hello").unwrap();
+
+        let event = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(
+            event,
+            WatchEvent::new(WatchEventKind::Created, root.join("outer/inner/file.txt"))
+        );
+
+        source.unwatch(&root.join("outer/inner")).unwrap();
+        assert!(source.watches.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+//! Watches files and directories for changes.
+//!
+//! A `Watcher` pulls raw events from an `EventSource` (FSEvents on macOS, inotify on Linux), then
+//! coalesces and debounces them before handing them to your `Dispatcher` on the main thread -
+//! so a burst of writes from an editor's save shows up as one `Modified` event, and it's safe to
+//! update UI straight from `on_ui_message`.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cacao::filesystem::{WatchEvent, Watcher};
+//! use cacao::notification_center::Dispatcher;
+//!
+//! struct ProjectBrowser;
+//!
+//! impl Dispatcher for ProjectBrowser {
+//!     type Message = Vec<WatchEvent>;
+//!
+//!     fn on_ui_message(&self, events: Vec<WatchEvent>) {
+//!         for event in events {
+//!             println!("{:?}: {}", event.kind, event.path.display());
+//!         }
+//!     }
+//! }
+//!
+//! // Hold on to the watcher for as long as you want events.
+//! let mut watcher = Watcher::new(ProjectBrowser).unwrap();
+//! watcher.watch("/tmp/project").unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use crate::foundation::{id, SecurityScopedAccess, NSURL};
+use crate::notification_center::Dispatcher;
+
+#[cfg(target_os = "macos")]
+mod fsevents;
+
+#[cfg(target_os = "macos")]
+pub use fsevents::FSEventsSource;
+
+#[cfg(target_os = "linux")]
+mod inotify;
+
+#[cfg(target_os = "linux")]
+pub use inotify::InotifySource;
+
+/// How long the watcher waits for things to settle before delivering events, by default.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// What happened to a path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WatchEventKind {
+    /// The item was created.
+    Created,
+
+    /// The item's contents or metadata changed.
+    Modified,
+
+    /// The item was renamed or moved. Both the old and new paths get one of these; check whether
+    /// the path exists to tell them apart.
+    Renamed,
+
+    /// The item was removed.
+    Removed,
+
+    /// Events were dropped (e.g, the system's event queue overflowed), so anything at or beneath
+    /// the path may have changed without being reported. Rescan it to catch up.
+    Rescan
+}
+
+/// A change to a watched path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    /// What happened.
+    pub kind: WatchEventKind,
+
+    /// The path it happened to.
+    pub path: PathBuf
+}
+
+impl WatchEvent {
+    /// Creates an event.
+    pub fn new<P: Into<PathBuf>>(kind: WatchEventKind, path: P) -> Self {
+        WatchEvent { kind, path: path.into() }
+    }
+}
+
+/// Called by an `EventSource` with each raw event, from whatever thread it likes.
+pub type EventSink = Arc<dyn Fn(WatchEvent) + Send + Sync>;
+
+/// A source of raw file system events. `Watcher` handles debouncing and delivery, so a source
+/// just needs to report what it sees.
+///
+/// Sources should stop reporting events when they're dropped.
+pub trait EventSource: Send {
+    /// Called once, before anything is watched, with the sink that events should be sent to.
+    fn start(&mut self, sink: EventSink) -> Result<(), Box<dyn Error>>;
+
+    /// Starts watching `path`. If `recursive` is `true` and `path` is a directory, everything
+    /// beneath it is watched too (including directories created later).
+    fn watch(&mut self, path: &Path, recursive: bool) -> Result<(), Box<dyn Error>>;
+
+    /// Stops watching `path`.
+    fn unwatch(&mut self, path: &Path) -> Result<(), Box<dyn Error>>;
+}
+
+/// Returns the event source for the current platform.
+#[cfg(target_os = "macos")]
+pub fn default_source() -> Box<dyn EventSource> {
+    Box::new(FSEventsSource::new())
+}
+
+/// Returns the event source for the current platform.
+#[cfg(target_os = "linux")]
+pub fn default_source() -> Box<dyn EventSource> {
+    Box::new(InotifySource::new())
+}
+
+/// Events waiting to be delivered.
+#[derive(Debug, Default)]
+struct Pending {
+    events: Vec<WatchEvent>,
+    last_event: Option<Instant>,
+    scheduled: bool,
+    debounce: Duration
+}
+
+/// The state shared between a `Watcher` and its event source.
+struct Shared {
+    pending: Mutex<Pending>,
+    deliver: Box<dyn Fn(Vec<WatchEvent>) + Send + Sync>
+}
+
+/// Watches files and directories, delivering debounced events to a `Dispatcher` on the main
+/// thread. Watching stops when this is dropped.
+pub struct Watcher {
+    source: Box<dyn EventSource>,
+    shared: Arc<Shared>,
+    recursive: bool,
+    scoped_access: HashMap<PathBuf, SecurityScopedAccess<'static>>
+}
+
+impl Watcher {
+    /// Creates a watcher that uses the platform's event source, and delivers events to
+    /// `handler`'s `on_ui_message` method.
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    pub fn new<T>(handler: T) -> Result<Self, Box<dyn Error>>
+    where
+        T: Dispatcher<Message = Vec<WatchEvent>> + Send + Sync + 'static
+    {
+        Watcher::with_source(default_source(), handler)
+    }
+
+    /// Creates a watcher that pulls events from `source`. This is mostly useful for testing, or
+    /// for platforms we don't have a source for.
+    pub fn with_source<T>(mut source: Box<dyn EventSource>, handler: T) -> Result<Self, Box<dyn Error>>
+    where
+        T: Dispatcher<Message = Vec<WatchEvent>> + Send + Sync + 'static
+    {
+        let shared = Arc::new(Shared {
+            pending: Mutex::new(Pending {
+                debounce: DEFAULT_DEBOUNCE,
+                ..Pending::default()
+            }),
+
+            deliver: Box::new(move |events| handler.on_ui_message(events))
+        });
+
+        // The source only holds a weak reference, so that nothing is delivered after the watcher
+        // has been dropped.
+        let weak = Arc::downgrade(&shared);
+        source.start(Arc::new(move |event| {
+            if let Some(shared) = weak.upgrade() {
+                push(&shared, event);
+            }
+        }))?;
+
+        Ok(Watcher {
+            source,
+            shared,
+            recursive: true,
+            scoped_access: HashMap::new()
+        })
+    }
+
+    /// Sets how long to wait, after the most recent event, before delivering. Defaults to
+    /// `DEFAULT_DEBOUNCE`.
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.shared.pending.lock().unwrap().debounce = debounce;
+    }
+
+    /// Sets whether paths watched from here on are watched recursively. Defaults to `true`.
+    pub fn set_recursive(&mut self, recursive: bool) {
+        self.recursive = recursive;
+    }
+
+    /// Starts watching `path`.
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        self.source.watch(path.as_ref(), self.recursive)
+    }
+
+    /// Starts watching the file or directory that `url` points to. If it's a security-scoped URL
+    /// (e.g, from a `FileSelectPanel`, or a resolved bookmark), access is held until it's
+    /// unwatched, or the watcher is dropped.
+    pub fn watch_url(&mut self, url: &NSURL) -> Result<(), Box<dyn Error>> {
+        let url: NSURL<'static> = NSURL::retain(&*url.objc as *const _ as id);
+        let access = url.security_scoped_access();
+        let path = url.pathbuf();
+        self.watch(&path)?;
+        self.scoped_access.insert(path, access);
+        Ok(())
+    }
+
+    /// Stops watching `path`, releasing any security-scoped access that `watch_url` took for it.
+    pub fn unwatch<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        self.source.unwatch(path)?;
+        self.scoped_access.remove(path);
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("recursive", &self.recursive)
+            .field("pending", &*self.shared.pending.lock().unwrap())
+            .finish()
+    }
+}
+
+/// Queues an event, scheduling a delivery if one isn't already pending.
+fn push(shared: &Arc<Shared>, event: WatchEvent) {
+    let mut pending = shared.pending.lock().unwrap();
+    coalesce(&mut pending.events, event);
+    pending.last_event = Some(Instant::now());
+
+    if !pending.scheduled {
+        pending.scheduled = true;
+        schedule(Arc::downgrade(shared), pending.debounce);
+    }
+}
+
+fn schedule(shared: Weak<Shared>, delay: Duration) {
+    dispatch::Queue::main().exec_after(delay, move || flush(shared));
+}
+
+/// Runs on the main queue. If events are still arriving, waits a bit longer; otherwise,
+/// delivers everything that's queued up.
+fn flush(shared: Weak<Shared>) {
+    let shared = match shared.upgrade() {
+        Some(shared) => shared,
+        None => return
+    };
+
+    let events = {
+        let mut pending = shared.pending.lock().unwrap();
+        let elapsed = pending.last_event.map(|last| last.elapsed()).unwrap_or_default();
+
+        if elapsed < pending.debounce {
+            schedule(Arc::downgrade(&shared), pending.debounce - elapsed);
+            return;
+        }
+
+        pending.scheduled = false;
+        std::mem::take(&mut pending.events)
+    };
+
+    if !events.is_empty() {
+        (shared.deliver)(events);
+    }
+}
+
+/// Folds `event` into the queued events, so each path reports what actually changed since the
+/// last delivery: a file that was created and then written is just `Created`, one that was
+/// created and then removed never shows up, and so on.
+fn coalesce(events: &mut Vec<WatchEvent>, event: WatchEvent) {
+    use WatchEventKind::*;
+
+    let index = match events.iter().rposition(|queued| queued.path == event.path) {
+        Some(index) => index,
+        None => {
+            events.push(event);
+            return;
+        }
+    };
+
+    let kind = match (events[index].kind, event.kind) {
+        (Created, Modified) => Some(Created),
+        (Created, Removed) => None,
+        (Modified, Modified) => Some(Modified),
+        (Modified, Removed) | (Removed, Removed) => Some(Removed),
+
+        // Atomic saves remove (or rename away) the original and put a new file in its place.
+        (Removed, Created) => Some(Modified),
+
+        (Rescan, Rescan) => Some(Rescan),
+
+        _ => {
+            events.push(event);
+            return;
+        }
+    };
+
+    match kind {
+        Some(kind) => events[index].kind = kind,
+        None => {
+            events.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{coalesce, WatchEvent, WatchEventKind::*};
+
+    #[test]
+    fn test_coalesce() {
+        let mut events = Vec::new();
+        coalesce(&mut events, WatchEvent::new(Created, "/a"));
+        coalesce(&mut events, WatchEvent::new(Modified, "/a"));
+        coalesce(&mut events, WatchEvent::new(Modified, "/b"));
+        coalesce(&mut events, WatchEvent::new(Modified, "/b"));
+        coalesce(&mut events, WatchEvent::new(Created, "/c"));
+        coalesce(&mut events, WatchEvent::new(Removed, "/c"));
+        coalesce(&mut events, WatchEvent::new(Removed, "/d"));
+        coalesce(&mut events, WatchEvent::new(Created, "/d"));
+        coalesce(&mut events, WatchEvent::new(Renamed, "/e"));
+        coalesce(&mut events, WatchEvent::new(Renamed, "/e"));
+        coalesce(&mut events, WatchEvent::new(Rescan, "/f"));
+        coalesce(&mut events, WatchEvent::new(Rescan, "/f"));
+
+        assert_eq!(events, vec![
+            WatchEvent::new(Created, "/a"),
+            WatchEvent::new(Modified, "/b"),
+            WatchEvent::new(Modified, "/d"),
+            WatchEvent::new(Renamed, "/e"),
+            WatchEvent::new(Renamed, "/e"),
+            WatchEvent::new(Rescan, "/f")
+        ]);
+    }
+}
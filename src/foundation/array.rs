@@ -1,10 +1,12 @@
+use std::iter::FromIterator;
+use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::Id;
 
-use crate::foundation::id;
+use crate::foundation::{id, nil, to_bool, NSUInteger, BOOL};
 
 /// A wrapper for `NSArray` that makes common operations in our framework a bit easier to handle
 /// and reason about. This also provides a central place to look at replacing with `CFArray` if
@@ -36,11 +38,28 @@ impl NSArray {
         NSArray(unsafe { Id::from_retained_ptr(array) })
     }
 
+    /// A helper method for determining if a given `NSObject` is an `NSArray`.
+    pub fn is(obj: id) -> bool {
+        let result: BOOL = unsafe { msg_send![obj, isKindOfClass: class!(NSArray)] };
+        to_bool(result)
+    }
+
     /// Returns the `count` (`len()` equivalent) for the backing `NSArray`.
     pub fn count(&self) -> usize {
         unsafe { msg_send![&*self.0, count] }
     }
 
+    /// Returns the object at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<id> {
+        object_at_index(&self.0, index)
+    }
+
+    /// Returns an iterator over the objects in this array. These aren't retained, and are valid for
+    /// as long as the array is.
+    pub fn iter(&self) -> NSArrayIter<'_> {
+        NSArrayIter::new(&self.0)
+    }
+
     /// A helper method for mapping over the backing `NSArray` items and producing a Rust `Vec<T>`.
     /// Often times we need to map in this framework to convert between Rust types, so isolating
     /// this out makes life much easier.
@@ -59,6 +78,22 @@ impl NSArray {
     }
 }
 
+impl<'a> IntoIterator for &'a NSArray {
+    type Item = id;
+    type IntoIter = NSArrayIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<id> for NSArray {
+    /// Collects objects into an `NSArray`, which retains them.
+    fn from_iter<I: IntoIterator<Item = id>>(iter: I) -> Self {
+        NSArray::from(iter.into_iter().collect::<Vec<id>>())
+    }
+}
+
 impl From<Vec<&Object>> for NSArray {
     /// Given a set of `Object`s, creates an `NSArray` that holds them.
     fn from(objects: Vec<&Object>) -> Self {
@@ -105,3 +140,237 @@ impl DerefMut for NSArray {
         &mut *self.0
     }
 }
+
+/// A wrapper for `NSMutableArray`.
+#[derive(Debug)]
+pub struct NSMutableArray(pub Id<Object>);
+
+impl Default for NSMutableArray {
+    /// Returns an empty `NSMutableArray`.
+    fn default() -> Self {
+        NSMutableArray::new()
+    }
+}
+
+impl NSMutableArray {
+    /// Constructs an empty `NSMutableArray` and retains it.
+    pub fn new() -> Self {
+        NSMutableArray(unsafe { Id::from_ptr(msg_send![class!(NSMutableArray), array]) })
+    }
+
+    /// Constructs an empty `NSMutableArray` with room for `capacity` objects.
+    pub fn with_capacity(capacity: usize) -> Self {
+        NSMutableArray(unsafe { Id::from_ptr(msg_send![class!(NSMutableArray), arrayWithCapacity: capacity as NSUInteger]) })
+    }
+
+    /// In some cases, we're vended an `NSMutableArray` by the system that we need to call retain
+    /// on. This handles that case.
+    pub fn retain(array: id) -> Self {
+        NSMutableArray(unsafe { Id::from_ptr(array) })
+    }
+
+    /// Wraps an `NSMutableArray` that we already own, without retaining it.
+    pub fn from_retained(array: id) -> Self {
+        NSMutableArray(unsafe { Id::from_retained_ptr(array) })
+    }
+
+    /// Returns the number of objects in this array.
+    pub fn count(&self) -> usize {
+        unsafe { msg_send![&*self.0, count] }
+    }
+
+    /// Returns the object at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<id> {
+        object_at_index(&self.0, index)
+    }
+
+    /// Appends an object, which the array retains.
+    pub fn push(&mut self, object: id) {
+        unsafe {
+            let _: () = msg_send![&*self.0, addObject: object];
+        }
+    }
+
+    /// Inserts an object at `index`, shifting everything after it along.
+    ///
+    /// Panics if `index` is greater than the count, as `Vec::insert` does.
+    pub fn insert(&mut self, index: usize, object: id) {
+        let count = self.count();
+        assert!(
+            index <= count,
+            "insertion index (is {}) should be <= count (is {})",
+            index,
+            count
+        );
+
+        unsafe {
+            let _: () = msg_send![&*self.0, insertObject:object atIndex:index as NSUInteger];
+        }
+    }
+
+    /// Removes the object at `index`, if there is one.
+    pub fn remove(&mut self, index: usize) {
+        if index < self.count() {
+            unsafe {
+                let _: () = msg_send![&*self.0, removeObjectAtIndex: index as NSUInteger];
+            }
+        }
+    }
+
+    /// Removes every object from this array.
+    pub fn clear(&mut self) {
+        unsafe {
+            let _: () = msg_send![&*self.0, removeAllObjects];
+        }
+    }
+
+    /// Returns an iterator over the objects in this array. These aren't retained, and are valid for
+    /// as long as the array is (and isn't mutated).
+    pub fn iter(&self) -> NSArrayIter<'_> {
+        NSArrayIter::new(&self.0)
+    }
+}
+
+impl<'a> IntoIterator for &'a NSMutableArray {
+    type Item = id;
+    type IntoIter = NSArrayIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<id> for NSMutableArray {
+    /// Collects objects into an `NSMutableArray`, which retains them.
+    fn from_iter<I: IntoIterator<Item = id>>(iter: I) -> Self {
+        let mut array = NSMutableArray::new();
+        array.extend(iter);
+        array
+    }
+}
+
+impl Extend<id> for NSMutableArray {
+    fn extend<I: IntoIterator<Item = id>>(&mut self, iter: I) {
+        for object in iter {
+            self.push(object);
+        }
+    }
+}
+
+impl From<NSMutableArray> for NSArray {
+    /// `NSMutableArray` is a subclass of `NSArray`, so this just rewraps it.
+    fn from(array: NSMutableArray) -> Self {
+        NSArray(array.0)
+    }
+}
+
+impl From<NSMutableArray> for id {
+    /// Consumes and returns the pointer to the underlying `NSMutableArray`.
+    fn from(mut array: NSMutableArray) -> Self {
+        &mut *array.0
+    }
+}
+
+impl Deref for NSMutableArray {
+    type Target = Object;
+
+    /// Derefs to the underlying Objective-C Object.
+    fn deref(&self) -> &Object {
+        &*self.0
+    }
+}
+
+impl DerefMut for NSMutableArray {
+    /// Derefs to the underlying Objective-C Object.
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut *self.0
+    }
+}
+
+/// An iterator over the objects in an `NSArray` (or `NSMutableArray`, or anything else vended as
+/// an array - e.g, `NSSet::iter()`). The array is retained for the lifetime of the iterator.
+#[derive(Debug)]
+pub struct NSArrayIter<'a> {
+    array: Id<Object>,
+    index: usize,
+    count: usize,
+    phantom: PhantomData<&'a Object>
+}
+
+impl<'a> NSArrayIter<'a> {
+    /// Retains `array` and starts iterating from the front.
+    pub(crate) fn new(array: &Object) -> Self {
+        let array = unsafe { Id::from_ptr(array as *const Object as id) };
+        let count: usize = unsafe { msg_send![&*array, count] };
+
+        NSArrayIter {
+            array,
+            index: 0,
+            count,
+            phantom: PhantomData
+        }
+    }
+}
+
+impl Iterator for NSArrayIter<'_> {
+    type Item = id;
+
+    fn next(&mut self) -> Option<id> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let item = object_at_index(&self.array, self.index);
+        self.index += 1;
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.count - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for NSArrayIter<'_> {}
+
+/// Bounds-checked `objectAtIndex:`, which would otherwise throw.
+fn object_at_index(array: &Object, index: usize) -> Option<id> {
+    let count: usize = unsafe { msg_send![array, count] };
+
+    match index < count {
+        true => {
+            let item: id = unsafe { msg_send![array, objectAtIndex: index as NSUInteger] };
+            Some(item).filter(|item| *item != nil)
+        },
+
+        false => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NSArray, NSMutableArray};
+    use crate::foundation::{id, NSString};
+
+    #[test]
+    fn test_iteration() {
+        let strings = [NSString::new("a"), NSString::new("b"), NSString::new("c")];
+        let objects: Vec<id> = strings.iter().map(|s| &*s.objc as *const _ as id).collect();
+
+        let array: NSArray = objects.iter().copied().collect();
+        assert_eq!(array.count(), 3);
+        assert_eq!(array.iter().collect::<Vec<id>>(), objects);
+        assert_eq!(array.iter().len(), 3);
+        assert_eq!(array.get(1), Some(objects[1]));
+        assert_eq!(array.get(3), None);
+
+        let mut mutable: NSMutableArray = array.iter().collect();
+        mutable.insert(0, objects[2]);
+        mutable.remove(3);
+        assert_eq!(mutable.iter().collect::<Vec<id>>(), vec![objects[2], objects[0], objects[1]]);
+
+        mutable.clear();
+        assert_eq!(mutable.count(), 0);
+        assert_eq!(mutable.iter().next(), None);
+    }
+}
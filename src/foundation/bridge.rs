@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use objc::runtime::Class;
+use objc::{class, msg_send, sel, sel_impl};
+
+use crate::foundation::{
+    id, nil, to_bool, NSArray, NSData, NSDate, NSDictionary, NSMutableDictionary, NSNumber, NSString, BOOL, NO, YES
+};
+
+/// A Rust representation of a property list object graph: anything made of `NSString`,
+/// `NSNumber`, `NSData`, `NSDate`, `NSArray`, `NSDictionary` and `NSNull`.
+///
+/// This is the one place that sniffs Foundation types, and is shared by `UserDefaults`, the
+/// pasteboard, user activities and notification `userInfo` dictionaries. Numbers keep enough of
+/// their encoding to round-trip without losing range or precision: signed and unsigned integers of
+/// any width, `float`s and `double`s, and booleans are all distinct.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ObjcValue {
+    /// `NSNull`.
+    Null,
+
+    /// An `NSNumber` holding a `BOOL` (or C `bool`).
+    Bool(bool),
+
+    /// An `NSNumber` holding a signed integer (`c`, `s`, `i`, `l` or `q`).
+    Integer(i64),
+
+    /// An `NSNumber` holding an unsigned integer (`C`, `S`, `I`, `L` or `Q`).
+    UnsignedInteger(u64),
+
+    /// An `NSNumber` holding a `float`.
+    Float(f32),
+
+    /// An `NSNumber` holding a `double`.
+    Double(f64),
+
+    /// An `NSString`.
+    String(String),
+
+    /// An `NSData`.
+    Data(Vec<u8>),
+
+    /// An `NSDate`.
+    Date(SystemTime),
+
+    /// An `NSArray`.
+    Array(Vec<ObjcValue>),
+
+    /// An `NSDictionary`. Property lists only allow string keys; entries with other keys are
+    /// skipped.
+    Dictionary(HashMap<String, ObjcValue>)
+}
+
+impl ObjcValue {
+    /// Converts an object graph into an `ObjcValue`, recursing into arrays and dictionaries.
+    /// Returns `None` for `nil` and for anything that isn't a property list type. Inside
+    /// collections, unsupported objects are skipped.
+    pub fn from_object(object: id) -> Option<ObjcValue> {
+        if object == nil {
+            return None;
+        }
+
+        if NSString::is(object) {
+            return Some(ObjcValue::String(NSString::retain(object).to_string()));
+        }
+
+        if NSNumber::is(object) {
+            return number_to_value(&NSNumber::retain(object));
+        }
+
+        if NSData::is(object) {
+            return Some(ObjcValue::Data(NSData::retain(object).into_vec()));
+        }
+
+        if NSDate::is(object) {
+            return Some(ObjcValue::Date(NSDate::retain(object).to_system_time()));
+        }
+
+        if NSArray::is(object) {
+            let array = NSArray::retain(object);
+            return Some(ObjcValue::Array(array.iter().filter_map(ObjcValue::from_object).collect()));
+        }
+
+        if NSDictionary::is(object) {
+            let dictionary = NSDictionary::retain(object);
+
+            let map = HashMap::from(&dictionary)
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, ObjcValue::from_object(value)?)))
+                .collect();
+
+            return Some(ObjcValue::Dictionary(map));
+        }
+
+        if is_kind_of(object, class!(NSNull)) {
+            return Some(ObjcValue::Null);
+        }
+
+        None
+    }
+
+    /// Converts a `userInfo`-style dictionary into a map, returning an empty one for `nil` or for
+    /// anything that isn't a dictionary.
+    pub fn dictionary_from_object(object: id) -> HashMap<String, ObjcValue> {
+        match ObjcValue::from_object(object) {
+            Some(ObjcValue::Dictionary(map)) => map,
+            _ => HashMap::new()
+        }
+    }
+}
+
+impl From<ObjcValue> for id {
+    /// Builds the equivalent Foundation object graph.
+    fn from(value: ObjcValue) -> Self {
+        unsafe {
+            match value {
+                ObjcValue::Null => msg_send![class!(NSNull), null],
+                ObjcValue::Bool(b) => msg_send![class!(NSNumber), numberWithBool:match b {
+                    true => YES,
+                    false => NO
+                }],
+                ObjcValue::Integer(i) => msg_send![class!(NSNumber), numberWithLongLong: i],
+                ObjcValue::UnsignedInteger(u) => msg_send![class!(NSNumber), numberWithUnsignedLongLong: u],
+                ObjcValue::Float(f) => msg_send![class!(NSNumber), numberWithFloat: f],
+                ObjcValue::Double(d) => msg_send![class!(NSNumber), numberWithDouble: d],
+                ObjcValue::String(s) => NSString::new(&s).into(),
+                ObjcValue::Data(data) => NSData::new(data).into(),
+                ObjcValue::Date(date) => NSDate::from(date).into(),
+                ObjcValue::Array(values) => values.into_iter().map(id::from).collect::<NSArray>().into(),
+                ObjcValue::Dictionary(map) => map
+                    .into_iter()
+                    .map(|(key, value)| (key, id::from(value)))
+                    .collect::<NSMutableDictionary>()
+                    .into_inner()
+            }
+        }
+    }
+}
+
+/// Reads an `NSNumber` according to its `objCType`. See <https://nshipster.com/type-encodings/>.
+fn number_to_value(number: &NSNumber) -> Option<ObjcValue> {
+    let objc = &*number.0;
+
+    unsafe {
+        match number.objc_type() {
+            "B" => Some(ObjcValue::Bool(number.as_bool())),
+
+            // `BOOL` is a `signed char` on most platforms, so booleans and chars share an
+            // encoding. Booleans come from a dedicated class, though.
+            "c" if is_boolean(objc as *const _ as id) => Some(ObjcValue::Bool(number.as_bool())),
+
+            "c" | "s" | "i" | "l" | "q" => Some(ObjcValue::Integer(msg_send![objc, longLongValue])),
+            "C" | "S" | "I" | "L" | "Q" => Some(ObjcValue::UnsignedInteger(msg_send![objc, unsignedLongLongValue])),
+            "f" => Some(ObjcValue::Float(msg_send![objc, floatValue])),
+            "d" => Some(ObjcValue::Double(msg_send![objc, doubleValue])),
+            _ => None
+        }
+    }
+}
+
+/// Whether `number` is of the class that `[NSNumber numberWithBool:]` vends.
+fn is_boolean(number: id) -> bool {
+    unsafe {
+        let boolean: id = msg_send![class!(NSNumber), numberWithBool: YES];
+        let boolean_class: *const Class = msg_send![boolean, class];
+        let number_class: *const Class = msg_send![number, class];
+        boolean_class == number_class
+    }
+}
+
+fn is_kind_of(object: id, class: &Class) -> bool {
+    let result: BOOL = unsafe { msg_send![object, isKindOfClass: class] };
+    to_bool(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use objc::{class, msg_send, sel, sel_impl};
+
+    use super::ObjcValue;
+    use crate::foundation::{id, NSNumber, NO, YES};
+
+    #[test]
+    fn test_round_trip() {
+        let mut map = HashMap::new();
+        map.insert("null".to_string(), ObjcValue::Null);
+        map.insert("bool".to_string(), ObjcValue::Bool(true));
+        map.insert("negative".to_string(), ObjcValue::Integer(-5));
+        map.insert("large".to_string(), ObjcValue::UnsignedInteger(u64::MAX));
+        map.insert("float".to_string(), ObjcValue::Float(0.1));
+        map.insert("double".to_string(), ObjcValue::Double(0.1));
+        map.insert("data".to_string(), ObjcValue::Data(vec![0, 1, 2]));
+        map.insert("date".to_string(), ObjcValue::Date(UNIX_EPOCH + Duration::from_secs(1_000)));
+        map.insert(
+            "array".to_string(),
+            ObjcValue::Array(vec![ObjcValue::String("a".into()), ObjcValue::Bool(false)])
+        );
+
+        let value = ObjcValue::Dictionary(map);
+        let object: id = value.clone().into();
+        assert_eq!(ObjcValue::from_object(object), Some(value));
+    }
+
+    #[test]
+    fn test_number_encodings() {
+        let cases: [(id, ObjcValue); 6] = unsafe {
+            [
+                (msg_send![class!(NSNumber), numberWithChar: -3i8], ObjcValue::Integer(-3)),
+                (msg_send![class!(NSNumber), numberWithInt: 7i32], ObjcValue::Integer(7)),
+                (msg_send![class!(NSNumber), numberWithFloat: 1.5f32], ObjcValue::Float(1.5)),
+                (msg_send![class!(NSNumber), numberWithBool: YES], ObjcValue::Bool(true)),
+                (msg_send![class!(NSNumber), numberWithBool: NO], ObjcValue::Bool(false)),
+                (
+                    msg_send![class!(NSNumber), numberWithUnsignedLongLong: u64::MAX],
+                    ObjcValue::UnsignedInteger(u64::MAX)
+                )
+            ]
+        };
+
+        for (object, expected) in cases.iter() {
+            let number = NSNumber::retain(*object);
+            assert_eq!(
+                ObjcValue::from_object(*object).as_ref(),
+                Some(expected),
+                "{}",
+                number.objc_type()
+            );
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::Id;
+
+use crate::foundation::{id, to_bool, BOOL};
+
+/// A wrapper for `NSDate`. This converts to and from `SystemTime`, which is what you generally
+/// want to work with on the Rust side.
+#[derive(Debug)]
+pub struct NSDate(pub Id<Object>);
+
+impl NSDate {
+    /// Returns the current date and time.
+    pub fn now() -> Self {
+        NSDate(unsafe { Id::from_ptr(msg_send![class!(NSDate), date]) })
+    }
+
+    /// Creates a date from (fractional) seconds relative to the Unix epoch.
+    pub fn with_timestamp(timestamp: f64) -> Self {
+        NSDate(unsafe { Id::from_ptr(msg_send![class!(NSDate), dateWithTimeIntervalSince1970: timestamp]) })
+    }
+
+    /// In some cases, we're vended an `NSDate` by the system that we need to call retain on.
+    /// This handles that case.
+    pub fn retain(date: id) -> Self {
+        NSDate(unsafe { Id::from_ptr(date) })
+    }
+
+    /// In some cases, we're vended an `NSDate` by the system, and it's ideal to not retain that.
+    /// This handles that edge case.
+    pub fn from_retained(date: id) -> Self {
+        NSDate(unsafe { Id::from_retained_ptr(date) })
+    }
+
+    /// A helper method for determining if a given `NSObject` is an `NSDate`.
+    pub fn is(obj: id) -> bool {
+        let result: BOOL = unsafe { msg_send![obj, isKindOfClass: class!(NSDate)] };
+        to_bool(result)
+    }
+
+    /// Returns (fractional) seconds relative to the Unix epoch, which is negative for dates
+    /// before 1970.
+    pub fn timestamp(&self) -> f64 {
+        unsafe { msg_send![&*self.0, timeIntervalSince1970] }
+    }
+
+    /// Converts this date into a `SystemTime`.
+    pub fn to_system_time(&self) -> SystemTime {
+        date_from_unix_timestamp(self.timestamp())
+    }
+}
+
+impl From<SystemTime> for NSDate {
+    fn from(date: SystemTime) -> Self {
+        NSDate::with_timestamp(date_to_unix_timestamp(&date))
+    }
+}
+
+impl From<&NSDate> for SystemTime {
+    fn from(date: &NSDate) -> Self {
+        date.to_system_time()
+    }
+}
+
+impl From<NSDate> for id {
+    /// Consumes and returns the pointer to the underlying `NSDate`.
+    fn from(mut date: NSDate) -> Self {
+        &mut *date.0
+    }
+}
+
+impl Deref for NSDate {
+    type Target = Object;
+
+    /// Derefs to the underlying Objective-C Object.
+    fn deref(&self) -> &Object {
+        &*self.0
+    }
+}
+
+impl DerefMut for NSDate {
+    /// Derefs to the underlying Objective-C Object.
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut *self.0
+    }
+}
+
+/// Converts a `SystemTime` into (fractional) seconds relative to the Unix epoch, which is negative
+/// for dates before 1970.
+pub(crate) fn date_to_unix_timestamp(date: &SystemTime) -> f64 {
+    match date.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs_f64(),
+        Err(error) => -error.duration().as_secs_f64()
+    }
+}
+
+/// Converts (fractional) seconds relative to the Unix epoch into a `SystemTime`.
+pub(crate) fn date_from_unix_timestamp(timestamp: f64) -> SystemTime {
+    match timestamp >= 0. {
+        true => UNIX_EPOCH + Duration::from_secs_f64(timestamp),
+        false => UNIX_EPOCH - Duration::from_secs_f64(-timestamp)
+    }
+}
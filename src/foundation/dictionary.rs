@@ -1,11 +1,130 @@
 use std::collections::HashMap;
+use std::iter::FromIterator;
 use std::ops::{Deref, DerefMut};
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::Id;
 
-use crate::foundation::{id, NSString};
+use crate::foundation::{id, nil, to_bool, NSArray, NSArrayIter, NSString, BOOL};
+
+/// A wrapper for `NSDictionary`, which is what the system generally vends (e.g, `userInfo`
+/// dictionaries, file attributes).
+#[derive(Debug)]
+pub struct NSDictionary(pub Id<Object>);
+
+impl Default for NSDictionary {
+    /// Returns an empty `NSDictionary`.
+    fn default() -> Self {
+        NSDictionary::new()
+    }
+}
+
+impl NSDictionary {
+    /// Returns an empty `NSDictionary`.
+    pub fn new() -> Self {
+        NSDictionary(unsafe { Id::from_ptr(msg_send![class!(NSDictionary), dictionary]) })
+    }
+
+    /// In some cases, we're vended an `NSDictionary` by the system that we need to call retain on.
+    /// This handles that case.
+    pub fn retain(dictionary: id) -> Self {
+        NSDictionary(unsafe { Id::from_ptr(dictionary) })
+    }
+
+    /// In some cases, we're vended an `NSDictionary` by the system, and it's ideal to not retain
+    /// that. This handles that edge case.
+    pub fn from_retained(dictionary: id) -> Self {
+        NSDictionary(unsafe { Id::from_retained_ptr(dictionary) })
+    }
+
+    /// A helper method for determining if a given `NSObject` is an `NSDictionary`.
+    pub fn is(obj: id) -> bool {
+        let result: BOOL = unsafe { msg_send![obj, isKindOfClass: class!(NSDictionary)] };
+        to_bool(result)
+    }
+
+    /// Returns the number of entries in this dictionary.
+    pub fn count(&self) -> usize {
+        unsafe { msg_send![&*self.0, count] }
+    }
+
+    /// Returns the object stored under the string `key`, if there is one.
+    pub fn get(&self, key: &str) -> Option<id> {
+        object_for_key(&self.0, &*NSString::new(key))
+    }
+
+    /// Returns the object stored under `key`, for dictionaries keyed by something other than
+    /// strings.
+    pub fn get_object(&self, key: id) -> Option<id> {
+        object_for_key(&self.0, key)
+    }
+
+    /// Returns the keys of this dictionary, in no particular order.
+    pub fn keys(&self) -> NSArray {
+        NSArray::retain(unsafe { msg_send![&*self.0, allKeys] })
+    }
+
+    /// Returns the values of this dictionary, in the same order as `keys()`.
+    pub fn values(&self) -> NSArray {
+        NSArray::retain(unsafe { msg_send![&*self.0, allValues] })
+    }
+
+    /// Returns an iterator over the `(key, value)` pairs in this dictionary. These aren't retained,
+    /// and are valid for as long as the dictionary is.
+    pub fn iter(&self) -> NSDictionaryIter<'_> {
+        NSDictionaryIter::new(&self.0)
+    }
+}
+
+impl<'a> IntoIterator for &'a NSDictionary {
+    type Item = (id, id);
+    type IntoIter = NSDictionaryIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K> FromIterator<(K, id)> for NSDictionary
+where
+    K: AsRef<str>
+{
+    /// Collects string-keyed objects into an `NSDictionary`, which retains them.
+    fn from_iter<I: IntoIterator<Item = (K, id)>>(iter: I) -> Self {
+        NSMutableDictionary::from_iter(iter).into()
+    }
+}
+
+impl From<&NSDictionary> for HashMap<String, id> {
+    /// Copies out the entries with string keys; anything keyed by another type is skipped.
+    fn from(dictionary: &NSDictionary) -> Self {
+        string_keyed_entries(dictionary.iter())
+    }
+}
+
+impl From<NSDictionary> for id {
+    /// Consumes and returns the pointer to the underlying `NSDictionary`.
+    fn from(mut dictionary: NSDictionary) -> Self {
+        &mut *dictionary.0
+    }
+}
+
+impl Deref for NSDictionary {
+    type Target = Object;
+
+    /// Derefs to the underlying Objective-C Object.
+    fn deref(&self) -> &Object {
+        &*self.0
+    }
+}
+
+impl DerefMut for NSDictionary {
+    /// Derefs to the underlying Objective-C Object.
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut *self.0
+    }
+}
 
 /// A wrapper for `NSMutableDictionary`.
 #[derive(Debug)]
@@ -38,12 +157,86 @@ impl NSMutableDictionary {
         }
     }
 
+    /// Removes the object stored under the string `key`, if there is one.
+    pub fn remove(&mut self, key: &str) {
+        unsafe {
+            let _: () = msg_send![&*self.0, removeObjectForKey:&*NSString::new(key)];
+        }
+    }
+
+    /// Returns the number of entries in this dictionary.
+    pub fn count(&self) -> usize {
+        unsafe { msg_send![&*self.0, count] }
+    }
+
+    /// Returns the object stored under the string `key`, if there is one.
+    pub fn get(&self, key: &str) -> Option<id> {
+        object_for_key(&self.0, &*NSString::new(key))
+    }
+
+    /// Returns the keys of this dictionary, in no particular order.
+    pub fn keys(&self) -> NSArray {
+        NSArray::retain(unsafe { msg_send![&*self.0, allKeys] })
+    }
+
+    /// Returns an iterator over the `(key, value)` pairs in this dictionary. These aren't retained,
+    /// and are valid for as long as the dictionary is (and isn't mutated).
+    pub fn iter(&self) -> NSDictionaryIter<'_> {
+        NSDictionaryIter::new(&self.0)
+    }
+
     /// Consumes and returns the underlying `NSMutableDictionary`.
     pub fn into_inner(mut self) -> id {
         &mut *self.0
     }
 }
 
+impl<'a> IntoIterator for &'a NSMutableDictionary {
+    type Item = (id, id);
+    type IntoIter = NSDictionaryIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K> FromIterator<(K, id)> for NSMutableDictionary
+where
+    K: AsRef<str>
+{
+    /// Collects string-keyed objects into an `NSMutableDictionary`, which retains them.
+    fn from_iter<I: IntoIterator<Item = (K, id)>>(iter: I) -> Self {
+        let mut dictionary = NSMutableDictionary::new();
+        dictionary.extend(iter);
+        dictionary
+    }
+}
+
+impl<K> Extend<(K, id)> for NSMutableDictionary
+where
+    K: AsRef<str>
+{
+    fn extend<I: IntoIterator<Item = (K, id)>>(&mut self, iter: I) {
+        for (key, object) in iter {
+            self.insert(NSString::new(key.as_ref()), object);
+        }
+    }
+}
+
+impl From<&NSMutableDictionary> for HashMap<String, id> {
+    /// Copies out the entries with string keys; anything keyed by another type is skipped.
+    fn from(dictionary: &NSMutableDictionary) -> Self {
+        string_keyed_entries(dictionary.iter())
+    }
+}
+
+impl From<NSMutableDictionary> for NSDictionary {
+    /// `NSMutableDictionary` is a subclass of `NSDictionary`, so this just rewraps it.
+    fn from(dictionary: NSMutableDictionary) -> Self {
+        NSDictionary(dictionary.0)
+    }
+}
+
 impl Deref for NSMutableDictionary {
     type Target = Object;
 
@@ -59,3 +252,83 @@ impl DerefMut for NSMutableDictionary {
         &mut *self.0
     }
 }
+
+/// An iterator over the `(key, value)` pairs of an `NSDictionary` or `NSMutableDictionary`. This
+/// walks a snapshot of the keys taken when it was created.
+#[derive(Debug)]
+pub struct NSDictionaryIter<'a> {
+    dictionary: Id<Object>,
+    keys: NSArrayIter<'a>
+}
+
+impl<'a> NSDictionaryIter<'a> {
+    fn new(dictionary: &Object) -> Self {
+        let keys: id = unsafe { msg_send![dictionary, allKeys] };
+
+        NSDictionaryIter {
+            dictionary: unsafe { Id::from_ptr(dictionary as *const Object as id) },
+            keys: NSArrayIter::new(unsafe { &*keys })
+        }
+    }
+}
+
+impl Iterator for NSDictionaryIter<'_> {
+    type Item = (id, id);
+
+    fn next(&mut self) -> Option<(id, id)> {
+        let key = self.keys.next()?;
+        let value = object_for_key(&self.dictionary, key)?;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+/// `objectForKey:`, mapping `nil` to `None`.
+fn object_for_key(dictionary: &Object, key: *const Object) -> Option<id> {
+    let object: id = unsafe { msg_send![dictionary, objectForKey: key] };
+    Some(object).filter(|object| *object != nil)
+}
+
+fn string_keyed_entries(entries: NSDictionaryIter<'_>) -> HashMap<String, id> {
+    entries
+        .filter(|(key, _)| NSString::is(*key))
+        .map(|(key, value)| (NSString::retain(key).to_string(), value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{NSDictionary, NSMutableDictionary};
+    use crate::foundation::{id, NSNumber, NSString};
+
+    #[test]
+    fn test_dictionary() {
+        let one = NSNumber::integer(1);
+        let two = NSNumber::integer(2);
+        let (one, two) = (&*one.0 as *const _ as id, &*two.0 as *const _ as id);
+
+        let mut map = HashMap::new();
+        map.insert("one".to_string(), one);
+        map.insert("two".to_string(), two);
+
+        let dictionary: NSDictionary = map.clone().into_iter().collect();
+        assert_eq!(dictionary.count(), 2);
+        assert_eq!(dictionary.get("one"), Some(one));
+        assert_eq!(dictionary.get("three"), None);
+        assert_eq!(dictionary.keys().count(), 2);
+        assert_eq!(dictionary.iter().count(), 2);
+        assert_eq!(HashMap::from(&dictionary), map);
+
+        let mut mutable: NSMutableDictionary = map.clone().into_iter().collect();
+        mutable.remove("one");
+        assert_eq!(mutable.get("one"), None);
+
+        let keys: Vec<String> = mutable.iter().map(|(key, _)| NSString::retain(key).to_string()).collect();
+        assert_eq!(keys, vec!["two".to_string()]);
+    }
+}
@@ -0,0 +1,171 @@
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::Id;
+
+use crate::foundation::{id, to_bool, NSInteger, NSUInteger, BOOL};
+
+/// `NSNotFound`, which `NSIndexSet` returns when it runs out of indexes.
+const NOT_FOUND: NSUInteger = NSInteger::MAX as NSUInteger;
+
+/// A wrapper for `NSIndexSet`, which AppKit uses for row and column selections (among other
+/// things).
+#[derive(Debug)]
+pub struct NSIndexSet(pub Id<Object>);
+
+impl Default for NSIndexSet {
+    /// Returns an empty `NSIndexSet`.
+    fn default() -> Self {
+        NSIndexSet::new(&[])
+    }
+}
+
+impl NSIndexSet {
+    /// Creates and retains an `NSIndexSet` holding the given indexes.
+    pub fn new(indexes: &[usize]) -> Self {
+        indexes.iter().copied().collect()
+    }
+
+    /// In some cases, we're vended an `NSIndexSet` by the system that we need to call retain on.
+    /// This handles that case.
+    pub fn retain(set: id) -> Self {
+        NSIndexSet(unsafe { Id::from_ptr(set) })
+    }
+
+    /// In some cases, we're vended an `NSIndexSet` by the system, and it's ideal to not retain
+    /// that. This handles that edge case.
+    pub fn from_retained(set: id) -> Self {
+        NSIndexSet(unsafe { Id::from_retained_ptr(set) })
+    }
+
+    /// A helper method for determining if a given `NSObject` is an `NSIndexSet`.
+    pub fn is(obj: id) -> bool {
+        let result: BOOL = unsafe { msg_send![obj, isKindOfClass: class!(NSIndexSet)] };
+        to_bool(result)
+    }
+
+    /// Returns the number of indexes in this set.
+    pub fn count(&self) -> usize {
+        unsafe {
+            let count: NSUInteger = msg_send![&*self.0, count];
+            count as usize
+        }
+    }
+
+    /// Returns whether `index` is in this set.
+    pub fn contains(&self, index: usize) -> bool {
+        let result: BOOL = unsafe { msg_send![&*self.0, containsIndex: index as NSUInteger] };
+        to_bool(result)
+    }
+
+    /// Returns the lowest index in this set, if it isn't empty.
+    pub fn first(&self) -> Option<usize> {
+        found(unsafe { msg_send![&*self.0, firstIndex] })
+    }
+
+    /// Returns the highest index in this set, if it isn't empty.
+    pub fn last(&self) -> Option<usize> {
+        found(unsafe { msg_send![&*self.0, lastIndex] })
+    }
+
+    /// Returns an iterator over the indexes in this set, in ascending order.
+    pub fn iter(&self) -> NSIndexSetIter<'_> {
+        NSIndexSetIter {
+            set: self,
+            next: self.first()
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a NSIndexSet {
+    type Item = usize;
+    type IntoIter = NSIndexSetIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<usize> for NSIndexSet {
+    /// Collects indexes into an `NSIndexSet`. Duplicates are ignored.
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        unsafe {
+            let set: id = msg_send![class!(NSMutableIndexSet), indexSet];
+
+            for index in iter {
+                let _: () = msg_send![set, addIndex: index as NSUInteger];
+            }
+
+            NSIndexSet(Id::from_ptr(set))
+        }
+    }
+}
+
+impl From<NSIndexSet> for id {
+    /// Consumes and returns the pointer to the underlying `NSIndexSet`.
+    fn from(mut set: NSIndexSet) -> Self {
+        &mut *set.0
+    }
+}
+
+impl Deref for NSIndexSet {
+    type Target = Object;
+
+    /// Derefs to the underlying Objective-C Object.
+    fn deref(&self) -> &Object {
+        &*self.0
+    }
+}
+
+impl DerefMut for NSIndexSet {
+    /// Derefs to the underlying Objective-C Object.
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut *self.0
+    }
+}
+
+/// An iterator over the indexes in an `NSIndexSet`, in ascending order.
+#[derive(Debug)]
+pub struct NSIndexSetIter<'a> {
+    set: &'a NSIndexSet,
+    next: Option<usize>
+}
+
+impl Iterator for NSIndexSetIter<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let index = self.next?;
+        self.next = found(unsafe { msg_send![&*self.set.0, indexGreaterThanIndex: index as NSUInteger] });
+        Some(index)
+    }
+}
+
+/// Maps `NSNotFound` to `None`.
+fn found(index: NSUInteger) -> Option<usize> {
+    match index {
+        NOT_FOUND => None,
+        index => Some(index as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NSIndexSet;
+
+    #[test]
+    fn test_index_set() {
+        let set: NSIndexSet = vec![5, 1, 3, 3].into_iter().collect();
+        assert_eq!(set.count(), 3);
+        assert_eq!(set.iter().collect::<Vec<usize>>(), vec![1, 3, 5]);
+        assert_eq!((set.first(), set.last()), (Some(1), Some(5)));
+        assert!(set.contains(3));
+        assert!(!set.contains(4));
+
+        let empty = NSIndexSet::default();
+        assert_eq!(empty.iter().next(), None);
+        assert_eq!(empty.first(), None);
+    }
+}
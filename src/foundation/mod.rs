@@ -25,7 +25,10 @@ mod autoreleasepool;
 pub use autoreleasepool::AutoReleasePool;
 
 mod array;
-pub use array::NSArray;
+pub use array::{NSArray, NSArrayIter, NSMutableArray};
+
+mod bridge;
+pub use bridge::ObjcValue;
 
 mod class;
 pub use class::load_or_register_class;
@@ -33,18 +36,40 @@ pub use class::load_or_register_class;
 mod data;
 pub use data::NSData;
 
+mod date;
+pub use date::NSDate;
+pub(crate) use date::{date_from_unix_timestamp, date_to_unix_timestamp};
+
 mod dictionary;
-pub use dictionary::NSMutableDictionary;
+pub use dictionary::{NSDictionary, NSDictionaryIter, NSMutableDictionary};
+
+mod index_set;
+pub use index_set::{NSIndexSet, NSIndexSetIter};
 
 mod number;
 pub use number::NSNumber;
 
+#[cfg(feature = "serde")]
+mod serialization;
+
+#[cfg(feature = "serde")]
+pub(crate) use serialization::to_objc_value;
+
+mod set;
+pub use set::NSSet;
+
 mod string;
 pub use string::NSString;
 
 // Separate named module to not conflict with the `url` crate. Go figure.
 mod urls;
-pub use urls::{NSURLBookmarkCreationOption, NSURLBookmarkResolutionOption, NSURL};
+pub use urls::{
+    NSURLBookmarkCreationOption, NSURLBookmarkResolutionOption, NSURLFileResource, NSURLResourceKey,
+    NSUbiquitousItemDownloadingStatus, ResourceValues, SecurityScopedAccess, NSURL
+};
+
+mod value;
+pub use value::NSValue;
 
 /// Bool mapping types differ between ARM and x64. There's a number of places that we need to check
 /// against BOOL results throughout the framework, and this just simplifies some mismatches.
@@ -0,0 +1,233 @@
+//! Serde support for `ObjcValue`, which is how values cross to and from JavaScript in a
+//! `WebView`. This reuses the machinery behind `defaults::to_value` and `defaults::from_value`,
+//! and follows the same rules - except that `ObjcValue` can hold `NSNull`, so `None` (and `()`)
+//! serialize as `ObjcValue::Null` wherever they appear, and `ObjcValue::Null` deserializes as
+//! `None` (or `()`).
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer, Serialize};
+
+use crate::defaults::{serialize_into, ConversionError, EnumDeserializer, SerializedValue, VariantValue, DATE_TOKEN};
+use crate::foundation::{date_to_unix_timestamp, ObjcValue};
+
+/// Converts any `Serialize` type into an `ObjcValue`.
+pub(crate) fn to_objc_value<T: Serialize + ?Sized>(value: &T) -> Result<ObjcValue, ConversionError> {
+    serialize_into(value)
+}
+
+impl SerializedValue for ObjcValue {
+    fn null() -> Option<Self> {
+        Some(ObjcValue::Null)
+    }
+
+    fn bool(value: bool) -> Self {
+        ObjcValue::Bool(value)
+    }
+
+    fn integer(value: i64) -> Self {
+        ObjcValue::Integer(value)
+    }
+
+    fn unsigned_integer(value: u64) -> Result<Self, ConversionError> {
+        Ok(match value <= i64::MAX as u64 {
+            true => ObjcValue::Integer(value as i64),
+            false => ObjcValue::UnsignedInteger(value)
+        })
+    }
+
+    fn float(value: f64) -> Self {
+        ObjcValue::Double(value)
+    }
+
+    fn string(value: String) -> Self {
+        ObjcValue::String(value)
+    }
+
+    fn data(value: Vec<u8>) -> Self {
+        ObjcValue::Data(value)
+    }
+
+    fn date(value: SystemTime) -> Self {
+        ObjcValue::Date(value)
+    }
+
+    fn array(values: Vec<Self>) -> Self {
+        ObjcValue::Array(values)
+    }
+
+    fn dictionary(map: HashMap<String, Self>) -> Self {
+        ObjcValue::Dictionary(map)
+    }
+
+    fn timestamp(&self) -> Option<f64> {
+        match self {
+            ObjcValue::Double(timestamp) => Some(*timestamp),
+            _ => None
+        }
+    }
+
+    fn into_key(self) -> Option<String> {
+        match self {
+            ObjcValue::String(key) => Some(key),
+            ObjcValue::Integer(key) => Some(key.to_string()),
+            ObjcValue::UnsignedInteger(key) => Some(key.to_string()),
+            _ => None
+        }
+    }
+}
+
+impl<'de> Deserializer<'de> for ObjcValue {
+    type Error = ConversionError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            ObjcValue::Null => visitor.visit_unit(),
+            ObjcValue::Bool(b) => visitor.visit_bool(b),
+            ObjcValue::Integer(i) => visitor.visit_i64(i),
+            ObjcValue::UnsignedInteger(u) => visitor.visit_u64(u),
+            ObjcValue::Float(f) => visitor.visit_f32(f),
+            ObjcValue::Double(d) => visitor.visit_f64(d),
+            ObjcValue::String(s) => visitor.visit_string(s),
+            ObjcValue::Data(data) => visitor.visit_byte_buf(data),
+
+            ObjcValue::Array(values) => {
+                let mut deserializer = de::value::SeqDeserializer::new(values.into_iter());
+                let value = visitor.visit_seq(&mut deserializer)?;
+                deserializer.end()?;
+                Ok(value)
+            },
+
+            ObjcValue::Dictionary(map) => {
+                let mut deserializer = de::value::MapDeserializer::new(map.into_iter());
+                let value = visitor.visit_map(&mut deserializer)?;
+                deserializer.end()?;
+                Ok(value)
+            },
+
+            ObjcValue::Date(date) => visitor.visit_newtype_struct(ObjcValue::Double(date_to_unix_timestamp(&date)))
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            ObjcValue::Null => visitor.visit_none(),
+            value => visitor.visit_some(value)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        match (name == DATE_TOKEN, self) {
+            (true, ObjcValue::Date(date)) => visitor.visit_newtype_struct(ObjcValue::Double(date_to_unix_timestamp(&date))),
+            (true, value) => value.deserialize_any(visitor),
+            (false, value) => visitor.visit_newtype_struct(value)
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V
+    ) -> Result<V::Value, Self::Error> {
+        let (variant, value) = match self {
+            ObjcValue::String(variant) => (variant, None),
+
+            ObjcValue::Dictionary(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("checked length above");
+                (variant, Some(value))
+            },
+
+            _ => {
+                return Err(ConversionError::new(
+                    "Expected a string or a single-entry dictionary for an enum"
+                ))
+            },
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, value })
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf seq tuple tuple_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ConversionError> for ObjcValue {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> VariantValue<'de> for ObjcValue {
+    fn variant(name: String) -> Self {
+        ObjcValue::String(name)
+    }
+
+    fn is_array(&self) -> bool {
+        matches!(self, ObjcValue::Array(_))
+    }
+
+    fn is_dictionary(&self) -> bool {
+        matches!(self, ObjcValue::Dictionary(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::to_objc_value;
+    use crate::foundation::ObjcValue;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Reply {
+        path: String,
+        size: Option<u64>,
+        tags: Vec<Option<String>>
+    }
+
+    #[test]
+    fn test_nulls_round_trip() {
+        let reply = Reply {
+            path: "/tmp/file.txt".to_string(),
+            size: None,
+            tags: vec![Some("draft".to_string()), None]
+        };
+
+        let mut expected = HashMap::new();
+        expected.insert("path".to_string(), ObjcValue::String("/tmp/file.txt".to_string()));
+        expected.insert("size".to_string(), ObjcValue::Null);
+        expected.insert(
+            "tags".to_string(),
+            ObjcValue::Array(vec![ObjcValue::String("draft".to_string()), ObjcValue::Null])
+        );
+
+        let value = to_objc_value(&reply).unwrap();
+        assert_eq!(value, ObjcValue::Dictionary(expected));
+        assert_eq!(Reply::deserialize(value).unwrap(), reply);
+
+        assert_eq!(to_objc_value(&None::<i64>).unwrap(), ObjcValue::Null);
+        assert_eq!(to_objc_value(&()).unwrap(), ObjcValue::Null);
+        assert_eq!(to_objc_value(&u64::MAX).unwrap(), ObjcValue::UnsignedInteger(u64::MAX));
+    }
+}
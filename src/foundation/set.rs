@@ -0,0 +1,113 @@
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::Id;
+
+use crate::foundation::{id, to_bool, NSArray, NSArrayIter, NSUInteger, BOOL};
+
+/// A wrapper for `NSSet`. Note that membership is determined by `isEqual:` on the Objective-C
+/// side, rather than by pointer.
+#[derive(Debug)]
+pub struct NSSet(pub Id<Object>);
+
+impl Default for NSSet {
+    /// Returns an empty `NSSet`.
+    fn default() -> Self {
+        NSSet::new(&[])
+    }
+}
+
+impl NSSet {
+    /// Given a set of `Object`s, creates and retains an `NSSet` that holds them. Duplicates (by
+    /// `isEqual:`) are dropped.
+    pub fn new(objects: &[id]) -> Self {
+        NSSet(unsafe {
+            Id::from_ptr(msg_send![class!(NSSet),
+                setWithObjects:objects.as_ptr()
+                count:objects.len() as NSUInteger
+            ])
+        })
+    }
+
+    /// In some cases, we're vended an `NSSet` by the system that we need to call retain on.
+    /// This handles that case.
+    pub fn retain(set: id) -> Self {
+        NSSet(unsafe { Id::from_ptr(set) })
+    }
+
+    /// In some cases, we're vended an `NSSet` by the system, and it's ideal to not retain that.
+    /// This handles that edge case.
+    pub fn from_retained(set: id) -> Self {
+        NSSet(unsafe { Id::from_retained_ptr(set) })
+    }
+
+    /// A helper method for determining if a given `NSObject` is an `NSSet`.
+    pub fn is(obj: id) -> bool {
+        let result: BOOL = unsafe { msg_send![obj, isKindOfClass: class!(NSSet)] };
+        to_bool(result)
+    }
+
+    /// Returns the number of objects in this set.
+    pub fn count(&self) -> usize {
+        unsafe { msg_send![&*self.0, count] }
+    }
+
+    /// Returns whether this set holds an object equal to `object`.
+    pub fn contains(&self, object: id) -> bool {
+        let result: BOOL = unsafe { msg_send![&*self.0, containsObject: object] };
+        to_bool(result)
+    }
+
+    /// Returns the objects in this set as an `NSArray`, in no particular order.
+    pub fn to_array(&self) -> NSArray {
+        NSArray::retain(unsafe { msg_send![&*self.0, allObjects] })
+    }
+
+    /// Returns an iterator over the objects in this set, in no particular order. These aren't
+    /// retained, and are valid for as long as the set is.
+    pub fn iter(&self) -> NSArrayIter<'_> {
+        let objects: id = unsafe { msg_send![&*self.0, allObjects] };
+        NSArrayIter::new(unsafe { &*objects })
+    }
+}
+
+impl<'a> IntoIterator for &'a NSSet {
+    type Item = id;
+    type IntoIter = NSArrayIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<id> for NSSet {
+    /// Collects objects into an `NSSet`, which retains them.
+    fn from_iter<I: IntoIterator<Item = id>>(iter: I) -> Self {
+        NSSet::new(&iter.into_iter().collect::<Vec<id>>())
+    }
+}
+
+impl From<NSSet> for id {
+    /// Consumes and returns the pointer to the underlying `NSSet`.
+    fn from(mut set: NSSet) -> Self {
+        &mut *set.0
+    }
+}
+
+impl Deref for NSSet {
+    type Target = Object;
+
+    /// Derefs to the underlying Objective-C Object.
+    fn deref(&self) -> &Object {
+        &*self.0
+    }
+}
+
+impl DerefMut for NSSet {
+    /// Derefs to the underlying Objective-C Object.
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut *self.0
+    }
+}
@@ -42,7 +42,7 @@ impl From<&NSURLBookmarkCreationOption> for NSUInteger {
 }
 
 /// Options used when resolving bookmark data.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug)]
 pub enum NSURLBookmarkResolutionOption {
     /// Specifies that no UI feedback should accompany resolution of the bookmark data.
     WithoutUI,
@@ -1,13 +1,15 @@
+use std::collections::HashMap;
 use std::error::Error;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
-use crate::foundation::{id, nil, NSData, NSString, NSUInteger};
+use crate::error::Error as AppKitError;
+use crate::foundation::{id, nil, to_bool, NSArray, NSData, NSDictionary, NSString, NSUInteger, ObjcValue, BOOL, NO};
 
 mod bookmark_options;
 pub use bookmark_options::{NSURLBookmarkCreationOption, NSURLBookmarkResolutionOption};
@@ -15,6 +17,12 @@ pub use bookmark_options::{NSURLBookmarkCreationOption, NSURLBookmarkResolutionO
 mod resource_keys;
 pub use resource_keys::{NSURLFileResource, NSURLResourceKey, NSUbiquitousItemDownloadingStatus};
 
+mod resource_values;
+pub use resource_values::ResourceValues;
+
+mod security_scope;
+pub use security_scope::SecurityScopedAccess;
+
 /// Wraps `NSURL` for use throughout the framework.
 ///
 /// This type may also be returned to users in some callbacks (e.g, file manager/selectors) as it's
@@ -64,6 +72,18 @@ impl<'a> NSURL<'a> {
         }
     }
 
+    /// Creates and returns a file URL for the given path, by calling through to
+    /// `[NSURL fileURLWithPath:]`. Relative paths are resolved against the current directory.
+    pub fn with_path<P: AsRef<Path>>(path: P) -> Self {
+        let path = NSString::new(&path.as_ref().to_string_lossy());
+
+        Self {
+            objc: unsafe { ShareId::from_ptr(msg_send![class!(NSURL), fileURLWithPath:&*path]) },
+
+            phantom: PhantomData
+        }
+    }
+
     /// Returns the absolute string path that this URL points to.
     ///
     /// Note that if the underlying file moved, this won't be accurate - you likely want to
@@ -82,59 +102,150 @@ impl<'a> NSURL<'a> {
         path.to_str().into()
     }
 
+    /// Fetches the resource values for `keys` (size, dates, type, and so on). Values are cached
+    /// by the system for the lifetime of this URL; see `remove_cached_resource_values()` if you
+    /// need them refreshed.
+    ///
+    /// Values that weren't requested, or that aren't available for this resource, come back as
+    /// `None` on the returned `ResourceValues`.
+    pub fn resource_values(&self, keys: &[NSURLResourceKey]) -> Result<ResourceValues, Box<dyn Error>> {
+        let keys: NSArray = keys.iter().map(|key| NSString::from(key).into()).collect();
+
+        unsafe {
+            let mut error: id = nil;
+            let values: id = msg_send![&*self.objc, resourceValuesForKeys:&*keys error:&mut error];
+
+            if values == nil {
+                return Err(AppKitError::new(error).into());
+            }
+
+            let values = HashMap::from(&NSDictionary::retain(values))
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, resource_value(value)?)))
+                .collect::<HashMap<String, ObjcValue>>();
+
+            Ok(values.into())
+        }
+    }
+
+    /// Sets a resource value for the given key. Only some keys are writable - e.g,
+    /// `IsExcludedFromBackup`, `IsHidden`, `HasHiddenExtension`, `CreationDate` or
+    /// `ContentModificationDate`.
+    ///
+    /// ```rust,no_run
+    /// use cacao::foundation::{NSURLResourceKey, ObjcValue, NSURL};
+    ///
+    /// let url = NSURL::with_path("/tmp/cache.db");
+    /// url.set_resource_value(NSURLResourceKey::IsExcludedFromBackup, ObjcValue::Bool(true)).unwrap();
+    /// ```
+    pub fn set_resource_value(&self, key: NSURLResourceKey, value: ObjcValue) -> Result<(), Box<dyn Error>> {
+        let key = NSString::from(&key);
+        let value: id = value.into();
+
+        unsafe {
+            let mut error: id = nil;
+            let result: BOOL = msg_send![&*self.objc, setResourceValue:value forKey:&*key error:&mut error];
+
+            match to_bool(result) {
+                true => Ok(()),
+                false => Err(AppKitError::new(error).into())
+            }
+        }
+    }
+
+    /// Discards any resource values the system has cached for this URL, so the next call to
+    /// `resource_values()` reads them fresh.
+    pub fn remove_cached_resource_values(&self) {
+        unsafe {
+            let _: () = msg_send![&*self.objc, removeAllCachedResourceValues];
+        }
+    }
+
     /// Returns bookmark data for this URL. Will error if the underlying API errors.
     ///
     /// Bookmarks are useful for sandboxed applications, as well as situations where you might want
     /// to later resolve the true location of a file (e.g, if the user moved it between when you
-    /// got the URL and when you need to use it).
+    /// got the URL and when you need to use it). Any `resource_value_keys` are stored in the
+    /// bookmark, and can be read back without resolving it.
+    ///
+    /// For a ready-made way to persist bookmarks, see `filesystem::BookmarkStore`.
     pub fn bookmark_data(
         &self,
         options: &[NSURLBookmarkCreationOption],
         resource_value_keys: &[NSURLResourceKey],
         relative_to_url: Option<NSURL>
     ) -> Result<NSData, Box<dyn Error>> {
-        let mut opts: NSUInteger = 0;
-        for mask in options {
-            let i: NSUInteger = mask.into();
-            opts = opts | i;
-        }
+        let opts = options
+            .iter()
+            .fold(0 as NSUInteger, |opts, option| opts | NSUInteger::from(option));
 
-        // Build NSArray of resource keys
-        let resource_keys = nil;
-
-        // Mutability woes mean we just go through a match here to satisfy message passing needs.
-        let bookmark_data = NSData::retain(match relative_to_url {
-            Some(relative_url) => unsafe {
-                msg_send![&*self.objc, bookmarkDataWithOptions:opts
-                    includingResourceValuesForKeys:resource_keys
-                    relativeToURL:relative_url
-                    error:nil
-                ]
-            },
-
-            None => unsafe {
-                msg_send![&*self.objc, bookmarkDataWithOptions:opts
-                    includingResourceValuesForKeys:resource_keys
-                    relativeToURL:nil
-                    error:nil
-                ]
-            }
-        });
+        let resource_keys: NSArray = resource_value_keys.iter().map(|key| NSString::from(key).into()).collect();
+
+        let relative_to_url: id = match &relative_to_url {
+            Some(url) => &*url.objc as *const Object as id,
+            None => nil
+        };
 
-        // Check for errors...
-        //Err("LOL".into())
+        unsafe {
+            let mut error: id = nil;
+            let data: id = msg_send![&*self.objc, bookmarkDataWithOptions:opts
+                includingResourceValuesForKeys:&*resource_keys
+                relativeToURL:relative_to_url
+                error:&mut error
+            ];
+
+            if data == nil {
+                return Err(AppKitError::new(error).into());
+            }
 
-        Ok(bookmark_data)
+            Ok(NSData::retain(data))
+        }
     }
 
-    /// Converts bookmark data into a URL.
+    /// Converts bookmark data into a URL. Alongside the URL, this returns whether the bookmark
+    /// data is stale - if it is, you should create new bookmark data from the returned URL and
+    /// store that instead.
     pub fn from_bookmark_data(
         data: NSData,
         options: &[NSURLBookmarkResolutionOption],
-        relative_to_url: Option<NSURL>,
-        data_is_stale: bool
-    ) -> Result<Self, Box<dyn Error>> {
-        Err("LOL".into())
+        relative_to_url: Option<NSURL>
+    ) -> Result<(Self, bool), Box<dyn Error>> {
+        let opts = options
+            .iter()
+            .fold(0 as NSUInteger, |opts, option| opts | NSUInteger::from(*option));
+
+        let relative_to_url: id = match &relative_to_url {
+            Some(url) => &*url.objc as *const Object as id,
+            None => nil
+        };
+
+        unsafe {
+            let mut error: id = nil;
+            let mut is_stale: BOOL = NO;
+            let url: id = msg_send![class!(NSURL), URLByResolvingBookmarkData:&*data
+                options:opts
+                relativeToURL:relative_to_url
+                bookmarkDataIsStale:&mut is_stale
+                error:&mut error
+            ];
+
+            if url == nil {
+                return Err(AppKitError::new(error).into());
+            }
+
+            Ok((NSURL::retain(url), to_bool(is_stale)))
+        }
+    }
+
+    /// Starts accessing the security-scoped resource this URL points to, returning a guard that
+    /// stops accessing it when dropped. This keeps calls to
+    /// `start_accessing_security_scoped_resource` and `stop_accessing_security_scoped_resource`
+    /// balanced.
+    ///
+    /// It's fine to call this for URLs that aren't security-scoped; the guard just won't do
+    /// anything (see `SecurityScopedAccess::is_active`).
+    pub fn security_scoped_access(&self) -> SecurityScopedAccess<'a> {
+        SecurityScopedAccess::new(self.clone())
     }
 
     /// In an app that has adopted App Sandbox, makes the resource pointed to by a security-scoped URL available to the app.
@@ -173,3 +284,20 @@ impl Deref for NSURL<'_> {
         &*self.objc
     }
 }
+
+/// Converts a resource value. Most are property list types; URLs are kept as their absolute
+/// string, and `UTType`s (for `NSURLContentTypeKey`) as their identifier.
+fn resource_value(value: id) -> Option<ObjcValue> {
+    ObjcValue::from_object(value).or_else(|| unsafe {
+        for selector in &[sel!(absoluteString), sel!(identifier)] {
+            let responds: BOOL = msg_send![value, respondsToSelector:*selector];
+
+            if to_bool(responds) {
+                let string: id = msg_send![value, performSelector:*selector];
+                return ObjcValue::from_object(string);
+            }
+        }
+
+        None
+    })
+}
@@ -1,7 +1,19 @@
-use crate::foundation::id;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::foundation::NSString;
+use crate::utils::load_constant;
+
+lazy_static! {
+    /// Resolved key strings, by constant name. Resolving a key means a `dlsym` call, and resource
+    /// values are read a key at a time, so each key is only looked up the first time it's used.
+    static ref RESOLVED_KEYS: RwLock<HashMap<&'static str, &'static str>> = RwLock::new(HashMap::new());
+}
 
 /// Possible values for the `NSURLResourceKey::FileResourceType` key.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum NSURLFileResource {
     /// The resource is a named pipe.
     NamedPipe,
@@ -29,7 +41,7 @@ pub enum NSURLFileResource {
 }
 
 /// Values that describe the iCloud storage state of a file.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum NSUbiquitousItemDownloadingStatus {
     /// A local copy of this item exists and is the most up-to-date version known to the device.
     Current,
@@ -41,7 +53,7 @@ pub enum NSUbiquitousItemDownloadingStatus {
     NotDownloaded
 }
 
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum NSURLResourceKey {
     IsApplication,
     IsScriptable,
@@ -168,3 +180,166 @@ pub enum NSURLResourceKey {
     UbiquitousItemIsExcludedFromSync,
     VolumeSupportsFileProtection
 }
+
+impl NSURLResourceKey {
+    /// Returns the string Foundation uses for this key at runtime, which is what resource value
+    /// dictionaries are keyed by. This is the value of the exported constant, read from Foundation
+    /// when it's available; otherwise (e.g, a key that's newer than the running OS), it's the
+    /// constant's name, which is what Apple's values have always been.
+    ///
+    /// These are looked up rather than declared as `extern` statics since a number of them are
+    /// macOS-only, or newer than the oldest macOS we support, and would fail to link (or launch)
+    /// there.
+    pub(crate) fn key(&self) -> &'static str {
+        let name = self.key_name();
+
+        if let Some(key) = RESOLVED_KEYS.read().unwrap().get(name) {
+            return key;
+        }
+
+        RESOLVED_KEYS
+            .write()
+            .unwrap()
+            .entry(name)
+            .or_insert_with(|| match load_constant(name) {
+                // There's a fixed number of keys, so leaking one string per key is bounded.
+                Some(value) => Box::leak(value.to_string().into_boxed_str()),
+                None => name
+            })
+    }
+
+    /// Returns the name of the Foundation constant for this key (e.g, `NSURLIsDirectoryKey`).
+    pub(crate) fn key_name(&self) -> &'static str {
+        match self {
+            NSURLResourceKey::IsApplication => "NSURLIsApplicationKey",
+            NSURLResourceKey::IsScriptable => "NSURLApplicationIsScriptableKey",
+            NSURLResourceKey::IsDirectory => "NSURLIsDirectoryKey",
+            NSURLResourceKey::ParentDirectoryURL => "NSURLParentDirectoryURLKey",
+            NSURLResourceKey::FileAllocatedSize => "NSURLFileAllocatedSizeKey",
+            NSURLResourceKey::FileProtection => "NSURLFileProtectionKey",
+            NSURLResourceKey::FileProtectionType => "NSURLFileProtectionKey",
+            NSURLResourceKey::FileResourceIdentifier => "NSURLFileResourceIdentifierKey",
+            NSURLResourceKey::FileResourceType(_) => "NSURLFileResourceTypeKey",
+            NSURLResourceKey::FileSecurity => "NSURLFileSecurityKey",
+            NSURLResourceKey::FileSize => "NSURLFileSizeKey",
+            NSURLResourceKey::IsAliasFile => "NSURLIsAliasFileKey",
+            NSURLResourceKey::IsPackage => "NSURLIsPackageKey",
+            NSURLResourceKey::IsRegularFile => "NSURLIsRegularFileKey",
+            NSURLResourceKey::PreferredIOBlockSize => "NSURLPreferredIOBlockSizeKey",
+            NSURLResourceKey::TotalFileAllocatedSize => "NSURLTotalFileAllocatedSizeKey",
+            NSURLResourceKey::TotalFileSize => "NSURLTotalFileSizeKey",
+            NSURLResourceKey::VolumeAvailableCapacity => "NSURLVolumeAvailableCapacityKey",
+            NSURLResourceKey::VolumeAvailableCapacityForImportantUsage => "NSURLVolumeAvailableCapacityForImportantUsageKey",
+            NSURLResourceKey::VolumeAvailableCapacityForOpportunisticUsage => {
+                "NSURLVolumeAvailableCapacityForOpportunisticUsageKey"
+            },
+            NSURLResourceKey::VolumeTotalCapacity => "NSURLVolumeTotalCapacityKey",
+            NSURLResourceKey::VolumeIsAutomounted => "NSURLVolumeIsAutomountedKey",
+            NSURLResourceKey::VolumeIsBrowsable => "NSURLVolumeIsBrowsableKey",
+            NSURLResourceKey::VolumeIsEjectable => "NSURLVolumeIsEjectableKey",
+            NSURLResourceKey::VolumeIsEncrypted => "NSURLVolumeIsEncryptedKey",
+            NSURLResourceKey::VolumeIsInternal => "NSURLVolumeIsInternalKey",
+            NSURLResourceKey::VolumeIsJournaling => "NSURLVolumeIsJournalingKey",
+            NSURLResourceKey::VolumeIsLocal => "NSURLVolumeIsLocalKey",
+            NSURLResourceKey::VolumeIsReadOnly => "NSURLVolumeIsReadOnlyKey",
+            NSURLResourceKey::VolumeIsRemovable => "NSURLVolumeIsRemovableKey",
+            NSURLResourceKey::VolumeIsRootFileSystem => "NSURLVolumeIsRootFileSystemKey",
+            NSURLResourceKey::IsMountTrigger => "NSURLIsMountTriggerKey",
+            NSURLResourceKey::IsVolume => "NSURLIsVolumeKey",
+            NSURLResourceKey::VolumeCreationDate => "NSURLVolumeCreationDateKey",
+            NSURLResourceKey::VolumeIdentifier => "NSURLVolumeIdentifierKey",
+            NSURLResourceKey::VolumeLocalizedFormatDescription => "NSURLVolumeLocalizedFormatDescriptionKey",
+            NSURLResourceKey::VolumeLocalizedName => "NSURLVolumeLocalizedNameKey",
+            NSURLResourceKey::VolumeMaximumFileSize => "NSURLVolumeMaximumFileSizeKey",
+            NSURLResourceKey::VolumeName => "NSURLVolumeNameKey",
+            NSURLResourceKey::VolumeResourceCount => "NSURLVolumeResourceCountKey",
+            NSURLResourceKey::VolumeSupportsAccessPermissions => "NSURLVolumeSupportsAccessPermissionsKey",
+            NSURLResourceKey::VolumeSupportsAdvisoryFileLocking => "NSURLVolumeSupportsAdvisoryFileLockingKey",
+            NSURLResourceKey::VolumeSupportsCasePreservedNames => "NSURLVolumeSupportsCasePreservedNamesKey",
+            NSURLResourceKey::VolumeSupportsCaseSensitiveNames => "NSURLVolumeSupportsCaseSensitiveNamesKey",
+            NSURLResourceKey::VolumeSupportsCompression => "NSURLVolumeSupportsCompressionKey",
+            NSURLResourceKey::VolumeSupportsExclusiveRenaming => "NSURLVolumeSupportsExclusiveRenamingKey",
+            NSURLResourceKey::VolumeSupportsExtendedSecurity => "NSURLVolumeSupportsExtendedSecurityKey",
+            NSURLResourceKey::VolumeSupportsFileCloning => "NSURLVolumeSupportsFileCloningKey",
+            NSURLResourceKey::VolumeSupportsHardLinks => "NSURLVolumeSupportsHardLinksKey",
+            NSURLResourceKey::VolumeSupportsImmutableFiles => "NSURLVolumeSupportsImmutableFilesKey",
+            NSURLResourceKey::VolumeSupportsJournaling => "NSURLVolumeSupportsJournalingKey",
+            NSURLResourceKey::VolumeSupportsPersistentIDs => "NSURLVolumeSupportsPersistentIDsKey",
+            NSURLResourceKey::VolumeSupportsRenaming => "NSURLVolumeSupportsRenamingKey",
+            NSURLResourceKey::VolumeSupportsRootDirectoryDates => "NSURLVolumeSupportsRootDirectoryDatesKey",
+            NSURLResourceKey::VolumeSupportsSparseFiles => "NSURLVolumeSupportsSparseFilesKey",
+            NSURLResourceKey::VolumeSupportsSwapRenaming => "NSURLVolumeSupportsSwapRenamingKey",
+            NSURLResourceKey::VolumeSupportsSymbolicLinks => "NSURLVolumeSupportsSymbolicLinksKey",
+            NSURLResourceKey::VolumeSupportsVolumeSizes => "NSURLVolumeSupportsVolumeSizesKey",
+            NSURLResourceKey::VolumeSupportsZeroRuns => "NSURLVolumeSupportsZeroRunsKey",
+            NSURLResourceKey::VolumeURLForRemounting => "NSURLVolumeURLForRemountingKey",
+            NSURLResourceKey::VolumeURL => "NSURLVolumeURLKey",
+            NSURLResourceKey::VolumeUUIDString => "NSURLVolumeUUIDStringKey",
+            NSURLResourceKey::IsUbiquitousItem => "NSURLIsUbiquitousItemKey",
+            NSURLResourceKey::UbiquitousSharedItemMostRecentEditorNameComponents => {
+                "NSURLUbiquitousSharedItemMostRecentEditorNameComponentsKey"
+            },
+            NSURLResourceKey::UbiquitousItemDownloadRequested => "NSURLUbiquitousItemDownloadRequestedKey",
+            NSURLResourceKey::UbiquitousItemIsDownloading => "NSURLUbiquitousItemIsDownloadingKey",
+            NSURLResourceKey::UbiquitousItemDownloadingError => "NSURLUbiquitousItemDownloadingErrorKey",
+            NSURLResourceKey::UbiquitousItemDownloadingStatus(_) => "NSURLUbiquitousItemDownloadingStatusKey",
+            NSURLResourceKey::UbiquitousItemIsUploaded => "NSURLUbiquitousItemIsUploadedKey",
+            NSURLResourceKey::UbiquitousItemIsUploading => "NSURLUbiquitousItemIsUploadingKey",
+            NSURLResourceKey::UbiquitousItemUploadingError => "NSURLUbiquitousItemUploadingErrorKey",
+            NSURLResourceKey::UbiquitousItemHasUnresolvedConflicts => "NSURLUbiquitousItemHasUnresolvedConflictsKey",
+            NSURLResourceKey::UbiquitousItemContainerDisplayName => "NSURLUbiquitousItemContainerDisplayNameKey",
+            NSURLResourceKey::UbiquitousSharedItemOwnerNameComponents => "NSURLUbiquitousSharedItemOwnerNameComponentsKey",
+            NSURLResourceKey::UbiquitousSharedItemCurrentUserPermissions => "NSURLUbiquitousSharedItemCurrentUserPermissionsKey",
+            NSURLResourceKey::UbiquitousSharedItemCurrentUserRole => "NSURLUbiquitousSharedItemCurrentUserRoleKey",
+            NSURLResourceKey::UbiquitousItemIsShared => "NSURLUbiquitousItemIsSharedKey",
+            NSURLResourceKey::UbiquitousSharedItemRole => "NSURLUbiquitousSharedItemRoleKey",
+            NSURLResourceKey::UbiquitousSharedItemPermissions => "NSURLUbiquitousSharedItemPermissionsKey",
+            NSURLResourceKey::ThumbnailDictionaryItem => "NSURLThumbnailDictionaryKey",
+            NSURLResourceKey::KeysOfUnsetValues => "NSURLKeysOfUnsetValuesKey",
+            NSURLResourceKey::QuarantineProperties => "NSURLQuarantinePropertiesKey",
+            NSURLResourceKey::AddedToDirectoryDate => "NSURLAddedToDirectoryDateKey",
+            NSURLResourceKey::AttributeModificationDate => "NSURLAttributeModificationDateKey",
+            NSURLResourceKey::ContentAccessDate => "NSURLContentAccessDateKey",
+            NSURLResourceKey::ContentModificationDate => "NSURLContentModificationDateKey",
+            NSURLResourceKey::CreationDate => "NSURLCreationDateKey",
+            NSURLResourceKey::CustomIcon => "NSURLCustomIconKey",
+            NSURLResourceKey::DocumentIdentifier => "NSURLDocumentIdentifierKey",
+            NSURLResourceKey::EffectiveIcon => "NSURLEffectiveIconKey",
+            NSURLResourceKey::GenerationIdentifier => "NSURLGenerationIdentifierKey",
+            NSURLResourceKey::HasHiddenExtension => "NSURLHasHiddenExtensionKey",
+            NSURLResourceKey::IsExcludedFromBackup => "NSURLIsExcludedFromBackupKey",
+            NSURLResourceKey::IsExecutable => "NSURLIsExecutableKey",
+            NSURLResourceKey::IsHidden => "NSURLIsHiddenKey",
+            NSURLResourceKey::IsReadable => "NSURLIsReadableKey",
+            NSURLResourceKey::IsSymbolicLink => "NSURLIsSymbolicLinkKey",
+            NSURLResourceKey::IsSystemImmutable => "NSURLIsSystemImmutableKey",
+            NSURLResourceKey::IsUserImmutable => "NSURLIsUserImmutableKey",
+            NSURLResourceKey::IsWritable => "NSURLIsWritableKey",
+            NSURLResourceKey::LabelColor => "NSURLLabelColorKey",
+            NSURLResourceKey::LabelNumber => "NSURLLabelNumberKey",
+            NSURLResourceKey::LinkCount => "NSURLLinkCountKey",
+            NSURLResourceKey::LocalizedLabel => "NSURLLocalizedLabelKey",
+            NSURLResourceKey::LocalizedName => "NSURLLocalizedNameKey",
+            NSURLResourceKey::LocalizedTypeDescription => "NSURLLocalizedTypeDescriptionKey",
+            NSURLResourceKey::Name => "NSURLNameKey",
+            NSURLResourceKey::Path => "NSURLPathKey",
+            NSURLResourceKey::CanonicalPath => "NSURLCanonicalPathKey",
+            NSURLResourceKey::TagNames => "NSURLTagNamesKey",
+            NSURLResourceKey::ContentType => "NSURLContentTypeKey",
+            NSURLResourceKey::FileContentIdentifier => "NSURLFileContentIdentifierKey",
+            NSURLResourceKey::IsPurgeable => "NSURLIsPurgeableKey",
+            NSURLResourceKey::IsSparse => "NSURLIsSparseKey",
+            NSURLResourceKey::MayHaveExtendedAttributes => "NSURLMayHaveExtendedAttributesKey",
+            NSURLResourceKey::MayShareFileContent => "NSURLMayShareFileContentKey",
+            NSURLResourceKey::UbiquitousItemIsExcludedFromSync => "NSURLUbiquitousItemIsExcludedFromSyncKey",
+            NSURLResourceKey::VolumeSupportsFileProtection => "NSURLVolumeSupportsFileProtectionKey"
+        }
+    }
+}
+
+impl From<&NSURLResourceKey> for NSString<'_> {
+    /// Returns the key that Foundation uses for this resource (e.g, `NSURLIsDirectoryKey`).
+    fn from(key: &NSURLResourceKey) -> Self {
+        NSString::new(key.key())
+    }
+}
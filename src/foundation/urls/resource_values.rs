@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use crate::foundation::ObjcValue;
+
+use super::{NSURLFileResource, NSURLResourceKey, NSUbiquitousItemDownloadingStatus};
+
+/// Resource values fetched for an `NSURL`, via `NSURL::resource_values()`.
+///
+/// The commonly needed values are pulled out into typed fields, which are `None` if they weren't
+/// requested (or aren't available for the resource). Everything that was fetched is also
+/// available, untyped, through `get()`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ResourceValues {
+    /// `NSURLNameKey`: the resource's name in the file system.
+    pub name: Option<String>,
+
+    /// `NSURLLocalizedNameKey`: the name as it should be shown to the user.
+    pub localized_name: Option<String>,
+
+    /// `NSURLIsDirectoryKey`.
+    pub is_directory: Option<bool>,
+
+    /// `NSURLIsRegularFileKey`.
+    pub is_regular_file: Option<bool>,
+
+    /// `NSURLIsSymbolicLinkKey`.
+    pub is_symbolic_link: Option<bool>,
+
+    /// `NSURLIsPackageKey`: whether this is a directory that's presented as a single file.
+    pub is_package: Option<bool>,
+
+    /// `NSURLIsHiddenKey`.
+    pub is_hidden: Option<bool>,
+
+    /// `NSURLIsExcludedFromBackupKey`.
+    pub is_excluded_from_backup: Option<bool>,
+
+    /// `NSURLFileSizeKey`, in bytes.
+    pub file_size: Option<u64>,
+
+    /// `NSURLTotalFileSizeKey`: the file size, including any metadata, in bytes.
+    pub total_file_size: Option<u64>,
+
+    /// `NSURLFileAllocatedSizeKey`: how much space the file takes up on disk, in bytes.
+    pub file_allocated_size: Option<u64>,
+
+    /// `NSURLCreationDateKey`.
+    pub creation_date: Option<SystemTime>,
+
+    /// `NSURLContentModificationDateKey`.
+    pub content_modification_date: Option<SystemTime>,
+
+    /// `NSURLContentAccessDateKey`.
+    pub content_access_date: Option<SystemTime>,
+
+    /// `NSURLContentTypeKey`, as a uniform type identifier (e.g, `public.png`).
+    pub content_type: Option<String>,
+
+    /// `NSURLLocalizedTypeDescriptionKey` (e.g, "PNG image").
+    pub localized_type_description: Option<String>,
+
+    /// `NSURLFileResourceTypeKey`.
+    pub file_resource_type: Option<NSURLFileResource>,
+
+    /// `NSURLUbiquitousItemDownloadingStatusKey`, for items in iCloud Drive.
+    pub ubiquitous_item_downloading_status: Option<NSUbiquitousItemDownloadingStatus>,
+
+    values: HashMap<String, ObjcValue>
+}
+
+impl ResourceValues {
+    /// Returns the value fetched for `key`, if any. URLs (e.g, `ParentDirectoryURL`) are stored
+    /// as their absolute string, and content types as their identifier.
+    pub fn get(&self, key: NSURLResourceKey) -> Option<&ObjcValue> {
+        self.values.get(key.key())
+    }
+}
+
+impl From<HashMap<String, ObjcValue>> for ResourceValues {
+    fn from(values: HashMap<String, ObjcValue>) -> Self {
+        let boolean = |key: NSURLResourceKey| match values.get(key.key()) {
+            Some(ObjcValue::Bool(b)) => Some(*b),
+            Some(ObjcValue::Integer(i)) => Some(*i != 0),
+            Some(ObjcValue::UnsignedInteger(u)) => Some(*u != 0),
+            _ => None
+        };
+
+        let integer = |key: NSURLResourceKey| match values.get(key.key()) {
+            Some(ObjcValue::Integer(i)) => Some(*i as u64),
+            Some(ObjcValue::UnsignedInteger(u)) => Some(*u),
+            _ => None
+        };
+
+        let date = |key: NSURLResourceKey| match values.get(key.key()) {
+            Some(ObjcValue::Date(date)) => Some(*date),
+            _ => None
+        };
+
+        let string = |key: &str| match values.get(key) {
+            Some(ObjcValue::String(s)) => Some(s.clone()),
+            _ => None
+        };
+
+        ResourceValues {
+            name: string(NSURLResourceKey::Name.key()),
+            localized_name: string(NSURLResourceKey::LocalizedName.key()),
+            is_directory: boolean(NSURLResourceKey::IsDirectory),
+            is_regular_file: boolean(NSURLResourceKey::IsRegularFile),
+            is_symbolic_link: boolean(NSURLResourceKey::IsSymbolicLink),
+            is_package: boolean(NSURLResourceKey::IsPackage),
+            is_hidden: boolean(NSURLResourceKey::IsHidden),
+            is_excluded_from_backup: boolean(NSURLResourceKey::IsExcludedFromBackup),
+            file_size: integer(NSURLResourceKey::FileSize),
+            total_file_size: integer(NSURLResourceKey::TotalFileSize),
+            file_allocated_size: integer(NSURLResourceKey::FileAllocatedSize),
+            creation_date: date(NSURLResourceKey::CreationDate),
+            content_modification_date: date(NSURLResourceKey::ContentModificationDate),
+            content_access_date: date(NSURLResourceKey::ContentAccessDate),
+            content_type: string(NSURLResourceKey::ContentType.key()),
+            localized_type_description: string(NSURLResourceKey::LocalizedTypeDescription.key()),
+            file_resource_type: string(NSURLResourceKey::FileResourceType(NSURLFileResource::Unknown).key())
+                .map(|value| file_resource(&value)),
+            ubiquitous_item_downloading_status: string(
+                NSURLResourceKey::UbiquitousItemDownloadingStatus(NSUbiquitousItemDownloadingStatus::Current).key()
+            )
+            .and_then(|value| downloading_status(&value)),
+            values
+        }
+    }
+}
+
+/// Maps an `NSURLFileResourceType` value.
+fn file_resource(value: &str) -> NSURLFileResource {
+    match value {
+        "NSURLFileResourceTypeNamedPipe" => NSURLFileResource::NamedPipe,
+        "NSURLFileResourceTypeCharacterSpecial" => NSURLFileResource::CharacterSpecial,
+        "NSURLFileResourceTypeDirectory" => NSURLFileResource::Directory,
+        "NSURLFileResourceTypeBlockSpecial" => NSURLFileResource::BlockSpecial,
+        "NSURLFileResourceTypeRegular" => NSURLFileResource::Regular,
+        "NSURLFileResourceTypeSymbolicLink" => NSURLFileResource::SymbolicLink,
+        "NSURLFileResourceTypeSocket" => NSURLFileResource::Socket,
+        _ => NSURLFileResource::Unknown
+    }
+}
+
+/// Maps an `NSURLUbiquitousItemDownloadingStatus` value.
+fn downloading_status(value: &str) -> Option<NSUbiquitousItemDownloadingStatus> {
+    match value {
+        "NSURLUbiquitousItemDownloadingStatusCurrent" => Some(NSUbiquitousItemDownloadingStatus::Current),
+        "NSURLUbiquitousItemDownloadingStatusDownloaded" => Some(NSUbiquitousItemDownloadingStatus::Downloaded),
+        "NSURLUbiquitousItemDownloadingStatusNotDownloaded" => Some(NSUbiquitousItemDownloadingStatus::NotDownloaded),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::ResourceValues;
+    use crate::foundation::{NSURLFileResource, NSURLResourceKey, NSUbiquitousItemDownloadingStatus, ObjcValue};
+
+    #[test]
+    fn test_from_values() {
+        let mut values = HashMap::new();
+        values.insert("NSURLIsDirectoryKey".to_string(), ObjcValue::Bool(false));
+        values.insert("NSURLFileSizeKey".to_string(), ObjcValue::Integer(1024));
+        values.insert("NSURLContentTypeKey".to_string(), ObjcValue::String("public.png".into()));
+        values.insert(
+            "NSURLFileResourceTypeKey".to_string(),
+            ObjcValue::String("NSURLFileResourceTypeRegular".into())
+        );
+        values.insert(
+            "NSURLUbiquitousItemDownloadingStatusKey".to_string(),
+            ObjcValue::String("NSURLUbiquitousItemDownloadingStatusNotDownloaded".into())
+        );
+
+        let resource_values = ResourceValues::from(values);
+        assert_eq!(resource_values.is_directory, Some(false));
+        assert_eq!(resource_values.file_size, Some(1024));
+        assert_eq!(resource_values.content_type.as_deref(), Some("public.png"));
+        assert_eq!(resource_values.file_resource_type, Some(NSURLFileResource::Regular));
+        assert_eq!(
+            resource_values.ubiquitous_item_downloading_status,
+            Some(NSUbiquitousItemDownloadingStatus::NotDownloaded)
+        );
+        assert_eq!(resource_values.is_hidden, None);
+        assert_eq!(
+            resource_values.get(NSURLResourceKey::FileSize),
+            Some(&ObjcValue::Integer(1024))
+        );
+    }
+}
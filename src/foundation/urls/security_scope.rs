@@ -0,0 +1,59 @@
+use std::ops::Deref;
+
+use objc::runtime::BOOL;
+use objc::{msg_send, sel, sel_impl};
+
+use crate::foundation::to_bool;
+
+use super::NSURL;
+
+/// Keeps a security-scoped resource accessible for as long as it's alive. Returned from
+/// `NSURL::security_scoped_access()` (and `filesystem::BookmarkStore::access()`).
+///
+/// Derefs to the `NSURL` being accessed.
+#[derive(Debug)]
+#[must_use = "access stops as soon as the guard is dropped"]
+pub struct SecurityScopedAccess<'a> {
+    url: NSURL<'a>,
+    active: bool
+}
+
+impl<'a> SecurityScopedAccess<'a> {
+    pub(crate) fn new(url: NSURL<'a>) -> Self {
+        let active: BOOL = unsafe { msg_send![&*url.objc, startAccessingSecurityScopedResource] };
+
+        SecurityScopedAccess {
+            url,
+            active: to_bool(active)
+        }
+    }
+
+    /// Whether access was actually granted. This is `false` for URLs that aren't
+    /// security-scoped (which are accessible anyway, outside the sandbox), and for ones that
+    /// the system refused.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Returns the URL being accessed.
+    pub fn url(&self) -> &NSURL<'a> {
+        &self.url
+    }
+}
+
+impl<'a> Deref for SecurityScopedAccess<'a> {
+    type Target = NSURL<'a>;
+
+    fn deref(&self) -> &NSURL<'a> {
+        &self.url
+    }
+}
+
+impl Drop for SecurityScopedAccess<'_> {
+    /// Stops accessing the resource, if access was granted.
+    fn drop(&mut self) {
+        if self.active {
+            self.url.stop_accessing_security_scoped_resource();
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::ffi::CStr;
+use std::ops::{Deref, DerefMut, Range};
+use std::os::raw::{c_char, c_void};
+
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl, Encode, Encoding};
+use objc_id::Id;
+
+use crate::foundation::{id, to_bool, NSUInteger, BOOL};
+use crate::geometry::{Point, Rect, Size};
+use crate::utils::EncodedRect;
+
+/// Mirrors `NSRange`, for boxing ranges.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+struct EncodedRange {
+    location: NSUInteger,
+    length: NSUInteger
+}
+
+unsafe impl Encode for EncodedRange {
+    fn encode() -> Encoding {
+        let encoding = format!(
+            "{{_NSRange={}{}}}",
+            NSUInteger::encode().as_str(),
+            NSUInteger::encode().as_str()
+        );
+
+        unsafe { Encoding::from_str(&encoding) }
+    }
+}
+
+/// A wrapper for `NSValue`, which boxes up C structs - points, sizes, rects and ranges - so they
+/// can be stored in collections (e.g, in a `userInfo` dictionary).
+#[derive(Debug)]
+pub struct NSValue(pub Id<Object>);
+
+impl NSValue {
+    /// Boxes a `Point`.
+    pub fn point(point: Point) -> Self {
+        NSValue::with_value(point)
+    }
+
+    /// Boxes a `Size`.
+    pub fn size(size: Size) -> Self {
+        NSValue::with_value(size)
+    }
+
+    /// Boxes a `Rect`.
+    pub fn rect(rect: Rect) -> Self {
+        NSValue::with_value(EncodedRect::from(rect))
+    }
+
+    /// Boxes a range as an `NSRange`.
+    pub fn range(range: Range<usize>) -> Self {
+        NSValue::with_value(EncodedRange {
+            location: range.start as NSUInteger,
+            length: range.end.saturating_sub(range.start) as NSUInteger
+        })
+    }
+
+    /// In some cases, we're vended an `NSValue` by the system that we need to call retain on.
+    /// This handles that case.
+    pub fn retain(value: id) -> Self {
+        NSValue(unsafe { Id::from_ptr(value) })
+    }
+
+    /// In some cases, we're vended an `NSValue` by the system, and it's ideal to not retain that.
+    /// This handles that edge case.
+    pub fn from_retained(value: id) -> Self {
+        NSValue(unsafe { Id::from_retained_ptr(value) })
+    }
+
+    /// A helper method for determining if a given `NSObject` is an `NSValue`. Note that `NSNumber`
+    /// is a subclass of `NSValue`, so this is `true` for numbers as well.
+    pub fn is(obj: id) -> bool {
+        let result: BOOL = unsafe { msg_send![obj, isKindOfClass: class!(NSValue)] };
+        to_bool(result)
+    }
+
+    /// Returns the `objCType` of the boxed value. For more information:
+    /// <https://nshipster.com/type-encodings/>
+    pub fn objc_type(&self) -> &str {
+        unsafe {
+            let t: *const c_char = msg_send![&*self.0, objCType];
+            CStr::from_ptr(t).to_str().unwrap_or("")
+        }
+    }
+
+    /// Unboxes a `Point`, if this holds a point-shaped value.
+    pub fn as_point(&self) -> Option<Point> {
+        self.get()
+    }
+
+    /// Unboxes a `Size`, if this holds a size-shaped value.
+    pub fn as_size(&self) -> Option<Size> {
+        self.get()
+    }
+
+    /// Unboxes a `Rect`, if this holds a rect-shaped value.
+    pub fn as_rect(&self) -> Option<Rect> {
+        self.get::<EncodedRect>().map(Rect::from)
+    }
+
+    /// Unboxes an `NSRange`, if this holds a range-shaped value.
+    pub fn as_range(&self) -> Option<Range<usize>> {
+        self.get::<EncodedRange>().map(|range| {
+            let start = range.location as usize;
+            start..start + range.length as usize
+        })
+    }
+
+    fn with_value<T: Encode>(value: T) -> Self {
+        let encoding = format!("{}\0", T::encode().as_str());
+
+        NSValue(unsafe {
+            Id::from_ptr(msg_send![class!(NSValue),
+                valueWithBytes:&value as *const T as *const c_void
+                objCType:encoding.as_ptr() as *const c_char
+            ])
+        })
+    }
+
+    /// Copies the boxed value out if its layout matches `T`. Struct names are ignored, as they
+    /// differ between platforms (e.g, `CGPoint` vs `_NSPoint` on GNUstep) for the same layout.
+    fn get<T: Encode + Default>(&self) -> Option<T> {
+        if layout(self.objc_type()) != layout(T::encode().as_str()) {
+            return None;
+        }
+
+        let mut value = T::default();
+
+        unsafe {
+            let _: () = msg_send![&*self.0, getValue:&mut value as *mut T as *mut c_void];
+        }
+
+        Some(value)
+    }
+}
+
+impl From<NSValue> for id {
+    /// Consumes and returns the pointer to the underlying `NSValue`.
+    fn from(mut value: NSValue) -> Self {
+        &mut *value.0
+    }
+}
+
+impl Deref for NSValue {
+    type Target = Object;
+
+    /// Derefs to the underlying Objective-C Object.
+    fn deref(&self) -> &Object {
+        &*self.0
+    }
+}
+
+impl DerefMut for NSValue {
+    /// Derefs to the underlying Objective-C Object.
+    fn deref_mut(&mut self) -> &mut Object {
+        &mut *self.0
+    }
+}
+
+/// Strips struct names from an encoding (`{CGRect={CGPoint=dd}{CGSize=dd}}` becomes
+/// `{{dd}{dd}}`), leaving just the layout.
+fn layout(encoding: &str) -> String {
+    let mut layout = String::with_capacity(encoding.len());
+    let mut chars = encoding.chars();
+
+    while let Some(c) = chars.next() {
+        layout.push(c);
+
+        if c == '{' {
+            let rest = chars.as_str();
+
+            if let Some(index) = rest.find(['=', '{', '}']) {
+                if rest[index..].starts_with('=') {
+                    chars = rest[index + 1..].chars();
+                }
+            }
+        }
+    }
+
+    layout
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layout, NSValue};
+    use crate::geometry::{Point, Rect, Size};
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(layout("{CGPoint=dd}"), "{dd}");
+        assert_eq!(layout("{_NSPoint=dd}"), "{dd}");
+        assert_eq!(layout("{CGRect={CGPoint=dd}{CGSize=dd}}"), "{{dd}{dd}}");
+        assert_eq!(layout("{_NSRect={_NSPoint=dd}{_NSSize=dd}}"), "{{dd}{dd}}");
+        assert_eq!(layout("{?}"), "{?}");
+        assert_eq!(layout("q"), "q");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let rect = Rect::new(1., 2., 3., 4.);
+        assert_eq!(NSValue::rect(rect).as_rect(), Some(rect));
+        assert_eq!(NSValue::point(Point::new(1., 2.)).as_point(), Some(Point::new(1., 2.)));
+        assert_eq!(NSValue::size(Size::new(3., 4.)).as_size(), Some(Size::new(3., 4.)));
+        assert_eq!(NSValue::range(2..5).as_range(), Some(2..5));
+        assert_eq!(NSValue::range(2..5).as_rect(), None);
+    }
+}
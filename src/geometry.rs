@@ -1,10 +1,215 @@
 //! Wrapper methods for various geometry types (rects, sizes, ec).
+//!
+//! `Point`, `Size` and `EdgeInsets` are laid out like their Core Graphics/AppKit/UIKit
+//! counterparts, so they can be passed to and from Objective-C as-is. Everything else here is
+//! plain math, and works the same on every platform.
 
-use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+use crate::core_graphics::base::CGFloat;
+use crate::core_graphics::geometry::{CGPoint, CGRect, CGSize};
+
+use objc::{Encode, Encoding};
+
+/// A point - `x` and `y`, in points.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Point {
+    /// The horizontal coordinate.
+    pub x: f64,
+
+    /// The vertical coordinate.
+    pub y: f64
+}
+
+impl Point {
+    /// Returns a new `Point` initialized with the values specified.
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+
+    /// Returns a `Point` at the origin.
+    pub fn zero() -> Self {
+        Point { x: 0., y: 0. }
+    }
+
+    /// Returns this point moved by `dx` and `dy`.
+    pub fn offset(&self, dx: f64, dy: f64) -> Self {
+        Point {
+            x: self.x + dx,
+            y: self.y + dy
+        }
+    }
+
+    /// Converts this point between a bottom-left origin (AppKit) and a top-left origin (UIKit),
+    /// within a container of the given height. Converting twice returns the original point.
+    pub fn flipped(&self, container_height: f64) -> Self {
+        Point {
+            x: self.x,
+            y: container_height - self.y
+        }
+    }
+}
+
+unsafe impl Encode for Point {
+    /// Adds support for CGPoint Objective-C encoding.
+    fn encode() -> Encoding {
+        let encoding = format!("{{CGPoint={}{}}}", CGFloat::encode().as_str(), CGFloat::encode().as_str());
+
+        unsafe { Encoding::from_str(&encoding) }
+    }
+}
+
+impl From<CGPoint> for Point {
+    fn from(point: CGPoint) -> Self {
+        Point::new(point.x as f64, point.y as f64)
+    }
+}
+
+impl From<Point> for CGPoint {
+    fn from(point: Point) -> Self {
+        CGPoint::new(point.x as CGFloat, point.y as CGFloat)
+    }
+}
+
+/// A size - width and height, in points.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Size {
+    /// The width of this size.
+    pub width: f64,
+
+    /// The height of this size.
+    pub height: f64
+}
+
+impl Size {
+    /// Returns a new `Size` initialized with the values specified.
+    pub fn new(width: f64, height: f64) -> Self {
+        Size { width, height }
+    }
+
+    /// Returns a `CGSizeZero` equivalent.
+    pub fn zero() -> Self {
+        Size { width: 0., height: 0. }
+    }
+
+    /// Whether this size has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0. || self.height <= 0.
+    }
+
+    /// Returns this size multiplied by `factor`.
+    pub fn scaled(&self, factor: f64) -> Self {
+        Size {
+            width: self.width * factor,
+            height: self.height * factor
+        }
+    }
+}
+
+unsafe impl Encode for Size {
+    /// Adds support for CGSize Objective-C encoding.
+    fn encode() -> Encoding {
+        let encoding = format!("{{CGSize={}{}}}", CGFloat::encode().as_str(), CGFloat::encode().as_str());
+
+        unsafe { Encoding::from_str(&encoding) }
+    }
+}
+
+impl From<CGSize> for Size {
+    fn from(size: CGSize) -> Self {
+        Size::new(size.width as f64, size.height as f64)
+    }
+}
+
+impl From<Size> for CGSize {
+    fn from(size: Size) -> Self {
+        CGSize::new(size.width as CGFloat, size.height as CGFloat)
+    }
+}
+
+/// Insets for each edge of a rect, in points. This maps to `NSEdgeInsets` on macOS, and
+/// `UIEdgeInsets` on iOS.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct EdgeInsets {
+    /// The inset from the top edge.
+    pub top: f64,
+
+    /// The inset from the left edge.
+    pub left: f64,
+
+    /// The inset from the bottom edge.
+    pub bottom: f64,
+
+    /// The inset from the right edge.
+    pub right: f64
+}
+
+impl EdgeInsets {
+    /// Returns new `EdgeInsets` initialized with the values specified.
+    pub fn new(top: f64, left: f64, bottom: f64, right: f64) -> Self {
+        EdgeInsets {
+            top,
+            left,
+            bottom,
+            right
+        }
+    }
+
+    /// Returns insets of `inset` on every edge.
+    pub fn uniform(inset: f64) -> Self {
+        EdgeInsets::new(inset, inset, inset, inset)
+    }
+
+    /// Returns zero'd out insets.
+    pub fn zero() -> Self {
+        EdgeInsets::uniform(0.)
+    }
+
+    /// The combined left and right insets.
+    pub fn horizontal(&self) -> f64 {
+        self.left + self.right
+    }
+
+    /// The combined top and bottom insets.
+    pub fn vertical(&self) -> f64 {
+        self.top + self.bottom
+    }
+}
+
+unsafe impl Encode for EdgeInsets {
+    /// Adds support for NSEdgeInsets (or UIEdgeInsets) Objective-C encoding.
+    fn encode() -> Encoding {
+        #[cfg(feature = "appkit")]
+        let name = "NSEdgeInsets";
+
+        #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+        let name = "UIEdgeInsets";
+
+        #[cfg(not(any(feature = "appkit", feature = "uikit")))]
+        let name = "NSEdgeInsets";
+
+        let float = CGFloat::encode();
+        let encoding = format!(
+            "{{{}={}{}{}{}}}",
+            name,
+            float.as_str(),
+            float.as_str(),
+            float.as_str(),
+            float.as_str()
+        );
+
+        unsafe { Encoding::from_str(&encoding) }
+    }
+}
 
 /// A struct that represents a box - top, left, width and height. You might use this for, say,
 /// setting the initial frame of a view.
-#[derive(Copy, Clone, Debug)]
+///
+/// Rects don't assume an orientation: `top` is simply the minimum `y` value, whether the
+/// coordinate system it lives in grows downwards (UIKit, flipped views) or upwards (AppKit).
+/// `flipped()` converts between the two.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct Rect {
     /// Distance from the top, in points.
     pub top: f64,
@@ -30,6 +235,11 @@ impl Rect {
         }
     }
 
+    /// Returns a new `Rect` with the given origin and size.
+    pub fn with_origin_and_size(origin: Point, size: Size) -> Self {
+        Rect::new(origin.y, origin.x, size.width, size.height)
+    }
+
     /// Returns a zero'd out Rect, with f64 (32-bit is mostly dead on Cocoa, so... this is "okay").
     pub fn zero() -> Rect {
         Rect {
@@ -39,6 +249,139 @@ impl Rect {
             height: 0.0
         }
     }
+
+    /// The origin (left, top) of this rect.
+    pub fn origin(&self) -> Point {
+        Point::new(self.left, self.top)
+    }
+
+    /// The size of this rect.
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+
+    /// The maximum `x` value of this rect.
+    pub fn right(&self) -> f64 {
+        self.left + self.width
+    }
+
+    /// The maximum `y` value of this rect.
+    pub fn bottom(&self) -> f64 {
+        self.top + self.height
+    }
+
+    /// The center point of this rect.
+    pub fn center(&self) -> Point {
+        Point::new(self.left + self.width / 2., self.top + self.height / 2.)
+    }
+
+    /// Whether this rect has no area.
+    pub fn is_empty(&self) -> bool {
+        self.size().is_empty()
+    }
+
+    /// Returns an equivalent rect with a non-negative width and height (as with
+    /// `CGRectStandardize`).
+    pub fn standardized(&self) -> Rect {
+        Rect::new(
+            self.top.min(self.bottom()),
+            self.left.min(self.right()),
+            self.width.abs(),
+            self.height.abs()
+        )
+    }
+
+    /// Whether `point` lies within this rect. As with `CGRectContainsPoint`, points on the
+    /// right and bottom edges are outside.
+    pub fn contains(&self, point: Point) -> bool {
+        let rect = self.standardized();
+        point.x >= rect.left && point.x < rect.right() && point.y >= rect.top && point.y < rect.bottom()
+    }
+
+    /// Whether `other` lies entirely within this rect.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        let (rect, other) = (self.standardized(), other.standardized());
+        other.left >= rect.left && other.right() <= rect.right() && other.top >= rect.top && other.bottom() <= rect.bottom()
+    }
+
+    /// Whether this rect and `other` overlap.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the overlap between this rect and `other`, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let (a, b) = (self.standardized(), other.standardized());
+        let left = a.left.max(b.left);
+        let top = a.top.max(b.top);
+        let right = a.right().min(b.right());
+        let bottom = a.bottom().min(b.bottom());
+
+        match right > left && bottom > top {
+            true => Some(Rect::new(top, left, right - left, bottom - top)),
+            false => None
+        }
+    }
+
+    /// Returns the smallest rect containing both this rect and `other`. Empty rects are
+    /// ignored, as with `CGRectUnion`.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let (a, b) = (self.standardized(), other.standardized());
+
+        match (a.is_empty(), b.is_empty()) {
+            (true, _) => b,
+            (false, true) => a,
+            (false, false) => {
+                let left = a.left.min(b.left);
+                let top = a.top.min(b.top);
+                Rect::new(top, left, a.right().max(b.right()) - left, a.bottom().max(b.bottom()) - top)
+            }
+        }
+    }
+
+    /// Returns this rect moved by `dx` and `dy`.
+    pub fn offset(&self, dx: f64, dy: f64) -> Rect {
+        Rect::new(self.top + dy, self.left + dx, self.width, self.height)
+    }
+
+    /// Returns this rect shrunk by `insets` on each edge. Negative insets grow it instead.
+    pub fn inset(&self, insets: &EdgeInsets) -> Rect {
+        Rect::new(
+            self.top + insets.top,
+            self.left + insets.left,
+            self.width - insets.horizontal(),
+            self.height - insets.vertical()
+        )
+    }
+
+    /// Returns this rect grown by `insets` on each edge.
+    pub fn outset(&self, insets: &EdgeInsets) -> Rect {
+        self.inset(&EdgeInsets::new(-insets.top, -insets.left, -insets.bottom, -insets.right))
+    }
+
+    /// Returns the smallest rect with integral coordinates that contains this one, as with
+    /// `CGRectIntegral`.
+    pub fn integral(&self) -> Rect {
+        self.pixel_aligned(1.)
+    }
+
+    /// Returns the smallest rect that contains this one and lands on whole pixels for the given
+    /// backing scale factor (e.g, `2.` on Retina displays).
+    pub fn pixel_aligned(&self, scale: f64) -> Rect {
+        let rect = self.standardized();
+        let left = (rect.left * scale).floor() / scale;
+        let top = (rect.top * scale).floor() / scale;
+        let right = (rect.right() * scale).ceil() / scale;
+        let bottom = (rect.bottom() * scale).ceil() / scale;
+
+        Rect::new(top, left, right - left, bottom - top)
+    }
+
+    /// Converts this rect between a bottom-left origin (AppKit) and a top-left origin (UIKit),
+    /// within a container of the given height. Converting twice returns the original rect.
+    pub fn flipped(&self, container_height: f64) -> Rect {
+        Rect::new(container_height - self.bottom(), self.left, self.width, self.height)
+    }
 }
 
 impl From<Rect> for CGRect {
@@ -57,3 +400,77 @@ impl From<CGRect> for Rect {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{EdgeInsets, Point, Rect, Size};
+
+    #[test]
+    fn test_accessors() {
+        let rect = Rect::with_origin_and_size(Point::new(10., 20.), Size::new(100., 50.));
+        assert_eq!(rect, Rect::new(20., 10., 100., 50.));
+        assert_eq!(rect.origin(), Point::new(10., 20.));
+        assert_eq!(rect.size(), Size::new(100., 50.));
+        assert_eq!((rect.right(), rect.bottom()), (110., 70.));
+        assert_eq!(rect.center(), Point::new(60., 45.));
+        assert_eq!(Rect::new(0., 0., -10., 20.).standardized(), Rect::new(0., -10., 10., 20.));
+        assert!(Rect::new(0., 0., 0., 10.).is_empty());
+        assert!(!rect.is_empty());
+    }
+
+    #[test]
+    fn test_containment() {
+        let rect = Rect::new(0., 0., 100., 100.);
+        let cases = [
+            (Point::new(0., 0.), true),
+            (Point::new(50., 99.9), true),
+            (Point::new(100., 50.), false),
+            (Point::new(50., 100.), false),
+            (Point::new(-1., 50.), false)
+        ];
+
+        for (point, contained) in cases.iter() {
+            assert_eq!(rect.contains(*point), *contained, "{:?}", point);
+        }
+
+        assert!(rect.contains_rect(&Rect::new(10., 10., 90., 90.)));
+        assert!(!rect.contains_rect(&Rect::new(10., 10., 91., 90.)));
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let a = Rect::new(0., 0., 100., 100.);
+        let b = Rect::new(50., 60., 100., 20.);
+        let c = Rect::new(200., 200., 10., 10.);
+
+        assert_eq!(a.intersection(&b), Some(Rect::new(50., 60., 40., 20.)));
+        assert_eq!(a.intersection(&c), None);
+        assert!(
+            !a.intersects(&Rect::new(0., 100., 10., 10.)),
+            "Touching edges don't intersect"
+        );
+
+        assert_eq!(a.union(&b), Rect::new(0., 0., 160., 100.));
+        assert_eq!(a.union(&c), Rect::new(0., 0., 210., 210.));
+        assert_eq!(Rect::zero().union(&c), c);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let rect = Rect::new(10., 20., 100., 50.);
+        assert_eq!(rect.offset(5., -5.), Rect::new(5., 25., 100., 50.));
+        assert_eq!(rect.inset(&EdgeInsets::new(1., 2., 3., 4.)), Rect::new(11., 22., 94., 46.));
+        assert_eq!(rect.outset(&EdgeInsets::uniform(10.)), Rect::new(0., 10., 120., 70.));
+        assert_eq!(rect.inset(&EdgeInsets::uniform(5.)).outset(&EdgeInsets::uniform(5.)), rect);
+
+        let rect = Rect::new(0.3, 10.6, 20.2, 10.);
+        assert_eq!(rect.integral(), Rect::new(0., 10., 21., 11.));
+        assert_eq!(rect.pixel_aligned(2.), Rect::new(0., 10.5, 20.5, 10.5));
+
+        // Flipping converts between bottom-left and top-left origins, and round trips.
+        let rect = Rect::new(10., 0., 100., 50.);
+        assert_eq!(rect.flipped(300.), Rect::new(240., 0., 100., 50.));
+        assert_eq!(rect.flipped(300.).flipped(300.), rect);
+        assert_eq!(Point::new(5., 10.).flipped(300.), Point::new(5., 290.));
+    }
+}
@@ -9,16 +9,90 @@
 
 use std::sync::Once;
 
+use crate::core_graphics::base::CGFloat;
+use crate::core_graphics::geometry::{CGRect, CGSize};
+
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel, BOOL};
-use objc::{class, sel, sel_impl};
+use objc::{class, msg_send, sel, sel_impl};
 use objc_id::Id;
 
 use crate::dragdrop::DragInfo;
-use crate::foundation::{id, NSUInteger, NO, YES};
-use crate::utils::load;
+use crate::foundation::{id, nil, to_bool, NSUInteger, NO, YES};
+use crate::geometry::Rect;
+use crate::image::ResizeBehavior;
+use crate::utils::{load, EncodedRect};
 use crate::view::{ViewDelegate, VIEW_DELEGATE_PTR};
 
+/// Holds the `ResizeBehavior` set on an `ImageView`, as its index in `ResizeBehavior::ALL` plus
+/// one. Zero means none has been set, and `NSImageView` handles drawing as usual.
+pub(crate) static RESIZE_BEHAVIOR_IVAR: &str = "rstResizeBehavior";
+
+/// `NSCompositingOperationSourceOver`.
+const SOURCE_OVER: NSUInteger = 2;
+
+/// Returns the `ResizeBehavior` set on this view, if any.
+fn resize_behavior(this: &Object) -> Option<ResizeBehavior> {
+    let index: NSUInteger = unsafe { *this.get_ivar(RESIZE_BEHAVIOR_IVAR) };
+    (index as usize)
+        .checked_sub(1)
+        .and_then(|index| ResizeBehavior::ALL.get(index))
+        .copied()
+}
+
+/// Draws the image according to the view's `ResizeBehavior`, if one has been set - otherwise,
+/// this defers to `NSImageView`.
+extern "C" fn draw_rect(this: &Object, _: Sel, dirty_rect: EncodedRect) {
+    let behavior = match resize_behavior(this) {
+        Some(behavior) => behavior,
+        None => unsafe {
+            let _: () = msg_send![super(this, class!(NSImageView)), drawRect: dirty_rect];
+            return;
+        }
+    };
+
+    unsafe {
+        let image: id = msg_send![this, image];
+        if image == nil {
+            return;
+        }
+
+        let size: CGSize = msg_send![image, size];
+        let bounds: CGRect = msg_send![this, bounds];
+        let flipped: BOOL = msg_send![this, isFlipped];
+        let target = Rect::from(bounds);
+
+        let _: () = msg_send![class!(NSGraphicsContext), saveGraphicsState];
+        let _: () = msg_send![class!(NSBezierPath), clipRect: bounds];
+
+        for frame in behavior.frames(size.into(), target) {
+            // Frames are computed with a top-left origin, which AppKit views don't use by default.
+            let frame = match to_bool(flipped) {
+                true => frame,
+                false => frame.flipped(target.top + target.bottom())
+            };
+
+            let _: () = msg_send![image, drawInRect:CGRect::from(frame)
+                fromRect:CGRect::from(Rect::zero())
+                operation:SOURCE_OVER
+                fraction:(1.0 as CGFloat)
+                respectFlipped:YES
+                hints:nil];
+        }
+
+        let _: () = msg_send![class!(NSGraphicsContext), restoreGraphicsState];
+    }
+}
+
+/// Layer-backed image views update their layer directly, skipping `drawRect:` - which we need
+/// when a `ResizeBehavior` has been set.
+extern "C" fn wants_update_layer(this: &Object, _: Sel) -> BOOL {
+    match resize_behavior(this) {
+        Some(_) => NO,
+        None => unsafe { msg_send![super(this, class!(NSImageView)), wantsUpdateLayer] }
+    }
+}
+
 /// Injects an `NSView` subclass. This is used for the default views that don't use delegates - we
 /// have separate classes here since we don't want to waste cycles on methods that will never be
 /// used if there's no delegates.
@@ -28,9 +102,15 @@ pub(crate) fn register_image_view_class() -> *const Class {
 
     INIT.call_once(|| unsafe {
         let superclass = class!(NSImageView);
-        let decl = ClassDecl::new("RSTImageView", superclass).unwrap();
+        let mut decl = ClassDecl::new("RSTImageView", superclass).unwrap();
+        decl.add_ivar::<NSUInteger>(RESIZE_BEHAVIOR_IVAR);
 
         //decl.add_method(sel!(isFlipped), enforce_normalcy as extern "C" fn(&Object, _) -> BOOL);
+        decl.add_method(sel!(drawRect:), draw_rect as extern "C" fn(&Object, _, EncodedRect));
+        decl.add_method(
+            sel!(wantsUpdateLayer),
+            wants_update_layer as extern "C" fn(&Object, _) -> BOOL
+        );
 
         VIEW_CLASS = decl.register();
     });
@@ -10,7 +10,7 @@ use crate::foundation::id;
 ///
 /// You can opt to include vector assets in your bundle, or draw icons with `Image::draw` by
 /// converting Core Graphics calls (e.g, PaintCode can work well for this).
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "gnustep"))]
 #[derive(Debug)]
 pub enum MacSystemIcon {
     /// A standard "General" preferences icon. This is intended for usage in Preferences toolbars.
@@ -42,7 +42,7 @@ extern "C" {
     static NSImageNameRemoveTemplate: id;
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", feature = "gnustep"))]
 impl MacSystemIcon {
     /// Maps system icons to their pre-11.0 framework identifiers.
     pub fn to_id(&self) -> id {
@@ -5,103 +5,17 @@ use objc::{class, msg_send, sel, sel_impl};
 
 use block::ConcreteBlock;
 
-use core_graphics::context::{CGContext, CGContextRef};
-use core_graphics::{
-    base::CGFloat,
-    geometry::{CGPoint, CGRect, CGSize}
-};
+#[cfg(all(feature = "appkit", target_vendor = "apple"))]
+use crate::core_graphics::context::{CGContext, CGContextRef};
+
+use crate::core_graphics::{base::CGFloat, geometry::CGRect};
 
 use super::icons::*;
-use crate::foundation::{id, NSData, NSString, NO, YES};
+use super::{PixelBuffer, PixelBufferError, ResizeBehavior};
+use crate::foundation::{id, nil, NSArray, NSData, NSInteger, NSNumber, NSString, NSUInteger, NO, YES};
+use crate::geometry::{Rect, Size};
 use crate::utils::os;
 
-/// Specifies resizing behavior for image drawing.
-#[derive(Copy, Clone, Debug)]
-pub enum ResizeBehavior {
-    /// Fit to the aspect ratio.
-    AspectFit,
-
-    /// Fill the aspect ratio.
-    AspectFill,
-
-    /// Stretch as necessary.
-    Stretch,
-
-    /// Center and then let whatever else flow around it.
-    Center
-}
-
-fn max_cgfloat(x: CGFloat, y: CGFloat) -> CGFloat {
-    if x == y {
-        return x;
-    }
-
-    match x > y {
-        true => x,
-        false => y
-    }
-}
-
-fn min_cgfloat(x: CGFloat, y: CGFloat) -> CGFloat {
-    if x == y {
-        return x;
-    }
-
-    match x < y {
-        true => x,
-        false => y
-    }
-}
-
-impl ResizeBehavior {
-    /// Given a source and target rectangle, configures and returns a new rectangle configured with
-    /// the resizing properties of this enum.
-    pub fn apply(&self, source: CGRect, target: CGRect) -> CGRect {
-        // if equal, just return source
-        if source.origin.x == target.origin.x
-            && source.origin.y == target.origin.y
-            && source.size.width == target.size.width
-            && source.size.height == target.size.height
-        {
-            return source;
-        }
-
-        if source.origin.x == 0. && source.origin.y == 0. && source.size.width == 0. && source.size.height == 0. {
-            return source;
-        }
-
-        let mut scales = CGSize::new(0., 0.);
-        scales.width = (target.size.width / source.size.width).abs();
-        scales.height = (target.size.height / source.size.height).abs();
-
-        match self {
-            ResizeBehavior::AspectFit => {
-                scales.width = min_cgfloat(scales.width, scales.height);
-                scales.height = scales.width;
-            },
-
-            ResizeBehavior::AspectFill => {
-                scales.width = max_cgfloat(scales.width, scales.height);
-                scales.height = scales.width;
-            },
-
-            ResizeBehavior::Stretch => { /* will do this as default */ },
-
-            ResizeBehavior::Center => {
-                scales.width = 1.;
-                scales.height = 1.;
-            }
-        }
-
-        let mut result = source;
-        result.size.width *= scales.width;
-        result.size.height *= scales.height;
-        result.origin.x = target.origin.x + (target.size.width - result.size.width) / 2.;
-        result.origin.y = target.origin.y + (target.size.height - result.size.height) / 2.;
-        result
-    }
-}
-
 /// A config object that specifies how drawing into an image context should scale.
 #[derive(Copy, Clone, Debug)]
 pub struct DrawConfig {
@@ -112,10 +26,53 @@ pub struct DrawConfig {
     /// scaled to this size.
     pub target: (CGFloat, CGFloat),
 
-    /// The type of resizing to use during drawing and scaling.
+    /// The type of resizing to use during drawing and scaling. With `ResizeBehavior::Tile`, the
+    /// handler is called once per tile.
     pub resize: ResizeBehavior
 }
 
+/// `NSBitmapImageFileTypeJPEG`.
+#[cfg(feature = "appkit")]
+const JPEG_FILE_TYPE: NSUInteger = 3;
+
+/// `NSBitmapImageFileTypePNG`.
+#[cfg(feature = "appkit")]
+const PNG_FILE_TYPE: NSUInteger = 4;
+
+/// `NSBitmapFormatAlphaNonpremultiplied`.
+#[cfg(feature = "appkit")]
+const NON_PREMULTIPLIED_BITMAP_FORMAT: NSUInteger = 1 << 1;
+
+/// `NSCompositingOperationCopy`.
+#[cfg(feature = "appkit")]
+const COPY_OPERATION: NSUInteger = 1;
+
+#[cfg(all(feature = "uikit", not(feature = "appkit")))]
+extern "C" {
+    fn UIImagePNGRepresentation(image: id) -> id;
+    fn UIImageJPEGRepresentation(image: id, quality: CGFloat) -> id;
+}
+
+/// Creates an 8-bit RGBA `NSBitmapImageRep`, with its own (uninitialized) storage.
+#[cfg(feature = "appkit")]
+unsafe fn allocate_bitmap(width: usize, height: usize, format: NSUInteger) -> id {
+    let color_space = NSString::new("NSCalibratedRGBColorSpace");
+    let planes: *mut *mut u8 = std::ptr::null_mut();
+
+    let rep: id = msg_send![class!(NSBitmapImageRep), alloc];
+    msg_send![rep, initWithBitmapDataPlanes:planes
+        pixelsWide:(width as NSInteger)
+        pixelsHigh:(height as NSInteger)
+        bitsPerSample:(8 as NSInteger)
+        samplesPerPixel:(4 as NSInteger)
+        hasAlpha:YES
+        isPlanar:NO
+        colorSpaceName:&*color_space
+        bitmapFormat:format
+        bytesPerRow:((width * 4) as NSInteger)
+        bitsPerPixel:(32 as NSInteger)]
+}
+
 /// Wraps `NSImage` under AppKit, and `UIImage` on under UIKit (iOS and tvOS). Can be used to display images, icons,
 /// and so on.
 #[derive(Clone, Debug)]
@@ -160,8 +117,8 @@ impl Image {
     // @TODO: for Airyx, unsure if this is supported - and it's somewhat modern macOS-specific, so
     // let's keep the os flag here for now.
     /// Returns a stock system icon. These are guaranteed to exist across all versions of macOS
-    /// supported.
-    #[cfg(target_os = "macos")]
+    /// supported, and in GNUstep's default theme.
+    #[cfg(any(target_os = "macos", feature = "gnustep"))]
     pub fn system_icon(icon: MacSystemIcon) -> Self {
         Image(unsafe {
             ShareId::from_ptr({
@@ -181,10 +138,11 @@ impl Image {
     /// versions.
     ///
     /// However, if you need the correct "folder" icon for instance, you probably want `system_icon`.
-    #[cfg(target_os = "macos")]
+    /// GNUstep has no SFSymbols, so this is always the `MacSystemIcon` image there.
+    #[cfg(any(target_os = "macos", feature = "gnustep"))]
     pub fn toolbar_icon(icon: MacSystemIcon, accessibility_description: &str) -> Self {
         Image(unsafe {
-            ShareId::from_ptr(match os::is_minimum_version(11) {
+            ShareId::from_ptr(match !cfg!(feature = "gnustep") && os::is_minimum_version(11) {
                 true => {
                     let icon = NSString::new(icon.to_sfsymbol_str());
                     let desc = NSString::new(accessibility_description);
@@ -205,9 +163,10 @@ impl Image {
     /// lower system. Take care to provide a fallback image or user experience if you
     /// need to support an older OS.
     ///
-    /// This is `target_os` gated as SFSymbols is fairly Apple-specific. If another runtime
-    /// ever exposes a compatible API, this can be tweaked in a PR.
-    #[cfg(any(target_os = "macos", target_os = "ios"))]
+    /// This is `target_os` gated as SFSymbols is fairly Apple-specific, and isn't available with
+    /// the `gnustep` feature. If another runtime ever exposes a compatible API, this can be
+    /// tweaked in a PR.
+    #[cfg(all(any(target_os = "macos", target_os = "ios"), not(feature = "gnustep")))]
     pub fn symbol(symbol: SFSymbol, accessibility_description: &str) -> Self {
         // SFSymbols is macOS 11.0+
         #[cfg(feature = "appkit")]
@@ -237,34 +196,184 @@ impl Image {
         })
     }
 
+    /// Creates an image from 8-bit RGBA pixels, with non-premultiplied alpha and rows running from
+    /// top to bottom. `scale` is the number of pixels per point - e.g, `2.` for a Retina
+    /// screenshot.
+    ///
+    /// This is currently only supported on AppKit-based backends.
+    #[cfg(feature = "appkit")]
+    pub fn from_rgba(width: usize, height: usize, pixels: &[u8], scale: f64) -> Result<Self, PixelBufferError> {
+        let buffer = PixelBuffer::new(width, height, pixels.to_vec(), scale)?;
+        Ok(Self::from_pixel_buffer(&buffer))
+    }
+
+    /// Creates an image from a `PixelBuffer`.
+    ///
+    /// This is currently only supported on AppKit-based backends.
+    #[cfg(feature = "appkit")]
+    pub fn from_pixel_buffer(buffer: &PixelBuffer) -> Self {
+        let size = buffer.size();
+
+        unsafe {
+            let rep = allocate_bitmap(buffer.width(), buffer.height(), NON_PREMULTIPLIED_BITMAP_FORMAT);
+            let data: *mut u8 = msg_send![rep, bitmapData];
+            std::ptr::copy_nonoverlapping(buffer.as_bytes().as_ptr(), data, buffer.as_bytes().len());
+            let _: () = msg_send![rep, setSize: size];
+
+            let image: id = msg_send![Self::class(), alloc];
+            let image: id = msg_send![image, initWithSize: size];
+            let _: () = msg_send![image, addRepresentation: rep];
+            let _: () = msg_send![rep, release];
+
+            Image(ShareId::from_retained_ptr(image))
+        }
+    }
+
+    /// Renders this image to 8-bit RGBA pixels, at the resolution of its largest bitmap
+    /// representation (or one pixel per point, for vector images). Returns `None` for empty
+    /// images, or if rendering fails.
+    ///
+    /// This is currently only supported on AppKit-based backends.
+    #[cfg(feature = "appkit")]
+    pub fn pixel_data(&self) -> Option<PixelBuffer> {
+        let size: Size = unsafe { msg_send![&*self.0, size] };
+        if size.is_empty() {
+            return None;
+        }
+
+        let representations = NSArray::retain(unsafe { msg_send![&*self.0, representations] });
+        let (width, height) = representations
+            .map(|rep| unsafe {
+                let width: NSInteger = msg_send![rep, pixelsWide];
+                let height: NSInteger = msg_send![rep, pixelsHigh];
+                (width.max(0) as usize, height.max(0) as usize)
+            })
+            .into_iter()
+            .max()
+            .filter(|(width, height)| *width > 0 && *height > 0)
+            .unwrap_or((size.width.ceil() as usize, size.height.ceil() as usize));
+
+        unsafe {
+            let rep = allocate_bitmap(width, height, 0);
+            let context: id = msg_send![class!(NSGraphicsContext), graphicsContextWithBitmapImageRep: rep];
+
+            if context == nil {
+                let _: () = msg_send![rep, release];
+                return None;
+            }
+
+            let _: () = msg_send![class!(NSGraphicsContext), saveGraphicsState];
+            let _: () = msg_send![class!(NSGraphicsContext), setCurrentContext: context];
+
+            let target = Rect::new(0., 0., width as f64, height as f64);
+            let _: () = msg_send![&*self.0, drawInRect:CGRect::from(target)
+                fromRect:CGRect::from(Rect::zero())
+                operation:COPY_OPERATION
+                fraction:(1.0 as CGFloat)
+                respectFlipped:YES
+                hints:nil];
+
+            let _: () = msg_send![context, flushGraphics];
+            let _: () = msg_send![class!(NSGraphicsContext), restoreGraphicsState];
+
+            let data: *const u8 = msg_send![rep, bitmapData];
+            let bytes_per_row: NSInteger = msg_send![rep, bytesPerRow];
+            let bytes = std::slice::from_raw_parts(data, bytes_per_row as usize * height);
+            let buffer = PixelBuffer::from_premultiplied(width, height, bytes_per_row as usize, bytes, width as f64 / size.width);
+            let _: () = msg_send![rep, release];
+
+            buffer.ok()
+        }
+    }
+
+    /// Encodes this image as PNG data. Returns `None` if the image can't be encoded.
+    pub fn to_png(&self) -> Option<Vec<u8>> {
+        #[cfg(feature = "appkit")]
+        return self.encode(PNG_FILE_TYPE, None);
+
+        #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+        return unsafe { Self::data_to_vec(UIImagePNGRepresentation(&*self.0 as *const Object as id)) };
+    }
+
+    /// Encodes this image as JPEG data, with a quality between `0.` (most compressed) and `1.`
+    /// (least compressed). Returns `None` if the image can't be encoded.
+    pub fn to_jpeg(&self, quality: f64) -> Option<Vec<u8>> {
+        let quality = quality.clamp(0., 1.);
+
+        #[cfg(feature = "appkit")]
+        return self.encode(JPEG_FILE_TYPE, Some(quality));
+
+        #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+        return unsafe { Self::data_to_vec(UIImageJPEGRepresentation(&*self.0 as *const Object as id, quality as CGFloat)) };
+    }
+
+    /// Encodes this image via `NSBitmapImageRep`.
+    #[cfg(feature = "appkit")]
+    fn encode(&self, file_type: NSUInteger, quality: Option<f64>) -> Option<Vec<u8>> {
+        unsafe {
+            let tiff: id = msg_send![&*self.0, TIFFRepresentation];
+            if tiff == nil {
+                return None;
+            }
+
+            let rep: id = msg_send![class!(NSBitmapImageRep), imageRepWithData: tiff];
+            if rep == nil {
+                return None;
+            }
+
+            let properties: id = match quality {
+                Some(quality) => {
+                    let factor = NSNumber::float(quality);
+                    let key = NSString::new("NSImageCompressionFactor");
+                    msg_send![class!(NSDictionary), dictionaryWithObject:&*factor.0 forKey:&*key]
+                },
+
+                None => msg_send![class!(NSDictionary), dictionary]
+            };
+
+            Self::data_to_vec(msg_send![rep, representationUsingType:file_type properties:properties])
+        }
+    }
+
+    fn data_to_vec(data: id) -> Option<Vec<u8>> {
+        match data == nil {
+            true => None,
+            false => Some(NSData::retain(data).into_vec())
+        }
+    }
+
     /// Draw a custom image and get it back as a returned `Image`.
     ///
     /// This is currently only supported on AppKit-based backends, and has
-    /// only been tested on macOS.
-    #[cfg(feature = "appkit")]
+    /// only been tested on macOS. It hands you a Core Graphics context, so it isn't available on
+    /// GNUstep.
+    #[cfg(all(feature = "appkit", target_vendor = "apple"))]
     pub fn draw<F>(config: DrawConfig, handler: F) -> Self
     where
         F: Fn(CGRect, &CGContextRef) -> bool + 'static
     {
-        let source_frame = CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(config.source.0, config.source.1));
-
-        let target_frame = CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(config.target.0, config.target.1));
+        let source = Size::new(config.source.0, config.source.1);
+        let target_frame = Rect::new(0., 0., config.target.0, config.target.1);
 
-        let resized_frame = config.resize.apply(source_frame, target_frame);
+        // There's more than one frame when tiling; each is drawn in its own coordinate space.
+        let frames = config.resize.frames(source, target_frame);
 
         let block = ConcreteBlock::new(move |_destination: CGRect| unsafe {
             let current_context: id = msg_send![class!(NSGraphicsContext), currentContext];
-            let context_ptr: core_graphics::sys::CGContextRef = msg_send![current_context, CGContext];
+            let context_ptr: crate::core_graphics::sys::CGContextRef = msg_send![current_context, CGContext];
             let context = CGContext::from_existing_context_ptr(context_ptr);
             let _: () = msg_send![class!(NSGraphicsContext), saveGraphicsState];
+            context.clip_to_rect(target_frame.into());
 
-            context.translate(resized_frame.origin.x, resized_frame.origin.y);
-            context.scale(
-                resized_frame.size.width / config.source.0,
-                resized_frame.size.height / config.source.1
-            );
+            let result = frames.iter().fold(true, |result, frame| {
+                context.save();
+                context.translate(frame.left, frame.top);
+                context.scale(frame.width / config.source.0, frame.height / config.source.1);
 
-            let result = handler(resized_frame, &context);
+                let drawn = handler((*frame).into(), &context);
+                context.restore();
+                drawn && result
+            });
 
             let _: () = msg_send![class!(NSGraphicsContext), restoreGraphicsState];
 
@@ -276,7 +385,7 @@ impl Image {
         let block = block.copy();
 
         Image(unsafe {
-            let img: id = msg_send![Self::class(), imageWithSize:target_frame.size
+            let img: id = msg_send![Self::class(), imageWithSize:target_frame.size()
                 flipped:YES
                 drawingHandler:block
             ];
@@ -3,7 +3,7 @@ use objc::{msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
 use crate::color::Color;
-use crate::foundation::{id, nil, NSArray, NSString, NO, YES};
+use crate::foundation::{id, nil, NSArray, NSInteger, NSString, NSUInteger, NO, YES};
 use crate::layout::Layout;
 use crate::objc_access::ObjcAccess;
 use crate::utils::properties::ObjcProperty;
@@ -15,7 +15,7 @@ use crate::layout::{LayoutAnchorDimension, LayoutAnchorX, LayoutAnchorY};
 mod appkit;
 
 #[cfg(feature = "appkit")]
-use appkit::register_image_view_class;
+use appkit::{register_image_view_class, RESIZE_BEHAVIOR_IVAR};
 
 #[cfg(feature = "uikit")]
 mod uikit;
@@ -24,7 +24,13 @@ mod uikit;
 use uikit::register_image_view_class;
 
 mod image;
-pub use image::{DrawConfig, Image, ResizeBehavior};
+pub use image::{DrawConfig, Image};
+
+mod resize;
+pub use resize::ResizeBehavior;
+
+mod pixels;
+pub use pixels::{ImageFormat, PixelBuffer, PixelBufferError};
 
 mod icons;
 pub use icons::*;
@@ -140,6 +146,9 @@ impl ImageView {
     }
 
     /// Call this to set the background color for the backing layer.
+    ///
+    /// Layers take a `CGColor`, so this isn't available on GNUstep.
+    #[cfg(target_vendor = "apple")]
     pub fn set_background_color<C: AsRef<Color>>(&self, color: C) {
         self.objc.with_mut(|obj| unsafe {
             let cg = color.as_ref().cg_color();
@@ -156,12 +165,40 @@ impl ImageView {
         });
     }
 
-    /*pub fn set_image_scaling(&self, scaling_type: ImageScale) {
+    /// Sets how the image is sized and positioned within this view.
+    ///
+    /// On AppKit, the view draws the image itself once this is set. On UIKit, this maps to
+    /// `contentMode` - which has no equivalent for `ScaleDownOnly` or `Tile`, so these fall back
+    /// to `AspectFit` and `TopLeft` respectively.
+    pub fn set_resize_behavior(&self, behavior: ResizeBehavior) {
+        #[cfg(feature = "appkit")]
         self.objc.with_mut(|obj| unsafe {
+            let index = ResizeBehavior::ALL.iter().position(|b| *b == behavior).unwrap_or(0) + 1;
+            (*obj).set_ivar::<NSUInteger>(RESIZE_BEHAVIOR_IVAR, index as NSUInteger);
+            let _: () = msg_send![obj, setNeedsDisplay: YES];
+        });
 
-            let _: () = msg_send![obj, setImageScaling:
+        #[cfg(all(feature = "uikit", not(feature = "appkit")))]
+        self.objc.with_mut(|obj| unsafe {
+            // UIViewContentMode
+            let mode: NSInteger = match behavior {
+                ResizeBehavior::Stretch => 0,
+                ResizeBehavior::AspectFit | ResizeBehavior::ScaleDownOnly => 1,
+                ResizeBehavior::AspectFill => 2,
+                ResizeBehavior::Center => 4,
+                ResizeBehavior::Top => 5,
+                ResizeBehavior::Bottom => 6,
+                ResizeBehavior::Left => 7,
+                ResizeBehavior::Right => 8,
+                ResizeBehavior::TopLeft | ResizeBehavior::Tile => 9,
+                ResizeBehavior::TopRight => 10,
+                ResizeBehavior::BottomLeft => 11,
+                ResizeBehavior::BottomRight => 12
+            };
+
+            let _: () = msg_send![obj, setContentMode: mode];
         });
-    }*/
+    }
 }
 
 impl ObjcAccess for ImageView {
@@ -198,8 +235,10 @@ impl Drop for ImageView {
 #[test]
 fn test_image() {
     let image_view = ImageView::new();
+    #[cfg(target_vendor = "apple")]
     image_view.set_background_color(Color::SystemBlue);
     let image_bytes = include_bytes!("../../test-data/favicon.ico");
     let image = Image::with_data(image_bytes);
     image_view.set_image(&image);
+    image_view.set_resize_behavior(ResizeBehavior::Tile);
 }
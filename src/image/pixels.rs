@@ -0,0 +1,347 @@
+//! Plain Rust pixel buffers and image format detection. These are what `Image::from_rgba()` and
+//! `Image::pixel_data()` move between, and don't depend on AppKit or UIKit.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::geometry::Size;
+use crate::utils::signatures;
+
+/// An error from constructing a `PixelBuffer`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PixelBufferError {
+    /// The width or height was zero.
+    InvalidDimensions {
+        /// The width that was passed.
+        width: usize,
+
+        /// The height that was passed.
+        height: usize
+    },
+
+    /// The pixel data wasn't `width * height * 4` bytes long.
+    LengthMismatch {
+        /// The number of bytes expected, or `usize::MAX` if that overflows.
+        expected: usize,
+
+        /// The number of bytes passed.
+        actual: usize
+    },
+
+    /// The scale wasn't a positive, finite number.
+    InvalidScale(f64)
+}
+
+impl fmt::Display for PixelBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PixelBufferError::InvalidDimensions { width, height } => {
+                write!(f, "Pixel buffers can't be empty (got {}x{})", width, height)
+            },
+
+            PixelBufferError::LengthMismatch { expected, actual } => {
+                write!(f, "Expected {} bytes of RGBA data, but got {}", expected, actual)
+            },
+
+            PixelBufferError::InvalidScale(scale) => write!(f, "Invalid scale factor: {}", scale)
+        }
+    }
+}
+
+impl Error for PixelBufferError {}
+
+/// A bitmap of 8-bit RGBA pixels, with non-premultiplied alpha and rows running from top to
+/// bottom. `scale` is the number of pixels per point (e.g, `2.` for Retina).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelBuffer {
+    width: usize,
+    height: usize,
+    scale: f64,
+    data: Vec<u8>
+}
+
+impl PixelBuffer {
+    /// Wraps `data`, checking that it's the right length for the given dimensions.
+    pub fn new(width: usize, height: usize, data: Vec<u8>, scale: f64) -> Result<Self, PixelBufferError> {
+        if width == 0 || height == 0 {
+            return Err(PixelBufferError::InvalidDimensions { width, height });
+        }
+
+        if !(scale.is_finite() && scale > 0.) {
+            return Err(PixelBufferError::InvalidScale(scale));
+        }
+
+        let expected = byte_len(width, height);
+        if expected != Some(data.len()) {
+            return Err(PixelBufferError::LengthMismatch {
+                expected: expected.unwrap_or(usize::MAX),
+                actual: data.len()
+            });
+        }
+
+        Ok(PixelBuffer {
+            width,
+            height,
+            scale,
+            data
+        })
+    }
+
+    /// Converts premultiplied RGBA rows (as rendered by Core Graphics), which may be padded out to
+    /// `bytes_per_row`, into a buffer.
+    pub(crate) fn from_premultiplied(
+        width: usize,
+        height: usize,
+        bytes_per_row: usize,
+        premultiplied: &[u8],
+        scale: f64
+    ) -> Result<Self, PixelBufferError> {
+        let capacity = byte_len(width, height).ok_or(PixelBufferError::LengthMismatch {
+            expected: usize::MAX,
+            actual: premultiplied.len()
+        })?;
+
+        let mut data = Vec::with_capacity(capacity.min(premultiplied.len()));
+
+        for row in premultiplied.chunks(bytes_per_row.max(1)).take(height) {
+            for pixel in row[..(width * 4).min(row.len())].chunks_exact(4) {
+                let alpha = pixel[3] as u32;
+
+                for channel in &pixel[..3] {
+                    data.push(match alpha {
+                        0 => 0,
+                        alpha => ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8
+                    });
+                }
+
+                data.push(pixel[3]);
+            }
+        }
+
+        PixelBuffer::new(width, height, data, scale)
+    }
+
+    /// The width, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of pixels per point.
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// The size in points (i.e, the pixel size divided by the scale).
+    pub fn size(&self) -> Size {
+        Size::new(self.width as f64, self.height as f64).scaled(1. / self.scale)
+    }
+
+    /// The RGBA bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Consumes the buffer, returning its RGBA bytes.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns the RGBA value of the pixel at `x`, `y` (from the top left), if it's in bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        let offset = (y * self.width + x) * 4;
+        let mut pixel = [0; 4];
+        pixel.copy_from_slice(&self.data[offset..offset + 4]);
+        Some(pixel)
+    }
+}
+
+/// The length of `width` by `height` RGBA pixels in bytes, if that fits in a `usize`.
+fn byte_len(width: usize, height: usize) -> Option<usize> {
+    width.checked_mul(height)?.checked_mul(4)
+}
+
+/// Encoded image formats that can be recognized from their first few bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// Portable Network Graphics.
+    Png,
+
+    /// JPEG.
+    Jpeg,
+
+    /// Graphics Interchange Format.
+    Gif,
+
+    /// Tagged Image File Format.
+    Tiff,
+
+    /// Windows bitmaps.
+    Bmp,
+
+    /// Windows icons.
+    Ico,
+
+    /// WebP.
+    WebP,
+
+    /// HEIF/HEIC.
+    Heic,
+
+    /// PDF documents, which AppKit can load as vector images.
+    Pdf
+}
+
+impl ImageFormat {
+    /// Every format, in declaration order.
+    const ALL: [ImageFormat; 9] = [
+        ImageFormat::Png,
+        ImageFormat::Jpeg,
+        ImageFormat::Gif,
+        ImageFormat::Tiff,
+        ImageFormat::Bmp,
+        ImageFormat::Ico,
+        ImageFormat::WebP,
+        ImageFormat::Heic,
+        ImageFormat::Pdf
+    ];
+
+    /// Detects the format of encoded image data from its signature.
+    pub fn sniff(data: &[u8]) -> Option<ImageFormat> {
+        let mime_type = signatures::sniff(data)?;
+
+        ImageFormat::ALL
+            .iter()
+            .find(|format| format.mime_type() == mime_type)
+            .copied()
+    }
+
+    /// The MIME type for this format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Tiff => "image/tiff",
+            ImageFormat::Bmp => "image/bmp",
+            ImageFormat::Ico => "image/vnd.microsoft.icon",
+            ImageFormat::WebP => "image/webp",
+            ImageFormat::Heic => "image/heic",
+            ImageFormat::Pdf => "application/pdf"
+        }
+    }
+
+    /// The usual file extension for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Tiff => "tiff",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Ico => "ico",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Heic => "heic",
+            ImageFormat::Pdf => "pdf"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageFormat, PixelBuffer, PixelBufferError};
+    use crate::geometry::Size;
+
+    #[test]
+    fn test_pixel_buffer() {
+        let data = vec![
+            255, 0, 0, 255, 0, 255, 0, 128, //
+            0, 0, 255, 0, 10, 20, 30, 40,
+        ];
+
+        let buffer = PixelBuffer::new(2, 2, data.clone(), 2.).unwrap();
+        assert_eq!(buffer.size(), Size::new(1., 1.));
+        assert_eq!(buffer.pixel(1, 0), Some([0, 255, 0, 128]));
+        assert_eq!(buffer.pixel(1, 1), Some([10, 20, 30, 40]));
+        assert_eq!(buffer.pixel(2, 0), None);
+        assert_eq!(buffer.into_vec(), data);
+
+        assert_eq!(
+            PixelBuffer::new(0, 2, vec![], 1.),
+            Err(PixelBufferError::InvalidDimensions { width: 0, height: 2 })
+        );
+
+        assert_eq!(
+            PixelBuffer::new(2, 2, vec![0; 15], 1.),
+            Err(PixelBufferError::LengthMismatch {
+                expected: 16,
+                actual: 15
+            })
+        );
+
+        assert_eq!(
+            PixelBuffer::new(1, 1, vec![0; 4], 0.),
+            Err(PixelBufferError::InvalidScale(0.))
+        );
+
+        assert_eq!(
+            PixelBuffer::new(usize::MAX, 2, vec![0; 4], 1.),
+            Err(PixelBufferError::LengthMismatch {
+                expected: usize::MAX,
+                actual: 4
+            })
+        );
+
+        assert!(PixelBuffer::from_premultiplied(usize::MAX, usize::MAX, 4, &[0; 4], 1.).is_err());
+    }
+
+    #[test]
+    fn test_from_premultiplied() {
+        // Two pixels per row, padded out to 12 bytes.
+        let premultiplied = [
+            255, 0, 0, 255, 0, 128, 0, 128, 9, 9, 9, 9, //
+            0, 0, 0, 0, 20, 40, 60, 80, 9, 9, 9, 9
+        ];
+
+        let buffer = PixelBuffer::from_premultiplied(2, 2, 12, &premultiplied, 1.).unwrap();
+        assert_eq!(buffer.as_bytes(), &[
+            255, 0, 0, 255, 0, 255, 0, 128, //
+            0, 0, 0, 0, 64, 128, 191, 80
+        ]);
+
+        assert!(PixelBuffer::from_premultiplied(2, 3, 12, &premultiplied, 1.).is_err());
+    }
+
+    #[test]
+    fn test_sniff() {
+        let cases: [(&[u8], Option<ImageFormat>); 12] = [
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", Some(ImageFormat::Png)),
+            (b"\xff\xd8\xff\xe0\0\x10JFIF", Some(ImageFormat::Jpeg)),
+            (b"GIF89a\x01\0", Some(ImageFormat::Gif)),
+            (b"II*\0\x08\0\0\0", Some(ImageFormat::Tiff)),
+            (b"MM\0*\0\0\0\x08", Some(ImageFormat::Tiff)),
+            (b"BM6\0\0\0", Some(ImageFormat::Bmp)),
+            (include_bytes!("../../test-data/favicon.ico"), Some(ImageFormat::Ico)),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", Some(ImageFormat::WebP)),
+            (b"\0\0\0\x18ftypheic\0\0\0\0", Some(ImageFormat::Heic)),
+            (b"\0\0\0\x18ftypisom\0\0\0\0", None),
+            (b"%PDF-1.7", Some(ImageFormat::Pdf)),
+            (b"", None)
+        ];
+
+        for (data, format) in cases.iter() {
+            assert_eq!(ImageFormat::sniff(data), *format, "{:?}", data);
+        }
+
+        assert_eq!(ImageFormat::Jpeg.mime_type(), "image/jpeg");
+        assert_eq!(ImageFormat::Jpeg.extension(), "jpg");
+    }
+}
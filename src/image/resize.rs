@@ -0,0 +1,309 @@
+//! Content modes for drawing an image into a frame. The math here is plain Rust, so it's shared
+//! between `Image::draw`, `ImageView` and anything else that needs to place an image.
+//!
+//! Rects use a top-left origin: `Top` anchors to the minimum `y` edge of the target.
+
+use crate::core_graphics::geometry::CGRect;
+
+use crate::geometry::{Rect, Size};
+
+/// The most tiles `ResizeBehavior::Tile` will ever produce for a single draw.
+pub(crate) const MAX_TILES: usize = 1024;
+
+/// Specifies resizing behavior for image drawing.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ResizeBehavior {
+    /// Fit to the aspect ratio.
+    AspectFit,
+
+    /// Fill the aspect ratio.
+    AspectFill,
+
+    /// Stretch as necessary.
+    Stretch,
+
+    /// Center and then let whatever else flow around it.
+    Center,
+
+    /// Don't scale, and anchor to the center of the top edge.
+    Top,
+
+    /// Don't scale, and anchor to the center of the bottom edge.
+    Bottom,
+
+    /// Don't scale, and anchor to the middle of the left edge.
+    Left,
+
+    /// Don't scale, and anchor to the middle of the right edge.
+    Right,
+
+    /// Don't scale, and anchor to the top left corner.
+    TopLeft,
+
+    /// Don't scale, and anchor to the top right corner.
+    TopRight,
+
+    /// Don't scale, and anchor to the bottom left corner.
+    BottomLeft,
+
+    /// Don't scale, and anchor to the bottom right corner.
+    BottomRight,
+
+    /// Fit to the aspect ratio if the source is larger than the target, and center it otherwise.
+    /// This never scales up.
+    ScaleDownOnly,
+
+    /// Repeat the source at its natural size, starting from the top left corner.
+    Tile
+}
+
+/// Where a source is placed along one axis.
+#[derive(Copy, Clone)]
+enum Alignment {
+    Start,
+    Middle,
+    End
+}
+
+impl Alignment {
+    fn position(&self, start: f64, available: f64, length: f64) -> f64 {
+        match self {
+            Alignment::Start => start,
+            Alignment::Middle => start + (available - length) / 2.,
+            Alignment::End => start + available - length
+        }
+    }
+}
+
+impl ResizeBehavior {
+    /// Every behavior, in declaration order.
+    pub(crate) const ALL: [ResizeBehavior; 14] = [
+        ResizeBehavior::AspectFit,
+        ResizeBehavior::AspectFill,
+        ResizeBehavior::Stretch,
+        ResizeBehavior::Center,
+        ResizeBehavior::Top,
+        ResizeBehavior::Bottom,
+        ResizeBehavior::Left,
+        ResizeBehavior::Right,
+        ResizeBehavior::TopLeft,
+        ResizeBehavior::TopRight,
+        ResizeBehavior::BottomLeft,
+        ResizeBehavior::BottomRight,
+        ResizeBehavior::ScaleDownOnly,
+        ResizeBehavior::Tile
+    ];
+
+    /// The horizontal and vertical alignment of the source within the target.
+    fn alignment(&self) -> (Alignment, Alignment) {
+        use Alignment::*;
+
+        match self {
+            ResizeBehavior::Top => (Middle, Start),
+            ResizeBehavior::Bottom => (Middle, End),
+            ResizeBehavior::Left => (Start, Middle),
+            ResizeBehavior::Right => (End, Middle),
+            ResizeBehavior::TopLeft | ResizeBehavior::Tile => (Start, Start),
+            ResizeBehavior::TopRight => (End, Start),
+            ResizeBehavior::BottomLeft => (Start, End),
+            ResizeBehavior::BottomRight => (End, End),
+            _ => (Middle, Middle)
+        }
+    }
+
+    /// The horizontal and vertical scale applied to a source of the given size.
+    fn scale(&self, source: Size, target: Size) -> (f64, f64) {
+        if source.is_empty() {
+            return (1., 1.);
+        }
+
+        let (x, y) = ((target.width / source.width).abs(), (target.height / source.height).abs());
+
+        match self {
+            ResizeBehavior::AspectFit => (x.min(y), x.min(y)),
+            ResizeBehavior::AspectFill => (x.max(y), x.max(y)),
+            ResizeBehavior::Stretch => (x, y),
+            ResizeBehavior::ScaleDownOnly => (x.min(y).min(1.), x.min(y).min(1.)),
+            _ => (1., 1.)
+        }
+    }
+
+    /// Returns where a source of the given size should be drawn within `target`. For `Tile`, this
+    /// is the first tile; see `frames` for the rest.
+    pub fn frame(&self, source: Size, target: Rect) -> Rect {
+        let (scale_x, scale_y) = self.scale(source, target.size());
+        let (width, height) = (source.width * scale_x, source.height * scale_y);
+        let (horizontal, vertical) = self.alignment();
+
+        Rect::new(
+            vertical.position(target.top, target.height, height),
+            horizontal.position(target.left, target.width, width),
+            width,
+            height
+        )
+    }
+
+    /// Returns every rect a source of the given size should be drawn in to fill `target`. This is
+    /// a single rect for everything other than `Tile`, which covers the target with tiles (the
+    /// last row and column may extend past it, and should be clipped).
+    ///
+    /// Tiling never produces more than 1024 rects: a source that's tiny relative to the
+    /// target is scaled up (keeping its aspect ratio) until the tiles fit within that limit.
+    pub fn frames(&self, source: Size, target: Rect) -> Vec<Rect> {
+        let first = self.frame(source, target);
+
+        if *self != ResizeBehavior::Tile || source.is_empty() || !(first.width > 0. && first.height > 0.) {
+            return vec![first];
+        }
+
+        let (mut width, mut height) = (first.width, first.height);
+        let mut columns = (target.width / width).ceil().max(1.);
+        let mut rows = (target.height / height).ceil().max(1.);
+
+        if columns * rows > MAX_TILES as f64 {
+            let factor = (columns * rows / MAX_TILES as f64).sqrt();
+            width *= factor;
+            height *= factor;
+            columns = (target.width / width).ceil().max(1.);
+            rows = (target.height / height).ceil().max(1.);
+
+            // Rounding each axis up (or an axis that's a single tile either way, for a long and
+            // thin target) can leave that over the limit. Cap each axis, then grow the tile until
+            // the capped counts still cover the target.
+            if columns * rows > MAX_TILES as f64 {
+                columns = columns.min(MAX_TILES as f64);
+                rows = rows.min((MAX_TILES as f64 / columns).floor());
+
+                let factor = (target.width / (columns * width)).max(target.height / (rows * height));
+                width *= factor.max(1.);
+                height *= factor.max(1.);
+                columns = (target.width / width).ceil().clamp(1., columns);
+                rows = (target.height / height).ceil().clamp(1., rows);
+            }
+        }
+
+        let (columns, rows) = (columns as usize, rows as usize);
+        let mut frames = Vec::with_capacity(columns * rows);
+
+        for row in 0..rows {
+            for column in 0..columns {
+                frames.push(Rect::new(
+                    target.top + row as f64 * height,
+                    target.left + column as f64 * width,
+                    width,
+                    height
+                ));
+            }
+        }
+
+        frames
+    }
+
+    /// Given a source and target rectangle, configures and returns a new rectangle configured with
+    /// the resizing properties of this enum.
+    pub fn apply(&self, source: CGRect, target: CGRect) -> CGRect {
+        if source.origin.x == 0. && source.origin.y == 0. && source.size.width == 0. && source.size.height == 0. {
+            return source;
+        }
+
+        self.frame(source.size.into(), target.into()).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core_graphics::geometry::{CGPoint, CGRect, CGSize};
+
+    use super::ResizeBehavior;
+    use crate::geometry::{Rect, Size};
+
+    #[test]
+    fn test_frames() {
+        let target = Rect::new(10., 20., 200., 100.);
+        let large = Size::new(400., 100.);
+        let small = Size::new(40., 20.);
+
+        let cases = [
+            (ResizeBehavior::AspectFit, large, Rect::new(35., 20., 200., 50.)),
+            (ResizeBehavior::AspectFit, small, Rect::new(10., 20., 200., 100.)),
+            (ResizeBehavior::AspectFill, large, Rect::new(10., -80., 400., 100.)),
+            (ResizeBehavior::AspectFill, small, Rect::new(10., 20., 200., 100.)),
+            (ResizeBehavior::Stretch, large, Rect::new(10., 20., 200., 100.)),
+            (ResizeBehavior::Stretch, small, Rect::new(10., 20., 200., 100.)),
+            (ResizeBehavior::Center, large, Rect::new(10., -80., 400., 100.)),
+            (ResizeBehavior::Center, small, Rect::new(50., 100., 40., 20.)),
+            (ResizeBehavior::Top, small, Rect::new(10., 100., 40., 20.)),
+            (ResizeBehavior::Bottom, small, Rect::new(90., 100., 40., 20.)),
+            (ResizeBehavior::Left, small, Rect::new(50., 20., 40., 20.)),
+            (ResizeBehavior::Right, small, Rect::new(50., 180., 40., 20.)),
+            (ResizeBehavior::TopLeft, small, Rect::new(10., 20., 40., 20.)),
+            (ResizeBehavior::TopRight, small, Rect::new(10., 180., 40., 20.)),
+            (ResizeBehavior::BottomLeft, small, Rect::new(90., 20., 40., 20.)),
+            (ResizeBehavior::BottomRight, small, Rect::new(90., 180., 40., 20.)),
+            (ResizeBehavior::BottomRight, large, Rect::new(10., -180., 400., 100.)),
+            (ResizeBehavior::ScaleDownOnly, large, Rect::new(35., 20., 200., 50.)),
+            (ResizeBehavior::ScaleDownOnly, small, Rect::new(50., 100., 40., 20.)),
+            (ResizeBehavior::Tile, small, Rect::new(10., 20., 40., 20.)),
+            (ResizeBehavior::AspectFit, Size::zero(), Rect::new(60., 120., 0., 0.))
+        ];
+
+        for (behavior, source, expected) in cases.iter() {
+            assert_eq!(behavior.frame(*source, target), *expected, "{:?} {:?}", behavior, source);
+        }
+
+        // Every behavior other than `Tile` draws exactly once.
+        for behavior in ResizeBehavior::ALL.iter().filter(|b| **b != ResizeBehavior::Tile) {
+            assert_eq!(behavior.frames(small, target), vec![behavior.frame(small, target)]);
+        }
+    }
+
+    #[test]
+    fn test_tiles() {
+        let target = Rect::new(0., 0., 100., 50.);
+        let tiles = ResizeBehavior::Tile.frames(Size::new(40., 30.), target);
+
+        assert_eq!(tiles, vec![
+            Rect::new(0., 0., 40., 30.),
+            Rect::new(0., 40., 40., 30.),
+            Rect::new(0., 80., 40., 30.),
+            Rect::new(30., 0., 40., 30.),
+            Rect::new(30., 40., 40., 30.),
+            Rect::new(30., 80., 40., 30.)
+        ]);
+
+        assert_eq!(ResizeBehavior::Tile.frames(Size::zero(), target).len(), 1);
+
+        // A tiny source is scaled up rather than producing millions of tiles.
+        let tiles = ResizeBehavior::Tile.frames(Size::new(0.01, 0.01), Rect::new(0., 0., 1000., 1000.));
+        assert!(tiles.len() <= super::MAX_TILES);
+        assert_eq!(tiles[0].width, tiles[0].height);
+        assert!(tiles.last().unwrap().right() >= 1000. && tiles.last().unwrap().bottom() >= 1000.);
+
+        // So is one for a long, thin target, where only one axis can give.
+        for target in [
+            Rect::new(0., 0., 10000., 1.),
+            Rect::new(0., 0., 1., 10000.),
+            Rect::new(0., 0., 3000., 7.)
+        ]
+        .iter()
+        {
+            let tiles = ResizeBehavior::Tile.frames(Size::new(1., 1.), *target);
+            let last = tiles.last().unwrap();
+            assert!(tiles.len() <= super::MAX_TILES, "{} tiles for {:?}", tiles.len(), target);
+            assert!(last.right() >= target.width - 0.001 && last.bottom() >= target.height - 0.001);
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let source = CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(400., 100.));
+        let target = CGRect::new(&CGPoint::new(20., 10.), &CGSize::new(200., 100.));
+
+        let result = ResizeBehavior::AspectFit.apply(source, target);
+        assert_eq!((result.origin.x, result.origin.y), (20., 35.));
+        assert_eq!((result.size.width, result.size.height), (200., 50.));
+
+        let zero = CGRect::new(&CGPoint::new(0., 0.), &CGSize::new(0., 0.));
+        assert_eq!(ResizeBehavior::Stretch.apply(zero, target).size.width, 0.);
+    }
+}
@@ -296,6 +296,9 @@ impl<T> TextField<T> {
     }
 
     /// Call this to set the background color for the backing layer.
+    ///
+    /// Layers take a `CGColor`, so this isn't available on GNUstep.
+    #[cfg(target_vendor = "apple")]
     pub fn set_background_color<C: AsRef<Color>>(&self, color: C) {
         self.objc.with_mut(|obj| unsafe {
             let cg = color.as_ref().cg_color();
@@ -12,7 +12,7 @@
 //! view.layer.set_corner_radius(4.0);
 //! ```
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::{class, msg_send, sel, sel_impl};
 
@@ -26,7 +26,7 @@ impl From<NSInteger> for LayoutConstraintOrientation {
 }
 
 /// Represents a relation between layout constraints. Used mostly internally.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutRelation {
     /// Relation is less than or equal to another specified relation.
     LessThanOrEqual,
@@ -59,7 +59,7 @@ impl From<NSInteger> for LayoutRelation {
 /// Note that this only covers attributes that are shared across platforms. In general, this is enough
 /// to build apps that work everywhere - but if you need to specify something else, you can handle
 /// it yourself with the `Unknown` variant.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutAttribute {
     /// The left side of the object’s alignment rectangle.
     Left,
@@ -137,7 +137,7 @@ impl From<NSInteger> for LayoutAttribute {
 /// Note that this only covers formats that are shared across platforms. In general, this is enough
 /// to build apps that work everywhere - but if you need to specify something else, you can handle
 /// it yourself with the `Unknown` variant.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutFormat {
     /// Align all specified interface elements using NSLayoutAttributeLeft on each.
     AlignAllLeft,
@@ -206,7 +206,7 @@ impl From<NSUInteger> for LayoutFormat {
 }
 
 /// Specifies layout priority.
-#[derive(Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum LayoutPriority {
     /// Highest priority.
     Required,
@@ -217,3 +217,14 @@ pub enum LayoutPriority {
     /// Low priority.
     Low
 }
+
+impl From<LayoutPriority> for f64 {
+    /// Returns the underlying `NSLayoutPriority` value.
+    fn from(priority: LayoutPriority) -> Self {
+        match priority {
+            LayoutPriority::Required => 1000.,
+            LayoutPriority::High => 750.,
+            LayoutPriority::Low => 250.
+        }
+    }
+}
@@ -2,7 +2,7 @@
 //! escape hatch, if you need it (we use it for things like width and height, which aren't handled
 //! by an axis).
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
@@ -13,14 +13,21 @@ use crate::foundation::{id, NO, YES};
 #[cfg(all(feature = "appkit", target_os = "macos"))]
 use super::LayoutConstraintAnimatorProxy;
 
+use super::engine::HeadlessConstraint;
+use super::LayoutPriority;
+
 /// A wrapper for `NSLayoutConstraint`. This both acts as a central path through which to activate
 /// constraints, as well as a wrapper for layout constraints that are not axis bound (e.g, width or
 /// height).
+///
+/// Constraints between `HeadlessView`s (see `layout::engine`) use the same type, but live in a
+/// `LayoutEngine` rather than the system.
 #[derive(Clone, Debug)]
 pub struct LayoutConstraint {
-    /// A shared pointer to the underlying view. Provided your view isn't dropped, this will always
-    /// be valid.
-    pub constraint: ShareId<Object>,
+    /// A shared pointer to the underlying constraint. Provided your view isn't dropped, this will
+    /// always be valid. This is always `Some` for constraints between system views, and `None`
+    /// for constraints between `HeadlessView`s.
+    pub constraint: Option<ShareId<Object>>,
 
     /// The offset used in computing this constraint.
     pub offset: f64,
@@ -32,9 +39,13 @@ pub struct LayoutConstraint {
     pub priority: f64,
 
     /// An animator proxy that can be used inside animation contexts.
-    /// This is currently only supported on macOS with the `appkit` feature.
+    /// This is currently only supported on macOS with the `appkit` feature. As with `constraint`,
+    /// it's always `Some` for constraints between system views, and `None` for constraints
+    /// between `HeadlessView`s.
     #[cfg(all(feature = "appkit", target_os = "macos"))]
-    pub animator: LayoutConstraintAnimatorProxy
+    pub animator: Option<LayoutConstraintAnimatorProxy>,
+
+    headless: Option<HeadlessConstraint>
 }
 
 impl LayoutConstraint {
@@ -42,53 +53,91 @@ impl LayoutConstraint {
     pub(crate) fn new(object: id) -> Self {
         LayoutConstraint {
             #[cfg(all(feature = "appkit", target_os = "macos"))]
-            animator: LayoutConstraintAnimatorProxy::new(object),
+            animator: Some(LayoutConstraintAnimatorProxy::new(object)),
 
-            constraint: unsafe { ShareId::from_ptr(object) },
+            constraint: Some(unsafe { ShareId::from_ptr(object) }),
             offset: 0.0,
             multiplier: 0.0,
-            priority: 0.0
+            priority: 0.0,
+            headless: None
         }
     }
 
-    /// Sets the offset for this constraint.
-    pub fn offset<F: Into<f64>>(self, offset: F) -> Self {
-        let offset: f64 = offset.into();
-        unsafe {
-            let o = offset as CGFloat;
-            let _: () = msg_send![&*self.constraint, setConstant: o];
-        }
-
+    /// An internal method for wrapping constraints created by a `LayoutEngine`.
+    pub(crate) fn headless(constraint: HeadlessConstraint) -> Self {
         LayoutConstraint {
             #[cfg(all(feature = "appkit", target_os = "macos"))]
-            animator: self.animator,
+            animator: None,
 
-            constraint: self.constraint,
-            offset: offset,
-            multiplier: self.multiplier,
-            priority: self.priority
+            constraint: None,
+            offset: 0.0,
+            multiplier: 1.0,
+            priority: 1000.0,
+            headless: Some(constraint)
         }
     }
 
+    /// Sets the offset for this constraint.
+    pub fn offset<F: Into<f64>>(self, offset: F) -> Self {
+        let offset: f64 = offset.into();
+        self.set_offset(offset);
+
+        LayoutConstraint { offset, ..self }
+    }
+
     /// Sets the offset of a borrowed constraint.
     pub fn set_offset<F: Into<f64>>(&self, offset: F) {
         let offset: f64 = offset.into();
 
-        unsafe {
-            let o = offset as CGFloat;
-            let _: () = msg_send![&*self.constraint, setConstant: o];
+        if let Some(constraint) = &self.constraint {
+            unsafe {
+                let o = offset as CGFloat;
+                let _: () = msg_send![&**constraint, setConstant: o];
+            }
+        }
+
+        if let Some(headless) = &self.headless {
+            headless.set_offset(offset);
         }
     }
 
+    /// Sets the priority for this constraint. As with `NSLayoutConstraint`, a constraint can't
+    /// be changed to or from `Required` once it's active.
+    pub fn priority(self, priority: LayoutPriority) -> Self {
+        self.with_priority(priority.into())
+    }
+
+    /// Sets an arbitrary priority (between 1 and 1000) for this constraint.
+    pub(crate) fn with_priority(self, priority: f64) -> Self {
+        if let Some(constraint) = &self.constraint {
+            unsafe {
+                let p = priority as f32;
+                let _: () = msg_send![&**constraint, setPriority: p];
+            }
+        }
+
+        if let Some(headless) = &self.headless {
+            headless.set_priority(priority);
+        }
+
+        LayoutConstraint { priority, ..self }
+    }
+
     /// Set whether this constraint is active or not. If you're doing this across a batch of
     /// constraints, it's often more performant to batch-deactivate with
     /// `LayoutConstraint::deactivate()`.
     pub fn set_active(&self, active: bool) {
-        unsafe {
-            let _: () = msg_send![&*self.constraint, setActive:match active {
-                true => YES,
-                false => NO
-            }];
+        if let Some(constraint) = &self.constraint {
+            unsafe {
+                let _: () = msg_send![&**constraint, setActive:match active {
+                    true => YES,
+                    false => NO
+                }];
+            }
+        }
+
+        if let Some(headless) = &self.headless {
+            headless.set_active(active);
         }
     }
 
@@ -101,18 +150,39 @@ impl LayoutConstraint {
     //
     // I regret nothing, lol. If you have a better solution I'm all ears.
     pub fn activate(constraints: &[LayoutConstraint]) {
-        let ids: Vec<&Object> = constraints.into_iter().map(|constraint| &*constraint.constraint).collect();
-        unsafe {
-            let constraints: id = msg_send![class!(NSArray), arrayWithObjects:ids.as_ptr() count:ids.len()];
-            let _: () = msg_send![class!(NSLayoutConstraint), activateConstraints: constraints];
+        let ids = Self::partition(constraints, true);
+
+        if !ids.is_empty() {
+            unsafe {
+                let constraints: id = msg_send![class!(NSArray), arrayWithObjects:ids.as_ptr() count:ids.len()];
+                let _: () = msg_send![class!(NSLayoutConstraint), activateConstraints: constraints];
+            }
         }
     }
 
     pub fn deactivate(constraints: &[LayoutConstraint]) {
-        let ids: Vec<&Object> = constraints.into_iter().map(|constraint| &*constraint.constraint).collect();
-        unsafe {
-            let constraints: id = msg_send![class!(NSArray), arrayWithObjects:ids.as_ptr() count:ids.len()];
-            let _: () = msg_send![class!(NSLayoutConstraint), deactivateConstraints: constraints];
+        let ids = Self::partition(constraints, false);
+
+        if !ids.is_empty() {
+            unsafe {
+                let constraints: id = msg_send![class!(NSArray), arrayWithObjects:ids.as_ptr() count:ids.len()];
+                let _: () = msg_send![class!(NSLayoutConstraint), deactivateConstraints: constraints];
+            }
         }
     }
+
+    /// Applies `active` to any headless constraints in the batch, returning the rest for the
+    /// system to handle.
+    fn partition(constraints: &[LayoutConstraint], active: bool) -> Vec<&Object> {
+        constraints
+            .iter()
+            .filter_map(|constraint| {
+                if let Some(headless) = &constraint.headless {
+                    headless.set_active(active);
+                }
+
+                constraint.constraint.as_deref()
+            })
+            .collect()
+    }
 }
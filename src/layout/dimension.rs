@@ -1,4 +1,4 @@
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
@@ -8,6 +8,7 @@ use crate::foundation::{id, nil, NSInteger};
 use crate::layout::constraint::LayoutConstraint;
 
 use super::attributes::{LayoutAttribute, LayoutRelation};
+use super::engine::HeadlessAnchor;
 
 /// A wrapper for `NSLayoutAnchor`. You should never be creating this yourself - it's more of a
 /// factory/helper for creating `LayoutConstraint` objects based on your views.
@@ -25,7 +26,10 @@ pub enum LayoutAnchorDimension {
     Width(ShareId<Object>),
 
     /// Represents a Height anchor.
-    Height(ShareId<Object>)
+    Height(ShareId<Object>),
+
+    /// Represents an anchor on a `HeadlessView`, which is resolved by a `LayoutEngine`.
+    Headless(HeadlessAnchor)
 }
 
 impl Default for LayoutAnchorDimension {
@@ -55,6 +59,10 @@ impl LayoutAnchorDimension {
             });
         }
 
+        if let Self::Headless(anchor) = self {
+            return LayoutConstraint::headless(anchor.constraint_to_constant(LayoutRelation::Equal, constant));
+        }
+
         panic!("Attempted to create a constant constraint with an uninitialized anchor.");
     }
 
@@ -67,6 +75,10 @@ impl LayoutAnchorDimension {
             });
         }
 
+        if let Self::Headless(anchor) = self {
+            return LayoutConstraint::headless(anchor.constraint_to_constant(LayoutRelation::GreaterThanOrEqual, constant));
+        }
+
         panic!("Attempted to create a constraint (>=) with an uninitialized anchor.");
     }
 
@@ -79,13 +91,17 @@ impl LayoutAnchorDimension {
             });
         }
 
+        if let Self::Headless(anchor) = self {
+            return LayoutConstraint::headless(anchor.constraint_to_constant(LayoutRelation::LessThanOrEqual, constant));
+        }
+
         panic!("Attempted to create a constraint (<=) with an uninitialized anchor.");
     }
 
     /// Boilerplate for handling constraint construction and panic'ing with some more helpful
     /// messages. The goal here is to make AutoLayout slightly easier to debug when things go
     /// wrong.
-    fn constraint_with<F>(&self, anchor_to: &LayoutAnchorDimension, handler: F) -> LayoutConstraint
+    fn constraint_with<F>(&self, anchor_to: &LayoutAnchorDimension, relation: LayoutRelation, handler: F) -> LayoutConstraint
     where
         F: Fn(&ShareId<Object>, &ShareId<Object>) -> id
     {
@@ -95,6 +111,8 @@ impl LayoutAnchorDimension {
             | (Self::Height(from), Self::Width(to))
             | (Self::Height(from), Self::Height(to)) => LayoutConstraint::new(handler(from, to)),
 
+            (Self::Headless(from), Self::Headless(to)) => LayoutConstraint::headless(from.constraint_to(relation, to)),
+
             (Self::Uninitialized, Self::Uninitialized) => {
                 panic!("Attempted to create constraints with an uninitialized \"from\" and \"to\" dimension anchor.");
             },
@@ -105,27 +123,31 @@ impl LayoutAnchorDimension {
 
             (_, Self::Uninitialized) => {
                 panic!("Attempted to create constraints with an uninitialized \"to\" dimension anchor.");
+            },
+
+            (Self::Headless(_), _) | (_, Self::Headless(_)) => {
+                panic!("Attempted to create constraints between a headless dimension anchor and a view's dimension anchor.");
             }
         }
     }
 
     /// Return a constraint equal to another dimension anchor.
     pub fn constraint_equal_to(&self, anchor_to: &LayoutAnchorDimension) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::Equal, |from, to| unsafe {
             msg_send![*from, constraintEqualToAnchor:&**to]
         })
     }
 
     /// Return a constraint greater than or equal to another dimension anchor.
     pub fn constraint_greater_than_or_equal_to(&self, anchor_to: &LayoutAnchorDimension) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::GreaterThanOrEqual, |from, to| unsafe {
             msg_send![*from, constraintGreaterThanOrEqualToAnchor:&**to]
         })
     }
 
     /// Return a constraint less than or equal to another dimension anchor.
     pub fn constraint_less_than_or_equal_to(&self, anchor_to: &LayoutAnchorDimension) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::LessThanOrEqual, |from, to| unsafe {
             msg_send![*from, constraintLessThanOrEqualToAnchor:&**to]
         })
     }
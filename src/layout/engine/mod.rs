@@ -0,0 +1,816 @@
+//! A headless layout engine, which resolves constraints in pure Rust rather than handing them to
+//! AppKit or UIKit.
+//!
+//! `HeadlessView`s expose the same anchors as a `View`, and constraints between them are built,
+//! offset, prioritized and activated through the usual `LayoutConstraint` API. Calling
+//! `LayoutEngine::solve()` then runs a Cassowary solver over the active constraints, producing a
+//! frame for each view - along with any ambiguity or conflicts it ran into. This makes it
+//! possible to test layout code without running an app (e.g, in CI on Linux).
+//!
+//! Frames use a top-left origin, with `y` increasing downwards, and are relative to the view's
+//! superview (if it has one).
+//!
+//! ## Example
+//! ```rust
+//! use cacao::geometry::Rect;
+//! use cacao::layout::engine::LayoutEngine;
+//! use cacao::layout::{LayoutConstraint, LayoutPriority};
+//!
+//! let engine = LayoutEngine::new();
+//! let window = engine.view("window");
+//! window.set_frame(Rect::new(0., 0., 800., 600.));
+//!
+//! let sidebar = engine.view("sidebar");
+//! window.add_subview(&sidebar);
+//!
+//! LayoutConstraint::activate(&[
+//!     sidebar.top.constraint_equal_to(&window.top),
+//!     sidebar.leading.constraint_equal_to(&window.leading),
+//!     sidebar.bottom.constraint_equal_to(&window.bottom),
+//!     sidebar.width.constraint_equal_to_constant(200.),
+//!     sidebar.width.constraint_equal_to_constant(300.).priority(LayoutPriority::Low)
+//! ]);
+//!
+//! let solution = engine.solve();
+//! assert_eq!(solution.frame(&sidebar), Rect::new(0., 0., 200., 600.));
+//! assert!(solution.issues.is_empty());
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::geometry::Rect;
+use crate::layout::{LayoutAnchorDimension, LayoutAnchorX, LayoutAnchorY, LayoutAttribute, LayoutRelation};
+
+mod solver;
+use solver::{Expression, Relation, Solver, REQUIRED};
+
+/// The strength used when probing a resolved layout for ambiguity. This is weaker than any
+/// constraint priority, so a probe can only move a variable that nothing cares about.
+const PROBE_STRENGTH: f64 = 1.0e-3;
+
+/// A view known to the engine.
+#[derive(Debug)]
+struct ViewState {
+    identifier: String,
+    superview: Option<usize>,
+    frame: Option<Rect>
+}
+
+/// A constraint of the form `first <relation> second * multiplier + offset`.
+#[derive(Debug)]
+struct ConstraintState {
+    first: (usize, LayoutAttribute),
+    relation: LayoutRelation,
+    second: Option<(usize, LayoutAttribute)>,
+    multiplier: f64,
+    offset: f64,
+    priority: f64
+}
+
+#[derive(Debug, Default)]
+struct EngineState {
+    views: Vec<ViewState>,
+    constraints: Vec<ConstraintState>,
+
+    /// Active constraints, in the order they were activated. When required constraints
+    /// conflict, the one activated last loses.
+    active: Vec<usize>,
+
+    right_to_left: bool
+}
+
+impl EngineState {
+    /// Returns the expression for a view attribute, in terms of the solver variables for that
+    /// view: its left edge, top edge, width and height (in that order), in window coordinates.
+    /// Attributes the engine can't resolve (e.g, baselines) are returned as the error.
+    fn expression(&self, view: usize, attribute: LayoutAttribute) -> Result<Expression, LayoutAttribute> {
+        let [left, top, width, height] = variables(view);
+
+        let terms = match (attribute, self.right_to_left) {
+            (LayoutAttribute::Left, _) | (LayoutAttribute::Leading, false) => vec![(left, 1.0)],
+            (LayoutAttribute::Right, _) | (LayoutAttribute::Trailing, false) => vec![(left, 1.0), (width, 1.0)],
+            (LayoutAttribute::CenterX, _) => vec![(left, 1.0), (width, 0.5)],
+
+            // Right to left, leading and trailing run the other way - so that offsets do, too.
+            (LayoutAttribute::Leading, true) => vec![(left, -1.0), (width, -1.0)],
+            (LayoutAttribute::Trailing, true) => vec![(left, -1.0)],
+
+            (LayoutAttribute::Top, _) => vec![(top, 1.0)],
+            (LayoutAttribute::Bottom, _) => vec![(top, 1.0), (height, 1.0)],
+            (LayoutAttribute::CenterY, _) => vec![(top, 1.0), (height, 0.5)],
+            (LayoutAttribute::Width, _) => vec![(width, 1.0)],
+            (LayoutAttribute::Height, _) => vec![(height, 1.0)],
+
+            (attribute, _) => return Err(attribute)
+        };
+
+        Ok(Expression { terms, constant: 0.0 })
+    }
+
+    /// Returns `first - (second * multiplier + offset)`, which the solver relates to zero.
+    fn constraint_expression(&self, constraint: &ConstraintState) -> Result<Expression, LayoutAttribute> {
+        let mut expression = self.expression(constraint.first.0, constraint.first.1)?;
+
+        if let Some((view, attribute)) = constraint.second {
+            expression.add(&self.expression(view, attribute)?, -constraint.multiplier);
+        }
+
+        expression.constant -= constraint.offset;
+        Ok(expression)
+    }
+
+    /// The expressions pinning each view with an explicit frame, relative to its superview.
+    fn frame_expressions(&self) -> Vec<Expression> {
+        let mut expressions = vec![];
+
+        for (index, view) in self.views.iter().enumerate() {
+            if let Some(frame) = view.frame {
+                let values = [frame.left, frame.top, frame.width, frame.height];
+
+                for (i, (variable, value)) in variables(index).iter().zip(values.iter()).enumerate() {
+                    let mut expression = Expression {
+                        terms: vec![(*variable, 1.0)],
+                        constant: -value
+                    };
+
+                    // Positions are relative to the superview, but sizes aren't.
+                    if let (Some(superview), true) = (view.superview, i < 2) {
+                        expression.terms.push((variables(superview)[i], -1.0));
+                    }
+
+                    expressions.push(expression);
+                }
+            }
+        }
+
+        expressions
+    }
+
+    fn describe_attribute(&self, (view, attribute): (usize, LayoutAttribute)) -> String {
+        format!("{}.{}", self.views[view].identifier, attribute_name(attribute))
+    }
+
+    /// A readable description of a constraint, e.g `label.leading == sidebar.trailing + 8`.
+    fn describe(&self, constraint: &ConstraintState) -> String {
+        let relation = match constraint.relation {
+            LayoutRelation::LessThanOrEqual => "<=",
+            LayoutRelation::GreaterThanOrEqual => ">=",
+            _ => "=="
+        };
+
+        let mut description = format!("{} {} ", self.describe_attribute(constraint.first), relation);
+
+        match constraint.second {
+            Some(second) => {
+                description.push_str(&self.describe_attribute(second));
+
+                if constraint.multiplier != 1.0 {
+                    description.push_str(&format!(" * {}", constraint.multiplier));
+                }
+
+                if constraint.offset > 0.0 {
+                    description.push_str(&format!(" + {}", constraint.offset));
+                } else if constraint.offset < 0.0 {
+                    description.push_str(&format!(" - {}", -constraint.offset));
+                }
+            },
+
+            None => description.push_str(&format!("{}", constraint.offset))
+        }
+
+        if constraint.priority < 1000.0 {
+            description.push_str(&format!(" @{}", constraint.priority));
+        }
+
+        description
+    }
+}
+
+/// The solver variables for a view's left edge, top edge, width and height.
+fn variables(view: usize) -> [usize; 4] {
+    [view * 4, view * 4 + 1, view * 4 + 2, view * 4 + 3]
+}
+
+fn attribute_name(attribute: LayoutAttribute) -> &'static str {
+    match attribute {
+        LayoutAttribute::Left => "left",
+        LayoutAttribute::Right => "right",
+        LayoutAttribute::Top => "top",
+        LayoutAttribute::Bottom => "bottom",
+        LayoutAttribute::Leading => "leading",
+        LayoutAttribute::Trailing => "trailing",
+        LayoutAttribute::Width => "width",
+        LayoutAttribute::Height => "height",
+        LayoutAttribute::CenterX => "centerX",
+        LayoutAttribute::CenterY => "centerY",
+        LayoutAttribute::LastBaseline => "lastBaseline",
+        LayoutAttribute::FirstBaseline => "firstBaseline",
+        _ => "unknown"
+    }
+}
+
+/// Maps a `LayoutPriority` value (1 - 1000) to a solver strength. Each 50 points of priority
+/// doubles the strength, so a `High` constraint outweighs around a thousand `Low` ones.
+fn strength(priority: f64) -> f64 {
+    match priority >= 1000.0 {
+        true => REQUIRED,
+        false => 2f64.powf(priority.max(1.0) / 50.0)
+    }
+}
+
+/// Absorbs floating point noise from the solver, so that frames land on whole points when they
+/// should.
+fn snap(value: f64) -> f64 {
+    match (value - value.round()).abs() < 1.0e-6 {
+        true => value.round() + 0.0,
+        false => value
+    }
+}
+
+/// Rebuilds a solver from constraints that are known to be satisfiable together.
+fn build_solver(constraints: &[(Expression, Relation, f64)]) -> Solver {
+    let mut solver = Solver::default();
+
+    for (expression, relation, strength) in constraints {
+        let _ = solver.add_constraint(expression, *relation, *strength);
+    }
+
+    solver
+}
+
+/// Returns whether `variable` can move without affecting anything the constraints care about.
+fn is_ambiguous(solver: &mut Solver, variable: usize) -> bool {
+    let value = solver.value(variable);
+
+    if solver.add_edit_variable(variable, PROBE_STRENGTH).is_err() {
+        return false;
+    }
+
+    let moved = [value + 1.0, value - 1.0]
+        .iter()
+        .any(|target| solver.suggest_value(variable, *target).is_ok() && (solver.value(variable) - value).abs() > 1.0e-6);
+
+    let _ = solver.remove_edit_variable(variable);
+    moved
+}
+
+/// A problem found while solving a layout.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutIssue {
+    /// A required constraint couldn't be satisfied alongside the ones activated before it. Much
+    /// like AppKit does at runtime, the constraint is left out of the solution.
+    Conflict(String),
+
+    /// A constraint refers to an attribute the engine can't resolve (e.g, a baseline). It's
+    /// left out of the solution.
+    Invalid {
+        /// A description of the constraint.
+        constraint: String,
+
+        /// The attribute that isn't supported.
+        attribute: LayoutAttribute
+    },
+
+    /// The constraints don't pin down the given attribute of a view: `Left` or `Top` for its
+    /// position, `Width` or `Height` for its size.
+    Ambiguous {
+        /// The identifier of the view.
+        view: String,
+
+        /// The attribute that could take more than one value.
+        attribute: LayoutAttribute
+    }
+}
+
+impl fmt::Display for LayoutIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutIssue::Conflict(constraint) => write!(f, "conflicting constraint: {}", constraint),
+            LayoutIssue::Invalid { constraint, attribute } => {
+                write!(f, "invalid constraint: {} ({:?} isn't supported)", constraint, attribute)
+            },
+            LayoutIssue::Ambiguous { view, attribute } => write!(f, "ambiguous {}.{}", view, attribute_name(*attribute))
+        }
+    }
+}
+
+/// The result of solving a layout: a frame for each view, and any issues encountered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    frames: Vec<(String, Rect)>,
+
+    /// Conflicting and invalid constraints (in activation order), followed by ambiguous
+    /// attributes (in view order).
+    pub issues: Vec<LayoutIssue>
+}
+
+impl Solution {
+    /// Returns the resolved frame for a view, relative to its superview. Unconstrained values
+    /// resolve to zero.
+    pub fn frame(&self, view: &HeadlessView) -> Rect {
+        self.frames[view.index].1
+    }
+
+    /// Returns whether any constraints conflicted.
+    pub fn has_conflicts(&self) -> bool {
+        self.issues.iter().any(|issue| matches!(issue, LayoutIssue::Conflict(_)))
+    }
+
+    /// Returns whether any view's frame isn't fully determined by its constraints.
+    pub fn is_ambiguous(&self) -> bool {
+        self.issues.iter().any(|issue| matches!(issue, LayoutIssue::Ambiguous { .. }))
+    }
+}
+
+impl fmt::Display for Solution {
+    /// Prints each frame (and then each issue) on its own line, in a stable format that's
+    /// suitable for snapshot tests.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (identifier, frame) in &self.frames {
+            writeln!(
+                f,
+                "{}: {{{}, {}}} {}x{}",
+                identifier, frame.left, frame.top, frame.width, frame.height
+            )?;
+        }
+
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lays out `HeadlessView`s, without needing AppKit or UIKit.
+///
+/// This is cheap to clone; clones refer to the same engine.
+#[derive(Clone, Debug, Default)]
+pub struct LayoutEngine(Rc<RefCell<EngineState>>);
+
+impl LayoutEngine {
+    /// Returns a new, empty engine.
+    pub fn new() -> Self {
+        LayoutEngine::default()
+    }
+
+    /// Creates a view in this engine. The identifier is used when describing frames and issues.
+    pub fn view<S: Into<String>>(&self, identifier: S) -> HeadlessView {
+        let index = {
+            let mut state = self.0.borrow_mut();
+            state.views.push(ViewState {
+                identifier: identifier.into(),
+                superview: None,
+                frame: None
+            });
+
+            state.views.len() - 1
+        };
+
+        HeadlessView::new(self.0.clone(), index)
+    }
+
+    /// Sets whether leading and trailing anchors should resolve for a right-to-left interface,
+    /// where leading is the right edge. Defaults to `false`.
+    pub fn set_right_to_left(&self, right_to_left: bool) {
+        self.0.borrow_mut().right_to_left = right_to_left;
+    }
+
+    /// Solves the active constraints, returning the resulting frames and any issues.
+    pub fn solve(&self) -> Solution {
+        let state = self.0.borrow();
+        let mut issues = vec![];
+
+        let mut accepted: Vec<(Expression, Relation, f64)> = state
+            .frame_expressions()
+            .into_iter()
+            .map(|expression| (expression, Relation::Equal, REQUIRED))
+            .collect();
+
+        let mut solver = build_solver(&accepted);
+
+        for index in &state.active {
+            let constraint = &state.constraints[*index];
+
+            let expression = match state.constraint_expression(constraint) {
+                Ok(expression) => expression,
+
+                Err(attribute) => {
+                    issues.push(LayoutIssue::Invalid {
+                        constraint: state.describe(constraint),
+                        attribute
+                    });
+
+                    continue;
+                }
+            };
+
+            let strength = strength(constraint.priority);
+
+            let relation = match constraint.relation {
+                LayoutRelation::LessThanOrEqual => Relation::LessThanOrEqual,
+                LayoutRelation::GreaterThanOrEqual => Relation::GreaterThanOrEqual,
+                _ => Relation::Equal
+            };
+
+            match solver.add_constraint(&expression, relation, strength) {
+                Ok(_) => accepted.push((expression, relation, strength)),
+
+                Err(_) => {
+                    issues.push(LayoutIssue::Conflict(state.describe(constraint)));
+                    solver = build_solver(&accepted);
+                }
+            }
+        }
+
+        let origins: Vec<(f64, f64)> = (0..state.views.len())
+            .map(|index| {
+                let [left, top, _, _] = variables(index);
+                (solver.value(left), solver.value(top))
+            })
+            .collect();
+
+        let frames = state
+            .views
+            .iter()
+            .enumerate()
+            .map(|(index, view)| {
+                let [_, _, width, height] = variables(index);
+                let (mut left, mut top) = origins[index];
+
+                if let Some(superview) = view.superview {
+                    left -= origins[superview].0;
+                    top -= origins[superview].1;
+                }
+
+                let frame = Rect::new(snap(top), snap(left), snap(solver.value(width)), snap(solver.value(height)));
+                (view.identifier.clone(), frame)
+            })
+            .collect();
+
+        let attributes = [
+            LayoutAttribute::Left,
+            LayoutAttribute::Top,
+            LayoutAttribute::Width,
+            LayoutAttribute::Height
+        ];
+
+        for (index, view) in state.views.iter().enumerate() {
+            for (variable, attribute) in variables(index).iter().zip(attributes.iter()) {
+                if is_ambiguous(&mut solver, *variable) {
+                    issues.push(LayoutIssue::Ambiguous {
+                        view: view.identifier.clone(),
+                        attribute: *attribute
+                    });
+                }
+            }
+        }
+
+        Solution { frames, issues }
+    }
+}
+
+/// An anchor on a `HeadlessView`. These are wrapped by `LayoutAnchorX`, `LayoutAnchorY` and
+/// `LayoutAnchorDimension`, and aren't used directly.
+#[derive(Clone)]
+pub struct HeadlessAnchor {
+    engine: Rc<RefCell<EngineState>>,
+    view: usize,
+    attribute: LayoutAttribute
+}
+
+impl fmt::Debug for HeadlessAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeadlessAnchor")
+            .field("view", &self.engine.borrow().views[self.view].identifier)
+            .field("attribute", &self.attribute)
+            .finish()
+    }
+}
+
+impl HeadlessAnchor {
+    /// The attribute this anchor refers to.
+    pub(crate) fn attribute(&self) -> LayoutAttribute {
+        self.attribute
+    }
+
+    fn create(&self, relation: LayoutRelation, second: Option<(usize, LayoutAttribute)>, offset: f64) -> HeadlessConstraint {
+        let id = {
+            let mut state = self.engine.borrow_mut();
+            state.constraints.push(ConstraintState {
+                first: (self.view, self.attribute),
+                relation,
+                second,
+                multiplier: 1.0,
+                offset,
+                priority: 1000.0
+            });
+
+            state.constraints.len() - 1
+        };
+
+        HeadlessConstraint {
+            engine: self.engine.clone(),
+            id
+        }
+    }
+
+    /// Returns a new, inactive constraint relating this anchor to another.
+    pub(crate) fn constraint_to(&self, relation: LayoutRelation, anchor_to: &HeadlessAnchor) -> HeadlessConstraint {
+        if !Rc::ptr_eq(&self.engine, &anchor_to.engine) {
+            panic!("Attempted to create a constraint between views from different layout engines.");
+        }
+
+        self.create(relation, Some((anchor_to.view, anchor_to.attribute)), 0.0)
+    }
+
+    /// Returns a new, inactive constraint relating this anchor to a constant.
+    pub(crate) fn constraint_to_constant(&self, relation: LayoutRelation, constant: f64) -> HeadlessConstraint {
+        self.create(relation, None, constant)
+    }
+}
+
+/// A constraint between `HeadlessView`s, as held by a `LayoutConstraint`.
+#[derive(Clone)]
+pub(crate) struct HeadlessConstraint {
+    engine: Rc<RefCell<EngineState>>,
+    id: usize
+}
+
+impl fmt::Debug for HeadlessConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.engine.borrow();
+        f.debug_tuple("HeadlessConstraint")
+            .field(&state.describe(&state.constraints[self.id]))
+            .finish()
+    }
+}
+
+impl HeadlessConstraint {
+    pub fn set_offset(&self, offset: f64) {
+        self.engine.borrow_mut().constraints[self.id].offset = offset;
+    }
+
+    pub fn set_priority(&self, priority: f64) {
+        self.engine.borrow_mut().constraints[self.id].priority = priority;
+    }
+
+    pub fn set_active(&self, active: bool) {
+        let mut state = self.engine.borrow_mut();
+        let position = state.active.iter().position(|id| *id == self.id);
+
+        match (active, position) {
+            (true, None) => state.active.push(self.id),
+            (false, Some(position)) => {
+                state.active.remove(position);
+            },
+            _ => {}
+        }
+    }
+}
+
+/// A view that only exists for the purposes of layout, with the same anchors as a `View`.
+#[derive(Clone, Debug)]
+pub struct HeadlessView {
+    engine: Rc<RefCell<EngineState>>,
+    index: usize,
+
+    /// A pointer to the top layout anchor.
+    pub top: LayoutAnchorY,
+
+    /// A pointer to the leading layout anchor.
+    pub leading: LayoutAnchorX,
+
+    /// A pointer to the left layout anchor.
+    pub left: LayoutAnchorX,
+
+    /// A pointer to the trailing layout anchor.
+    pub trailing: LayoutAnchorX,
+
+    /// A pointer to the right layout anchor.
+    pub right: LayoutAnchorX,
+
+    /// A pointer to the bottom layout anchor.
+    pub bottom: LayoutAnchorY,
+
+    /// A pointer to the width layout anchor.
+    pub width: LayoutAnchorDimension,
+
+    /// A pointer to the height layout anchor.
+    pub height: LayoutAnchorDimension,
+
+    /// A pointer to the center X layout anchor.
+    pub center_x: LayoutAnchorX,
+
+    /// A pointer to the center Y layout anchor.
+    pub center_y: LayoutAnchorY
+}
+
+impl HeadlessView {
+    fn new(engine: Rc<RefCell<EngineState>>, index: usize) -> Self {
+        let anchor = |attribute| HeadlessAnchor {
+            engine: engine.clone(),
+            view: index,
+            attribute
+        };
+
+        HeadlessView {
+            top: LayoutAnchorY::Headless(anchor(LayoutAttribute::Top)),
+            leading: LayoutAnchorX::Headless(anchor(LayoutAttribute::Leading)),
+            left: LayoutAnchorX::Headless(anchor(LayoutAttribute::Left)),
+            trailing: LayoutAnchorX::Headless(anchor(LayoutAttribute::Trailing)),
+            right: LayoutAnchorX::Headless(anchor(LayoutAttribute::Right)),
+            bottom: LayoutAnchorY::Headless(anchor(LayoutAttribute::Bottom)),
+            width: LayoutAnchorDimension::Headless(anchor(LayoutAttribute::Width)),
+            height: LayoutAnchorDimension::Headless(anchor(LayoutAttribute::Height)),
+            center_x: LayoutAnchorX::Headless(anchor(LayoutAttribute::CenterX)),
+            center_y: LayoutAnchorY::Headless(anchor(LayoutAttribute::CenterY)),
+            engine,
+            index
+        }
+    }
+
+    /// The identifier this view was created with.
+    pub fn identifier(&self) -> String {
+        self.engine.borrow().views[self.index].identifier.clone()
+    }
+
+    /// The view this one was added to with `add_subview`, if any.
+    pub fn superview(&self) -> Option<HeadlessView> {
+        let superview = self.engine.borrow().views[self.index].superview;
+        superview.map(|index| HeadlessView::new(self.engine.clone(), index))
+    }
+
+    /// Makes `view` a subview of this one. Its frame will be reported relative to this view.
+    pub fn add_subview(&self, view: &HeadlessView) {
+        if !Rc::ptr_eq(&self.engine, &view.engine) {
+            panic!("Attempted to add a subview from a different layout engine.");
+        }
+
+        self.engine.borrow_mut().views[view.index].superview = Some(self.index);
+    }
+
+    /// Pins this view to the given frame (relative to its superview), as if it were laid out
+    /// without constraints. This is typically used for the root view, standing in for a window.
+    pub fn set_frame(&self, frame: Rect) {
+        self.engine.borrow_mut().views[self.index].frame = Some(frame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HeadlessAnchor, LayoutEngine, LayoutIssue};
+    use crate::geometry::Rect;
+    use crate::layout::{LayoutAttribute, LayoutConstraint, LayoutPriority, LayoutRelation};
+
+    #[test]
+    fn test_resolves_frames() {
+        let engine = LayoutEngine::new();
+        let window = engine.view("window");
+        window.set_frame(Rect::new(0., 0., 400., 300.));
+
+        let sidebar = engine.view("sidebar");
+        let content = engine.view("content");
+        let button = engine.view("button");
+        window.add_subview(&sidebar);
+        window.add_subview(&content);
+        content.add_subview(&button);
+
+        LayoutConstraint::activate(&[
+            sidebar.top.constraint_equal_to(&window.top),
+            sidebar.bottom.constraint_equal_to(&window.bottom),
+            sidebar.leading.constraint_equal_to(&window.leading),
+            sidebar.width.constraint_equal_to_constant(100.),
+            content.top.constraint_equal_to(&window.top).offset(10.),
+            content.bottom.constraint_equal_to(&window.bottom).offset(-10.),
+            content.leading.constraint_equal_to(&sidebar.trailing).offset(10.),
+            content.trailing.constraint_equal_to(&window.trailing).offset(-10.),
+            button.center_x.constraint_equal_to(&content.center_x),
+            button.center_y.constraint_equal_to(&content.center_y),
+            button.width.constraint_equal_to(&button.height),
+            button.height.constraint_equal_to_constant(40.)
+        ]);
+
+        let solution = engine.solve();
+        assert_eq!(solution.frame(&sidebar), Rect::new(0., 0., 100., 300.));
+        assert_eq!(solution.frame(&content), Rect::new(10., 110., 280., 280.));
+        assert_eq!(solution.frame(&button), Rect::new(120., 120., 40., 40.));
+        assert!(solution.issues.is_empty());
+
+        assert_eq!(
+            solution.to_string(),
+            "window: {0, 0} 400x300\n\
+             sidebar: {0, 0} 100x300\n\
+             content: {110, 10} 280x280\n\
+             button: {120, 120} 40x40\n"
+        );
+
+        // Right to left, the sidebar moves over to the other side.
+        engine.set_right_to_left(true);
+        let solution = engine.solve();
+        assert_eq!(solution.frame(&sidebar), Rect::new(0., 300., 100., 300.));
+        assert_eq!(solution.frame(&content), Rect::new(10., 10., 280., 280.));
+    }
+
+    #[test]
+    fn test_priorities_offsets_and_activation() {
+        let engine = LayoutEngine::new();
+        let window = engine.view("window");
+        window.set_frame(Rect::new(0., 0., 400., 300.));
+
+        let panel = engine.view("panel");
+        window.add_subview(&panel);
+
+        let width = panel.width.constraint_equal_to_constant(500.).priority(LayoutPriority::Low);
+        let max_width = panel
+            .width
+            .constraint_less_than_or_equal_to(&window.width)
+            .priority(LayoutPriority::High);
+        let leading = panel.leading.constraint_equal_to(&window.leading);
+
+        LayoutConstraint::activate(&[
+            panel.top.constraint_equal_to(&window.top),
+            panel.height.constraint_equal_to_constant(50.),
+            leading.clone(),
+            width.clone(),
+            max_width.clone()
+        ]);
+
+        assert_eq!(engine.solve().frame(&panel), Rect::new(0., 0., 400., 50.));
+
+        leading.set_offset(20.);
+        max_width.set_active(false);
+        let solution = engine.solve();
+        assert_eq!(solution.frame(&panel), Rect::new(0., 20., 500., 50.));
+        assert!(solution.issues.is_empty());
+
+        LayoutConstraint::deactivate(&[width]);
+        assert_eq!(engine.solve().issues, vec![LayoutIssue::Ambiguous {
+            view: "panel".to_string(),
+            attribute: LayoutAttribute::Width
+        }]);
+    }
+
+    #[test]
+    fn test_ambiguity_and_conflicts() {
+        let engine = LayoutEngine::new();
+        let window = engine.view("window");
+        window.set_frame(Rect::new(0., 0., 400., 300.));
+
+        let label = engine.view("label");
+        window.add_subview(&label);
+
+        LayoutConstraint::activate(&[
+            label.top.constraint_equal_to(&window.top),
+            label.leading.constraint_equal_to(&window.leading).offset(8.),
+            label.width.constraint_greater_than_or_equal_to_constant(100.),
+            label.height.constraint_equal_to_constant(20.),
+            label.height.constraint_equal_to(&window.height)
+        ]);
+
+        let solution = engine.solve();
+        assert!(solution.has_conflicts());
+        assert!(solution.is_ambiguous());
+        assert_eq!(solution.issues, vec![
+            LayoutIssue::Conflict("label.height == window.height".to_string()),
+            LayoutIssue::Ambiguous {
+                view: "label".to_string(),
+                attribute: LayoutAttribute::Width
+            }
+        ]);
+
+        // The conflicting constraint is left out, and everything else still applies.
+        assert_eq!(solution.frame(&label).height, 20.);
+        assert_eq!(solution.frame(&label).left, 8.);
+    }
+
+    #[test]
+    fn test_unsupported_attributes() {
+        let engine = LayoutEngine::new();
+        let label = engine.view("label");
+
+        // Headless views don't vend baseline anchors, but nothing stops a constraint referring
+        // to one from reaching the engine.
+        let baseline = HeadlessAnchor {
+            engine: engine.0.clone(),
+            view: label.index,
+            attribute: LayoutAttribute::LastBaseline
+        };
+
+        baseline.constraint_to_constant(LayoutRelation::Equal, 20.).set_active(true);
+        LayoutConstraint::activate(&[label.height.constraint_equal_to_constant(20.)]);
+
+        let solution = engine.solve();
+        assert_eq!(solution.frame(&label).height, 20.);
+        assert_eq!(solution.issues[0], LayoutIssue::Invalid {
+            constraint: "label.lastBaseline == 20".to_string(),
+            attribute: LayoutAttribute::LastBaseline
+        });
+        assert_eq!(
+            solution.issues[0].to_string(),
+            "invalid constraint: label.lastBaseline == 20 (LastBaseline isn't supported)"
+        );
+    }
+}
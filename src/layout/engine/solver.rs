@@ -0,0 +1,696 @@
+//! An incremental Cassowary solver, used by `LayoutEngine` to resolve constraints without
+//! AppKit or UIKit.
+//!
+//! This follows the structure of the Kiwi implementation of the algorithm: constraints are kept
+//! in a simplex tableau of rows, each expressing a basic symbol in terms of the parametric ones,
+//! and non-required constraints contribute weighted error terms to an objective row that's
+//! minimized after every change. Edit variables (used for suggesting values) ride on the dual
+//! simplex, so re-solving after a suggestion only touches the rows that became infeasible.
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Values this close to zero are treated as zero, to absorb floating point noise.
+const EPSILON: f64 = 1.0e-8;
+
+/// The strength of a constraint that must be satisfied. Anything weaker is optional.
+pub(crate) const REQUIRED: f64 = 1.0e12;
+
+fn near_zero(value: f64) -> bool {
+    value.abs() < EPSILON
+}
+
+/// Identifies a variable. Callers pick their own numbering.
+pub(crate) type Variable = usize;
+
+/// Identifies a constraint added to a `Solver`.
+pub(crate) type ConstraintId = usize;
+
+/// The relation between a constraint's expression and zero.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Relation {
+    LessThanOrEqual,
+    Equal,
+    GreaterThanOrEqual
+}
+
+/// A linear expression: the sum of each variable times its coefficient, plus a constant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Expression {
+    pub terms: Vec<(Variable, f64)>,
+    pub constant: f64
+}
+
+impl Expression {
+    /// Adds `other * coefficient` to this expression.
+    pub fn add(&mut self, other: &Expression, coefficient: f64) {
+        self.terms
+            .extend(other.terms.iter().map(|(variable, value)| (*variable, value * coefficient)));
+        self.constant += other.constant * coefficient;
+    }
+}
+
+/// Errors that can occur when changing the constraints in a `Solver`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum SolverError {
+    /// A required constraint can't be satisfied alongside the ones already added.
+    Unsatisfiable,
+
+    /// The given constraint or edit variable isn't known to the solver.
+    Unknown,
+
+    /// The tableau ended up somewhere it shouldn't; this indicates a bug.
+    Internal(&'static str)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum SymbolKind {
+    Invalid,
+    External,
+    Slack,
+    Error,
+    Dummy
+}
+
+/// A symbol in the tableau. Ordering by id keeps pivoting deterministic.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Symbol {
+    id: usize,
+    kind: SymbolKind
+}
+
+impl Symbol {
+    fn invalid() -> Self {
+        Symbol {
+            id: 0,
+            kind: SymbolKind::Invalid
+        }
+    }
+
+    fn is_pivotable(&self) -> bool {
+        self.kind == SymbolKind::Slack || self.kind == SymbolKind::Error
+    }
+}
+
+/// A row of the tableau: `constant + sum(cells)`.
+#[derive(Clone, Debug, Default)]
+struct Row {
+    constant: f64,
+    cells: BTreeMap<Symbol, f64>
+}
+
+impl Row {
+    fn new(constant: f64) -> Self {
+        Row {
+            constant,
+            cells: BTreeMap::new()
+        }
+    }
+
+    /// Adds `value` to the constant, returning the result.
+    fn add(&mut self, value: f64) -> f64 {
+        self.constant += value;
+        self.constant
+    }
+
+    fn insert_symbol(&mut self, symbol: Symbol, coefficient: f64) {
+        let value = self.cells.entry(symbol).or_insert(0.0);
+        *value += coefficient;
+
+        if near_zero(*value) {
+            self.cells.remove(&symbol);
+        }
+    }
+
+    fn insert_row(&mut self, other: &Row, coefficient: f64) {
+        self.constant += other.constant * coefficient;
+
+        for (symbol, value) in &other.cells {
+            self.insert_symbol(*symbol, value * coefficient);
+        }
+    }
+
+    fn remove(&mut self, symbol: Symbol) {
+        self.cells.remove(&symbol);
+    }
+
+    fn reverse_sign(&mut self) {
+        self.constant = -self.constant;
+
+        for value in self.cells.values_mut() {
+            *value = -*value;
+        }
+    }
+
+    /// Rearranges the row so that it expresses `symbol`, which is removed from the cells.
+    fn solve_for(&mut self, symbol: Symbol) {
+        let coefficient = -1.0 / self.cells.remove(&symbol).unwrap_or(1.0);
+        self.constant *= coefficient;
+
+        for value in self.cells.values_mut() {
+            *value *= coefficient;
+        }
+    }
+
+    /// Rearranges a row of `lhs = row` to express `rhs` instead.
+    fn solve_for_symbols(&mut self, lhs: Symbol, rhs: Symbol) {
+        self.insert_symbol(lhs, -1.0);
+        self.solve_for(rhs);
+    }
+
+    fn coefficient_for(&self, symbol: Symbol) -> f64 {
+        self.cells.get(&symbol).copied().unwrap_or(0.0)
+    }
+
+    /// Replaces `symbol` with the contents of `row`, if this row references it.
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        if let Some(coefficient) = self.cells.remove(&symbol) {
+            self.insert_row(row, coefficient);
+        }
+    }
+}
+
+/// The markers a constraint left in the tableau, used to remove it later.
+#[derive(Copy, Clone, Debug)]
+struct Tag {
+    marker: Symbol,
+    other: Symbol
+}
+
+#[derive(Clone, Debug)]
+struct ConstraintInfo {
+    tag: Tag,
+    strength: f64
+}
+
+#[derive(Clone, Debug)]
+struct EditInfo {
+    constraint: ConstraintId,
+    tag: Tag,
+    constant: f64
+}
+
+/// Which objective `optimize` should minimize.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Objective {
+    Main,
+    Artificial
+}
+
+/// An incremental constraint solver.
+#[derive(Debug, Default)]
+pub(crate) struct Solver {
+    constraints: HashMap<ConstraintId, ConstraintInfo>,
+    rows: BTreeMap<Symbol, Row>,
+    variables: HashMap<Variable, Symbol>,
+    edits: HashMap<Variable, EditInfo>,
+    infeasible_rows: Vec<Symbol>,
+    objective: Row,
+    artificial: Option<Row>,
+    next_symbol: usize,
+    next_constraint: ConstraintId
+}
+
+impl Solver {
+    /// Adds the constraint `expression <relation> 0` at the given strength.
+    ///
+    /// If the constraint is required and can't be satisfied, this returns
+    /// `SolverError::Unsatisfiable`. The solver should be considered spoiled at that point, and
+    /// rebuilt without the offending constraint.
+    pub fn add_constraint(
+        &mut self,
+        expression: &Expression,
+        relation: Relation,
+        strength: f64
+    ) -> Result<ConstraintId, SolverError> {
+        let strength = strength.clamp(0.0, REQUIRED);
+        let (mut row, tag) = self.create_row(expression, relation, strength);
+        let mut subject = choose_subject(&row, tag);
+
+        if subject.kind == SymbolKind::Invalid && row.cells.keys().all(|symbol| symbol.kind == SymbolKind::Dummy) {
+            if !near_zero(row.constant) {
+                return Err(SolverError::Unsatisfiable);
+            }
+
+            subject = tag.marker;
+        }
+
+        if subject.kind == SymbolKind::Invalid {
+            if !self.add_with_artificial_variable(&row)? {
+                return Err(SolverError::Unsatisfiable);
+            }
+        } else {
+            row.solve_for(subject);
+            self.substitute(subject, &row);
+            self.rows.insert(subject, row);
+        }
+
+        let id = self.next_constraint;
+        self.next_constraint += 1;
+        self.constraints.insert(id, ConstraintInfo { tag, strength });
+
+        self.optimize(Objective::Main)?;
+        Ok(id)
+    }
+
+    /// Removes a constraint previously returned from `add_constraint`.
+    pub fn remove_constraint(&mut self, id: ConstraintId) -> Result<(), SolverError> {
+        let info = self.constraints.remove(&id).ok_or(SolverError::Unknown)?;
+
+        // Errors from the constraint no longer count against the objective.
+        for marker in [info.tag.marker, info.tag.other].iter() {
+            if marker.kind == SymbolKind::Error {
+                match self.rows.get(marker) {
+                    Some(row) => self.objective.insert_row(row, -info.strength),
+                    None => self.objective.insert_symbol(*marker, -info.strength)
+                }
+            }
+        }
+
+        if self.rows.remove(&info.tag.marker).is_none() {
+            let leaving = self
+                .marker_leaving_row(info.tag.marker)
+                .ok_or(SolverError::Internal("failed to find a leaving row for a marker"))?;
+
+            let mut row = self.rows.remove(&leaving).unwrap_or_default();
+            row.solve_for_symbols(leaving, info.tag.marker);
+            self.substitute(info.tag.marker, &row);
+        }
+
+        self.optimize(Objective::Main)
+    }
+
+    /// Makes `variable` suggestible via `suggest_value`, at the given (non-required) strength.
+    pub fn add_edit_variable(&mut self, variable: Variable, strength: f64) -> Result<(), SolverError> {
+        let expression = Expression {
+            terms: vec![(variable, 1.0)],
+            constant: 0.0
+        };
+
+        let constraint = self.add_constraint(&expression, Relation::Equal, strength.min(REQUIRED / 10.0))?;
+        let tag = self.constraints[&constraint].tag;
+
+        self.edits.insert(variable, EditInfo {
+            constraint,
+            tag,
+            constant: 0.0
+        });
+
+        Ok(())
+    }
+
+    /// Stops editing `variable`, removing the constraint behind its suggestions.
+    pub fn remove_edit_variable(&mut self, variable: Variable) -> Result<(), SolverError> {
+        let edit = self.edits.remove(&variable).ok_or(SolverError::Unknown)?;
+        self.remove_constraint(edit.constraint)
+    }
+
+    /// Suggests a value for an edit variable, and re-solves.
+    pub fn suggest_value(&mut self, variable: Variable, value: f64) -> Result<(), SolverError> {
+        let (tag, delta) = {
+            let edit = self.edits.get_mut(&variable).ok_or(SolverError::Unknown)?;
+            let delta = value - edit.constant;
+            edit.constant = value;
+            (edit.tag, delta)
+        };
+
+        if let Some(row) = self.rows.get_mut(&tag.marker) {
+            if row.add(-delta) < 0.0 {
+                self.infeasible_rows.push(tag.marker);
+            }
+        } else if let Some(row) = self.rows.get_mut(&tag.other) {
+            if row.add(delta) < 0.0 {
+                self.infeasible_rows.push(tag.other);
+            }
+        } else {
+            for (symbol, row) in self.rows.iter_mut() {
+                let coefficient = row.coefficient_for(tag.marker);
+
+                if coefficient != 0.0 && row.add(delta * coefficient) < 0.0 && symbol.kind != SymbolKind::External {
+                    self.infeasible_rows.push(*symbol);
+                }
+            }
+        }
+
+        self.dual_optimize()
+    }
+
+    /// The current value of `variable`. Variables the solver hasn't seen are zero.
+    pub fn value(&self, variable: Variable) -> f64 {
+        self.variables
+            .get(&variable)
+            .and_then(|symbol| self.rows.get(symbol))
+            .map(|row| row.constant)
+            .unwrap_or(0.0)
+    }
+
+    fn symbol(&mut self, kind: SymbolKind) -> Symbol {
+        self.next_symbol += 1;
+
+        Symbol {
+            id: self.next_symbol,
+            kind
+        }
+    }
+
+    fn variable_symbol(&mut self, variable: Variable) -> Symbol {
+        if let Some(symbol) = self.variables.get(&variable) {
+            return *symbol;
+        }
+
+        let symbol = self.symbol(SymbolKind::External);
+        self.variables.insert(variable, symbol);
+        symbol
+    }
+
+    /// Builds a tableau row for a constraint, substituting any basic variables, and adds its
+    /// error terms to the objective.
+    fn create_row(&mut self, expression: &Expression, relation: Relation, strength: f64) -> (Row, Tag) {
+        let mut row = Row::new(expression.constant);
+
+        for (variable, coefficient) in &expression.terms {
+            if near_zero(*coefficient) {
+                continue;
+            }
+
+            let symbol = self.variable_symbol(*variable);
+
+            match self.rows.get(&symbol) {
+                Some(basic) => row.insert_row(basic, *coefficient),
+                None => row.insert_symbol(symbol, *coefficient)
+            }
+        }
+
+        let mut tag = Tag {
+            marker: Symbol::invalid(),
+            other: Symbol::invalid()
+        };
+
+        match relation {
+            Relation::LessThanOrEqual | Relation::GreaterThanOrEqual => {
+                let coefficient = match relation {
+                    Relation::LessThanOrEqual => 1.0,
+                    _ => -1.0
+                };
+
+                let slack = self.symbol(SymbolKind::Slack);
+                tag.marker = slack;
+                row.insert_symbol(slack, coefficient);
+
+                if strength < REQUIRED {
+                    let error = self.symbol(SymbolKind::Error);
+                    tag.other = error;
+                    row.insert_symbol(error, -coefficient);
+                    self.objective.insert_symbol(error, strength);
+                }
+            },
+
+            Relation::Equal if strength < REQUIRED => {
+                let plus = self.symbol(SymbolKind::Error);
+                let minus = self.symbol(SymbolKind::Error);
+                tag.marker = plus;
+                tag.other = minus;
+                row.insert_symbol(plus, -1.0);
+                row.insert_symbol(minus, 1.0);
+                self.objective.insert_symbol(plus, strength);
+                self.objective.insert_symbol(minus, strength);
+            },
+
+            Relation::Equal => {
+                let dummy = self.symbol(SymbolKind::Dummy);
+                tag.marker = dummy;
+                row.insert_symbol(dummy, 1.0);
+            }
+        }
+
+        if row.constant < 0.0 {
+            row.reverse_sign();
+        }
+
+        (row, tag)
+    }
+
+    /// Adds a row that has no obvious subject, by way of a temporary artificial variable.
+    /// Returns whether the row could be satisfied.
+    fn add_with_artificial_variable(&mut self, row: &Row) -> Result<bool, SolverError> {
+        let artificial = self.symbol(SymbolKind::Slack);
+        self.rows.insert(artificial, row.clone());
+        self.artificial = Some(row.clone());
+
+        self.optimize(Objective::Artificial)?;
+        let success = self.artificial.take().map(|row| near_zero(row.constant)).unwrap_or(false);
+
+        if let Some(mut basic) = self.rows.remove(&artificial) {
+            if basic.cells.is_empty() {
+                return Ok(success);
+            }
+
+            let entering = match basic.cells.keys().find(|symbol| symbol.is_pivotable()) {
+                Some(symbol) => *symbol,
+                None => return Ok(false)
+            };
+
+            basic.solve_for_symbols(artificial, entering);
+            self.substitute(entering, &basic);
+            self.rows.insert(entering, basic);
+        }
+
+        for row in self.rows.values_mut() {
+            row.remove(artificial);
+        }
+
+        self.objective.remove(artificial);
+        Ok(success)
+    }
+
+    /// Replaces `symbol` with `row` throughout the tableau and objectives.
+    fn substitute(&mut self, symbol: Symbol, row: &Row) {
+        for (basic, other) in self.rows.iter_mut() {
+            other.substitute(symbol, row);
+
+            if basic.kind != SymbolKind::External && other.constant < 0.0 {
+                self.infeasible_rows.push(*basic);
+            }
+        }
+
+        self.objective.substitute(symbol, row);
+
+        if let Some(artificial) = self.artificial.as_mut() {
+            artificial.substitute(symbol, row);
+        }
+    }
+
+    /// Runs the primal simplex on the given objective until it can't be improved.
+    fn optimize(&mut self, objective: Objective) -> Result<(), SolverError> {
+        loop {
+            let entering = {
+                let row = match objective {
+                    Objective::Main => &self.objective,
+                    Objective::Artificial => self.artificial.as_ref().unwrap_or(&self.objective)
+                };
+
+                row.cells
+                    .iter()
+                    .find(|(symbol, value)| symbol.kind != SymbolKind::Dummy && **value < 0.0)
+                    .map(|(symbol, _)| *symbol)
+            };
+
+            let entering = match entering {
+                Some(symbol) => symbol,
+                None => return Ok(())
+            };
+
+            let leaving = self
+                .leaving_row(entering)
+                .ok_or(SolverError::Internal("the objective is unbounded"))?;
+
+            let mut row = self.rows.remove(&leaving).unwrap_or_default();
+            row.solve_for_symbols(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+    }
+
+    /// Runs the dual simplex, restoring feasibility after edits.
+    fn dual_optimize(&mut self) -> Result<(), SolverError> {
+        while let Some(leaving) = self.infeasible_rows.pop() {
+            let entering = match self.rows.get(&leaving) {
+                Some(row) if row.constant < 0.0 => {
+                    let mut entering = None;
+                    let mut ratio = f64::MAX;
+
+                    for (symbol, value) in &row.cells {
+                        if *value > 0.0 && symbol.kind != SymbolKind::Dummy {
+                            let candidate = self.objective.coefficient_for(*symbol) / value;
+
+                            if candidate < ratio {
+                                ratio = candidate;
+                                entering = Some(*symbol);
+                            }
+                        }
+                    }
+
+                    entering.ok_or(SolverError::Internal("dual optimize failed"))?
+                },
+
+                _ => continue
+            };
+
+            let mut row = self.rows.remove(&leaving).unwrap_or_default();
+            row.solve_for_symbols(leaving, entering);
+            self.substitute(entering, &row);
+            self.rows.insert(entering, row);
+        }
+
+        Ok(())
+    }
+
+    /// Finds the row that limits how far `entering` can increase.
+    fn leaving_row(&self, entering: Symbol) -> Option<Symbol> {
+        let mut ratio = f64::MAX;
+        let mut found = None;
+
+        for (symbol, row) in &self.rows {
+            if symbol.kind == SymbolKind::External {
+                continue;
+            }
+
+            let coefficient = row.coefficient_for(entering);
+
+            if coefficient < 0.0 {
+                let candidate = -row.constant / coefficient;
+
+                if candidate < ratio {
+                    ratio = candidate;
+                    found = Some(*symbol);
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Finds the row to pivot a constraint's marker into, so that the constraint can be removed.
+    fn marker_leaving_row(&self, marker: Symbol) -> Option<Symbol> {
+        let mut first = (f64::MAX, None);
+        let mut second = (f64::MAX, None);
+        let mut third = None;
+
+        for (symbol, row) in &self.rows {
+            let coefficient = row.coefficient_for(marker);
+
+            if coefficient == 0.0 {
+                continue;
+            }
+
+            if symbol.kind == SymbolKind::External {
+                third = Some(*symbol);
+            } else if coefficient < 0.0 {
+                let ratio = -row.constant / coefficient;
+
+                if ratio < first.0 {
+                    first = (ratio, Some(*symbol));
+                }
+            } else {
+                let ratio = row.constant / coefficient;
+
+                if ratio < second.0 {
+                    second = (ratio, Some(*symbol));
+                }
+            }
+        }
+
+        first.1.or(second.1).or(third)
+    }
+}
+
+/// Picks the symbol a new row should be solved for, if there's an easy choice.
+fn choose_subject(row: &Row, tag: Tag) -> Symbol {
+    if let Some(symbol) = row.cells.keys().find(|symbol| symbol.kind == SymbolKind::External) {
+        return *symbol;
+    }
+
+    for marker in [tag.marker, tag.other].iter() {
+        if marker.is_pivotable() && row.coefficient_for(*marker) < 0.0 {
+            return *marker;
+        }
+    }
+
+    Symbol::invalid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expression, Relation, Solver, SolverError, REQUIRED};
+
+    fn expression(terms: &[(usize, f64)], constant: f64) -> Expression {
+        Expression {
+            terms: terms.to_vec(),
+            constant
+        }
+    }
+
+    #[test]
+    fn test_required_and_optional_constraints() {
+        let mut solver = Solver::default();
+
+        // x + 10 == y, x >= 20, y <= 100, and x wants to be 50 (but only weakly).
+        solver
+            .add_constraint(&expression(&[(0, 1.0), (1, -1.0)], 10.0), Relation::Equal, REQUIRED)
+            .unwrap();
+        solver
+            .add_constraint(&expression(&[(0, 1.0)], -20.0), Relation::GreaterThanOrEqual, REQUIRED)
+            .unwrap();
+        solver
+            .add_constraint(&expression(&[(1, 1.0)], -100.0), Relation::LessThanOrEqual, REQUIRED)
+            .unwrap();
+
+        let weak = solver
+            .add_constraint(&expression(&[(0, 1.0)], -50.0), Relation::Equal, 1.0)
+            .unwrap();
+        assert_eq!((solver.value(0), solver.value(1)), (50.0, 60.0));
+
+        // A stronger preference wins, and is clamped by the required constraints.
+        solver
+            .add_constraint(&expression(&[(1, 1.0)], -200.0), Relation::Equal, 10.0)
+            .unwrap();
+        assert_eq!((solver.value(0), solver.value(1)), (90.0, 100.0));
+
+        solver.remove_constraint(weak).unwrap();
+        assert_eq!(solver.remove_constraint(weak), Err(SolverError::Unknown));
+        assert_eq!((solver.value(0), solver.value(1)), (90.0, 100.0));
+    }
+
+    #[test]
+    fn test_unsatisfiable() {
+        let mut solver = Solver::default();
+        solver
+            .add_constraint(&expression(&[(0, 1.0)], -10.0), Relation::Equal, REQUIRED)
+            .unwrap();
+
+        let conflict = solver.add_constraint(&expression(&[(0, 1.0)], -20.0), Relation::GreaterThanOrEqual, REQUIRED);
+        assert_eq!(conflict, Err(SolverError::Unsatisfiable));
+    }
+
+    #[test]
+    fn test_edit_variables() {
+        let mut solver = Solver::default();
+
+        // y == 2x, with x editable and y capped at 50.
+        solver
+            .add_constraint(&expression(&[(1, 1.0), (0, -2.0)], 0.0), Relation::Equal, REQUIRED)
+            .unwrap();
+        solver
+            .add_constraint(&expression(&[(1, 1.0)], -50.0), Relation::LessThanOrEqual, REQUIRED)
+            .unwrap();
+        solver.add_edit_variable(0, 1000.0).unwrap();
+
+        solver.suggest_value(0, 10.0).unwrap();
+        assert_eq!((solver.value(0), solver.value(1)), (10.0, 20.0));
+
+        solver.suggest_value(0, 40.0).unwrap();
+        assert_eq!((solver.value(0), solver.value(1)), (25.0, 50.0));
+
+        solver.remove_edit_variable(0).unwrap();
+        assert_eq!(solver.suggest_value(0, 1.0), Err(SolverError::Unknown));
+    }
+}
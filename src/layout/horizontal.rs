@@ -4,6 +4,8 @@ use objc_id::ShareId;
 
 use crate::foundation::id;
 use crate::layout::constraint::LayoutConstraint;
+use crate::layout::engine::HeadlessAnchor;
+use crate::layout::{LayoutAttribute, LayoutRelation};
 
 /// A wrapper for `NSLayoutAnchorX`, used to handle values for how a given view should
 /// layout along the x-axis.
@@ -28,7 +30,10 @@ pub enum LayoutAnchorX {
     Right(ShareId<Object>),
 
     /// Represents a center anchor on the X axis.
-    Center(ShareId<Object>)
+    Center(ShareId<Object>),
+
+    /// Represents an anchor on a `HeadlessView`, which is resolved by a `LayoutEngine`.
+    Headless(HeadlessAnchor)
 }
 
 impl Default for LayoutAnchorX {
@@ -67,7 +72,7 @@ impl LayoutAnchorX {
     /// Boilerplate for handling constraint construction and panic'ing with some more helpful
     /// messages. The goal here is to make AutoLayout slightly easier to debug when things go
     /// wrong.
-    fn constraint_with<F>(&self, anchor_to: &LayoutAnchorX, handler: F) -> LayoutConstraint
+    fn constraint_with<F>(&self, anchor_to: &LayoutAnchorX, relation: LayoutRelation, handler: F) -> LayoutConstraint
     where
         F: Fn(&ShareId<Object>, &ShareId<Object>) -> id
     {
@@ -142,6 +147,33 @@ impl LayoutAnchorX {
                 );
             },
 
+            // Headless anchors follow the same rules as above.
+            (Self::Headless(from), Self::Headless(to)) => {
+                let side = |anchor: &HeadlessAnchor| match anchor.attribute() {
+                    LayoutAttribute::Leading => Some(("leading", true)),
+                    LayoutAttribute::Trailing => Some(("trailing", true)),
+                    LayoutAttribute::Left => Some(("left", false)),
+                    LayoutAttribute::Right => Some(("right", false)),
+                    _ => None
+                };
+
+                if let (Some((from_name, from_directional)), Some((to_name, to_directional))) = (side(from), side(to)) {
+                    if from_directional != to_directional {
+                        panic!(
+                            r#"
+                            Attempted to attach a "{}" constraint to a "{}" constraint. This will
+                            result in undefined behavior for LTR and RTL system settings, and Cacao blocks this.
+
+                            Use either left/right or leading/trailing.
+                        "#,
+                            from_name, to_name
+                        );
+                    }
+                }
+
+                LayoutConstraint::headless(from.constraint_to(relation, to))
+            },
+
             // If anything is attempted with an uninitialized anchor, then block it.
             (Self::Uninitialized, Self::Uninitialized) => {
                 panic!("Attempted to create constraints with an uninitialized \"from\" and \"to\" X anchor.");
@@ -153,27 +185,31 @@ impl LayoutAnchorX {
 
             (_, Self::Uninitialized) => {
                 panic!("Attempted to create constraints with an uninitialized \"to\" X anchor.");
+            },
+
+            (Self::Headless(_), _) | (_, Self::Headless(_)) => {
+                panic!("Attempted to create constraints between a headless X anchor and a view's X anchor.");
             }
         }
     }
 
     /// Return a constraint equal to another horizontal anchor.
     pub fn constraint_equal_to(&self, anchor_to: &LayoutAnchorX) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::Equal, |from, to| unsafe {
             msg_send![*from, constraintEqualToAnchor:&**to]
         })
     }
 
     /// Return a constraint greater than or equal to another horizontal anchor.
     pub fn constraint_greater_than_or_equal_to(&self, anchor_to: &LayoutAnchorX) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::GreaterThanOrEqual, |from, to| unsafe {
             msg_send![*from, constraintGreaterThanOrEqualToAnchor:&**to]
         })
     }
 
     /// Return a constraint less than or equal to another horizontal anchor.
     pub fn constraint_less_than_or_equal_to(&self, anchor_to: &LayoutAnchorX) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::LessThanOrEqual, |from, to| unsafe {
             msg_send![*from, constraintLessThanOrEqualToAnchor:&**to]
         })
     }
@@ -42,6 +42,12 @@ mod vertical;
 #[cfg(feature = "autolayout")]
 pub use vertical::LayoutAnchorY;
 
+#[cfg(feature = "autolayout")]
+pub mod engine;
+
+#[cfg(feature = "autolayout")]
+pub mod visual_format;
+
 #[cfg(feature = "autolayout")]
 mod safe_guide;
 
@@ -1,8 +1,8 @@
 //! Various traits related to controllers opting in to autolayout routines and support for view
 //! heirarchies.
 
-use core_graphics::base::CGFloat;
-use core_graphics::geometry::{CGPoint, CGRect, CGSize};
+use crate::core_graphics::base::CGFloat;
+use crate::core_graphics::geometry::{CGPoint, CGRect, CGSize};
 
 use objc::runtime::Object;
 use objc::{msg_send, sel, sel_impl};
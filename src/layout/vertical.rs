@@ -4,6 +4,8 @@ use objc_id::ShareId;
 
 use crate::foundation::id;
 use crate::layout::constraint::LayoutConstraint;
+use crate::layout::engine::HeadlessAnchor;
+use crate::layout::LayoutRelation;
 
 /// A wrapper for `NSLayoutAnchorY`, used to handle values for how a given view should
 /// layout along the y-axis.
@@ -19,7 +21,10 @@ pub enum LayoutAnchorY {
     Bottom(ShareId<Object>),
 
     /// Represents a center anchor for the Y axis.
-    Center(ShareId<Object>)
+    Center(ShareId<Object>),
+
+    /// Represents an anchor on a `HeadlessView`, which is resolved by a `LayoutEngine`.
+    Headless(HeadlessAnchor)
 }
 
 impl Default for LayoutAnchorY {
@@ -47,7 +52,7 @@ impl LayoutAnchorY {
     /// Boilerplate for handling constraint construction and panic'ing with some more helpful
     /// messages. The goal here is to make AutoLayout slightly easier to debug when things go
     /// wrong.
-    fn constraint_with<F>(&self, anchor_to: &LayoutAnchorY, handler: F) -> LayoutConstraint
+    fn constraint_with<F>(&self, anchor_to: &LayoutAnchorY, relation: LayoutRelation, handler: F) -> LayoutConstraint
     where
         F: Fn(&ShareId<Object>, &ShareId<Object>) -> id
     {
@@ -62,6 +67,8 @@ impl LayoutAnchorY {
             | (Self::Center(from), Self::Top(to))
             | (Self::Center(from), Self::Bottom(to)) => LayoutConstraint::new(handler(from, to)),
 
+            (Self::Headless(from), Self::Headless(to)) => LayoutConstraint::headless(from.constraint_to(relation, to)),
+
             (Self::Uninitialized, Self::Uninitialized) => {
                 panic!("Attempted to create constraints with uninitialized \"from\" and \"to\" y anchors.");
             },
@@ -72,27 +79,31 @@ impl LayoutAnchorY {
 
             (_, Self::Uninitialized) => {
                 panic!("Attempted to create constraints with an uninitialized \"to\" y anchor.");
+            },
+
+            (Self::Headless(_), _) | (_, Self::Headless(_)) => {
+                panic!("Attempted to create constraints between a headless y anchor and a view's y anchor.");
             }
         }
     }
 
     /// Return a constraint equal to another vertical anchor.
     pub fn constraint_equal_to(&self, anchor_to: &LayoutAnchorY) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::Equal, |from, to| unsafe {
             msg_send![*from, constraintEqualToAnchor:&**to]
         })
     }
 
     /// Return a constraint greater than or equal to another vertical anchor.
     pub fn constraint_greater_than_or_equal_to(&self, anchor_to: &LayoutAnchorY) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::GreaterThanOrEqual, |from, to| unsafe {
             msg_send![*from, constraintGreaterThanOrEqualToAnchor:&**to]
         })
     }
 
     /// Return a constraint less than or equal to another vertical anchor.
     pub fn constraint_less_than_or_equal_to(&self, anchor_to: &LayoutAnchorY) -> LayoutConstraint {
-        self.constraint_with(anchor_to, |from, to| unsafe {
+        self.constraint_with(anchor_to, LayoutRelation::LessThanOrEqual, |from, to| unsafe {
             msg_send![*from, constraintLessThanOrEqualToAnchor:&**to]
         })
     }
@@ -0,0 +1,558 @@
+//! Support for building constraints from Apple's [Visual Format
+//! Language](https://developer.apple.com/library/archive/documentation/UserExperience/Conceptual/AutolayoutPG/VisualFormatLanguage.html).
+//!
+//! Format strings are parsed in pure Rust (see `parse`), and then turned into constraints via the
+//! usual anchors - so they work the same for AppKit/UIKit views and for `HeadlessView`s.
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use cacao::geometry::Rect;
+//! use cacao::layout::engine::LayoutEngine;
+//! use cacao::layout::visual_format::LayoutAnchors;
+//! use cacao::layout::LayoutConstraint;
+//!
+//! let engine = LayoutEngine::new();
+//! let window = engine.view("window");
+//! window.set_frame(Rect::new(0., 0., 400., 300.));
+//!
+//! let (a, b) = (engine.view("a"), engine.view("b"));
+//! window.add_subview(&a);
+//! window.add_subview(&b);
+//!
+//! let mut views = HashMap::new();
+//! views.insert("a", LayoutAnchors::from(&a));
+//! views.insert("b", LayoutAnchors::from(&b));
+//!
+//! let mut metrics = HashMap::new();
+//! metrics.insert("height", 24.);
+//!
+//! let mut constraints = LayoutConstraint::with_visual_format("H:|-[a]-8-[b(==a)]-|", &[], &metrics, &views).unwrap();
+//! constraints.extend(LayoutConstraint::with_visual_format("V:|-[a(height)]", &[], &metrics, &views).unwrap());
+//! constraints.extend(LayoutConstraint::with_visual_format("V:|-[b(height)]", &[], &metrics, &views).unwrap());
+//! LayoutConstraint::activate(&constraints);
+//!
+//! let solution = engine.solve();
+//! assert_eq!(solution.frame(&a), Rect::new(20., 20., 176., 24.));
+//! assert_eq!(solution.frame(&b), Rect::new(20., 204., 176., 24.));
+//! ```
+
+use std::collections::HashMap;
+
+use objc::runtime::Object;
+use objc::{msg_send, sel, sel_impl};
+
+use crate::foundation::id;
+use crate::layout::engine::HeadlessView;
+use crate::layout::{
+    Layout, LayoutAnchorDimension, LayoutAnchorX, LayoutAnchorY, LayoutConstraint, LayoutFormat, LayoutRelation
+};
+
+mod parser;
+pub use parser::{parse, Connection, Operand, Orientation, Predicate, Spacing, ViewSpec, VisualFormat, VisualFormatError};
+
+/// The spacing used for `-` between two views.
+pub const STANDARD_SPACING: f64 = 8.;
+
+/// The spacing used for `-` between a view and its superview.
+pub const STANDARD_SUPERVIEW_SPACING: f64 = 20.;
+
+/// The anchors of a view, as referred to by name in a visual format string.
+///
+/// These can be created from any `Layout` implementor (or a `HeadlessView`) with `From`. The
+/// view's superview - which `|` refers to - is captured at that point, so add the view to its
+/// superview first.
+#[derive(Clone, Debug, Default)]
+pub struct LayoutAnchors {
+    /// A pointer to the top layout anchor.
+    pub top: LayoutAnchorY,
+
+    /// A pointer to the leading layout anchor.
+    pub leading: LayoutAnchorX,
+
+    /// A pointer to the left layout anchor.
+    pub left: LayoutAnchorX,
+
+    /// A pointer to the trailing layout anchor.
+    pub trailing: LayoutAnchorX,
+
+    /// A pointer to the right layout anchor.
+    pub right: LayoutAnchorX,
+
+    /// A pointer to the bottom layout anchor.
+    pub bottom: LayoutAnchorY,
+
+    /// A pointer to the width layout anchor.
+    pub width: LayoutAnchorDimension,
+
+    /// A pointer to the height layout anchor.
+    pub height: LayoutAnchorDimension,
+
+    /// A pointer to the center X layout anchor.
+    pub center_x: LayoutAnchorX,
+
+    /// A pointer to the center Y layout anchor.
+    pub center_y: LayoutAnchorY,
+
+    /// The anchors of the view's superview, if it has one.
+    pub superview: Option<Box<LayoutAnchors>>
+}
+
+impl LayoutAnchors {
+    /// Returns the anchors for a native view.
+    fn native(view: id, superview: Option<Box<LayoutAnchors>>) -> Self {
+        LayoutAnchors {
+            top: LayoutAnchorY::top(view),
+            leading: LayoutAnchorX::leading(view),
+            left: LayoutAnchorX::left(view),
+            trailing: LayoutAnchorX::trailing(view),
+            right: LayoutAnchorX::right(view),
+            bottom: LayoutAnchorY::bottom(view),
+            width: LayoutAnchorDimension::width(view),
+            height: LayoutAnchorDimension::height(view),
+            center_x: LayoutAnchorX::center(view),
+            center_y: LayoutAnchorY::center(view),
+            superview
+        }
+    }
+
+    /// Returns the anchors (along the given orientation) of the start edge, end edge and size.
+    fn axis(&self, orientation: Orientation, direction: Direction) -> (Anchor<'_>, Anchor<'_>, &LayoutAnchorDimension) {
+        match (orientation, direction) {
+            (Orientation::Horizontal, Direction::LeadingToTrailing) => {
+                (Anchor::X(&self.leading), Anchor::X(&self.trailing), &self.width)
+            },
+
+            (Orientation::Horizontal, Direction::LeftToRight) => (Anchor::X(&self.left), Anchor::X(&self.right), &self.width),
+            (Orientation::Horizontal, Direction::RightToLeft) => (Anchor::X(&self.right), Anchor::X(&self.left), &self.width),
+            (Orientation::Vertical, _) => (Anchor::Y(&self.top), Anchor::Y(&self.bottom), &self.height)
+        }
+    }
+}
+
+impl<V: Layout> From<&V> for LayoutAnchors {
+    fn from(view: &V) -> Self {
+        view.get_from_backing_obj(|obj| {
+            let view = obj as *const Object as id;
+            let superview: id = unsafe { msg_send![view, superview] };
+
+            let superview = match superview.is_null() {
+                true => None,
+                false => Some(Box::new(LayoutAnchors::native(superview, None)))
+            };
+
+            LayoutAnchors::native(view, superview)
+        })
+    }
+}
+
+impl From<&HeadlessView> for LayoutAnchors {
+    fn from(view: &HeadlessView) -> Self {
+        LayoutAnchors {
+            top: view.top.clone(),
+            leading: view.leading.clone(),
+            left: view.left.clone(),
+            trailing: view.trailing.clone(),
+            right: view.right.clone(),
+            bottom: view.bottom.clone(),
+            width: view.width.clone(),
+            height: view.height.clone(),
+            center_x: view.center_x.clone(),
+            center_y: view.center_y.clone(),
+            superview: view.superview().map(|superview| Box::new(LayoutAnchors::from(&superview)))
+        }
+    }
+}
+
+/// The order views are laid out in horizontally.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Direction {
+    LeadingToTrailing,
+    LeftToRight,
+    RightToLeft
+}
+
+/// An edge anchor on either axis.
+#[derive(Copy, Clone)]
+enum Anchor<'a> {
+    X(&'a LayoutAnchorX),
+    Y(&'a LayoutAnchorY)
+}
+
+impl<'a> Anchor<'a> {
+    fn constraint(&self, relation: LayoutRelation, anchor_to: &Anchor) -> LayoutConstraint {
+        match (self, anchor_to, relation) {
+            (Anchor::X(from), Anchor::X(to), LayoutRelation::Equal) => from.constraint_equal_to(to),
+            (Anchor::X(from), Anchor::X(to), LayoutRelation::LessThanOrEqual) => from.constraint_less_than_or_equal_to(to),
+            (Anchor::X(from), Anchor::X(to), LayoutRelation::GreaterThanOrEqual) => from.constraint_greater_than_or_equal_to(to),
+
+            (Anchor::Y(from), Anchor::Y(to), LayoutRelation::Equal) => from.constraint_equal_to(to),
+            (Anchor::Y(from), Anchor::Y(to), LayoutRelation::LessThanOrEqual) => from.constraint_less_than_or_equal_to(to),
+            (Anchor::Y(from), Anchor::Y(to), LayoutRelation::GreaterThanOrEqual) => from.constraint_greater_than_or_equal_to(to),
+
+            _ => unreachable!("Edges along a visual format's orientation are always on the same axis")
+        }
+    }
+}
+
+/// Turns a parsed format into constraints, resolving names as it goes.
+struct Builder<'a> {
+    format: &'a str,
+    parsed: &'a VisualFormat,
+    direction: Direction,
+    metrics: &'a HashMap<&'a str, f64>,
+    views: &'a HashMap<&'a str, LayoutAnchors>,
+    constraints: Vec<LayoutConstraint>
+}
+
+impl<'a> Builder<'a> {
+    fn error<S: Into<String>>(&self, position: usize, message: S) -> VisualFormatError {
+        VisualFormatError::new(self.format, position, message)
+    }
+
+    fn view(&self, spec: &ViewSpec) -> Result<&'a LayoutAnchors, VisualFormatError> {
+        self.views
+            .get(spec.name.as_str())
+            .ok_or_else(|| self.error(spec.position, format!("Unknown view '{}'", spec.name)))
+    }
+
+    fn superview(&self, view: &'a LayoutAnchors, connection: &Connection) -> Result<&'a LayoutAnchors, VisualFormatError> {
+        view.superview
+            .as_deref()
+            .ok_or_else(|| self.error(connection.position, "'|' can only be used with views that have a superview"))
+    }
+
+    /// Resolves a number or metric name. View names are an error here.
+    fn constant(&self, operand: &Operand, position: usize) -> Result<f64, VisualFormatError> {
+        match operand {
+            Operand::Number(number) => Ok(*number),
+            Operand::Name(name) => self
+                .metrics
+                .get(name.as_str())
+                .copied()
+                .ok_or_else(|| self.error(position, format!("Unknown metric '{}'", name)))
+        }
+    }
+
+    fn priority(&self, predicate: &Predicate) -> Result<f64, VisualFormatError> {
+        let priority = match &predicate.priority {
+            Some(priority) => self.constant(priority, predicate.position)?,
+            None => return Ok(1000.)
+        };
+
+        match priority > 0. && priority <= 1000. {
+            true => Ok(priority),
+            false => Err(self.error(predicate.position, "Priorities must be greater than 0 and at most 1000"))
+        }
+    }
+
+    /// Adds constraints for a connection between `start` and `end` (i.e, from the end edge of one
+    /// view, to the start edge of the next).
+    fn connect(&mut self, start: Anchor, end: Anchor, connection: &Connection, standard: f64) -> Result<(), VisualFormatError> {
+        let predicates = match &connection.spacing {
+            Spacing::Flush => vec![(LayoutRelation::Equal, 0., 1000.)],
+            Spacing::Standard => vec![(LayoutRelation::Equal, standard, 1000.)],
+            Spacing::Predicates(predicates) => predicates
+                .iter()
+                .map(|predicate| {
+                    let constant = self.constant(&predicate.operand, predicate.position)?;
+                    Ok((predicate.relation, constant, self.priority(predicate)?))
+                })
+                .collect::<Result<_, _>>()?
+        };
+
+        for (relation, constant, priority) in predicates {
+            // Right to left, views run against the axis, so the edges swap around to keep spacing
+            // positive.
+            let constraint = match self.direction {
+                Direction::RightToLeft if self.parsed.orientation == Orientation::Horizontal => start.constraint(relation, &end),
+
+                _ => end.constraint(relation, &start)
+            };
+
+            self.constraints.push(constraint.offset(constant).with_priority(priority));
+        }
+
+        Ok(())
+    }
+
+    /// Adds constraints for the predicates on a view's size.
+    fn size(&mut self, spec: &ViewSpec, size: &LayoutAnchorDimension) -> Result<(), VisualFormatError> {
+        for predicate in &spec.predicates {
+            let priority = self.priority(predicate)?;
+
+            let other = match &predicate.operand {
+                Operand::Name(name) if !self.metrics.contains_key(name.as_str()) => self.views.get(name.as_str()),
+                _ => None
+            };
+
+            let constraint = match (other, predicate.relation) {
+                (Some(other), relation) => {
+                    let (_, _, other) = other.axis(self.parsed.orientation, self.direction);
+
+                    match relation {
+                        LayoutRelation::Equal => size.constraint_equal_to(other),
+                        LayoutRelation::LessThanOrEqual => size.constraint_less_than_or_equal_to(other),
+                        LayoutRelation::GreaterThanOrEqual => size.constraint_greater_than_or_equal_to(other),
+                        LayoutRelation::Unknown(_) => unreachable!("Predicates are always ==, <= or >=")
+                    }
+                },
+
+                (None, relation) => {
+                    let constant = match self.constant(&predicate.operand, predicate.position) {
+                        Ok(constant) => constant,
+                        Err(_) => return Err(self.error(predicate.position, "Unknown view or metric"))
+                    };
+
+                    match relation {
+                        LayoutRelation::Equal => size.constraint_equal_to_constant(constant),
+                        LayoutRelation::LessThanOrEqual => size.constraint_less_than_or_equal_to_constant(constant),
+                        LayoutRelation::GreaterThanOrEqual => size.constraint_greater_than_or_equal_to_constant(constant),
+                        LayoutRelation::Unknown(_) => unreachable!("Predicates are always ==, <= or >=")
+                    }
+                }
+            };
+
+            self.constraints.push(constraint.with_priority(priority));
+        }
+
+        Ok(())
+    }
+
+    /// Adds constraints aligning every view to the first, for each alignment option.
+    fn align(&mut self, views: &[&'a LayoutAnchors], options: &[LayoutFormat]) -> Result<(), VisualFormatError> {
+        let horizontal = self.parsed.orientation == Orientation::Horizontal;
+
+        for option in options {
+            let anchor = |view: &'a LayoutAnchors| match option {
+                LayoutFormat::AlignAllTop if horizontal => Some(Anchor::Y(&view.top)),
+                LayoutFormat::AlignAllBottom if horizontal => Some(Anchor::Y(&view.bottom)),
+                LayoutFormat::AlignAllCenterY if horizontal => Some(Anchor::Y(&view.center_y)),
+                LayoutFormat::AlignAllLeft if !horizontal => Some(Anchor::X(&view.left)),
+                LayoutFormat::AlignAllRight if !horizontal => Some(Anchor::X(&view.right)),
+                LayoutFormat::AlignAllLeading if !horizontal => Some(Anchor::X(&view.leading)),
+                LayoutFormat::AlignAllTrailing if !horizontal => Some(Anchor::X(&view.trailing)),
+                LayoutFormat::AlignAllCenterX if !horizontal => Some(Anchor::X(&view.center_x)),
+                _ => None
+            };
+
+            match option {
+                LayoutFormat::DirectionLeadingToTrailing
+                | LayoutFormat::DirectionLeftToRight
+                | LayoutFormat::DirectionRightToLeft => {
+                    continue;
+                },
+
+                LayoutFormat::AlignAllLastBaseline | LayoutFormat::Unknown(_) => {
+                    return Err(self.error(0, format!("{:?} isn't supported in visual formats", option)));
+                },
+
+                _ if anchor(views[0]).is_none() => {
+                    return Err(self.error(0, format!("{:?} must be perpendicular to the format's orientation", option)));
+                },
+
+                _ => {}
+            }
+
+            for view in &views[1..] {
+                if let (Some(first), Some(other)) = (anchor(views[0]), anchor(view)) {
+                    self.constraints.push(other.constraint(LayoutRelation::Equal, &first));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn build(mut self, options: &[LayoutFormat]) -> Result<Vec<LayoutConstraint>, VisualFormatError> {
+        let (parsed, orientation, direction) = (self.parsed, self.parsed.orientation, self.direction);
+        let views = parsed
+            .views
+            .iter()
+            .map(|spec| self.view(spec))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if let Some(connection) = &parsed.leading {
+            let superview = self.superview(views[0], connection)?;
+            let (start, _, _) = superview.axis(orientation, direction);
+            let (view_start, _, _) = views[0].axis(orientation, direction);
+            self.connect(start, view_start, connection, STANDARD_SUPERVIEW_SPACING)?;
+        }
+
+        for (index, spec) in parsed.views.iter().enumerate() {
+            let (_, end, size) = views[index].axis(orientation, direction);
+            self.size(spec, size)?;
+
+            if let Some(connection) = parsed.connections.get(index) {
+                let (next_start, _, _) = views[index + 1].axis(orientation, direction);
+                self.connect(end, next_start, connection, STANDARD_SPACING)?;
+            }
+        }
+
+        if let Some(connection) = &parsed.trailing {
+            let last = views[views.len() - 1];
+            let superview = self.superview(last, connection)?;
+            let (_, end, _) = superview.axis(orientation, direction);
+            let (_, view_end, _) = last.axis(orientation, direction);
+            self.connect(view_end, end, connection, STANDARD_SUPERVIEW_SPACING)?;
+        }
+
+        self.align(&views, options)?;
+        Ok(self.constraints)
+    }
+}
+
+impl LayoutConstraint {
+    /// Creates (inactive) constraints from a visual format string, e.g
+    /// `"H:|-[a]-8-[b(>=40)]-|"`. Views and metrics are looked up by name; `|` refers to the
+    /// superview of the first (or last) view.
+    ///
+    /// `options` may include a direction (horizontal formats run leading to trailing by
+    /// default), and any alignments perpendicular to the format's orientation, which align every
+    /// view to the first.
+    ///
+    /// Syntax errors, unknown names and invalid options are reported with their position in the
+    /// format string.
+    pub fn with_visual_format(
+        format: &str,
+        options: &[LayoutFormat],
+        metrics: &HashMap<&str, f64>,
+        views: &HashMap<&str, LayoutAnchors>
+    ) -> Result<Vec<LayoutConstraint>, VisualFormatError> {
+        let parsed = parse(format)?;
+
+        let direction = options
+            .iter()
+            .rev()
+            .find_map(|option| match option {
+                LayoutFormat::DirectionLeftToRight => Some(Direction::LeftToRight),
+                LayoutFormat::DirectionRightToLeft => Some(Direction::RightToLeft),
+                LayoutFormat::DirectionLeadingToTrailing => Some(Direction::LeadingToTrailing),
+                _ => None
+            })
+            .unwrap_or(Direction::LeadingToTrailing);
+
+        Builder {
+            format,
+            parsed: &parsed,
+            direction,
+            metrics,
+            views,
+            constraints: vec![]
+        }
+        .build(options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::LayoutAnchors;
+    use crate::geometry::Rect;
+    use crate::layout::engine::LayoutEngine;
+    use crate::layout::{LayoutConstraint, LayoutFormat};
+
+    #[test]
+    fn test_builds_constraints() {
+        let engine = LayoutEngine::new();
+        let window = engine.view("window");
+        window.set_frame(Rect::new(0., 0., 400., 300.));
+
+        let (a, b, c) = (engine.view("a"), engine.view("b"), engine.view("c"));
+        window.add_subview(&a);
+        window.add_subview(&b);
+        window.add_subview(&c);
+
+        let mut views = HashMap::new();
+        views.insert("a", LayoutAnchors::from(&a));
+        views.insert("b", LayoutAnchors::from(&b));
+        views.insert("c", LayoutAnchors::from(&c));
+
+        let mut metrics = HashMap::new();
+        metrics.insert("side", 50.);
+        metrics.insert("low", 250.);
+
+        let formats = [
+            (
+                "H:|-[a(side)]-(>=10,==100@low)-[b(>=40)][c(==a)]-|",
+                &[LayoutFormat::AlignAllTop][..]
+            ),
+            ("V:|-10-[a(side)]", &[]),
+            ("V:[b(==20)]-(>=0)-|", &[]),
+            ("V:[c(==b)]", &[])
+        ];
+
+        let mut constraints = vec![];
+        for (format, options) in formats.iter() {
+            constraints.extend(LayoutConstraint::with_visual_format(format, options, &metrics, &views).unwrap());
+        }
+
+        LayoutConstraint::activate(&constraints);
+        let solution = engine.solve();
+        assert!(solution.issues.is_empty(), "{:?}", solution.issues);
+        assert_eq!(solution.frame(&a), Rect::new(10., 20., 50., 50.));
+        assert_eq!(solution.frame(&b), Rect::new(10., 170., 160., 20.));
+        assert_eq!(solution.frame(&c), Rect::new(10., 330., 50., 20.));
+
+        // Right to left, the views run the other way.
+        LayoutConstraint::deactivate(&constraints);
+        let constraints =
+            LayoutConstraint::with_visual_format("H:|[a(10)][b(20)]", &[LayoutFormat::DirectionRightToLeft], &metrics, &views)
+                .unwrap();
+
+        LayoutConstraint::activate(&constraints);
+        let solution = engine.solve();
+        assert_eq!(solution.frame(&a).left, 390.);
+        assert_eq!(solution.frame(&b).left, 370.);
+    }
+
+    #[test]
+    fn test_errors() {
+        let engine = LayoutEngine::new();
+        let (orphan, child) = (engine.view("orphan"), engine.view("child"));
+        orphan.add_subview(&child);
+
+        let mut views = HashMap::new();
+        views.insert("orphan", LayoutAnchors::from(&orphan));
+        views.insert("child", LayoutAnchors::from(&child));
+
+        let mut metrics = HashMap::new();
+        metrics.insert("gap", 8.);
+
+        let cases: [(&str, &[LayoutFormat], usize, &str); 7] = [
+            ("H:[missing]", &[], 3, "Unknown view 'missing'"),
+            (
+                "H:|-[orphan]",
+                &[],
+                2,
+                "'|' can only be used with views that have a superview"
+            ),
+            ("H:[child]-(spacing)-|", &[], 11, "Unknown metric 'spacing'"),
+            ("H:[child(==what)]", &[], 9, "Unknown view or metric"),
+            (
+                "H:|-(gap@2000)-[child]",
+                &[],
+                5,
+                "Priorities must be greater than 0 and at most 1000"
+            ),
+            (
+                "H:[child][orphan]",
+                &[LayoutFormat::AlignAllLeading],
+                0,
+                "AlignAllLeading must be perpendicular to the format's orientation"
+            ),
+            (
+                "V:[child]",
+                &[LayoutFormat::AlignAllLastBaseline],
+                0,
+                "AlignAllLastBaseline isn't supported in visual formats"
+            )
+        ];
+
+        for (format, options, position, message) in cases.iter() {
+            let error = LayoutConstraint::with_visual_format(format, options, &metrics, &views).unwrap_err();
+            assert_eq!((error.position, error.message.as_str()), (*position, *message), "{}", format);
+        }
+    }
+}
@@ -0,0 +1,476 @@
+//! A parser for Apple's Visual Format Language. This only deals with syntax - names are left
+//! unresolved, so that it can be used (and tested) without any views or metrics.
+//!
+//! The grammar is as documented by Apple:
+//!
+//! ```text
+//! format      ::= (orientation ':')? ('|' connection)? view (connection view)* (connection '|')?
+//! orientation ::= 'H' | 'V'
+//! connection  ::= '' | '-' | '-' predicates '-'
+//! predicates  ::= number | name | '(' predicate (',' predicate)* ')'
+//! predicate   ::= ('==' | '<=' | '>=')? (number | name) ('@' (number | name))?
+//! view        ::= '[' name ('(' predicate (',' predicate)* ')')? ']'
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::layout::LayoutRelation;
+
+/// The axis a visual format string lays views out along.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Orientation {
+    /// Views are laid out horizontally (`H:`, the default).
+    Horizontal,
+
+    /// Views are laid out vertically (`V:`).
+    Vertical
+}
+
+/// A number, or the name of a metric (or view) to be resolved later.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// A literal number.
+    Number(f64),
+
+    /// A metric or view name.
+    Name(String)
+}
+
+/// A single predicate, e.g `>=40@750`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Predicate {
+    /// The offset of the predicate in the format string.
+    pub position: usize,
+
+    /// The relation; `Equal` if none was given.
+    pub relation: LayoutRelation,
+
+    /// What the predicate relates to.
+    pub operand: Operand,
+
+    /// The priority, if one was given.
+    pub priority: Option<Operand>
+}
+
+/// The spacing between two views, or a view and its superview.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Spacing {
+    /// No spacing, e.g `[a][b]`.
+    Flush,
+
+    /// The standard spacing, e.g `[a]-[b]`.
+    Standard,
+
+    /// Spacing given by predicates, e.g `[a]-(>=8)-[b]`.
+    Predicates(Vec<Predicate>)
+}
+
+/// A connection between two views, or a view and its superview.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Connection {
+    /// The offset of the connection in the format string. For connections to the superview,
+    /// this is the offset of the `|`.
+    pub position: usize,
+
+    /// The spacing the connection asks for.
+    pub spacing: Spacing
+}
+
+/// A view, e.g `[button(>=40)]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewSpec {
+    /// The offset of the view's name in the format string.
+    pub position: usize,
+
+    /// The name of the view.
+    pub name: String,
+
+    /// Predicates for the view's size along the format's orientation.
+    pub predicates: Vec<Predicate>
+}
+
+/// A parsed visual format string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VisualFormat {
+    /// The axis the views are laid out along.
+    pub orientation: Orientation,
+
+    /// The connection to the superview before the first view, if there is one.
+    pub leading: Option<Connection>,
+
+    /// The views, in order.
+    pub views: Vec<ViewSpec>,
+
+    /// The connections between consecutive views; there's one less of these than there are
+    /// views.
+    pub connections: Vec<Connection>,
+
+    /// The connection to the superview after the last view, if there is one.
+    pub trailing: Option<Connection>
+}
+
+/// An error in a visual format string, along with where it occurred.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VisualFormatError {
+    /// The byte offset in the format string where the error was found.
+    pub position: usize,
+
+    /// A description of the problem.
+    pub message: String,
+
+    format: String
+}
+
+impl VisualFormatError {
+    /// Creates a new error at `position` in `format`.
+    pub(crate) fn new<S: Into<String>>(format: &str, position: usize, message: S) -> Self {
+        VisualFormatError {
+            position,
+            message: message.into(),
+            format: format.to_string()
+        }
+    }
+}
+
+impl fmt::Display for VisualFormatError {
+    /// Prints the message, followed by the format string with a caret under the problem (in the
+    /// style of AppKit's own error).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = self.format.get(..self.position).map(|s| s.chars().count()).unwrap_or(0);
+        write!(f, "{}\n{}\n{}^", self.message, self.format, " ".repeat(column))
+    }
+}
+
+impl Error for VisualFormatError {}
+
+/// Parses a visual format string.
+pub fn parse(format: &str) -> Result<VisualFormat, VisualFormatError> {
+    Parser { format, position: 0 }.format()
+}
+
+struct Parser<'a> {
+    format: &'a str,
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    fn error<S: Into<String>>(&self, message: S) -> VisualFormatError {
+        VisualFormatError::new(self.format, self.position, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.format[self.position..].chars().next()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.format[self.position..].starts_with(token) {
+            self.position += token.len();
+            return true;
+        }
+
+        false
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), VisualFormatError> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(self.error(format!("Expected '{}'", token)))
+        }
+    }
+
+    fn format(mut self) -> Result<VisualFormat, VisualFormatError> {
+        let orientation = match (self.eat("H:"), self.eat("V:")) {
+            (false, true) => Orientation::Vertical,
+            _ => Orientation::Horizontal
+        };
+
+        let mut leading = None;
+
+        if self.peek() == Some('|') {
+            let position = self.position;
+            self.position += 1;
+            leading = Some(Connection {
+                position,
+                spacing: self.spacing()?
+            });
+        }
+
+        let mut views = vec![self.view()?];
+        let mut connections = vec![];
+        let mut trailing = None;
+
+        while self.position < self.format.len() {
+            let position = self.position;
+            let spacing = self.spacing()?;
+
+            match self.peek() {
+                Some('[') => {
+                    connections.push(Connection { position, spacing });
+                    views.push(self.view()?);
+                },
+
+                Some('|') => {
+                    trailing = Some(Connection {
+                        position: self.position,
+                        spacing
+                    });
+
+                    self.position += 1;
+
+                    if self.position < self.format.len() {
+                        return Err(self.error("Expected the end of the format after '|'"));
+                    }
+                },
+
+                _ => return Err(self.error("Expected '[' or '|'"))
+            }
+        }
+
+        Ok(VisualFormat {
+            orientation,
+            leading,
+            views,
+            connections,
+            trailing
+        })
+    }
+
+    /// Parses the (possibly empty) connection before a view or `|`.
+    fn spacing(&mut self) -> Result<Spacing, VisualFormatError> {
+        if !self.eat("-") {
+            return Ok(Spacing::Flush);
+        }
+
+        if let Some('[') | Some('|') = self.peek() {
+            return Ok(Spacing::Standard);
+        }
+
+        let predicates = match self.peek() {
+            Some('(') => self.predicate_list()?,
+
+            _ => {
+                let position = self.position;
+                let operand = self.operand(false)?;
+
+                vec![Predicate {
+                    position,
+                    relation: LayoutRelation::Equal,
+                    operand,
+                    priority: None
+                }]
+            }
+        };
+
+        self.expect("-")?;
+        Ok(Spacing::Predicates(predicates))
+    }
+
+    fn view(&mut self) -> Result<ViewSpec, VisualFormatError> {
+        self.expect("[")?;
+
+        let position = self.position;
+        let name = self.name().ok_or_else(|| self.error("Expected a view name"))?;
+
+        let predicates = match self.peek() {
+            Some('(') => self.predicate_list()?,
+            _ => vec![]
+        };
+
+        self.expect("]")?;
+
+        Ok(ViewSpec {
+            position,
+            name,
+            predicates
+        })
+    }
+
+    fn predicate_list(&mut self) -> Result<Vec<Predicate>, VisualFormatError> {
+        self.expect("(")?;
+        let mut predicates = vec![self.predicate()?];
+
+        while self.eat(",") {
+            predicates.push(self.predicate()?);
+        }
+
+        self.expect(")")?;
+        Ok(predicates)
+    }
+
+    fn predicate(&mut self) -> Result<Predicate, VisualFormatError> {
+        let position = self.position;
+
+        let relation = if self.eat("==") {
+            LayoutRelation::Equal
+        } else if self.eat("<=") {
+            LayoutRelation::LessThanOrEqual
+        } else if self.eat(">=") {
+            LayoutRelation::GreaterThanOrEqual
+        } else {
+            LayoutRelation::Equal
+        };
+
+        let operand = self.operand(true)?;
+
+        let priority = match self.eat("@") {
+            true => Some(self.operand(false)?),
+            false => None
+        };
+
+        Ok(Predicate {
+            position,
+            relation,
+            operand,
+            priority
+        })
+    }
+
+    /// Parses a number or a name. Negative numbers are only allowed inside parentheses, where
+    /// they can't be confused with a connection.
+    fn operand(&mut self, allow_negative: bool) -> Result<Operand, VisualFormatError> {
+        if let Some(name) = self.name() {
+            return Ok(Operand::Name(name));
+        }
+
+        let start = self.position;
+        let rest = &self.format[start..];
+        let sign = match allow_negative && rest.starts_with('-') {
+            true => 1,
+            false => 0
+        };
+
+        let length = sign
+            + rest[sign..]
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len() - sign);
+
+        match rest[..length].parse::<f64>() {
+            Ok(number) if length > sign => {
+                self.position += length;
+                Ok(Operand::Number(number))
+            },
+
+            _ => Err(self.error("Expected a number or a metric name"))
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        let rest = &self.format[self.position..];
+
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+
+        let length = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+
+        self.position += length;
+        Some(rest[..length].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Connection, Operand, Orientation, Predicate, Spacing, ViewSpec};
+    use crate::layout::LayoutRelation;
+
+    fn predicate(position: usize, relation: LayoutRelation, operand: Operand, priority: Option<Operand>) -> Predicate {
+        Predicate {
+            position,
+            relation,
+            operand,
+            priority
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let format = parse("H:|-[a]-8-[b(>=40,<=wide@750)]-(>=gap)-|").unwrap();
+        assert_eq!(format.orientation, Orientation::Horizontal);
+        assert_eq!(
+            format.leading,
+            Some(Connection {
+                position: 2,
+                spacing: Spacing::Standard
+            })
+        );
+
+        assert_eq!(format.views, vec![
+            ViewSpec {
+                position: 5,
+                name: "a".to_string(),
+                predicates: vec![]
+            },
+            ViewSpec {
+                position: 11,
+                name: "b".to_string(),
+                predicates: vec![
+                    predicate(13, LayoutRelation::GreaterThanOrEqual, Operand::Number(40.), None),
+                    predicate(
+                        18,
+                        LayoutRelation::LessThanOrEqual,
+                        Operand::Name("wide".to_string()),
+                        Some(Operand::Number(750.))
+                    ),
+                ]
+            },
+        ]);
+
+        assert_eq!(format.connections, vec![Connection {
+            position: 7,
+            spacing: Spacing::Predicates(vec![predicate(8, LayoutRelation::Equal, Operand::Number(8.), None)])
+        }]);
+
+        assert_eq!(
+            format.trailing,
+            Some(Connection {
+                position: 39,
+                spacing: Spacing::Predicates(vec![predicate(
+                    32,
+                    LayoutRelation::GreaterThanOrEqual,
+                    Operand::Name("gap".to_string()),
+                    None
+                )])
+            })
+        );
+
+        let format = parse("V:[top][bottom(==top)]|").unwrap();
+        assert_eq!(format.orientation, Orientation::Vertical);
+        assert_eq!(format.leading, None);
+        assert_eq!(format.connections[0].spacing, Spacing::Flush);
+        assert_eq!(format.views[1].predicates, vec![predicate(
+            15,
+            LayoutRelation::Equal,
+            Operand::Name("top".to_string()),
+            None
+        )]);
+        assert_eq!(format.trailing.unwrap().spacing, Spacing::Flush);
+
+        let format = parse("[a(-10.5)]").unwrap();
+        assert_eq!(format.views[0].predicates[0].operand, Operand::Number(-10.5));
+    }
+
+    #[test]
+    fn test_errors() {
+        let cases = [
+            ("", 0, "Expected '['"),
+            ("H:|-[a", 6, "Expected ']'"),
+            ("H:|-[]", 5, "Expected a view name"),
+            ("[a]-8[b]", 5, "Expected '-'"),
+            ("[a]-(>=)-[b]", 7, "Expected a number or a metric name"),
+            ("[a]-(8-[b]", 6, "Expected ')'"),
+            ("[a]|[b]", 4, "Expected the end of the format after '|'"),
+            ("[a]x", 3, "Expected '[' or '|'"),
+            ("[a]--[b]", 4, "Expected a number or a metric name")
+        ];
+
+        for (format, position, message) in cases.iter() {
+            let error = parse(format).unwrap_err();
+            assert_eq!((error.position, error.message.as_str()), (*position, *message), "{}", format);
+        }
+
+        assert_eq!(parse("H:|-[a").unwrap_err().to_string(), "Expected ']'\nH:|-[a\n      ^");
+    }
+}
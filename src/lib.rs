@@ -74,10 +74,16 @@
 //! feature, but is gated to enable platforms that might shim AppKit without AutoLayout support.
 //! - `cloudkit`: Links `CloudKit.framework` and provides some wrappers around CloudKit
 //! functionality. Currently not feature complete.
+//! - `gnustep`: Builds the `appkit` APIs against GNUstep Base/GUI and libobjc2 rather than Apple's
+//! frameworks. Apple-only APIs (SF Symbols, dynamic colors, anything needing Core Graphics) are
+//! compiled out, and Apple-only features (`cloudkit`, `quicklook`, `user-notifications`,
+//! `webview`) are compile errors.
 //! - `color_fallbacks`: Provides fallback colors for older systems where `systemColor` types don't
 //! exist. This feature is very uncommon and you probably don't need it.
 //! - `quicklook`: Links `QuickLook.framework` and offers methods for generating preview images for
 //! files.
+//! - `serde`: Implements `Serialize`/`Deserialize` for `defaults::Value` and `color::ColorValue`,
+//! and enables storing any serde-compatible type in `UserDefaults`.
 //! - `user-notifications`: Links `UserNotifications.framework` and provides functionality for
 //! emitting notifications on appkit and uikit. Note that this _requires_ your application be
 //! code-signed, and will not work without it.
@@ -90,8 +96,18 @@
 //!
 //! [cargo-features]: https://doc.rust-lang.org/stable/cargo/reference/manifest.html#the-features-section
 
+#[cfg(target_vendor = "apple")]
 pub use core_foundation;
+
+#[cfg(target_vendor = "apple")]
 pub use core_graphics;
+
+#[cfg(not(target_vendor = "apple"))]
+pub mod core_foundation;
+
+#[cfg(not(target_vendor = "apple"))]
+pub mod core_graphics;
+
 pub use lazy_static;
 pub use objc;
 pub use url;
@@ -99,6 +115,21 @@ pub use url;
 //#[cfg(all(feature = "appkit", feature = "uikit", not(feature = "doc_cfg")))]
 //compile_error!("The \"appkit\" and \"uikit\" features cannot be enabled together. Pick one. :)");
 
+#[cfg(all(feature = "gnustep", feature = "uikit"))]
+compile_error!("The \"gnustep\" feature builds against GNUstep's AppKit, and can't be combined with \"uikit\".");
+
+#[cfg(all(feature = "gnustep", feature = "cloudkit"))]
+compile_error!("CloudKit is Apple-only, so the \"cloudkit\" feature isn't available with \"gnustep\".");
+
+#[cfg(all(feature = "gnustep", feature = "quicklook"))]
+compile_error!("QuickLook is Apple-only, so the \"quicklook\" feature isn't available with \"gnustep\".");
+
+#[cfg(all(feature = "gnustep", feature = "user-notifications"))]
+compile_error!("UserNotifications is Apple-only, so the \"user-notifications\" feature isn't available with \"gnustep\".");
+
+#[cfg(all(feature = "gnustep", feature = "webview"))]
+compile_error!("GNUstep has no WKWebView, so the \"webview\" feature isn't available with \"gnustep\".");
+
 #[cfg(feature = "appkit")]
 #[cfg_attr(docsrs, doc(cfg(feature = "appkit")))]
 pub mod appkit;
@@ -44,7 +44,7 @@
 
 use std::collections::HashMap;
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 use objc::runtime::{Class, Object};
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::ShareId;
@@ -59,8 +59,11 @@ use crate::layout::{LayoutAnchorDimension, LayoutAnchorX, LayoutAnchorY};
 use crate::objc_access::ObjcAccess;
 use crate::scrollview::ScrollView;
 use crate::utils::properties::{ObjcProperty, PropertyNullable};
-use crate::utils::{os, CGSize, CellFactory};
-use crate::view::{ViewAnimatorProxy, ViewDelegate};
+use crate::utils::{os, CellFactory};
+use crate::view::ViewDelegate;
+
+#[cfg(all(feature = "appkit", target_os = "macos"))]
+use crate::view::ViewAnimatorProxy;
 
 #[cfg(feature = "appkit")]
 use crate::appkit::menu::MenuItem;
@@ -145,6 +148,9 @@ pub struct ListView<T = ()> {
     pub objc: ObjcProperty,
 
     /// An object that supports limited animations. Can be cloned into animation closures.
+    ///
+    /// This is currently only supported on macOS with the `appkit` feature.
+    #[cfg(all(feature = "appkit", target_os = "macos"))]
     pub animator: ViewAnimatorProxy,
 
     /// In AppKit, we need to manage the NSScrollView ourselves. It's a bit
@@ -266,6 +272,7 @@ impl ListView {
             // Note that AppKit needs this to be the ScrollView!
             // @TODO: Figure out if there's a use case for exposing the inner tableview animator
             // property...
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: ViewAnimatorProxy::new(anchor_view),
 
             objc: ObjcProperty::retain(view),
@@ -317,6 +324,7 @@ where
             menu: PropertyNullable::default(),
             delegate: None,
             objc: ObjcProperty::retain(view),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: ViewAnimatorProxy::new(anchor_view),
 
             #[cfg(feature = "autolayout")]
@@ -369,6 +377,7 @@ impl<T> ListView<T> {
             menu: self.menu.clone(),
             delegate: None,
             objc: self.objc.clone(),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: self.animator.clone(),
 
             #[cfg(feature = "autolayout")]
@@ -436,6 +445,9 @@ impl<T> ListView<T> {
     }
 
     /// Call this to set the background color for the backing layer.
+    ///
+    /// Layers take a `CGColor`, so this isn't available on GNUstep.
+    #[cfg(target_vendor = "apple")]
     pub fn set_background_color<C: AsRef<Color>>(&self, color: C) {
         // @TODO: This is wrong.
         self.objc.with_mut(|obj| unsafe {
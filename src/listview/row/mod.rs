@@ -55,7 +55,10 @@ use crate::layer::Layer;
 use crate::layout::Layout;
 use crate::objc_access::ObjcAccess;
 use crate::utils::properties::ObjcProperty;
-use crate::view::{ViewAnimatorProxy, ViewDelegate};
+use crate::view::ViewDelegate;
+
+#[cfg(all(feature = "appkit", target_os = "macos"))]
+use crate::view::ViewAnimatorProxy;
 
 #[cfg(feature = "autolayout")]
 use crate::layout::{LayoutAnchorDimension, LayoutAnchorX, LayoutAnchorY, SafeAreaLayoutGuide};
@@ -96,6 +99,9 @@ fn allocate_view(registration_fn: fn() -> *const Class) -> id {
 #[derive(Debug)]
 pub struct ListViewRow<T = ()> {
     /// An object that supports limited animations. Can be cloned into animation closures.
+    ///
+    /// This is currently only supported on macOS with the `appkit` feature.
+    #[cfg(all(feature = "appkit", target_os = "macos"))]
     pub animator: ViewAnimatorProxy,
 
     /// A pointer to the Objective-C runtime view controller.
@@ -163,6 +169,7 @@ impl ListViewRow {
         ListViewRow {
             delegate: None,
             objc: ObjcProperty::retain(view),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: ViewAnimatorProxy::new(view),
 
             #[cfg(feature = "autolayout")]
@@ -227,6 +234,7 @@ where
         let view = ListViewRow {
             delegate: Some(delegate),
             objc: ObjcProperty::retain(view),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: ViewAnimatorProxy::new(view),
 
             #[cfg(feature = "autolayout")]
@@ -283,6 +291,7 @@ where
         let mut view = ListViewRow {
             delegate: None,
             objc: ObjcProperty::retain(view),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: ViewAnimatorProxy::new(view),
 
             #[cfg(feature = "autolayout")]
@@ -335,6 +344,7 @@ where
         ListViewRow {
             delegate: None,
             objc: self.objc.clone(),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: self.animator.clone(),
 
             #[cfg(feature = "autolayout")]
@@ -384,6 +394,7 @@ impl<T> ListViewRow<T> {
             is_handle: true,
             layer: Layer::new(), // @TODO: Fix & return cloned true layer for this row.
             objc: self.objc.clone(),
+            #[cfg(all(feature = "appkit", target_os = "macos"))]
             animator: self.animator.clone(),
 
             #[cfg(feature = "autolayout")]
@@ -0,0 +1,223 @@
+//! A pure-Rust notification center. This backs `NotificationCenter::local()`, and is handy for
+//! notifications that never need to leave your app - as well as for testing observer bookkeeping
+//! on platforms without Foundation.
+//!
+//! Unlike `NSNotificationCenter`, delivery here is synchronous: `post` calls each matching
+//! observer on the posting thread before returning.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, Weak};
+
+use super::{Notification, NotificationName};
+
+/// Called with each notification an observer receives.
+pub(crate) type Handler = Arc<dyn Fn(&Notification) + Send + Sync>;
+
+/// A registered observer.
+struct Registration {
+    id: usize,
+    name: NotificationName,
+    handler: Handler
+}
+
+#[derive(Default)]
+struct Registry {
+    observers: Vec<Registration>,
+    next_id: usize
+}
+
+/// The state shared by every handle to a local center.
+#[derive(Clone, Default)]
+pub(crate) struct LocalCenter(Arc<Mutex<Registry>>);
+
+impl fmt::Debug for LocalCenter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registry = self.0.lock().unwrap();
+        f.debug_struct("LocalCenter")
+            .field("observers", &registry.observers.len())
+            .finish()
+    }
+}
+
+impl LocalCenter {
+    /// Registers `handler` for notifications named `name`, returning an identifier that can be
+    /// passed to `WeakLocalCenter::remove`.
+    pub fn observe(&self, name: NotificationName, handler: Handler) -> usize {
+        let mut registry = self.0.lock().unwrap();
+        let id = registry.next_id;
+        registry.next_id += 1;
+        registry.observers.push(Registration { id, name, handler });
+        id
+    }
+
+    /// Returns a weak handle, for removing observers without keeping the center alive.
+    pub fn downgrade(&self) -> WeakLocalCenter {
+        WeakLocalCenter(Arc::downgrade(&self.0))
+    }
+
+    /// Calls every observer registered for `notification.name`, in the order they were added.
+    /// Observers may add or remove observers (or post) from within their handler.
+    pub fn post(&self, notification: &Notification) {
+        let handlers: Vec<Handler> = {
+            let registry = self.0.lock().unwrap();
+            registry
+                .observers
+                .iter()
+                .filter(|registration| registration.name == notification.name)
+                .map(|registration| registration.handler.clone())
+                .collect()
+        };
+
+        for handler in handlers {
+            handler(notification);
+        }
+    }
+
+    /// The number of registered observers.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().observers.len()
+    }
+}
+
+/// A weak handle to a `LocalCenter`, held by observer tokens.
+#[derive(Clone, Debug)]
+pub(crate) struct WeakLocalCenter(Weak<Mutex<Registry>>);
+
+impl WeakLocalCenter {
+    /// Removes the observer with the given identifier, if the center is still around.
+    pub fn remove(&self, id: usize) {
+        if let Some(registry) = self.0.upgrade() {
+            // Pulled out before the lock is released, so the handler is dropped unlocked.
+            let removed = {
+                let mut registry = registry.lock().unwrap();
+                let index = registry.observers.iter().position(|registration| registration.id == id);
+                index.map(|index| registry.observers.remove(index))
+            };
+
+            drop(removed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::LocalCenter;
+    use crate::foundation::ObjcValue;
+    use crate::notification_center::{Dispatcher, Notification, NotificationCenter, NotificationName};
+
+    fn notification(name: NotificationName, count: i64) -> Notification {
+        let mut user_info = HashMap::new();
+        user_info.insert("count".to_string(), ObjcValue::Integer(count));
+        Notification { name, user_info }
+    }
+
+    #[test]
+    fn test_observe_post_remove() {
+        let center = LocalCenter::default();
+        let received = Arc::new(Mutex::new(vec![]));
+
+        let first = center.observe(NotificationName::NSApplicationDidBecomeActive, {
+            let received = received.clone();
+            Arc::new(move |notification| received.lock().unwrap().push(("first", notification.clone())))
+        });
+
+        center.observe(NotificationName::NSApplicationDidBecomeActive, {
+            let received = received.clone();
+            Arc::new(move |notification| received.lock().unwrap().push(("second", notification.clone())))
+        });
+
+        center.observe(
+            NotificationName::NSApplicationWillTerminate,
+            Arc::new(|_| panic!("Wrong name"))
+        );
+        assert_eq!(center.len(), 3);
+
+        center.post(&notification(NotificationName::NSApplicationDidBecomeActive, 1));
+        assert_eq!(*received.lock().unwrap(), vec![
+            ("first", notification(NotificationName::NSApplicationDidBecomeActive, 1)),
+            ("second", notification(NotificationName::NSApplicationDidBecomeActive, 1))
+        ]);
+
+        center.downgrade().remove(first);
+        assert_eq!(center.len(), 2);
+
+        center.post(&notification(NotificationName::NSApplicationDidBecomeActive, 2));
+        assert_eq!(received.lock().unwrap().len(), 3);
+        assert_eq!(received.lock().unwrap()[2].0, "second");
+
+        // Removing twice, or from a center that's gone, is harmless.
+        center.downgrade().remove(first);
+        let weak = center.downgrade();
+        drop(center);
+        weak.remove(1);
+    }
+
+    #[test]
+    fn test_reentrant_handlers() {
+        let center = LocalCenter::default();
+        let count = Arc::new(Mutex::new(0));
+
+        center.observe(NotificationName::NSApplicationDidHide, {
+            let center = center.clone();
+            let count = count.clone();
+
+            Arc::new(move |_| {
+                *count.lock().unwrap() += 1;
+
+                // Observing and posting from within a handler must not deadlock.
+                center.observe(NotificationName::NSApplicationDidUnhide, Arc::new(|_| {}));
+                center.post(&notification(NotificationName::NSApplicationDidUnhide, 0));
+            })
+        });
+
+        center.post(&notification(NotificationName::NSApplicationDidHide, 0));
+        assert_eq!(*count.lock().unwrap(), 1);
+        assert_eq!(center.len(), 2);
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        ui: Mutex<Vec<NotificationName>>,
+        background: Mutex<Vec<NotificationName>>
+    }
+
+    impl Dispatcher for Arc<Recorder> {
+        type Message = Notification;
+
+        fn on_ui_message(&self, notification: Notification) {
+            self.ui.lock().unwrap().push(notification.name);
+        }
+
+        fn on_background_message(&self, notification: Notification) {
+            self.background.lock().unwrap().push(notification.name);
+        }
+    }
+
+    #[test]
+    fn test_center_dispatch_and_tokens() {
+        let center = NotificationCenter::local();
+        let recorder = Arc::new(Recorder::default());
+
+        let ui = center.observe(NotificationName::NSApplicationDidHide, recorder.clone());
+        let background = center.observe_in_background(NotificationName::NSApplicationDidUnhide, recorder.clone());
+
+        center.post(NotificationName::NSApplicationDidHide, HashMap::new());
+        center.post(NotificationName::NSApplicationDidUnhide, HashMap::new());
+        assert_eq!(*recorder.ui.lock().unwrap(), vec![NotificationName::NSApplicationDidHide]);
+        assert_eq!(*recorder.background.lock().unwrap(), vec![
+            NotificationName::NSApplicationDidUnhide
+        ]);
+
+        // Dropping a token removes its observer, and releases the handler.
+        drop(ui);
+        center.clone().post(NotificationName::NSApplicationDidHide, HashMap::new());
+        assert_eq!(recorder.ui.lock().unwrap().len(), 1);
+
+        drop(background);
+        assert_eq!(Arc::strong_count(&recorder), 1);
+    }
+}
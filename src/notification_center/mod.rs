@@ -11,14 +11,42 @@
 //! integrating with certain aspects of the underlying Cocoa/Foundation/Kit frameworks.
 //!
 //! ## Example
+//! ```rust,no_run
+//! use std::collections::HashMap;
+//!
+//! use cacao::notification_center::{Dispatcher, Notification, NotificationCenter, NotificationName};
+//!
+//! struct ActivityTracker;
+//!
+//! impl Dispatcher for ActivityTracker {
+//!     type Message = Notification;
+//!
+//!     fn on_ui_message(&self, notification: Notification) {
+//!         println!("{:?}: {:?}", notification.name, notification.user_info);
+//!     }
+//! }
+//!
+//! let center = NotificationCenter::default();
+//!
+//! // Hold on to the token for as long as you want to be notified.
+//! let token = center.observe(NotificationName::NSApplicationDidBecomeActive, ActivityTracker);
+//!
+//! center.post(NotificationName::NSApplicationDidBecomeActive, HashMap::new());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use block::ConcreteBlock;
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::{Id, ShareId};
 
-//use std::sync::Mutex;
-//use std::collections::HashMap;
+use crate::foundation::{id, nil, NSString, ObjcValue};
 
-//use lazy_static::lazy_static;
-//use objc::{class, msg_send, sel, sel_impl};
-//use objc::runtime::Object;
-//use objc_id::ShareId;
+mod local;
+use local::{Handler, LocalCenter};
 
 mod name;
 pub use name::NotificationName;
@@ -26,48 +54,218 @@ pub use name::NotificationName;
 mod traits;
 pub use traits::Dispatcher;
 
-/*lazy_static! {
-    pub static ref DefaultNotificationCenter: NotificationCenter = {
-        NotificationCenter {
-            objc: unsafe {
-                ShareId::from_ptr(msg_send![class!(NSNotificationCenter), defaultCenter])
-            },
+/// A notification, as delivered to observers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    /// The name of the notification.
+    pub name: NotificationName,
 
-            subscribers: Mutex::new(HashMap::new())
-        }
-    };
-}*/
+    /// Any extra information the poster attached. Entries that can't be represented as an
+    /// `ObjcValue` are skipped.
+    pub user_info: HashMap<String, ObjcValue>
+}
 
-// Wraps a reference to an `NSNotificationCenter` instance. Currently this only supports the
-// default center; in the future it should aim to support custom variants.
-//#[derive(Debug)]
-//pub struct NotificationCenter {
-//    pub objc: ShareId<Object>,
-//pub subscribers: Mutex<HashMap<String, Vec<Dispatcher>>>
-//}
+/// Which side of a `Dispatcher` a notification is delivered to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Delivery {
+    /// `on_ui_message`, on the main thread.
+    Main,
 
-/*impl Default for NotificationCenter {
+    /// `on_background_message`, on a background queue.
+    Background
+}
+
+#[derive(Clone, Debug)]
+enum Backend {
+    Foundation(ShareId<Object>),
+    Local(LocalCenter)
+}
+
+/// Wraps a notification center - either `NSNotificationCenter`, or a pure-Rust local one.
+///
+/// Observers are `Dispatcher`s: notifications are delivered to `on_ui_message` (on the main
+/// thread) or `on_background_message` (on a background queue), depending on whether you
+/// registered with `observe` or `observe_in_background`.
+#[derive(Clone, Debug)]
+pub struct NotificationCenter(Backend);
+
+impl Default for NotificationCenter {
     /// Returns a wrapper over `[NSNotificationCenter defaultCenter]`. From here you can handle
     /// observing, removing, and posting notifications.
     fn default() -> Self {
-        NotificationCenter {
-            objc: unsafe {
-                ShareId::from_ptr(msg_send![class!(NSNotificationCenter), defaultCenter])
-            }
+        NotificationCenter(Backend::Foundation(unsafe {
+            ShareId::from_ptr(msg_send![class!(NSNotificationCenter), defaultCenter])
+        }))
+    }
+}
+
+impl NotificationCenter {
+    /// Returns a new, pure-Rust notification center with the same API as the default one. It
+    /// never sees system notifications, and only delivers what's posted to it (or to a clone of
+    /// it).
+    ///
+    /// Delivery is synchronous: `post` calls each observer's `on_ui_message` or
+    /// `on_background_message` on the posting thread before returning. This makes it well suited
+    /// to testing, and to platforms without Foundation.
+    pub fn local() -> Self {
+        NotificationCenter(Backend::Local(LocalCenter::default()))
+    }
+
+    /// Registers `handler` for notifications with the given name, delivering them to its
+    /// `on_ui_message` method on the main thread.
+    ///
+    /// Observation stops when the returned `ObserverToken` is dropped.
+    pub fn observe<T>(&self, name: NotificationName, handler: T) -> ObserverToken
+    where
+        T: Dispatcher<Message = Notification> + Send + Sync + 'static
+    {
+        self.add_observer(name, Delivery::Main, handler)
+    }
+
+    /// Registers `handler` for notifications with the given name, delivering them to its
+    /// `on_background_message` method on a background queue.
+    ///
+    /// Observation stops when the returned `ObserverToken` is dropped.
+    pub fn observe_in_background<T>(&self, name: NotificationName, handler: T) -> ObserverToken
+    where
+        T: Dispatcher<Message = Notification> + Send + Sync + 'static
+    {
+        self.add_observer(name, Delivery::Background, handler)
+    }
+
+    /// Posts a notification with the given name and user info to every registered observer.
+    pub fn post(&self, name: NotificationName, user_info: HashMap<String, ObjcValue>) {
+        match &self.0 {
+            Backend::Foundation(center) => {
+                let name = NSString::from(name);
+
+                unsafe {
+                    match user_info.is_empty() {
+                        true => {
+                            let _: () = msg_send![&**center, postNotificationName:&*name object:nil userInfo:nil];
+                        },
+
+                        false => {
+                            let user_info: id = ObjcValue::Dictionary(user_info).into();
+                            let _: () = msg_send![&**center, postNotificationName:&*name
+                                object:nil
+                                userInfo:user_info];
+                        }
+                    }
+                }
+            },
+
+            Backend::Local(center) => center.post(&Notification { name, user_info })
         }
     }
-}*/
 
-/*impl NotificationCenter {
-    pub fn observe<T: Dispatcher>(&self, name: &str, handler: &T) {
+    fn add_observer<T>(&self, name: NotificationName, delivery: Delivery, handler: T) -> ObserverToken
+    where
+        T: Dispatcher<Message = Notification> + Send + Sync + 'static
+    {
+        let handler = Arc::new(handler);
+
+        match &self.0 {
+            Backend::Foundation(center) => {
+                // NSNotificationCenter calls us on the posting thread; hop to the right queue from
+                // there. Queued deliveries only hold a weak reference, so that nothing arrives
+                // after the token has been dropped.
+                let deliver: Handler = Arc::new(move |notification| {
+                    let handler = Arc::downgrade(&handler);
+                    let notification = notification.clone();
+
+                    let task = move || {
+                        if let Some(handler) = handler.upgrade() {
+                            match delivery {
+                                Delivery::Main => handler.on_ui_message(notification),
+                                Delivery::Background => handler.on_background_message(notification)
+                            }
+                        }
+                    };
 
+                    match delivery {
+                        Delivery::Main => dispatch::Queue::main().exec_async(task),
+                        Delivery::Background => dispatch::Queue::global(dispatch::QueuePriority::Default).exec_async(task)
+                    }
+                });
+
+                observe_foundation(center, name, deliver)
+            },
+
+            Backend::Local(center) => {
+                let id = center.observe(
+                    name,
+                    Arc::new(move |notification| match delivery {
+                        Delivery::Main => handler.on_ui_message(notification.clone()),
+                        Delivery::Background => handler.on_background_message(notification.clone())
+                    })
+                );
+
+                let center = center.downgrade();
+                ObserverToken::new(move || center.remove(id))
+            }
+        }
     }
+}
 
-    pub fn remove<T: Dispatcher>(&self, name: &str, handler: &T) {
+/// Registers a block-based observer with `NSNotificationCenter`.
+fn observe_foundation(center: &ShareId<Object>, name: NotificationName, deliver: Handler) -> ObserverToken {
+    let ns_name = NSString::from(&name);
 
+    let block = ConcreteBlock::new(move |notification: id| {
+        let user_info = unsafe {
+            let user_info: id = msg_send![notification, userInfo];
+            ObjcValue::dictionary_from_object(user_info)
+        };
+
+        deliver(&Notification {
+            name: name.clone(),
+            user_info
+        });
+    });
+
+    let block = block.copy();
+
+    let observer: Id<Object> = unsafe {
+        Id::from_ptr(msg_send![&**center, addObserverForName:&*ns_name
+            object:nil
+            queue:nil
+            usingBlock:&*block])
+    };
+
+    let center = center.clone();
+
+    ObserverToken::new(move || unsafe {
+        let _: () = msg_send![&*center, removeObserver:&*observer];
+    })
+}
+
+/// Returned when registering an observer. The observer is removed when this is dropped, so hold
+/// on to it for as long as you want to receive notifications.
+#[must_use = "the observer is removed as soon as the token is dropped"]
+pub struct ObserverToken {
+    remove: Option<Box<dyn FnOnce()>>
+}
+
+impl ObserverToken {
+    fn new<F: FnOnce() + 'static>(remove: F) -> Self {
+        ObserverToken {
+            remove: Some(Box::new(remove))
+        }
     }
+}
 
-    pub fn post(&self, name: &str) {
+impl fmt::Debug for ObserverToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObserverToken").finish()
+    }
+}
 
+impl Drop for ObserverToken {
+    /// Removes the observer.
+    fn drop(&mut self) {
+        if let Some(remove) = self.remove.take() {
+            remove();
+        }
     }
-}*/
+}
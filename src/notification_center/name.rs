@@ -1,11 +1,19 @@
-#[allow(non_camel_case_types)]
+//! Names for the notifications posted by the system frameworks, along with the Cocoa constant
+//! each one wraps.
+
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
 use crate::foundation::NSString;
+use crate::utils::load_constant;
 
 /// An enum that wraps NSNotificationName.
 ///
 /// Since this framework utilizes Objective-C, these are ultimately backed by `NSString`... but we
 /// want them to be a bit more type-friendly and autocomplete-able.
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum NotificationName {
     /// Posted when the audio engine config changes.
     ///
@@ -553,7 +561,9 @@ pub enum NotificationName {
     ///
     NSColorPanelColorDidChange,
 
-    ///
+    /// This isn't a notification Cocoa posts; it's kept as an alias for
+    /// `NSColorPanelColorDidChange`, and parses to that.
+    #[deprecated(note = "Use `NotificationName::NSColorPanelColorDidChange` instead.")]
     NSColorPanel,
 
     ///
@@ -1310,13 +1320,582 @@ pub enum NotificationName {
     SKStorefrontCountryCodeDidChange,
 
     ///
-    WKAccessibilityReduceMotionStatusDidChange
+    WKAccessibilityReduceMotionStatusDidChange,
+
+    /// A notification that isn't part of the system frameworks - e.g, one posted by your app or
+    /// a plugin. The string is used as the notification name as-is.
+    Custom(String)
+}
+
+impl NotificationName {
+    /// Returns the string Foundation uses for this notification at runtime, which is what gets
+    /// handed to `NSNotificationCenter`. For a `Custom` name that's the string itself; for
+    /// everything else it's the value of the Cocoa constant, which doesn't always match the
+    /// constant's name (e.g, `NSAccessibilityMovedNotification` is `AXMoved`).
+    ///
+    /// The constants here are spread across frameworks this crate doesn't link (and across AppKit
+    /// and UIKit), so rather than declaring them as `extern` statics - which would fail to link -
+    /// they're looked up by symbol in whatever's loaded into the process. If the framework that
+    /// defines one isn't loaded, nothing can post that notification anyway, and you get
+    /// `as_str()` back.
+    pub fn value(&self) -> Cow<'_, str> {
+        match self {
+            NotificationName::Custom(name) => Cow::Borrowed(name),
+
+            known => match load_constant(known.as_str()) {
+                Some(value) => Cow::Owned(value.to_string()),
+                None => Cow::Borrowed(known.as_str())
+            }
+        }
+    }
+}
+
+impl fmt::Display for NotificationName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NotificationName {
+    type Err = Infallible;
+
+    /// Parses a Cocoa constant name (e.g, `NSWindowDidMoveNotification`) into the matching
+    /// variant. Anything else becomes `NotificationName::Custom`, so this never fails - and a
+    /// `Custom` name that matches a known constant comes back as the known variant.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(NotificationName::from_constant(s).unwrap_or_else(|| NotificationName::Custom(s.to_string())))
+    }
+}
+
+impl From<&NotificationName> for NSString<'_> {
+    fn from(name: &NotificationName) -> Self {
+        NSString::new(&name.value())
+    }
 }
 
 impl From<NotificationName> for NSString<'_> {
     fn from(name: NotificationName) -> Self {
-        match name {
-            _ => NSString::no_copy("")
+        NSString::from(&name)
+    }
+}
+
+/// Generates the mapping between variants and the names of the Cocoa constants they wrap, in
+/// both directions. `as_str()` is an exhaustive `match`, so a variant without an entry here is a
+/// compile error - as is a constant listed twice, which makes an arm in `from_constant()`
+/// unreachable.
+macro_rules! constants {
+    ($($variant:ident => $constant:literal,)*) => {
+        impl NotificationName {
+            /// Returns the name of the Cocoa constant this wraps (e.g,
+            /// `NSWindowDidMoveNotification`), or the string for a `Custom` name. This is also
+            /// what `Display` prints.
+            #[allow(deprecated)]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    NotificationName::Custom(name) => name,
+                    NotificationName::NSColorPanel => "NSColorPanelColorDidChangeNotification",
+                    $(NotificationName::$variant => $constant,)*
+                }
+            }
+
+            /// The reverse of `as_str()`, for everything but `Custom` names.
+            #[deny(unreachable_patterns)]
+            fn from_constant(constant: &str) -> Option<NotificationName> {
+                match constant {
+                    $($constant => Some(NotificationName::$variant),)*
+                    _ => None
+                }
+            }
+        }
+
+        /// Every known variant, aside from the deprecated alias.
+        #[cfg(test)]
+        const ALL: &[NotificationName] = &[$(NotificationName::$variant,)*];
+    };
+}
+
+constants! {
+    AudioEngineConfigurationChange => "AVAudioEngineConfigurationChangeNotification",
+    AudioSessionInterruption => "AVAudioSessionInterruptionNotification",
+    AudioSessionMediaServicesWereLost => "AVAudioSessionMediaServicesWereLostNotification",
+    AudioSessionMediaServicesWereReset => "AVAudioSessionMediaServicesWereResetNotification",
+    AudioSessionRouteChange => "AVAudioSessionRouteChangeNotification",
+    AudioSessionSilenceSecondaryAudioHint => "AVAudioSessionSilenceSecondaryAudioHintNotification",
+    AudioUnitComponentTagsDidChange => "AVAudioUnitComponentTagsDidChangeNotification",
+    CloudKitAccountChanged => "CKAccountChangedNotification",
+    CLKComplicationServerActiveComplicationsDidChange => "CLKComplicationServerActiveComplicationsDidChangeNotification",
+    CNContactStoreDidChange => "CNContactStoreDidChangeNotification",
+    EKEventStoreChanged => "EKEventStoreChangedNotification",
+    HKUserPreferencesDidChange => "HKUserPreferencesDidChangeNotification",
+    HMCharacteristicPropertySupportsEvent => "HMCharacteristicPropertySupportsEventNotification",
+    NSBundleResourceRequestLowDiskSpace => "NSBundleResourceRequestLowDiskSpaceNotification",
+    NSCalendarDayChanged => "NSCalendarDayChangedNotification",
+    NSExtensionHostDidBecomeActive => "NSExtensionHostDidBecomeActiveNotification",
+    NSExtensionHostDidEnterBackground => "NSExtensionHostDidEnterBackgroundNotification",
+    NSExtensionHostWillEnterForeground => "NSExtensionHostWillEnterForegroundNotification",
+    NSExtensionHostWillResignActive => "NSExtensionHostWillResignActiveNotification",
+    NSFileHandleConnectionAccepted => "NSFileHandleConnectionAcceptedNotification",
+    NSFileHandleDataAvailable => "NSFileHandleDataAvailableNotification",
+    NSFileHandleReadToEndOfFileCompletion => "NSFileHandleReadToEndOfFileCompletionNotification",
+    NSHTTPCookieManagerAcceptPolicyChanged => "NSHTTPCookieManagerAcceptPolicyChangedNotification",
+    NSHTTPCookieManagerCookiesChanged => "NSHTTPCookieManagerCookiesChangedNotification",
+    NSManagedObjectContextDidSave => "NSManagedObjectContextDidSaveNotification",
+    NSManagedObjectContextObjectsDidChange => "NSManagedObjectContextObjectsDidChangeNotification",
+    NSManagedObjectContextWillSave => "NSManagedObjectContextWillSaveNotification",
+    NSMetadataQueryDidFinishGathering => "NSMetadataQueryDidFinishGatheringNotification",
+    NSMetadataQueryDidStartGathering => "NSMetadataQueryDidStartGatheringNotification",
+    NSMetadataQueryDidUpdate => "NSMetadataQueryDidUpdateNotification",
+    NSMetadataQueryGatheringProgress => "NSMetadataQueryGatheringProgressNotification",
+    NSPersistentStoreCoordinatorStoresDidChange => "NSPersistentStoreCoordinatorStoresDidChangeNotification",
+    NSPersistentStoreCoordinatorStoresWillChange => "NSPersistentStoreCoordinatorStoresWillChangeNotification",
+    NSPersistentStoreCoordinatorWillRemoveStore => "NSPersistentStoreCoordinatorWillRemoveStoreNotification",
+    NSProcessInfoPowerStateDidChange => "NSProcessInfoPowerStateDidChangeNotification",
+    NSSystemClockDidChange => "NSSystemClockDidChangeNotification",
+    NSSystemTimeZoneDidChange => "NSSystemTimeZoneDidChangeNotification",
+    NSURLCredentialStorageChanged => "NSURLCredentialStorageChangedNotification",
+    NSUbiquityIdentityDidChange => "NSUbiquityIdentityDidChangeNotification",
+    NSUndoManagerCheckpoint => "NSUndoManagerCheckpointNotification",
+    NSUndoManagerDidCloseUndoGroup => "NSUndoManagerDidCloseUndoGroupNotification",
+    NSUndoManagerDidOpenUndoGroup => "NSUndoManagerDidOpenUndoGroupNotification",
+    NSUndoManagerDidRedoChange => "NSUndoManagerDidRedoChangeNotification",
+    NSUndoManagerDidUndoChange => "NSUndoManagerDidUndoChangeNotification",
+    NSUndoManagerWillCloseUndoGroup => "NSUndoManagerWillCloseUndoGroupNotification",
+    NSUndoManagerWillRedoChange => "NSUndoManagerWillRedoChangeNotification",
+    NSUndoManagerWillUndoChange => "NSUndoManagerWillUndoChangeNotification",
+    PKPassLibraryDidChange => "PKPassLibraryDidChangeNotification",
+    PKPassLibraryRemotePaymentPassesDidChange => "PKPassLibraryRemotePaymentPassesDidChangeNotification",
+    UIAccessibilityAnnouncementDidFinish => "UIAccessibilityAnnouncementDidFinishNotification",
+    UIAccessibilityElementFocused => "UIAccessibilityElementFocusedNotification",
+    WKAudioFilePlayerItemDidPlayToEndTime => "WKAudioFilePlayerItemDidPlayToEndTimeNotification",
+    WKAudioFilePlayerItemFailedToPlayToEndTime => "WKAudioFilePlayerItemFailedToPlayToEndTimeNotification",
+    WKAudioFilePlayerItemTimeJumped => "WKAudioFilePlayerItemTimeJumpedNotification",
+    ABPeoplePickerDisplayedPropertyDidChange => "ABPeoplePickerDisplayedPropertyDidChangeNotification",
+    ABPeoplePickerGroupSelectionDidChange => "ABPeoplePickerGroupSelectionDidChangeNotification",
+    ABPeoplePickerNameSelectionDidChange => "ABPeoplePickerNameSelectionDidChangeNotification",
+    ABPeoplePickerValueSelectionDidChange => "ABPeoplePickerValueSelectionDidChangeNotification",
+    ACAccountStoreDidChange => "ACAccountStoreDidChangeNotification",
+    AVAssetChapterMetadataGroupsDidChange => "AVAssetChapterMetadataGroupsDidChangeNotification",
+    AVAssetContainsFragmentsDidChange => "AVAssetContainsFragmentsDidChangeNotification",
+    AVAssetDurationDidChange => "AVAssetDurationDidChangeNotification",
+    AVAssetMediaSelectionGroupsDidChange => "AVAssetMediaSelectionGroupsDidChangeNotification",
+    AVAssetTrackSegmentsDidChange => "AVAssetTrackSegmentsDidChangeNotification",
+    AVAssetTrackTimeRangeDidChange => "AVAssetTrackTimeRangeDidChangeNotification",
+    AVAssetTrackTrackAssociationsDidChange => "AVAssetTrackTrackAssociationsDidChangeNotification",
+    AVAssetWasDefragmented => "AVAssetWasDefragmentedNotification",
+    AVCaptureDeviceWasConnected => "AVCaptureDeviceWasConnectedNotification",
+    AVCaptureDeviceWasDisconnected => "AVCaptureDeviceWasDisconnectedNotification",
+    AVCaptureInputPortFormatDescriptionDidChange => "AVCaptureInputPortFormatDescriptionDidChangeNotification",
+    AVCaptureSessionDidStartRunning => "AVCaptureSessionDidStartRunningNotification",
+    AVCaptureSessionDidStopRunning => "AVCaptureSessionDidStopRunningNotification",
+    AVCaptureSessionRuntimeError => "AVCaptureSessionRuntimeErrorNotification",
+    AVFragmentedMovieContainsMovieFragmentsDidChange => "AVFragmentedMovieContainsMovieFragmentsDidChangeNotification",
+    AVFragmentedMovieDurationDidChange => "AVFragmentedMovieDurationDidChangeNotification",
+    AVFragmentedMovieTrackSegmentsDidChange => "AVFragmentedMovieTrackSegmentsDidChangeNotification",
+    AVFragmentedMovieTrackTimeRangeDidChange => "AVFragmentedMovieTrackTimeRangeDidChangeNotification",
+    AVFragmentedMovieTrackTotalSampleDataLengthDidChange => "AVFragmentedMovieTrackTotalSampleDataLengthDidChangeNotification",
+    AVFragmentedMovieWasDefragmented => "AVFragmentedMovieWasDefragmentedNotification",
+    AVPlayerItemDidPlayToEndTime => "AVPlayerItemDidPlayToEndTimeNotification",
+    AVPlayerItemFailedToPlayToEndTime => "AVPlayerItemFailedToPlayToEndTimeNotification",
+    AVPlayerItemNewAccessLogEntry => "AVPlayerItemNewAccessLogEntryNotification",
+    AVPlayerItemNewErrorLogEntry => "AVPlayerItemNewErrorLogEntryNotification",
+    AVPlayerItemPlaybackStalled => "AVPlayerItemPlaybackStalledNotification",
+    AVPlayerItemTimeJumped => "AVPlayerItemTimeJumpedNotification",
+    AVSampleBufferDisplayLayerFailedToDecode => "AVSampleBufferDisplayLayerFailedToDecodeNotification",
+    CWBSSIDDidChange => "CWBSSIDDidChangeNotification",
+    CWCountryCodeDidChange => "CWCountryCodeDidChangeNotification",
+    CWLinkDidChange => "CWLinkDidChangeNotification",
+    CWLinkQualityDidChange => "CWLinkQualityDidChangeNotification",
+    CWModeDidChange => "CWModeDidChangeNotification",
+    CWPowerDidChange => "CWPowerDidChangeNotification",
+    CWSSIDDidChange => "CWSSIDDidChangeNotification",
+    CWScanCacheDidUpdate => "CWScanCacheDidUpdateNotification",
+    GCControllerDidConnect => "GCControllerDidConnectNotification",
+    GCControllerDidDisconnect => "GCControllerDidDisconnectNotification",
+    IKFilterBrowserFilterDoubleClick => "IKFilterBrowserFilterDoubleClickNotification",
+    IKFilterBrowserFilterSelected => "IKFilterBrowserFilterSelectedNotification",
+    IKFilterBrowserWillPreviewFilter => "IKFilterBrowserWillPreviewFilterNotification",
+    IOBluetoothHostControllerPoweredOff => "IOBluetoothHostControllerPoweredOffNotification",
+    IOBluetoothHostControllerPoweredOn => "IOBluetoothHostControllerPoweredOnNotification",
+    IOBluetoothL2CAPChannelPublished => "IOBluetoothL2CAPChannelPublishedNotification",
+    IOBluetoothL2CAPChannelTerminated => "IOBluetoothL2CAPChannelTerminatedNotification",
+    MKAnnotationCalloutInfoDidChange => "MKAnnotationCalloutInfoDidChangeNotification",
+    NEFilterConfigurationDidChange => "NEFilterConfigurationDidChangeNotification",
+    NEVPNConfigurationChange => "NEVPNConfigurationChangeNotification",
+    NEVPNStatusDidChange => "NEVPNStatusDidChangeNotification",
+    NSAccessibilityAnnouncementRequested => "NSAccessibilityAnnouncementRequestedNotification",
+    NSAccessibilityAnnouncementKey => "NSAccessibilityAnnouncementKey",
+    NSAccessibilityPriorityKey => "NSAccessibilityPriorityKey",
+    NSAccessibilityApplicationActivated => "NSAccessibilityApplicationActivatedNotification",
+    NSAccessibilityApplicationDeactivated => "NSAccessibilityApplicationDeactivatedNotification",
+    NSAccessibilityApplicationHidden => "NSAccessibilityApplicationHiddenNotification",
+    NSAccessibilityApplicationShown => "NSAccessibilityApplicationShownNotification",
+    NSAccessibilityCreated => "NSAccessibilityCreatedNotification",
+    NSAccessibilityDrawerCreated => "NSAccessibilityDrawerCreatedNotification",
+    NSAccessibilityFocusedUIElementChanged => "NSAccessibilityFocusedUIElementChangedNotification",
+    NSAccessibilityFocusedWindowChanged => "NSAccessibilityFocusedWindowChangedNotification",
+    NSAccessibilityHelpTagCreated => "NSAccessibilityHelpTagCreatedNotification",
+    NSAccessibilityLayoutChanged => "NSAccessibilityLayoutChangedNotification",
+    NSAccessibilityUIElementsKey => "NSAccessibilityUIElementsKey",
+    NSAccessibilityMainWindowChanged => "NSAccessibilityMainWindowChangedNotification",
+    NSAccessibilityMoved => "NSAccessibilityMovedNotification",
+    NSAccessibilityResized => "NSAccessibilityResizedNotification",
+    NSAccessibilityRowCollapsed => "NSAccessibilityRowCollapsedNotification",
+    NSAccessibilityRowCountChanged => "NSAccessibilityRowCountChangedNotification",
+    NSAccessibilityRowExpanded => "NSAccessibilityRowExpandedNotification",
+    NSAccessibilitySelectedCellsChanged => "NSAccessibilitySelectedCellsChangedNotification",
+    NSAccessibilitySelectedChildrenChanged => "NSAccessibilitySelectedChildrenChangedNotification",
+    NSAccessibilitySelectedChildrenMoved => "NSAccessibilitySelectedChildrenMovedNotification",
+    NSAccessibilitySelectedColumnsChanged => "NSAccessibilitySelectedColumnsChangedNotification",
+    NSAccessibilitySelectedRowsChanged => "NSAccessibilitySelectedRowsChangedNotification",
+    NSAccessibilitySelectedTextChanged => "NSAccessibilitySelectedTextChangedNotification",
+    NSAccessibilitySheetCreated => "NSAccessibilitySheetCreatedNotification",
+    NSAccessibilityTitleChanged => "NSAccessibilityTitleChangedNotification",
+    NSAccessibilityUIElementDestroyed => "NSAccessibilityUIElementDestroyedNotification",
+    NSAccessibilityUnitsChanged => "NSAccessibilityUnitsChangedNotification",
+    NSAccessibilityValueChanged => "NSAccessibilityValueChangedNotification",
+    NSAccessibilityWindowCreated => "NSAccessibilityWindowCreatedNotification",
+    NSAccessibilityWindowDeminiaturized => "NSAccessibilityWindowDeminiaturizedNotification",
+    NSAccessibilityWindowMiniaturized => "NSAccessibilityWindowMiniaturizedNotification",
+    NSAccessibilityWindowMoved => "NSAccessibilityWindowMovedNotification",
+    NSAccessibilityWindowResized => "NSAccessibilityWindowResizedNotification",
+    NSAnimationProgressMark => "NSAnimationProgressMarkNotification",
+    NSAntialiasThresholdChanged => "NSAntialiasThresholdChangedNotification",
+    NSAppleEventManagerWillProcessFirstEvent => "NSAppleEventManagerWillProcessFirstEventNotification",
+    NSApplicationDidBecomeActive => "NSApplicationDidBecomeActiveNotification",
+    NSApplicationDidChangeOcclusionState => "NSApplicationDidChangeOcclusionStateNotification",
+    NSApplicationDidChangeScreenParameters => "NSApplicationDidChangeScreenParametersNotification",
+    NSApplicationDidFinishLaunching => "NSApplicationDidFinishLaunchingNotification",
+    NSApplicationDidFinishRestoringWindows => "NSApplicationDidFinishRestoringWindowsNotification",
+    NSApplicationDidHide => "NSApplicationDidHideNotification",
+    NSApplicationDidResignActive => "NSApplicationDidResignActiveNotification",
+    NSApplicationDidUnhide => "NSApplicationDidUnhideNotification",
+    NSApplicationDidUpdate => "NSApplicationDidUpdateNotification",
+    NSApplicationWillBecomeActive => "NSApplicationWillBecomeActiveNotification",
+    NSApplicationWillFinishLaunching => "NSApplicationWillFinishLaunchingNotification",
+    NSApplicationWillHide => "NSApplicationWillHideNotification",
+    NSApplicationWillResignActive => "NSApplicationWillResignActiveNotification",
+    NSApplicationWillTerminate => "NSApplicationWillTerminateNotification",
+    NSApplicationWillUnhide => "NSApplicationWillUnhideNotification",
+    NSApplicationWillUpdate => "NSApplicationWillUpdateNotification",
+    NSBrowserColumnConfigurationDidChange => "NSBrowserColumnConfigurationDidChangeNotification",
+    NSClassDescriptionNeededForClass => "NSClassDescriptionNeededForClassNotification",
+    NSColorListDidChange => "NSColorListDidChangeNotification",
+    NSColorPanelColorDidChange => "NSColorPanelColorDidChangeNotification",
+    NSComboBoxSelectionDidChange => "NSComboBoxSelectionDidChangeNotification",
+    NSComboBoxSelectionIsChanging => "NSComboBoxSelectionIsChangingNotification",
+    NSComboBoxWillDismiss => "NSComboBoxWillDismissNotification",
+    NSComboBoxWillPopUp => "NSComboBoxWillPopUpNotification",
+    NSContextHelpModeDidActivate => "NSContextHelpModeDidActivateNotification",
+    NSContextHelpModeDidDeactivate => "NSContextHelpModeDidDeactivateNotification",
+    NSControlTextDidBeginEditing => "NSControlTextDidBeginEditingNotification",
+    NSControlTextDidChange => "NSControlTextDidChangeNotification",
+    NSControlTextDidEndEditing => "NSControlTextDidEndEditingNotification",
+    NSControlTintDidChange => "NSControlTintDidChangeNotification",
+    NSDrawerDidClose => "NSDrawerDidCloseNotification",
+    NSDrawerDidOpen => "NSDrawerDidOpenNotification",
+    NSDrawerWillClose => "NSDrawerWillCloseNotification",
+    NSDrawerWillOpen => "NSDrawerWillOpenNotification",
+    NSFontCollectionDidChange => "NSFontCollectionDidChangeNotification",
+    NSFontSetChanged => "NSFontSetChangedNotification",
+    NSImageRepRegistryDidChange => "NSImageRepRegistryDidChangeNotification",
+    NSMenuDidAddItem => "NSMenuDidAddItemNotification",
+    NSMenuDidBeginTracking => "NSMenuDidBeginTrackingNotification",
+    NSMenuDidChangeItem => "NSMenuDidChangeItemNotification",
+    NSMenuDidEndTracking => "NSMenuDidEndTrackingNotification",
+    NSMenuDidRemoveItem => "NSMenuDidRemoveItemNotification",
+    NSMenuDidSendAction => "NSMenuDidSendActionNotification",
+    NSMenuWillSendAction => "NSMenuWillSendActionNotification",
+    NSOutlineViewColumnDidMove => "NSOutlineViewColumnDidMoveNotification",
+    NSOutlineViewColumnDidResize => "NSOutlineViewColumnDidResizeNotification",
+    NSOutlineViewItemDidCollapse => "NSOutlineViewItemDidCollapseNotification",
+    NSOutlineViewItemDidExpand => "NSOutlineViewItemDidExpandNotification",
+    NSOutlineViewItemWillCollapse => "NSOutlineViewItemWillCollapseNotification",
+    NSOutlineViewItemWillExpand => "NSOutlineViewItemWillExpandNotification",
+    NSOutlineViewSelectionDidChange => "NSOutlineViewSelectionDidChangeNotification",
+    NSOutlineViewSelectionIsChanging => "NSOutlineViewSelectionIsChangingNotification",
+    NSPersistentStoreDidImportUbiquitousContentChanges => "NSPersistentStoreDidImportUbiquitousContentChangesNotification",
+    NSPopUpButtonCellWillPopUp => "NSPopUpButtonCellWillPopUpNotification",
+    NSPopUpButtonWillPopUp => "NSPopUpButtonWillPopUpNotification",
+    NSPopoverDidClose => "NSPopoverDidCloseNotification",
+    NSPopoverDidShow => "NSPopoverDidShowNotification",
+    NSPopoverWillClose => "NSPopoverWillCloseNotification",
+    NSPopoverWillShow => "NSPopoverWillShowNotification",
+    NSPreferencePaneCancelUnselect => "NSPreferencePaneCancelUnselectNotification",
+    NSPreferencePaneDoUnselect => "NSPreferencePaneDoUnselectNotification",
+    NSPreferencePaneSwitchToPane => "NSPreferencePaneSwitchToPaneNotification",
+    NSPreferencePaneUpdateHelpMenu => "NSPreferencePaneUpdateHelpMenuNotification",
+    NSPreferencePrefPaneIsAvailable => "NSPreferencePrefPaneIsAvailableNotification",
+    NSPreferredScrollerStyleDidChange => "NSPreferredScrollerStyleDidChangeNotification",
+    NSRuleEditorRowsDidChange => "NSRuleEditorRowsDidChangeNotification",
+    NSScreenColorSpaceDidChange => "NSScreenColorSpaceDidChangeNotification",
+    NSScrollViewDidEndLiveMagnify => "NSScrollViewDidEndLiveMagnifyNotification",
+    NSScrollViewDidEndLiveScroll => "NSScrollViewDidEndLiveScrollNotification",
+    NSScrollViewDidLiveScroll => "NSScrollViewDidLiveScrollNotification",
+    NSScrollViewWillStartLiveMagnify => "NSScrollViewWillStartLiveMagnifyNotification",
+    NSScrollViewWillStartLiveScroll => "NSScrollViewWillStartLiveScrollNotification",
+    NSSpellCheckerDidChangeAutomaticCapitalization => "NSSpellCheckerDidChangeAutomaticCapitalizationNotification",
+    NSSpellCheckerDidChangeAutomaticDashSubstitution => "NSSpellCheckerDidChangeAutomaticDashSubstitutionNotification",
+    NSSpellCheckerDidChangeAutomaticPeriodSubstitution => "NSSpellCheckerDidChangeAutomaticPeriodSubstitutionNotification",
+    NSSpellCheckerDidChangeAutomaticQuoteSubstitution => "NSSpellCheckerDidChangeAutomaticQuoteSubstitutionNotification",
+    NSSpellCheckerDidChangeAutomaticSpellingCorrection => "NSSpellCheckerDidChangeAutomaticSpellingCorrectionNotification",
+    NSSpellCheckerDidChangeAutomaticTextReplacement => "NSSpellCheckerDidChangeAutomaticTextReplacementNotification",
+    NSSplitViewDidResizeSubviews => "NSSplitViewDidResizeSubviewsNotification",
+    NSSplitViewWillResizeSubviews => "NSSplitViewWillResizeSubviewsNotification",
+    NSSystemColorsDidChange => "NSSystemColorsDidChangeNotification",
+    NSTableViewColumnDidMove => "NSTableViewColumnDidMoveNotification",
+    NSTableViewColumnDidResize => "NSTableViewColumnDidResizeNotification",
+    NSTableViewSelectionDidChange => "NSTableViewSelectionDidChangeNotification",
+    NSTableViewSelectionIsChanging => "NSTableViewSelectionIsChangingNotification",
+    NSTextAlternativesSelectedAlternativeString => "NSTextAlternativesSelectedAlternativeStringNotification",
+    NSTextDidBeginEditing => "NSTextDidBeginEditingNotification",
+    NSTextDidChange => "NSTextDidChangeNotification",
+    NSTextDidEndEditing => "NSTextDidEndEditingNotification",
+    NSTextInputContextKeyboardSelectionDidChange => "NSTextInputContextKeyboardSelectionDidChangeNotification",
+    NSTextStorageDidProcessEditing => "NSTextStorageDidProcessEditingNotification",
+    NSTextStorageWillProcessEditing => "NSTextStorageWillProcessEditingNotification",
+    NSTextViewDidChangeSelection => "NSTextViewDidChangeSelectionNotification",
+    NSTextViewDidChangeTypingAttributes => "NSTextViewDidChangeTypingAttributesNotification",
+    NSTextViewWillChangeNotifyingTextView => "NSTextViewWillChangeNotifyingTextViewNotification",
+    NSToolbarDidRemoveItem => "NSToolbarDidRemoveItemNotification",
+    NSToolbarWillAddItem => "NSToolbarWillAddItemNotification",
+    NSViewBoundsDidChange => "NSViewBoundsDidChangeNotification",
+    NSViewDidUpdateTrackingAreas => "NSViewDidUpdateTrackingAreasNotification",
+    NSViewFocusDidChange => "NSViewFocusDidChangeNotification",
+    NSViewFrameDidChange => "NSViewFrameDidChangeNotification",
+    NSViewGlobalFrameDidChange => "NSViewGlobalFrameDidChangeNotification",
+    NSWindowDidBecomeKey => "NSWindowDidBecomeKeyNotification",
+    NSWindowDidBecomeMain => "NSWindowDidBecomeMainNotification",
+    NSWindowDidChangeBackingProperties => "NSWindowDidChangeBackingPropertiesNotification",
+    NSWindowDidChangeOcclusionState => "NSWindowDidChangeOcclusionStateNotification",
+    NSWindowDidChangeScreen => "NSWindowDidChangeScreenNotification",
+    NSWindowDidChangeScreenProfile => "NSWindowDidChangeScreenProfileNotification",
+    NSWindowDidDeminiaturize => "NSWindowDidDeminiaturizeNotification",
+    NSWindowDidEndLiveResize => "NSWindowDidEndLiveResizeNotification",
+    NSWindowDidEndSheet => "NSWindowDidEndSheetNotification",
+    NSWindowDidEnterFullScreen => "NSWindowDidEnterFullScreenNotification",
+    NSWindowDidEnterVersionBrowser => "NSWindowDidEnterVersionBrowserNotification",
+    NSWindowDidExitFullScreen => "NSWindowDidExitFullScreenNotification",
+    NSWindowDidExitVersionBrowser => "NSWindowDidExitVersionBrowserNotification",
+    NSWindowDidExpose => "NSWindowDidExposeNotification",
+    NSWindowDidMiniaturize => "NSWindowDidMiniaturizeNotification",
+    NSWindowDidMove => "NSWindowDidMoveNotification",
+    NSWindowDidResignKey => "NSWindowDidResignKeyNotification",
+    NSWindowDidResignMain => "NSWindowDidResignMainNotification",
+    NSWindowDidResize => "NSWindowDidResizeNotification",
+    NSWindowDidUpdate => "NSWindowDidUpdateNotification",
+    NSWindowWillBeginSheet => "NSWindowWillBeginSheetNotification",
+    NSWindowWillClose => "NSWindowWillCloseNotification",
+    NSWindowWillEnterFullScreen => "NSWindowWillEnterFullScreenNotification",
+    NSWindowWillEnterVersionBrowser => "NSWindowWillEnterVersionBrowserNotification",
+    NSWindowWillExitFullScreen => "NSWindowWillExitFullScreenNotification",
+    NSWindowWillExitVersionBrowser => "NSWindowWillExitVersionBrowserNotification",
+    NSWindowWillMiniaturize => "NSWindowWillMiniaturizeNotification",
+    NSWindowWillMove => "NSWindowWillMoveNotification",
+    NSWindowWillStartLiveResize => "NSWindowWillStartLiveResizeNotification",
+    NSWorkspaceAccessibilityDisplayOptionsDidChange => "NSWorkspaceAccessibilityDisplayOptionsDidChangeNotification",
+    NSWorkspaceActiveSpaceDidChange => "NSWorkspaceActiveSpaceDidChangeNotification",
+    NSWorkspaceDidActivateApplication => "NSWorkspaceDidActivateApplicationNotification",
+    NSWorkspaceDidChangeFileLabels => "NSWorkspaceDidChangeFileLabelsNotification",
+    NSWorkspaceDidDeactivateApplication => "NSWorkspaceDidDeactivateApplicationNotification",
+    NSWorkspaceDidHideApplication => "NSWorkspaceDidHideApplicationNotification",
+    NSWorkspaceDidLaunchApplication => "NSWorkspaceDidLaunchApplicationNotification",
+    NSWorkspaceDidMount => "NSWorkspaceDidMountNotification",
+    NSWorkspaceDidPerformFileOperation => "NSWorkspaceDidPerformFileOperationNotification",
+    NSWorkspaceDidRenameVolume => "NSWorkspaceDidRenameVolumeNotification",
+    NSWorkspaceDidTerminateApplication => "NSWorkspaceDidTerminateApplicationNotification",
+    NSWorkspaceDidUnhideApplication => "NSWorkspaceDidUnhideApplicationNotification",
+    NSWorkspaceDidUnmount => "NSWorkspaceDidUnmountNotification",
+    NSWorkspaceDidWake => "NSWorkspaceDidWakeNotification",
+    NSWorkspaceScreensDidSleep => "NSWorkspaceScreensDidSleepNotification",
+    NSWorkspaceScreensDidWake => "NSWorkspaceScreensDidWakeNotification",
+    NSWorkspaceSessionDidBecomeActive => "NSWorkspaceSessionDidBecomeActiveNotification",
+    NSWorkspaceSessionDidResignActive => "NSWorkspaceSessionDidResignActiveNotification",
+    NSWorkspaceWillLaunchApplication => "NSWorkspaceWillLaunchApplicationNotification",
+    NSWorkspaceWillPowerOff => "NSWorkspaceWillPowerOffNotification",
+    NSWorkspaceWillSleep => "NSWorkspaceWillSleepNotification",
+    NSWorkspaceWillUnmount => "NSWorkspaceWillUnmountNotification",
+    PDFDocumentDidBeginFind => "PDFDocumentDidBeginFindNotification",
+    PDFDocumentDidBeginPageFind => "PDFDocumentDidBeginPageFindNotification",
+    PDFDocumentDidBeginPageWrite => "PDFDocumentDidBeginPageWriteNotification",
+    PDFDocumentDidBeginWrite => "PDFDocumentDidBeginWriteNotification",
+    PDFDocumentDidEndFind => "PDFDocumentDidEndFindNotification",
+    PDFDocumentDidEndPageFind => "PDFDocumentDidEndPageFindNotification",
+    PDFDocumentDidEndPageWrite => "PDFDocumentDidEndPageWriteNotification",
+    PDFDocumentDidEndWrite => "PDFDocumentDidEndWriteNotification",
+    PDFDocumentDidFindMatch => "PDFDocumentDidFindMatchNotification",
+    PDFDocumentDidUnlock => "PDFDocumentDidUnlockNotification",
+    PDFThumbnailViewDocumentEdited => "PDFThumbnailViewDocumentEditedNotification",
+    PDFViewAnnotationHit => "PDFViewAnnotationHitNotification",
+    PDFViewAnnotationWillHit => "PDFViewAnnotationWillHitNotification",
+    PDFViewChangedHistory => "PDFViewChangedHistoryNotification",
+    PDFViewCopyPermission => "PDFViewCopyPermissionNotification",
+    PDFViewDisplayBoxChanged => "PDFViewDisplayBoxChangedNotification",
+    PDFViewDisplayModeChanged => "PDFViewDisplayModeChangedNotification",
+    PDFViewDocumentChanged => "PDFViewDocumentChangedNotification",
+    PDFViewPageChanged => "PDFViewPageChangedNotification",
+    PDFViewPrintPermission => "PDFViewPrintPermissionNotification",
+    PDFViewScaleChanged => "PDFViewScaleChangedNotification",
+    PDFViewSelectionChanged => "PDFViewSelectionChangedNotification",
+    PDFViewVisiblePagesChanged => "PDFViewVisiblePagesChangedNotification",
+    KABDatabaseChanged => "kABDatabaseChangedNotification",
+    KABDatabaseChangedExternally => "kABDatabaseChangedExternallyNotification",
+    KQuartzFilterManagerDidAddFilter => "kQuartzFilterManagerDidAddFilterNotification",
+    KQuartzFilterManagerDidModifyFilter => "kQuartzFilterManagerDidModifyFilterNotification",
+    KQuartzFilterManagerDidRemoveFilter => "kQuartzFilterManagerDidRemoveFilterNotification",
+    KQuartzFilterManagerDidSelectFilter => "kQuartzFilterManagerDidSelectFilterNotification",
+    EAAccessoryDidConnect => "EAAccessoryDidConnectNotification",
+    EAAccessoryDidDisconnect => "EAAccessoryDidDisconnectNotification",
+    SKCloudServiceCapabilitiesDidChange => "SKCloudServiceCapabilitiesDidChangeNotification",
+    SKStorefrontIdentifierDidChange => "SKStorefrontIdentifierDidChangeNotification",
+    UIAccessibilityAssistiveTouchStatusDidChange => "UIAccessibilityAssistiveTouchStatusDidChangeNotification",
+    UIAccessibilityBoldTextStatusDidChange => "UIAccessibilityBoldTextStatusDidChangeNotification",
+    UIAccessibilityClosedCaptioningStatusDidChange => "UIAccessibilityClosedCaptioningStatusDidChangeNotification",
+    UIAccessibilityDarkerSystemColorsStatusDidChange => "UIAccessibilityDarkerSystemColorsStatusDidChangeNotification",
+    UIAccessibilityGrayscaleStatusDidChange => "UIAccessibilityGrayscaleStatusDidChangeNotification",
+    UIAccessibilityGuidedAccessStatusDidChange => "UIAccessibilityGuidedAccessStatusDidChangeNotification",
+    UIAccessibilityHearingDevicePairedEarDidChange => "UIAccessibilityHearingDevicePairedEarDidChangeNotification",
+    UIAccessibilityInvertColorsStatusDidChange => "UIAccessibilityInvertColorsStatusDidChangeNotification",
+    UIAccessibilityMonoAudioStatusDidChange => "UIAccessibilityMonoAudioStatusDidChangeNotification",
+    UIAccessibilityReduceMotionStatusDidChange => "UIAccessibilityReduceMotionStatusDidChangeNotification",
+    UIAccessibilityReduceTransparencyStatusDidChange => "UIAccessibilityReduceTransparencyStatusDidChangeNotification",
+    UIAccessibilityShakeToUndoDidChange => "UIAccessibilityShakeToUndoDidChangeNotification",
+    UIAccessibilitySpeakScreenStatusDidChange => "UIAccessibilitySpeakScreenStatusDidChangeNotification",
+    UIAccessibilitySpeakSelectionStatusDidChange => "UIAccessibilitySpeakSelectionStatusDidChangeNotification",
+    UIAccessibilitySwitchControlStatusDidChange => "UIAccessibilitySwitchControlStatusDidChangeNotification",
+    UIApplicationDidBecomeActive => "UIApplicationDidBecomeActiveNotification",
+    UIApplicationDidEnterBackground => "UIApplicationDidEnterBackgroundNotification",
+    UIApplicationDidFinishLaunching => "UIApplicationDidFinishLaunchingNotification",
+    UIApplicationDidReceiveMemoryWarning => "UIApplicationDidReceiveMemoryWarningNotification",
+    UIApplicationSignificantTimeChange => "UIApplicationSignificantTimeChangeNotification",
+    UIApplicationUserDidTakeScreenshot => "UIApplicationUserDidTakeScreenshotNotification",
+    UIApplicationWillEnterForeground => "UIApplicationWillEnterForegroundNotification",
+    UIApplicationWillResignActive => "UIApplicationWillResignActiveNotification",
+    UIApplicationWillTerminate => "UIApplicationWillTerminateNotification",
+    UIContentSizeCategoryDidChange => "UIContentSizeCategoryDidChangeNotification",
+    UIDeviceProximityStateDidChange => "UIDeviceProximityStateDidChangeNotification",
+    UIScreenBrightnessDidChange => "UIScreenBrightnessDidChangeNotification",
+    UIScreenDidConnect => "UIScreenDidConnectNotification",
+    UIScreenDidDisconnect => "UIScreenDidDisconnectNotification",
+    UIScreenModeDidChange => "UIScreenModeDidChangeNotification",
+    UITableViewSelectionDidChange => "UITableViewSelectionDidChangeNotification",
+    UITextFieldTextDidBeginEditing => "UITextFieldTextDidBeginEditingNotification",
+    UITextFieldTextDidChange => "UITextFieldTextDidChangeNotification",
+    UITextFieldTextDidEndEditing => "UITextFieldTextDidEndEditingNotification",
+    UITextInputCurrentInputModeDidChange => "UITextInputCurrentInputModeDidChangeNotification",
+    UITextViewTextDidBeginEditing => "UITextViewTextDidBeginEditingNotification",
+    UITextViewTextDidChange => "UITextViewTextDidChangeNotification",
+    UITextViewTextDidEndEditing => "UITextViewTextDidEndEditingNotification",
+    UIViewControllerShowDetailTargetDidChange => "UIViewControllerShowDetailTargetDidChangeNotification",
+    UIWindowDidBecomeHidden => "UIWindowDidBecomeHiddenNotification",
+    UIWindowDidBecomeKey => "UIWindowDidBecomeKeyNotification",
+    UIWindowDidBecomeVisible => "UIWindowDidBecomeVisibleNotification",
+    UIWindowDidResignKey => "UIWindowDidResignKeyNotification",
+    AVCaptureDeviceSubjectAreaDidChange => "AVCaptureDeviceSubjectAreaDidChangeNotification",
+    AVCaptureSessionInterruptionEnded => "AVCaptureSessionInterruptionEndedNotification",
+    AVCaptureSessionWasInterrupted => "AVCaptureSessionWasInterruptedNotification",
+    MFMessageComposeViewControllerTextMessageAvailabilityDidChange => "MFMessageComposeViewControllerTextMessageAvailabilityDidChangeNotification",
+    MPMediaLibraryDidChange => "MPMediaLibraryDidChangeNotification",
+    MPMusicPlayerControllerNowPlayingItemDidChange => "MPMusicPlayerControllerNowPlayingItemDidChangeNotification",
+    MPMusicPlayerControllerPlaybackStateDidChange => "MPMusicPlayerControllerPlaybackStateDidChangeNotification",
+    MPMusicPlayerControllerVolumeDidChange => "MPMusicPlayerControllerVolumeDidChangeNotification",
+    UIApplicationBackgroundRefreshStatusDidChange => "UIApplicationBackgroundRefreshStatusDidChangeNotification",
+    UIDeviceBatteryLevelDidChange => "UIDeviceBatteryLevelDidChangeNotification",
+    UIDeviceBatteryStateDidChange => "UIDeviceBatteryStateDidChangeNotification",
+    UIDeviceOrientationDidChange => "UIDeviceOrientationDidChangeNotification",
+    UIDocumentStateChanged => "UIDocumentStateChangedNotification",
+    UIKeyboardDidChangeFrame => "UIKeyboardDidChangeFrameNotification",
+    UIKeyboardDidHide => "UIKeyboardDidHideNotification",
+    UIKeyboardDidShow => "UIKeyboardDidShowNotification",
+    UIKeyboardWillChangeFrame => "UIKeyboardWillChangeFrameNotification",
+    UIKeyboardWillHide => "UIKeyboardWillHideNotification",
+    UIKeyboardWillShow => "UIKeyboardWillShowNotification",
+    UIMenuControllerDidHideMenu => "UIMenuControllerDidHideMenuNotification",
+    UIMenuControllerDidShowMenu => "UIMenuControllerDidShowMenuNotification",
+    UIMenuControllerMenuFrameDidChange => "UIMenuControllerMenuFrameDidChangeNotification",
+    UIMenuControllerWillHideMenu => "UIMenuControllerWillHideMenuNotification",
+    UIMenuControllerWillShowMenu => "UIMenuControllerWillShowMenuNotification",
+    UIPasteboardChanged => "UIPasteboardChangedNotification",
+    UIPasteboardRemoved => "UIPasteboardRemovedNotification",
+    UIApplicationProtectedDataDidBecomeAvailable => "UIApplicationProtectedDataDidBecomeAvailableNotification",
+    UIApplicationProtectedDataWillBecomeUnavailable => "UIApplicationProtectedDataWillBecomeUnavailableNotification",
+    NSSpellCheckerDidChangeAutomaticTextCompletion => "NSSpellCheckerDidChangeAutomaticTextCompletionNotification",
+    MPMusicPlayerControllerQueueDidChange => "MPMusicPlayerControllerQueueDidChangeNotification",
+    AVDisplayManagerModeSwitchEnd => "AVDisplayManagerModeSwitchEndNotification",
+    AVDisplayManagerModeSwitchSettingsChanged => "AVDisplayManagerModeSwitchSettingsChangedNotification",
+    AVDisplayManagerModeSwitchStart => "AVDisplayManagerModeSwitchStartNotification",
+    AVPlayerAvailableHDRModesDidChange => "AVPlayerAvailableHDRModesDidChangeNotification",
+    AVRouteDetectorMultipleRoutesDetectedDidChange => "AVRouteDetectorMultipleRoutesDetectedDidChangeNotification",
+    AVSampleBufferAudioRendererWasFlushedAutomatically => "AVSampleBufferAudioRendererWasFlushedAutomaticallyNotification",
+    CTServiceRadioAccessTechnologyDidChange => "CTServiceRadioAccessTechnologyDidChangeNotification",
+    GKPlayerAuthenticationDidChangeNotificationName => "GKPlayerAuthenticationDidChangeNotificationName",
+    GKPlayerDidChangeNotificationName => "GKPlayerDidChangeNotificationName",
+    NEDNSProxyConfigurationDidChange => "NEDNSProxyConfigurationDidChangeNotification",
+    NSPersistentStoreRemoteChange => "NSPersistentStoreRemoteChangeNotification",
+    SKStorefrontCountryCodeDidChange => "SKStorefrontCountryCodeDidChangeNotification",
+    WKAccessibilityReduceMotionStatusDidChange => "WKAccessibilityReduceMotionStatusDidChangeNotification",
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{NotificationName, ALL};
+
+    #[test]
+    fn test_names_round_trip() {
+        for name in ALL {
+            let constant = name.as_str();
+            assert_eq!(name.to_string(), constant);
+            assert_eq!(constant.parse::<NotificationName>().unwrap(), *name);
         }
+
+        let constants: HashSet<_> = ALL.iter().map(|name| name.as_str()).collect();
+        assert_eq!(constants.len(), ALL.len());
+
+        #[allow(deprecated)]
+        let alias = NotificationName::NSColorPanel;
+        assert_eq!(
+            alias.as_str().parse::<NotificationName>().unwrap(),
+            NotificationName::NSColorPanelColorDidChange
+        );
+    }
+
+    #[test]
+    fn test_custom_names() {
+        let name: NotificationName = "com.example.plugin.DidLoad".parse().unwrap();
+        assert_eq!(name, NotificationName::Custom("com.example.plugin.DidLoad".to_string()));
+        assert_eq!(name.to_string(), "com.example.plugin.DidLoad");
+        assert_eq!(name.value(), "com.example.plugin.DidLoad");
+
+        // Parsing always prefers the known variant.
+        let custom = NotificationName::Custom("NSWindowDidMoveNotification".to_string());
+        assert_eq!(
+            custom.to_string().parse::<NotificationName>().unwrap(),
+            NotificationName::NSWindowDidMove
+        );
+    }
+
+    #[test]
+    fn test_constant_names() {
+        assert_eq!(
+            NotificationName::KABDatabaseChanged.as_str(),
+            "kABDatabaseChangedNotification"
+        );
+        assert_eq!(
+            NotificationName::AudioSessionInterruption.as_str(),
+            "AVAudioSessionInterruptionNotification"
+        );
+        assert_eq!(
+            NotificationName::NSManagedObjectContextObjectsDidChange.as_str(),
+            "NSManagedObjectContextObjectsDidChangeNotification"
+        );
+    }
+
+    /// These read the constants out of AppKit, so need it loaded.
+    #[cfg(all(feature = "appkit", not(feature = "gnustep")))]
+    #[test]
+    fn test_runtime_values() {
+        assert_eq!(NotificationName::NSWindowDidMove.value(), "NSWindowDidMoveNotification");
+        assert_eq!(NotificationName::NSAccessibilityMoved.value(), "AXMoved");
     }
 }
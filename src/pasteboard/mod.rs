@@ -21,7 +21,7 @@ use objc_id::ShareId;
 use url::Url;
 
 use crate::error::Error;
-use crate::foundation::{id, nil, NSArray, NSString, NSURL};
+use crate::foundation::{id, nil, to_bool, NSArray, NSString, ObjcValue, BOOL, NSURL};
 
 mod types;
 pub use types::{PasteboardName, PasteboardType};
@@ -67,6 +67,30 @@ impl Pasteboard {
         }
     }
 
+    /// Writes a property list (e.g, an `ObjcValue::Dictionary`) to the pasteboard for the given
+    /// type. Returns `false` if the pasteboard rejected it.
+    ///
+    /// As with `NSPasteboard`, you'll generally want to call `clear_contents()` first.
+    pub fn set_property_list(&self, value: ObjcValue, pasteboard_type: PasteboardType) -> bool {
+        let ptype: NSString = pasteboard_type.into();
+
+        unsafe {
+            let value: id = value.into();
+            let result: BOOL = msg_send![&*self.0, setPropertyList:value forType:&*ptype];
+            to_bool(result)
+        }
+    }
+
+    /// Reads the property list stored on the pasteboard for the given type, if there is one.
+    pub fn property_list(&self, pasteboard_type: PasteboardType) -> Option<ObjcValue> {
+        let ptype: NSString = pasteboard_type.into();
+
+        unsafe {
+            let value: id = msg_send![&*self.0, propertyListForType:&*ptype];
+            ObjcValue::from_object(value)
+        }
+    }
+
     /// Releases the receiver’s resources in the pasteboard server. It's rare-ish to need to use
     /// this, but considering this stuff happens on the Objective-C side you may need it.
     pub fn release_globally(&self) {
@@ -99,11 +123,11 @@ impl Pasteboard {
                 // This error is not necessarily "correct", but in the event of an error in
                 // Pasteboard server retrieval I'm not sure where to check... and this stuff is
                 // kinda ancient and has conflicting docs in places. ;P
-                return Err(Box::new(Error {
-                    code: 666,
-                    domain: "com.cacao-rs.pasteboard".to_string(),
-                    description: "Pasteboard server returned no data.".to_string()
-                }));
+                return Err(Box::new(Error::with_description(
+                    "com.cacao-rs.pasteboard",
+                    666,
+                    "Pasteboard server returned no data."
+                )));
             }
 
             let urls = NSArray::retain(contents).map(|url| NSURL::retain(url)).into_iter().collect();
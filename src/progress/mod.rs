@@ -15,7 +15,7 @@
 //! my_view.add_subview(&indicator);
 //! ```
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::runtime::{Class, Object};
 use objc::{class, msg_send, sel, sel_impl};
@@ -1,12 +1,12 @@
 use std::path::Path;
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
 use crate::foundation::{id, NSString, NSUInteger, YES};
-use crate::utils::CGSize;
+use crate::geometry::Size;
 
 /// Describes the quality of the thumbnail you expect back from the
 /// generator service.
@@ -107,7 +107,7 @@ impl ThumbnailConfig {
         }
 
         unsafe {
-            let size = CGSize::new(self.size.0, self.size.1);
+            let size = Size::new(self.size.0, self.size.1);
             // @TODO: Check nil here, or other bad conversion
             let from_url: id = msg_send![class!(NSURL), fileURLWithPath:&*file];
 
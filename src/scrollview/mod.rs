@@ -294,6 +294,9 @@ impl<T> ScrollView<T> {
     }
 
     /// Call this to set the background color for the backing layer.
+    ///
+    /// Layers take a `CGColor`, so this isn't available on GNUstep.
+    #[cfg(target_vendor = "apple")]
     pub fn set_background_color<C: AsRef<Color>>(&self, color: C) {
         // @TODO: This is wrong.
         self.objc.with_mut(|obj| unsafe {
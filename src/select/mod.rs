@@ -2,7 +2,7 @@
 
 use std::sync::Once;
 
-use core_graphics::geometry::CGRect;
+use crate::core_graphics::geometry::CGRect;
 
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
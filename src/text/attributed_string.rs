@@ -3,7 +3,7 @@ use std::ops::{Deref, DerefMut, Range};
 use std::os::raw::c_char;
 use std::{fmt, slice, str};
 
-use core_foundation::base::CFRange;
+use crate::core_foundation::base::CFRange;
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
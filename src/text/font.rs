@@ -2,7 +2,7 @@
 
 use std::ops::Deref;
 
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::runtime::{Class, Object};
 use objc::{class, msg_send, sel, sel_impl};
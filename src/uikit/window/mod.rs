@@ -1,4 +1,4 @@
-use core_graphics::geometry::CGRect;
+use crate::core_graphics::geometry::CGRect;
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
@@ -2,10 +2,13 @@
 //!
 //! This is primarily used in handling app handoff between devices.
 
+use std::collections::HashMap;
+
 use objc::runtime::Object;
+use objc::{msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
-use crate::foundation::id;
+use crate::foundation::{id, NSString, ObjcValue};
 
 /// Represents an `NSUserActivity`, which acts as a lightweight method to capture
 /// the state of your app.
@@ -17,4 +20,23 @@ impl UserActivity {
     pub(crate) fn with_inner(object: id) -> Self {
         UserActivity(unsafe { ShareId::from_ptr(object) })
     }
+
+    /// The type of this activity (typically a reverse-DNS string declared in your `Info.plist`).
+    pub fn activity_type(&self) -> String {
+        NSString::retain(unsafe { msg_send![&*self.0, activityType] }).to_string()
+    }
+
+    /// The state needed to continue this activity elsewhere. Entries that aren't property list
+    /// types are skipped.
+    pub fn user_info(&self) -> HashMap<String, ObjcValue> {
+        ObjcValue::dictionary_from_object(unsafe { msg_send![&*self.0, userInfo] })
+    }
+
+    /// Replaces the state needed to continue this activity elsewhere.
+    pub fn set_user_info(&self, user_info: HashMap<String, ObjcValue>) {
+        unsafe {
+            let user_info: id = ObjcValue::Dictionary(user_info).into();
+            let _: () = msg_send![&*self.0, setUserInfo: user_info];
+        }
+    }
 }
@@ -2,7 +2,7 @@
 //! belong to. These are typically internal, and if you rely on them... well, don't be surprised if
 //! they go away one day.
 
-use core_graphics::base::CGFloat;
+use std::ffi::CString;
 
 use objc::{class, msg_send, sel, sel_impl};
 
@@ -10,7 +10,8 @@ use objc::runtime::Object;
 use objc::{Encode, Encoding};
 use objc_id::ShareId;
 
-use crate::foundation::{id, BOOL, NO, YES};
+use crate::foundation::{id, NSString, BOOL, NO, YES};
+use crate::geometry::{Point, Rect, Size};
 
 mod cell_factory;
 pub use cell_factory::CellFactory;
@@ -18,6 +19,12 @@ pub use cell_factory::CellFactory;
 pub mod os;
 pub mod properties;
 
+#[cfg(any(feature = "appkit", feature = "uikit", feature = "webview"))]
+pub(crate) mod signatures;
+
+#[cfg(test)]
+pub(crate) mod tempdir;
+
 /// A generic trait that's used throughout multiple different controls in this framework - acts as
 /// a guard for whether something is a (View|Window|etc)Controller.
 pub trait Controller {
@@ -67,39 +74,63 @@ where
     queue.exec_sync(method);
 }
 
-/// Upstream core graphics does not implement Encode for certain things, so we wrap them here -
-/// these are only used in reading certain types passed to us from some delegate methods.
-#[repr(C)]
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
-pub struct CGSize {
-    /// The width of this size.
-    pub width: CGFloat,
+/// Reads the `NSString *` exported under `symbol` (e.g, `NSURLNameKey`), if whatever defines it
+/// is loaded into the process. This is for constants that may not exist on every platform or OS
+/// version we support, which an `extern` static would fail to link (or launch) against.
+pub(crate) fn load_constant(symbol: &str) -> Option<NSString<'static>> {
+    let symbol = CString::new(symbol).ok()?;
 
-    /// The height of this size.
-    pub height: CGFloat
+    unsafe {
+        let address = libc::dlsym(libc::RTLD_DEFAULT, symbol.as_ptr());
+        if address.is_null() {
+            return None;
+        }
+
+        let value = *(address as *const id);
+        match value.is_null() {
+            true => None,
+            false => Some(NSString::retain(value))
+        }
+    }
 }
 
-impl CGSize {
-    /// Create and return a new `CGSize`.
-    pub fn new(width: CGFloat, height: CGFloat) -> Self {
-        CGSize { width, height }
-    }
+/// Upstream core graphics does not implement Encode for certain things, so this used to wrap
+/// `CGSize`. It's now an alias for `geometry::Size`, which can be passed to Objective-C as-is.
+#[deprecated(note = "Use `cacao::geometry::Size` instead")]
+pub type CGSize = crate::geometry::Size;
 
-    /// Create and return a `CGSizeZero` equivalent.
-    pub fn zero() -> Self {
-        CGSize { width: 0., height: 0. }
-    }
+/// Upstream core graphics doesn't implement Encode for `CGRect` either. This has the same layout,
+/// for methods we implement that receive one (e.g, `drawRect:`).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct EncodedRect {
+    pub origin: Point,
+    pub size: Size
 }
 
-unsafe impl Encode for CGSize {
-    /// Adds support for CGSize Objective-C encoding.
+unsafe impl Encode for EncodedRect {
     fn encode() -> Encoding {
-        let encoding = format!("{{CGSize={}{}}}", CGFloat::encode().as_str(), CGFloat::encode().as_str());
+        let encoding = format!("{{CGRect={}{}}}", Point::encode().as_str(), Size::encode().as_str());
 
         unsafe { Encoding::from_str(&encoding) }
     }
 }
 
+impl From<EncodedRect> for Rect {
+    fn from(rect: EncodedRect) -> Rect {
+        Rect::with_origin_and_size(rect.origin, rect.size)
+    }
+}
+
+impl From<Rect> for EncodedRect {
+    fn from(rect: Rect) -> EncodedRect {
+        EncodedRect {
+            origin: rect.origin(),
+            size: rect.size()
+        }
+    }
+}
+
 /// A helper method for ensuring that Cocoa is running in multi-threaded mode.
 ///
 /// Why do we need this? According to Apple, if you're going to make use of standard POSIX threads,
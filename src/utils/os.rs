@@ -10,8 +10,15 @@ lazy_static! {
 
 /// In rare cases we need to check whether something is a specific version of macOS. This is a
 /// runtime check thhat returns a boolean indicating whether the current version is a minimum target.
+///
+/// With the `gnustep` feature this is always `false`: the OS version there is the Linux (or BSD)
+/// release, which says nothing about which macOS APIs exist, so we take the oldest code path.
 #[inline(always)]
 pub fn is_minimum_version(minimum_major: u64) -> bool {
+    if cfg!(feature = "gnustep") {
+        return false;
+    }
+
     match OS_VERSION.version() {
         Version::Semantic(os_major, _, _) => *os_major >= minimum_major,
         _ => false
@@ -20,8 +27,14 @@ pub fn is_minimum_version(minimum_major: u64) -> bool {
 
 /// In rare cases we need to check whether something is a specific version of macOS. This is a
 /// runtime check thhat returns a boolean indicating whether the current version is a minimum target.
+///
+/// As with `is_minimum_version`, this is always `false` with the `gnustep` feature.
 #[inline(always)]
 pub fn is_minimum_semversion(major: u64, minor: u64, patch: u64) -> bool {
+    if cfg!(feature = "gnustep") {
+        return false;
+    }
+
     let target = Version::Semantic(major, minor, patch);
     OS_VERSION.version() > &target
 }
@@ -0,0 +1,58 @@
+//! Signatures for the binary formats we care about, shared by `ImageFormat::sniff()` and
+//! `webview::mimetype::sniff()` so the two can't disagree about what a file is.
+
+/// Bytes expected at an offset from the start of the data.
+type Part = (usize, &'static [u8]);
+
+/// Parts that must all match, and the MIME type they identify. Order matters: the first match
+/// wins, so the specific `ftyp` brands come before the catch-all.
+const SIGNATURES: &[(&[Part], &str)] = &[
+    (&[(0, b"\0asm")], "application/wasm"),
+    (&[(0, b"wOFF")], "font/woff"),
+    (&[(0, b"wOF2")], "font/woff2"),
+    (&[(0, b"OTTO")], "font/otf"),
+    (&[(0, b"\0\x01\0\0")], "font/ttf"),
+    (&[(0, b"ttcf")], "font/collection"),
+    (&[(0, b"\x89PNG\r\n\x1a\n")], "image/png"),
+    (&[(0, b"\xff\xd8\xff")], "image/jpeg"),
+    (&[(0, b"GIF87a")], "image/gif"),
+    (&[(0, b"GIF89a")], "image/gif"),
+    (&[(0, b"II*\0")], "image/tiff"),
+    (&[(0, b"MM\0*")], "image/tiff"),
+    (&[(0, b"BM")], "image/bmp"),
+    (&[(0, b"\0\0\x01\0")], "image/vnd.microsoft.icon"),
+    (&[(0, b"RIFF"), (8, b"WEBP")], "image/webp"),
+    (&[(0, b"RIFF"), (8, b"WAVE")], "audio/wav"),
+    (&[(0, b"RIFF"), (8, b"AVI ")], "video/x-msvideo"),
+    (&[(0, b"\x1a\x45\xdf\xa3")], "video/webm"),
+    (&[(0, b"OggS")], "audio/ogg"),
+    (&[(0, b"fLaC")], "audio/flac"),
+    (&[(0, b"ID3")], "audio/mpeg"),
+    (&[(0, b"\xff\xfb")], "audio/mpeg"),
+    (&[(0, b"\xff\xf3")], "audio/mpeg"),
+    (&[(0, b"\xff\xf2")], "audio/mpeg"),
+    (&[(0, b"%PDF-")], "application/pdf"),
+    (&[(4, b"ftypavif")], "image/avif"),
+    (&[(4, b"ftypavis")], "image/avif"),
+    (&[(4, b"ftypheic")], "image/heic"),
+    (&[(4, b"ftypheix")], "image/heic"),
+    (&[(4, b"ftyphevc")], "image/heic"),
+    (&[(4, b"ftyphevx")], "image/heic"),
+    (&[(4, b"ftypmif1")], "image/heic"),
+    (&[(4, b"ftypmsf1")], "image/heic"),
+    (&[(4, b"ftypM4A ")], "audio/mp4"),
+    (&[(4, b"ftypqt  ")], "video/quicktime"),
+    (&[(4, b"ftyp")], "video/mp4")
+];
+
+/// Returns the MIME type of `data`, if it starts with one of the signatures above.
+pub(crate) fn sniff(data: &[u8]) -> Option<&'static str> {
+    SIGNATURES
+        .iter()
+        .find(|(parts, _)| {
+            parts
+                .iter()
+                .all(|(offset, bytes)| data.get(*offset..offset + bytes.len()) == Some(*bytes))
+        })
+        .map(|(_, mime_type)| *mime_type)
+}
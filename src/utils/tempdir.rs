@@ -0,0 +1,45 @@
+//! A scratch directory for tests that need to touch the file system.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Bumped for every directory, so that tests running in parallel never share one.
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A uniquely named directory under `std::env::temp_dir()`, which is created empty and removed
+/// (along with everything in it) when this is dropped.
+#[derive(Debug)]
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates a new directory, with `name` in its name to make it easy to trace back to a test.
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "cacao-{}-{}-{}",
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("Unable to create a temporary directory");
+        TempDir(path)
+    }
+
+    /// The directory's path.
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Shorthand for `self.path().join(path)`.
+    pub(crate) fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
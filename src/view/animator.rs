@@ -1,4 +1,4 @@
-use core_graphics::base::CGFloat;
+use crate::core_graphics::base::CGFloat;
 
 use objc::runtime::{Class, Object};
 use objc::{msg_send, sel, sel_impl};
@@ -3,7 +3,6 @@
 //! we configure an NSToolbar and WKWebview on top of them.
 
 use std::ffi::c_void;
-use std::ptr::null;
 use std::sync::Once;
 
 use block::Block;
@@ -14,8 +13,10 @@ use objc::{class, msg_send, sel, sel_impl};
 
 use crate::foundation::{id, load_or_register_class, nil, NSArray, NSInteger, NSString, NO, YES};
 use crate::webview::actions::{NavigationAction, NavigationResponse};
-use crate::webview::{mimetype::MimeType, WebViewDelegate, WEBVIEW_DELEGATE_PTR}; //, OpenPanelParameters};
-                                                                                 //use crate::webview::enums::{NavigationPolicy, NavigationResponsePolicy};
+use crate::webview::javascript::{ScriptMessage, ScriptReply};
+use crate::webview::protocol::{stop_task, ProtocolRequest, ProtocolTask};
+use crate::webview::{WebViewDelegate, WEBVIEW_DELEGATE_PTR}; //, OpenPanelParameters};
+                                                             //use crate::webview::enums::{NavigationPolicy, NavigationResponsePolicy};
 use crate::utils::load;
 
 /// Called when an `alert()` from the underlying `WKWebView` is fired. Will call over to your
@@ -46,46 +47,29 @@ extern "C" fn alert<T: WebViewDelegate>(_: &Object, _: Sel, _: id, _: id, _: id,
 /// Fires when a message has been passed from the underlying `WKWebView`.
 extern "C" fn on_message<T: WebViewDelegate>(this: &Object, _: Sel, _: id, script_message: id) {
     let delegate = load::<T>(this, WEBVIEW_DELEGATE_PTR);
+    delegate.on_script_message(ScriptMessage::new(script_message));
+}
 
-    unsafe {
-        let name = NSString::from_retained(msg_send![script_message, name]);
-        let body = NSString::retain(msg_send![script_message, body]);
-        delegate.on_message(name.to_str(), body.to_str());
-    }
+/// Fires when a message has been passed to a handler that can reply.
+extern "C" fn on_message_with_reply<T: WebViewDelegate>(this: &Object, _: Sel, _: id, script_message: id, reply_handler: usize) {
+    let delegate = load::<T>(this, WEBVIEW_DELEGATE_PTR);
+    let reply = ScriptReply::new(reply_handler as *mut Block<(id, id), ()>);
+    delegate.on_message_with_reply(ScriptMessage::new(script_message), reply);
 }
 
 /// Fires when a custom protocol URI is requested from the underlying `WKWebView`.
 extern "C" fn start_url_scheme_task<T: WebViewDelegate>(this: &Object, _: Sel, _webview: id, task: id) {
     let delegate = load::<T>(this, WEBVIEW_DELEGATE_PTR);
 
-    unsafe {
-        let request: id = msg_send![task, request];
-        let url: id = msg_send![request, URL];
-
-        let uri = NSString::from_retained(msg_send![url, absoluteString]);
-        let uri_str = uri.to_str();
-
-        if let Some(content) = delegate.on_custom_protocol_request(uri_str) {
-            let mime = MimeType::parse(&content, uri_str);
-            let nsurlresponse: id = msg_send![class!(NSURLResponse), alloc];
-            let response: id = msg_send![nsurlresponse, initWithURL:url MIMEType:NSString::new(&mime)
-                expectedContentLength:content.len() textEncodingName:null::<c_void>()];
-            let _: () = msg_send![task, didReceiveResponse: response];
-
-            // Send data
-            let bytes = content.as_ptr() as *mut c_void;
-            let data: id = msg_send![class!(NSData), alloc];
-            let data: id = msg_send![data, initWithBytes:bytes length:content.len()];
-            let _: () = msg_send![task, didReceiveData: data];
-
-            // Finish
-            let () = msg_send![task, didFinish];
-        }
-    }
+    let request = ProtocolRequest::new(unsafe { msg_send![task, request] });
+    delegate.on_custom_protocol_task(request, ProtocolTask::new(task));
 }
 
-/// Fires when a custom protocol completed the task from the underlying `WKWebView`.
-extern "C" fn stop_url_scheme_task<T: WebViewDelegate>(_: &Object, _: Sel, _webview: id, _task: id) {}
+/// Fires when the underlying `WKWebView` no longer needs a custom protocol task (e.g, the page
+/// navigated away).
+extern "C" fn stop_url_scheme_task<T: WebViewDelegate>(_: &Object, _: Sel, _webview: id, task: id) {
+    stop_task(task);
+}
 
 /// Fires when deciding a navigation policy - i.e, should something be allowed or not.
 extern "C" fn decide_policy_for_action<T: WebViewDelegate>(this: &Object, _: Sel, _: id, action: id, handler: usize) {
@@ -196,6 +180,12 @@ pub fn register_webview_delegate_class<T: WebViewDelegate>(instance: &T) -> *con
             on_message::<T> as extern "C" fn(&Object, _, _, id)
         );
 
+        // WKScriptMessageHandlerWithReply
+        decl.add_method(
+            sel!(userContentController:didReceiveScriptMessage:replyHandler:),
+            on_message_with_reply::<T> as extern "C" fn(&Object, _, _, id, usize)
+        );
+
         // Custom protocol handler
         decl.add_method(
             sel!(webView:startURLSchemeTask:),
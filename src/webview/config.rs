@@ -14,6 +14,7 @@ use crate::webview::enums::InjectAt;
 pub struct WebViewConfig {
     pub objc: Id<Object>,
     pub handlers: Vec<String>,
+    pub reply_handlers: Vec<String>,
     pub protocols: Vec<String>
 }
 
@@ -28,6 +29,7 @@ impl Default for WebViewConfig {
         WebViewConfig {
             objc: config,
             handlers: vec![],
+            reply_handlers: vec![],
             protocols: vec![]
         }
     }
@@ -40,6 +42,16 @@ impl WebViewConfig {
         self.handlers.push(name.to_string());
     }
 
+    /// Pushes the specified handler name onto the stack, queuing it for initialization with the
+    /// `WKWebView` as a handler that can reply. Messages posted to it are routed to
+    /// `WebViewDelegate::on_message_with_reply`, and `postMessage` returns a promise that settles
+    /// with your reply.
+    ///
+    /// Reply handlers require macOS 11 or iOS 14.
+    pub fn add_reply_handler(&mut self, name: &str) {
+        self.reply_handlers.push(name.to_string());
+    }
+
     /// Adds the given user script to the underlying `WKWebView` user content controller.
     pub fn add_user_script(&mut self, script: &str, at: InjectAt, main_frame_only: bool) {
         let source = NSString::new(script);
@@ -0,0 +1,327 @@
+//! Types for talking to JavaScript running in a `WebView`: the result of evaluating a script,
+//! messages posted from the page, and replies to them.
+//!
+//! Values cross the bridge as `ObjcValue`s - WebKit converts JavaScript strings, numbers,
+//! booleans, `Date`s, arrays, plain objects and `null` into their Foundation equivalents (and
+//! back). With the `serde` feature enabled, you can skip that step and work with your own types;
+//! the mapping follows `defaults::from_value`/`defaults::to_value`, except that `null` (at the top
+//! level, or inside arrays and objects) becomes `None` (or `()`), and vice versa.
+
+use std::error;
+use std::fmt;
+
+use block::{Block, RcBlock};
+use objc::runtime::BOOL;
+use objc::{msg_send, sel, sel_impl};
+
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::defaults::ConversionError;
+
+use crate::error::Error;
+use crate::foundation::{id, nil, to_bool, NSInteger, NSString, ObjcValue};
+
+#[cfg(feature = "serde")]
+use crate::foundation::to_objc_value;
+
+/// `WKErrorDomain`.
+pub const WK_ERROR_DOMAIN: &str = "WKErrorDomain";
+
+/// `WKErrorJavaScriptExceptionOccurred`.
+const JAVASCRIPT_EXCEPTION_OCCURRED: NSInteger = 4;
+
+/// `WKErrorJavaScriptResultTypeIsUnsupported`.
+const JAVASCRIPT_RESULT_TYPE_IS_UNSUPPORTED: NSInteger = 5;
+
+/// Why evaluating JavaScript failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JavaScriptError {
+    /// The script threw.
+    Exception {
+        /// The exception's message, e.g `"ReferenceError: Can't find variable: foo"`.
+        message: String,
+
+        /// The line the exception was thrown from, if known.
+        line: Option<i64>,
+
+        /// The column the exception was thrown from, if known.
+        column: Option<i64>,
+
+        /// The URL of the script that threw, if known.
+        source_url: Option<String>
+    },
+
+    /// The script ran, but its result can't be converted - e.g, it was a function or a DOM node.
+    UnsupportedResultType,
+
+    /// The result couldn't be deserialized into the type you asked for.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    Conversion(ConversionError),
+
+    /// Anything else WebKit reported (e.g, the web content process went away).
+    Other(Error)
+}
+
+impl From<Error> for JavaScriptError {
+    fn from(error: Error) -> Self {
+        if error.domain != WK_ERROR_DOMAIN {
+            return JavaScriptError::Other(error);
+        }
+
+        let integer = |key: &str| match error.user_info.get(key) {
+            Some(ObjcValue::Integer(i)) => Some(*i),
+            Some(ObjcValue::UnsignedInteger(u)) => Some(*u as i64),
+            _ => None
+        };
+
+        let string = |key: &str| match error.user_info.get(key) {
+            Some(ObjcValue::String(s)) => Some(s.clone()),
+            _ => None
+        };
+
+        match error.code {
+            JAVASCRIPT_EXCEPTION_OCCURRED => JavaScriptError::Exception {
+                message: string("WKJavaScriptExceptionMessage").unwrap_or_else(|| error.description.clone()),
+                line: integer("WKJavaScriptExceptionLineNumber"),
+                column: integer("WKJavaScriptExceptionColumnNumber"),
+                source_url: string("WKJavaScriptExceptionSourceURL")
+            },
+
+            JAVASCRIPT_RESULT_TYPE_IS_UNSUPPORTED => JavaScriptError::UnsupportedResultType,
+            _ => JavaScriptError::Other(error)
+        }
+    }
+}
+
+impl fmt::Display for JavaScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JavaScriptError::Exception {
+                message, line, column, ..
+            } => match (line, column) {
+                (Some(line), Some(column)) => write!(f, "{} (line {}, column {})", message, line, column),
+                _ => write!(f, "{}", message)
+            },
+
+            JavaScriptError::UnsupportedResultType => write!(f, "JavaScript returned a value that can't be converted"),
+
+            #[cfg(feature = "serde")]
+            JavaScriptError::Conversion(error) => write!(f, "{}", error),
+
+            JavaScriptError::Other(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl error::Error for JavaScriptError {}
+
+/// A message posted from JavaScript, via `webkit.messageHandlers.<name>.postMessage(body)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptMessage {
+    /// The name of the handler the message was posted to.
+    pub name: String,
+
+    /// Whatever was posted. Anything that doesn't survive the trip (e.g, `undefined`) shows up
+    /// as `ObjcValue::Null`.
+    pub body: ObjcValue,
+
+    /// Whether the message came from the main frame, rather than an iframe.
+    pub is_main_frame: bool
+}
+
+impl ScriptMessage {
+    /// Reads a `WKScriptMessage`.
+    pub(crate) fn new(message: id) -> Self {
+        unsafe {
+            let name = NSString::retain(msg_send![message, name]);
+            let body: id = msg_send![message, body];
+            let frame: id = msg_send![message, frameInfo];
+
+            let is_main_frame = match frame == nil {
+                true => true,
+                false => {
+                    let is_main_frame: BOOL = msg_send![frame, isMainFrame];
+                    to_bool(is_main_frame)
+                }
+            };
+
+            ScriptMessage {
+                name: name.to_string(),
+                body: ObjcValue::from_object(body).unwrap_or(ObjcValue::Null),
+                is_main_frame
+            }
+        }
+    }
+
+    /// Deserializes the body into any `Deserialize` type.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, ConversionError> {
+        from_js_value(self.body.clone())
+    }
+}
+
+/// Replies to a message posted to a handler added with `WebViewConfig::add_reply_handler`,
+/// settling the promise that `postMessage` returned on the JavaScript side.
+///
+/// A reply can only be sent once, so the methods here take `self`. You don't have to reply right
+/// away - hold on to this and reply later if you need to - but it must happen on the main
+/// thread. Dropping this without replying rejects the promise.
+#[must_use = "dropping a reply rejects the JavaScript promise"]
+pub struct ScriptReply(Option<RcBlock<(id, id), ()>>);
+
+impl ScriptReply {
+    /// Takes ownership of a `WKScriptMessageHandlerWithReply` reply handler.
+    pub(crate) fn new(handler: *mut Block<(id, id), ()>) -> Self {
+        ScriptReply(Some(unsafe { RcBlock::copy(handler) }))
+    }
+
+    /// Resolves the promise with `value`.
+    pub fn resolve(mut self, value: ObjcValue) {
+        self.send(value.into(), None);
+    }
+
+    /// Rejects the promise with an `Error` whose message is `message`.
+    pub fn reject<S: AsRef<str>>(mut self, message: S) {
+        self.send(nil, Some(message.as_ref()));
+    }
+
+    /// Resolves the promise with `value`, serialized. If serializing fails, the promise is
+    /// rejected with the error, which is also returned.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn resolve_with<T: Serialize + ?Sized>(self, value: &T) -> Result<(), ConversionError> {
+        match to_objc_value(value) {
+            Ok(value) => {
+                self.resolve(value);
+                Ok(())
+            },
+
+            Err(error) => {
+                self.reject(error.to_string());
+                Err(error)
+            }
+        }
+    }
+
+    fn send(&mut self, reply: id, error: Option<&str>) {
+        if let Some(handler) = self.0.take() {
+            let error = error.map(NSString::new);
+
+            let error: id = match &error {
+                Some(error) => &**error as *const _ as id,
+                None => nil
+            };
+
+            unsafe {
+                handler.call((reply, error));
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ScriptReply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptReply").field("replied", &self.0.is_none()).finish()
+    }
+}
+
+impl Drop for ScriptReply {
+    /// WebKit requires every reply handler to be called, so anything left unanswered is rejected.
+    fn drop(&mut self) {
+        self.send(nil, Some("No reply was sent"));
+    }
+}
+
+/// Deserializes a value that came from JavaScript.
+#[cfg(feature = "serde")]
+pub(crate) fn from_js_value<T: DeserializeOwned>(value: ObjcValue) -> Result<T, ConversionError> {
+    T::deserialize(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JavaScriptError, WK_ERROR_DOMAIN};
+    use crate::error::Error;
+    use crate::foundation::ObjcValue;
+
+    #[test]
+    fn test_error_mapping() {
+        let mut error = Error::with_description(WK_ERROR_DOMAIN, 4, "A JavaScript exception occurred");
+        error.user_info.insert(
+            "WKJavaScriptExceptionMessage".into(),
+            ObjcValue::String("ReferenceError: Can't find variable: foo".into())
+        );
+        error
+            .user_info
+            .insert("WKJavaScriptExceptionLineNumber".into(), ObjcValue::Integer(1));
+        error
+            .user_info
+            .insert("WKJavaScriptExceptionColumnNumber".into(), ObjcValue::Integer(4));
+
+        let error = JavaScriptError::from(error);
+        assert_eq!(error, JavaScriptError::Exception {
+            message: "ReferenceError: Can't find variable: foo".into(),
+            line: Some(1),
+            column: Some(4),
+            source_url: None
+        });
+        assert_eq!(
+            error.to_string(),
+            "ReferenceError: Can't find variable: foo (line 1, column 4)"
+        );
+
+        let unsupported = Error::with_description(WK_ERROR_DOMAIN, 5, "Unsupported");
+        assert_eq!(JavaScriptError::from(unsupported), JavaScriptError::UnsupportedResultType);
+
+        let other = Error::with_description("NSCocoaErrorDomain", 4, "Missing");
+        assert_eq!(JavaScriptError::from(other.clone()), JavaScriptError::Other(other));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_message() {
+        use std::collections::HashMap;
+
+        use serde::Deserialize;
+
+        use super::{from_js_value, ScriptMessage};
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Save {
+            path: String,
+            overwrite: Option<bool>
+        }
+
+        let mut body = HashMap::new();
+        body.insert("path".to_string(), ObjcValue::String("/tmp/notes.txt".into()));
+        body.insert("overwrite".to_string(), ObjcValue::Null);
+
+        let message = ScriptMessage {
+            name: "save".into(),
+            body: ObjcValue::Dictionary(body),
+            is_main_frame: true
+        };
+
+        assert_eq!(message.deserialize::<Save>().unwrap(), Save {
+            path: "/tmp/notes.txt".into(),
+            overwrite: None
+        });
+
+        assert_eq!(from_js_value::<Option<i64>>(ObjcValue::Null).unwrap(), None);
+        assert_eq!(from_js_value::<Option<i64>>(ObjcValue::Integer(3)).unwrap(), Some(3));
+
+        // `[1, null, 3]` keeps its shape, rather than dropping the null.
+        let array = ObjcValue::Array(vec![ObjcValue::Integer(1), ObjcValue::Null, ObjcValue::Integer(3)]);
+        assert_eq!(from_js_value::<Vec<Option<i64>>>(array).unwrap(), vec![
+            Some(1),
+            None,
+            Some(3)
+        ]);
+
+        assert_eq!(from_js_value::<u64>(ObjcValue::UnsignedInteger(u64::MAX)).unwrap(), u64::MAX);
+    }
+}
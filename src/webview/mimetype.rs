@@ -1,73 +1,385 @@
-use std::fmt;
-
-const MIMETYPE_PLAIN: &str = "text/plain";
-
-/// [Web Compatible MimeTypes](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types#important_mime_types_for_web_developers)
-pub enum MimeType {
-    CSS,
-    CSV,
-    HTML,
-    ICO,
-    JS,
-    JSON,
-    JSONLD,
-    OCTETSTREAM,
-    RTF,
-    SVG
+//! Works out the `Content-Type` for custom protocol responses.
+//!
+//! Types come from the extension on the request's path. When that's missing or unknown, the
+//! content is sniffed instead - a handful of signatures that matter on the web (fonts, `wasm`,
+//! images, audio and video), then anything else `infer` knows about. Text formats (CSS,
+//! JavaScript, JSON and so on) have no signature, so they always come down to the extension.
+//!
+//! If the built-in registry gets something wrong for your app, or doesn't know an extension
+//! you serve, register your own:
+//!
+//! ```rust
+//! use cacao::webview::mimetype;
+//!
+//! mimetype::register("glb", "model/gltf-binary");
+//! assert_eq!(mimetype::from_uri("app://bundle/models/chair.glb?v=2"), "model/gltf-binary");
+//! ```
+//!
+//! Registered types win over the built-in registry.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+use crate::utils::signatures;
+
+/// What a resource with no extension (e.g, `app://bundle/` or `app://bundle/settings`) is served
+/// as - it's most likely a page, or a client-side route.
+const MIMETYPE_HTML: &str = "text/html";
+
+/// What a resource with an unknown extension is served as.
+///
+/// [https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types)
+const MIMETYPE_OCTET_STREAM: &str = "application/octet-stream";
+
+lazy_static! {
+    /// Types registered with `register()`, keyed by lowercased extension.
+    static ref OVERRIDES: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+/// Registers `mime_type` for files ending in `extension` (with or without the leading `.`,
+/// ignoring case), replacing the built-in type if there is one.
+pub fn register<E: AsRef<str>, M: Into<String>>(extension: E, mime_type: M) {
+    let extension = normalize(extension.as_ref());
+    OVERRIDES.write().unwrap().insert(extension, mime_type.into());
+}
+
+/// Removes a type added with `register()`, returning it. The built-in type (if any) applies again.
+pub fn unregister<E: AsRef<str>>(extension: E) -> Option<String> {
+    OVERRIDES.write().unwrap().remove(&normalize(extension.as_ref()))
 }
 
-impl std::fmt::Display for MimeType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mime = match self {
-            MimeType::CSS => "text/css",
-            MimeType::CSV => "text/csv",
-            MimeType::HTML => "text/html",
-            MimeType::ICO => "image/vnd.microsoft.icon",
-            MimeType::JS => "text/javascript",
-            MimeType::JSON => "application/json",
-            MimeType::JSONLD => "application/ld+json",
-            MimeType::OCTETSTREAM => "application/octet-stream",
-            MimeType::RTF => "application/rtf",
-            MimeType::SVG => "image/svg+xml"
-        };
-        write!(f, "{}", mime)
+/// Returns the type for `extension` (with or without the leading `.`, ignoring case), if it's
+/// registered or built in.
+pub fn from_extension(extension: &str) -> Option<String> {
+    let extension = normalize(extension);
+
+    match registered(&extension) {
+        Some(mime_type) => Some(mime_type),
+        None => builtin(&extension).map(String::from)
     }
 }
 
-impl MimeType {
-    /// parse a URI suffix to convert text/plain mimeType to their actual web compatible mimeType.
-    pub fn parse_from_uri(uri: &str) -> MimeType {
-        let suffix = uri.split('.').last();
-        match suffix {
-            Some("bin") => Self::OCTETSTREAM,
-            Some("css") => Self::CSS,
-            Some("csv") => Self::CSV,
-            Some("html") => Self::HTML,
-            Some("ico") => Self::ICO,
-            Some("js") => Self::JS,
-            Some("json") => Self::JSON,
-            Some("jsonld") => Self::JSONLD,
-            Some("rtf") => Self::RTF,
-            Some("svg") => Self::SVG,
-            // Assume HTML when a TLD is found for eg. `protocol:://example.com`
-            Some(_) => Self::HTML,
-            // https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types
-            // using octet stream according to this:
-            None => Self::OCTETSTREAM
-        }
+/// Returns the lowercased extension of the last path segment in `uri`, ignoring any query
+/// string or fragment. `uri` can be a full URL (`app://bundle/index.html?v=2#top`) or just a
+/// path (`/css/site.css`).
+pub fn extension_from_uri(uri: &str) -> Option<String> {
+    let uri = uri.split(&['?', '#'][..]).next().unwrap_or("");
+
+    // Skip past the scheme and host, if there are any - `app://bundle.js` has no path at all.
+    let path = match uri.find("://") {
+        Some(index) => {
+            let rest = &uri[index + 3..];
+            rest.find('/').map(|index| &rest[index..]).unwrap_or("")
+        },
+
+        None => uri
+    };
+
+    let name = path.rsplit('/').next().unwrap_or("");
+
+    match name.rfind('.') {
+        // Dotfiles (`.htaccess`) have a name, not an extension.
+        Some(0) | None => None,
+        Some(index) if index + 1 == name.len() => None,
+        Some(index) => Some(name[index + 1..].to_ascii_lowercase())
     }
+}
 
-    /// infer mimetype from content (or) URI if needed.
-    pub fn parse(content: &[u8], uri: &str) -> String {
-        let mime = match infer::get(&content) {
-            Some(info) => info.mime_type(),
-            None => MIMETYPE_PLAIN
-        };
+/// Guesses the type of `uri` from its extension alone. A path with no extension is assumed to
+/// be a page, and gets `text/html`; an extension we don't know gets `application/octet-stream`.
+pub fn from_uri(uri: &str) -> String {
+    match extension_from_uri(uri) {
+        Some(extension) => from_extension(&extension).unwrap_or_else(|| MIMETYPE_OCTET_STREAM.to_string()),
+        None => MIMETYPE_HTML.to_string()
+    }
+}
+
+/// Guesses the type of `content` from its leading bytes, if it has a signature we (or `infer`)
+/// recognize. Text formats don't have one, so this returns `None` for them.
+pub fn sniff(content: &[u8]) -> Option<&'static str> {
+    signatures::sniff(content).or_else(|| {
+        // `infer` also guesses at text (e.g, anything starting `<?xml` is `text/xml`, SVGs
+        // included), which the extension knows better.
+        infer::get(content)
+            .filter(|info| info.matcher_type() != infer::MatcherType::Text)
+            .map(|info| info.mime_type())
+    })
+}
 
-        if mime == MIMETYPE_PLAIN {
-            return Self::parse_from_uri(uri).to_string();
+/// Works out the type to serve `content` as, for a request to `uri`. A registered or built-in
+/// type for the extension wins; `content` is only sniffed when the extension is missing or
+/// unknown, falling back to `from_uri()`.
+///
+/// Containers like Ogg, WebM and MP4 hold audio or video, and their signatures can't say which -
+/// so `movie.ogv` is `video/ogg`, even though it sniffs as `audio/ogg`.
+pub fn parse(content: &[u8], uri: &str) -> String {
+    if let Some(mime_type) = extension_from_uri(uri).and_then(|extension| from_extension(&extension)) {
+        return mime_type;
+    }
+
+    match sniff(content) {
+        Some(mime_type) => mime_type.to_string(),
+        None => from_uri(uri)
+    }
+}
+
+/// Lowercases an extension, dropping any leading `.`.
+fn normalize(extension: &str) -> String {
+    extension.trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// Returns the type registered for a (normalized) extension, if there is one.
+fn registered(extension: &str) -> Option<String> {
+    OVERRIDES.read().unwrap().get(extension).cloned()
+}
+
+/// The built-in registry, keyed by (normalized) extension.
+///
+/// [https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types](https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types/Common_types)
+fn builtin(extension: &str) -> Option<&'static str> {
+    Some(match extension {
+        // Documents and code
+        "html" | "htm" => "text/html",
+        "xhtml" => "application/xhtml+xml",
+        "css" => "text/css",
+        "js" | "mjs" | "cjs" => "text/javascript",
+        "json" | "map" => "application/json",
+        "jsonld" => "application/ld+json",
+        "webmanifest" => "application/manifest+json",
+        "xml" => "application/xml",
+        "txt" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "ics" => "text/calendar",
+        "vtt" => "text/vtt",
+        "rtf" => "application/rtf",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+
+        // Images
+        "apng" => "image/apng",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        "gif" => "image/gif",
+        "heic" | "heif" => "image/heic",
+        "ico" | "cur" => "image/vnd.microsoft.icon",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        "tif" | "tiff" => "image/tiff",
+        "webp" => "image/webp",
+
+        // Fonts
+        "eot" => "application/vnd.ms-fontobject",
+        "otf" => "font/otf",
+        "ttc" => "font/collection",
+        "ttf" => "font/ttf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+
+        // Audio
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "m4a" => "audio/mp4",
+        "mid" | "midi" => "audio/midi",
+        "mp3" => "audio/mpeg",
+        "oga" | "ogg" | "opus" => "audio/ogg",
+        "wav" => "audio/wav",
+        "weba" => "audio/webm",
+
+        // Video
+        "avi" => "video/x-msvideo",
+        "m4v" | "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "mpeg" | "mpg" => "video/mpeg",
+        "ogv" => "video/ogg",
+        "webm" => "video/webm",
+
+        // Archives
+        "bin" => MIMETYPE_OCTET_STREAM,
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "zip" => "application/zip",
+
+        _ => return None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extension_from_uri, from_extension, from_uri, parse, register, sniff, unregister};
+
+    /// `(file name, contents, type, sniffed type)` for everything in `test-data/mimetype`.
+    const FIXTURES: &[(&str, &[u8], &str, Option<&str>)] = &[
+        (
+            "index.html",
+            include_bytes!("../../test-data/mimetype/index.html"),
+            "text/html",
+            None
+        ),
+        (
+            "style.css",
+            include_bytes!("../../test-data/mimetype/style.css"),
+            "text/css",
+            None
+        ),
+        (
+            "app.mjs",
+            include_bytes!("../../test-data/mimetype/app.mjs"),
+            "text/javascript",
+            None
+        ),
+        (
+            "data.json",
+            include_bytes!("../../test-data/mimetype/data.json"),
+            "application/json",
+            None
+        ),
+        (
+            "manifest.webmanifest",
+            include_bytes!("../../test-data/mimetype/manifest.webmanifest"),
+            "application/manifest+json",
+            None
+        ),
+        (
+            "logo.svg",
+            include_bytes!("../../test-data/mimetype/logo.svg"),
+            "image/svg+xml",
+            None
+        ),
+        (
+            "pixel.png",
+            include_bytes!("../../test-data/mimetype/pixel.png"),
+            "image/png",
+            Some("image/png")
+        ),
+        (
+            "pixel.gif",
+            include_bytes!("../../test-data/mimetype/pixel.gif"),
+            "image/gif",
+            Some("image/gif")
+        ),
+        (
+            "module.wasm",
+            include_bytes!("../../test-data/mimetype/module.wasm"),
+            "application/wasm",
+            Some("application/wasm")
+        ),
+        (
+            "font.woff2",
+            include_bytes!("../../test-data/mimetype/font.woff2"),
+            "font/woff2",
+            Some("font/woff2")
+        ),
+        (
+            "font.ttf",
+            include_bytes!("../../test-data/mimetype/font.ttf"),
+            "font/ttf",
+            Some("font/ttf")
+        ),
+        (
+            "sound.wav",
+            include_bytes!("../../test-data/mimetype/sound.wav"),
+            "audio/wav",
+            Some("audio/wav")
+        ),
+        (
+            "sound.mp3",
+            include_bytes!("../../test-data/mimetype/sound.mp3"),
+            "audio/mpeg",
+            Some("audio/mpeg")
+        ),
+        (
+            "sound.ogg",
+            include_bytes!("../../test-data/mimetype/sound.ogg"),
+            "audio/ogg",
+            Some("audio/ogg")
+        ),
+        // An audio-only WebM and MP4 look the same as video ones, so these sniff as video.
+        (
+            "sound.weba",
+            include_bytes!("../../test-data/mimetype/sound.weba"),
+            "audio/webm",
+            Some("video/webm")
+        ),
+        (
+            "sound.m4a",
+            include_bytes!("../../test-data/mimetype/sound.m4a"),
+            "audio/mp4",
+            Some("video/mp4")
+        )
+    ];
+
+    #[test]
+    fn test_fixtures() {
+        for (name, contents, mime_type, sniffed) in FIXTURES {
+            let uri = format!("app://bundle/assets/{}?v=3#main", name);
+            assert_eq!(&parse(contents, &uri), mime_type, "{}", name);
+            assert_eq!(&from_uri(&uri), mime_type, "{}", name);
+            assert_eq!(&sniff(contents), sniffed, "{}", name);
+
+            // Without an extension, anything with a signature is recognized by it.
+            if let Some(sniffed) = sniffed {
+                assert_eq!(&parse(contents, "app://bundle/download"), sniffed, "{}", name);
+                assert_eq!(&parse(contents, "app://bundle/download.unknown"), sniffed, "{}", name);
+            }
         }
+    }
+
+    #[test]
+    fn test_containers() {
+        let ogg = include_bytes!("../../test-data/mimetype/sound.ogg");
+        assert_eq!(parse(ogg, "app://bundle/movie.ogv"), "video/ogg");
+        assert_eq!(parse(ogg, "app://bundle/voice.opus"), "audio/ogg");
+
+        let webm = include_bytes!("../../test-data/mimetype/sound.weba");
+        assert_eq!(parse(webm, "app://bundle/clip.webm"), "video/webm");
+
+        let mp4 = include_bytes!("../../test-data/mimetype/sound.m4a");
+        assert_eq!(parse(mp4, "app://bundle/clip.mp4"), "video/mp4");
+        assert_eq!(parse(mp4, "app://bundle/clip.m4v"), "video/mp4");
+        assert_eq!(parse(mp4, "app://bundle/clip.mov"), "video/quicktime");
+    }
+
+    #[test]
+    fn test_uri_parsing() {
+        assert_eq!(extension_from_uri("app://bundle/index.html").as_deref(), Some("html"));
+        assert_eq!(extension_from_uri("app://bundle/Site.CSS?v=1.2").as_deref(), Some("css"));
+        assert_eq!(extension_from_uri("app://bundle/app.js#L1.5").as_deref(), Some("js"));
+        assert_eq!(extension_from_uri("app://bundle/archive.tar.gz").as_deref(), Some("gz"));
+        assert_eq!(extension_from_uri("/fonts/inter.woff2").as_deref(), Some("woff2"));
+        assert_eq!(extension_from_uri("app://example.com"), None);
+        assert_eq!(extension_from_uri("app://example.com/"), None);
+        assert_eq!(extension_from_uri("app://bundle/v1.2/settings"), None);
+        assert_eq!(extension_from_uri("app://bundle/.htaccess"), None);
+        assert_eq!(extension_from_uri("app://bundle/file."), None);
+
+        assert_eq!(from_uri("app://example.com"), "text/html");
+        assert_eq!(from_uri("app://bundle/settings?tab=general"), "text/html");
+        assert_eq!(from_uri("app://bundle/data.unknown"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_overrides() {
+        assert_eq!(from_extension("cacaotest"), None);
+
+        register(".CacaoTest", "application/x-cacao-test");
+        assert_eq!(from_extension("cacaotest").as_deref(), Some("application/x-cacao-test"));
+        assert_eq!(from_uri("app://bundle/file.cacaotest?v=1"), "application/x-cacao-test");
+
+        // Registered types win over sniffing...
+        let png = include_bytes!("../../test-data/mimetype/pixel.png");
+        assert_eq!(parse(png, "app://bundle/file.cacaotest"), "application/x-cacao-test");
+
+        assert_eq!(unregister("cacaotest").as_deref(), Some("application/x-cacao-test"));
+        assert_eq!(from_extension("cacaotest"), None);
 
-        mime.to_string()
+        // ...and over the built-in registry, until they're removed.
+        register("csv", "text/x-cacao-csv");
+        assert_eq!(from_uri("app://bundle/export.csv"), "text/x-cacao-csv");
+        unregister("csv");
+        assert_eq!(from_uri("app://bundle/export.csv"), "text/csv");
     }
 }
@@ -6,6 +6,7 @@
 //! - `WKWebView`
 //! - `WKUIDelegate`
 //! - `WKScriptMessageHandler`
+//! - `WKScriptMessageHandlerWithReply`
 //!
 //! This is, thankfully, a pretty similar class across platforms.
 //!
@@ -13,19 +14,25 @@
 //! Apple does not ship `WKWebView` on tvOS, and as a result this control is not provided on that
 //! platform.
 
-use core_graphics::geometry::CGRect;
+use crate::core_graphics::geometry::CGRect;
 
 use objc::runtime::Object;
 use objc::{class, msg_send, sel, sel_impl};
 use objc_id::ShareId;
 
-use crate::foundation::{id, nil, NSString, NO, YES};
+use block::ConcreteBlock;
+
+use crate::error::Error;
+use crate::foundation::{id, nil, NSString, ObjcValue, NO, YES};
 use crate::geometry::Rect;
 use crate::layer::Layer;
 use crate::layout::Layout;
 use crate::objc_access::ObjcAccess;
 use crate::utils::properties::ObjcProperty;
 
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+
 #[cfg(feature = "autolayout")]
 use crate::layout::{LayoutAnchorDimension, LayoutAnchorX, LayoutAnchorY};
 
@@ -38,11 +45,18 @@ pub use config::WebViewConfig;
 mod enums;
 pub use enums::*;
 
+mod javascript;
+pub use javascript::{JavaScriptError, ScriptMessage, ScriptReply, WK_ERROR_DOMAIN};
+
 pub(crate) mod class;
 use class::{register_webview_class, register_webview_delegate_class};
 //pub(crate) mod process_pool;
 
-mod mimetype;
+pub mod mimetype;
+
+mod protocol;
+pub use protocol::{ByteRange, ProtocolRequest, ProtocolResponse, ProtocolTask, RangeRequest, ResponseBody};
+
 mod traits;
 pub use traits::WebViewDelegate;
 
@@ -52,6 +66,7 @@ fn allocate_webview(mut config: WebViewConfig, objc_delegate: Option<&Object>) -
     unsafe {
         // Not a fan of this, but we own it anyway, so... meh.
         let handlers = std::mem::take(&mut config.handlers);
+        let reply_handlers = std::mem::take(&mut config.reply_handlers);
         let protocols = std::mem::take(&mut config.protocols);
         let configuration = config.into_inner();
 
@@ -70,6 +85,16 @@ fn allocate_webview(mut config: WebViewConfig, objc_delegate: Option<&Object>) -
                 let _: () = msg_send![content_controller, addScriptMessageHandler:*delegate name:&*name];
             }
 
+            if !reply_handlers.is_empty() {
+                let world: id = msg_send![class!(WKContentWorld), pageWorld];
+
+                for handler in reply_handlers {
+                    let name = NSString::new(&handler);
+                    let _: () =
+                        msg_send![content_controller, addScriptMessageHandlerWithReply:*delegate contentWorld:world name:&*name];
+                }
+            }
+
             for protocol in protocols {
                 let name = NSString::new(&protocol);
                 let _: () = msg_send![configuration, setURLSchemeHandler:*delegate forURLScheme:&*name];
@@ -318,6 +343,55 @@ impl<T> WebView<T> {
         });
     }
 
+    /// Evaluates `script` in the page, and calls `handler` with the result - or with the error, if
+    /// the script threw. Scripts that don't produce a value (e.g, end in a statement) result in
+    /// `ObjcValue::Null`.
+    ///
+    /// `handler` is called on the main thread.
+    pub fn evaluate_javascript<F>(&self, script: &str, handler: F)
+    where
+        F: Fn(Result<ObjcValue, JavaScriptError>) + 'static
+    {
+        let script = NSString::new(script);
+
+        let completion = ConcreteBlock::new(move |result: id, error: id| {
+            handler(match error == nil {
+                true => Ok(ObjcValue::from_object(result).unwrap_or(ObjcValue::Null)),
+                false => Err(Error::new(error).into())
+            });
+        });
+        let completion = completion.copy();
+
+        self.objc.with_mut(|obj| unsafe {
+            let _: () = msg_send![&*obj, evaluateJavaScript:&*script completionHandler:&*completion];
+        });
+    }
+
+    /// Like `evaluate_javascript`, but deserializes the result into any `Deserialize` type.
+    ///
+    /// ```rust,no_run
+    /// use cacao::webview::WebView;
+    ///
+    /// let webview = WebView::default();
+    /// webview.evaluate_javascript_as("document.title", |title: Result<String, _>| {
+    ///     println!("{:?}", title);
+    /// });
+    /// ```
+    #[cfg(feature = "serde")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    pub fn evaluate_javascript_as<R, F>(&self, script: &str, handler: F)
+    where
+        R: DeserializeOwned,
+        F: Fn(Result<R, JavaScriptError>) + 'static
+    {
+        self.evaluate_javascript(script, move |result| {
+            handler(match result {
+                Ok(value) => javascript::from_js_value(value).map_err(JavaScriptError::Conversion),
+                Err(error) => Err(error)
+            });
+        });
+    }
+
     /// Go back in history, if possible.
     pub fn go_back(&self) {
         self.objc.with_mut(|obj| unsafe {
@@ -0,0 +1,201 @@
+//! Serves custom protocol (e.g, `app://`) requests from a `WebView`.
+//!
+//! Register the scheme with `WebViewConfig::add_custom_protocol`, then implement
+//! `WebViewDelegate::on_custom_protocol_task`. You're handed the request (method, headers and
+//! body) along with a `ProtocolTask` to respond through. The task is `Send`, so you can move it
+//! to a background thread and respond whenever you're ready; responses are streamed to WebKit in
+//! chunks, so serving a large file doesn't mean reading it all into memory first.
+//!
+//! ```rust,no_run
+//! use cacao::webview::{ProtocolRequest, ProtocolResponse, ProtocolTask, WebViewDelegate};
+//!
+//! struct Assets;
+//!
+//! impl WebViewDelegate for Assets {
+//!     const NAME: &'static str = "AssetsWebViewDelegate";
+//!
+//!     fn on_custom_protocol_task(&self, request: ProtocolRequest, task: ProtocolTask) {
+//!         std::thread::spawn(move || {
+//!             // Handles `Range` requests, so `<video>` can seek.
+//!             let response = ProtocolResponse::file(&request, "/path/to/movie.mp4")
+//!                 .unwrap_or_else(|_| ProtocolResponse::not_found());
+//!
+//!             task.respond(response);
+//!         });
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+
+use objc::{msg_send, sel, sel_impl};
+
+use crate::foundation::{id, nil, NSData, NSString, ObjcValue};
+
+mod response;
+pub use response::{ProtocolResponse, ResponseBody};
+
+mod task;
+pub(crate) use task::stop_task;
+pub use task::ProtocolTask;
+
+/// A request made to a custom protocol.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProtocolRequest {
+    /// The full URL, e.g `app://bundle/index.html`.
+    pub url: String,
+
+    /// The HTTP method, e.g `GET`.
+    pub method: String,
+
+    /// The request headers.
+    pub headers: HashMap<String, String>,
+
+    /// The request body, if there was one.
+    pub body: Option<Vec<u8>>
+}
+
+impl ProtocolRequest {
+    /// Reads an `NSURLRequest`.
+    pub(crate) fn new(request: id) -> Self {
+        unsafe {
+            let url: id = msg_send![request, URL];
+            let method: id = msg_send![request, HTTPMethod];
+            let body: id = msg_send![request, HTTPBody];
+
+            let headers = ObjcValue::dictionary_from_object(msg_send![request, allHTTPHeaderFields])
+                .into_iter()
+                .filter_map(|(name, value)| match value {
+                    ObjcValue::String(value) => Some((name, value)),
+                    _ => None
+                })
+                .collect();
+
+            ProtocolRequest {
+                url: NSString::retain(msg_send![url, absoluteString]).to_string(),
+                method: match method == nil {
+                    true => "GET".to_string(),
+                    false => NSString::retain(method).to_string()
+                },
+                headers,
+                body: match body == nil {
+                    true => None,
+                    false => Some(NSData::retain(body).into_vec())
+                }
+            }
+        }
+    }
+
+    /// Returns the value of the header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the byte range this request asks for, for a resource that's `length` bytes long.
+    pub fn range(&self, length: u64) -> RangeRequest {
+        match self.header("Range") {
+            Some(header) => parse_range(header, length),
+            None => RangeRequest::Full
+        }
+    }
+}
+
+/// An inclusive range of bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ByteRange {
+    /// The first byte.
+    pub start: u64,
+
+    /// The last byte.
+    pub end: u64
+}
+
+impl ByteRange {
+    /// The number of bytes in the range.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Always `false` - a range holds at least one byte.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+/// What a request's `Range` header asks for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// The whole resource - there's no `Range` header, or it's one we don't support (e.g,
+    /// multiple ranges), which HTTP allows servers to ignore.
+    Full,
+
+    /// Just part of the resource.
+    Partial(ByteRange),
+
+    /// A range that starts past the end of the resource. Respond with a 416.
+    Unsatisfiable
+}
+
+/// Parses a single `bytes=` range, clamping it to `length`.
+fn parse_range(header: &str, length: u64) -> RangeRequest {
+    let spec = match header.trim().strip_prefix("bytes=") {
+        Some(spec) if !spec.contains(',') => spec.trim(),
+        _ => return RangeRequest::Full
+    };
+
+    let (start, end) = match spec.find('-') {
+        Some(index) => (spec[..index].trim(), spec[index + 1..].trim()),
+        None => return RangeRequest::Full
+    };
+
+    let range = match (start.parse::<u64>(), end.parse::<u64>()) {
+        // `bytes=500-999`
+        (Ok(start), Ok(end)) if start <= end => (start, end.min(length.saturating_sub(1))),
+
+        // `bytes=500-`
+        (Ok(start), Err(_)) if end.is_empty() => (start, length.saturating_sub(1)),
+
+        // `bytes=-500`: the last 500 bytes.
+        (Err(_), Ok(suffix)) if start.is_empty() => match suffix {
+            0 => return RangeRequest::Unsatisfiable,
+            suffix => (length.saturating_sub(suffix), length.saturating_sub(1))
+        },
+
+        _ => return RangeRequest::Full
+    };
+
+    match range {
+        (start, _) if start >= length => RangeRequest::Unsatisfiable,
+        (start, end) => RangeRequest::Partial(ByteRange { start, end })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_range, ByteRange, ProtocolRequest, RangeRequest};
+
+    #[test]
+    fn test_parse_range() {
+        let partial = |start, end| RangeRequest::Partial(ByteRange { start, end });
+
+        assert_eq!(parse_range("bytes=0-99", 1000), partial(0, 99));
+        assert_eq!(parse_range("bytes=500-", 1000), partial(500, 999));
+        assert_eq!(parse_range("bytes=-100", 1000), partial(900, 999));
+        assert_eq!(parse_range("bytes=-5000", 1000), partial(0, 999));
+        assert_eq!(parse_range("bytes=900-5000", 1000), partial(900, 999));
+        assert_eq!(parse_range("bytes=1000-", 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-", 0), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-1,5-6", 1000), RangeRequest::Full);
+        assert_eq!(parse_range("bytes=9-1", 1000), RangeRequest::Full);
+        assert_eq!(parse_range("items=0-1", 1000), RangeRequest::Full);
+
+        let mut request = ProtocolRequest::default();
+        assert_eq!(request.range(1000), RangeRequest::Full);
+
+        request.headers.insert("range".into(), "bytes=10-19".into());
+        assert_eq!(request.range(1000), partial(10, 19));
+    }
+}
@@ -0,0 +1,244 @@
+//! Builds responses to custom protocol requests.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use super::{ProtocolRequest, RangeRequest};
+use crate::webview::mimetype;
+
+/// How many bytes of a file are read to sniff its type.
+const SNIFF_LENGTH: usize = 512;
+
+/// The body of a `ProtocolResponse`.
+pub enum ResponseBody {
+    /// The whole body, up front.
+    Bytes(Vec<u8>),
+
+    /// A body that's read (and sent on to WebKit) a chunk at a time. `length` becomes the
+    /// `Content-Length`, if it's known.
+    Stream {
+        reader: Box<dyn Read + Send>,
+        length: Option<u64>
+    }
+}
+
+impl fmt::Debug for ResponseBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseBody::Bytes(bytes) => f.debug_tuple("Bytes").field(&bytes.len()).finish(),
+            ResponseBody::Stream { length, .. } => f.debug_struct("Stream").field("length", length).finish()
+        }
+    }
+}
+
+/// A response to a custom protocol request, built up in a chain:
+///
+/// ```rust
+/// use cacao::webview::ProtocolResponse;
+///
+/// let response = ProtocolResponse::ok()
+///     .content_type("application/json")
+///     .header("Cache-Control", "no-store")
+///     .body(r#"{"ok": true}"#);
+///
+/// assert_eq!(response.status(), 200);
+/// assert_eq!(response.headers()["Content-Length"], "12");
+/// ```
+#[derive(Debug)]
+pub struct ProtocolResponse {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: ResponseBody
+}
+
+impl ProtocolResponse {
+    /// Creates an empty response with the given HTTP status code.
+    pub fn new(status: u16) -> Self {
+        ProtocolResponse {
+            status,
+            headers: HashMap::new(),
+            body: ResponseBody::Bytes(Vec::new())
+        }
+    }
+
+    /// Creates an empty `200 OK` response.
+    pub fn ok() -> Self {
+        ProtocolResponse::new(200)
+    }
+
+    /// Creates a `404 Not Found` response.
+    pub fn not_found() -> Self {
+        ProtocolResponse::new(404).content_type("text/plain").body("Not Found")
+    }
+
+    /// Creates a response for `bytes`, honoring any `Range` header on `request`. The content
+    /// type comes from the request URL's extension, or is sniffed from the bytes if it has none
+    /// we know (see `mimetype::parse()`).
+    pub fn bytes_for(request: &ProtocolRequest, bytes: Vec<u8>) -> Self {
+        let content_type = mimetype::parse(&bytes, &request.url);
+        let length = bytes.len() as u64;
+
+        match request.range(length) {
+            RangeRequest::Full => ProtocolResponse::ok()
+                .header("Accept-Ranges", "bytes")
+                .content_type(content_type)
+                .body(bytes),
+
+            RangeRequest::Partial(range) => ProtocolResponse::partial(range.start, range.end, length)
+                .content_type(content_type)
+                .body(&bytes[range.start as usize..=range.end as usize]),
+
+            RangeRequest::Unsatisfiable => ProtocolResponse::unsatisfiable(length)
+        }
+    }
+
+    /// Creates a response that streams the file at `path`, honoring any `Range` header on
+    /// `request` - which is what `<video>` and `<audio>` need to seek. The content type comes
+    /// from the file's extension, or is sniffed from the start of the file if it has none we
+    /// know.
+    pub fn file<P: AsRef<Path>>(request: &ProtocolRequest, path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path)?;
+        let length = file.metadata()?.len();
+
+        let mut head = Vec::with_capacity(SNIFF_LENGTH);
+        (&mut file).take(SNIFF_LENGTH as u64).read_to_end(&mut head)?;
+        let content_type = mimetype::parse(&head, &path.to_string_lossy());
+
+        let (response, start, count) = match request.range(length) {
+            RangeRequest::Full => (ProtocolResponse::ok().header("Accept-Ranges", "bytes"), 0, length),
+            RangeRequest::Partial(range) => (
+                ProtocolResponse::partial(range.start, range.end, length),
+                range.start,
+                range.len()
+            ),
+            RangeRequest::Unsatisfiable => return Ok(ProtocolResponse::unsatisfiable(length))
+        };
+
+        file.seek(SeekFrom::Start(start))?;
+
+        Ok(response.content_type(content_type).stream(file.take(count), Some(count)))
+    }
+
+    /// Sets a header, replacing any existing header with the same name (ignoring case).
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        let name = name.into();
+        self.headers.retain(|key, _| !key.eq_ignore_ascii_case(&name));
+        self.headers.insert(name, value.into());
+        self
+    }
+
+    /// Sets the `Content-Type` header.
+    pub fn content_type<S: Into<String>>(self, content_type: S) -> Self {
+        self.header("Content-Type", content_type)
+    }
+
+    /// Sets the body, along with the `Content-Length` header.
+    pub fn body<B: Into<Vec<u8>>>(mut self, body: B) -> Self {
+        let body = body.into();
+        self = self.header("Content-Length", body.len().to_string());
+        self.body = ResponseBody::Bytes(body);
+        self
+    }
+
+    /// Streams the body from `reader`. If you know the `length`, pass it along so it can be
+    /// sent as the `Content-Length`.
+    pub fn stream<R: Read + Send + 'static>(mut self, reader: R, length: Option<u64>) -> Self {
+        if let Some(length) = length {
+            self = self.header("Content-Length", length.to_string());
+        }
+
+        self.body = ResponseBody::Stream {
+            reader: Box::new(reader),
+            length
+        };
+
+        self
+    }
+
+    /// Returns the HTTP status code.
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Returns the headers.
+    pub fn headers(&self) -> &HashMap<String, String> {
+        &self.headers
+    }
+
+    /// Splits this into its status, headers and body, for sending.
+    pub(crate) fn into_parts(self) -> (u16, HashMap<String, String>, ResponseBody) {
+        (self.status, self.headers, self.body)
+    }
+
+    /// A `206 Partial Content` response for bytes `start..=end` of `length`.
+    fn partial(start: u64, end: u64, length: u64) -> Self {
+        ProtocolResponse::new(206)
+            .header("Accept-Ranges", "bytes")
+            .header("Content-Range", format!("bytes {}-{}/{}", start, end, length))
+    }
+
+    /// A `416 Range Not Satisfiable` response for a resource that's `length` bytes long.
+    fn unsatisfiable(length: u64) -> Self {
+        ProtocolResponse::new(416)
+            .header("Accept-Ranges", "bytes")
+            .header("Content-Range", format!("bytes */{}", length))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::{ProtocolResponse, ResponseBody};
+    use crate::utils::tempdir::TempDir;
+    use crate::webview::ProtocolRequest;
+
+    fn body(response: ProtocolResponse) -> Vec<u8> {
+        match response.into_parts().2 {
+            ResponseBody::Bytes(bytes) => bytes,
+            ResponseBody::Stream { mut reader, .. } => {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes).unwrap();
+                bytes
+            }
+        }
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut request = ProtocolRequest {
+            url: "app://bundle/notes.txt".into(),
+            ..ProtocolRequest::default()
+        };
+
+        let response = ProtocolResponse::bytes_for(&request, b"hello world".to_vec());
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["Content-Length"], "11");
+        assert_eq!(body(response), b"hello world");
+
+        request.headers.insert("Range".into(), "bytes=6-".into());
+        let response = ProtocolResponse::bytes_for(&request, b"hello world".to_vec());
+        assert_eq!(response.status(), 206);
+        assert_eq!(response.headers()["Content-Range"], "bytes 6-10/11");
+        assert_eq!(body(response), b"world");
+
+        let directory = TempDir::new("protocol");
+        let path = directory.join("hello.txt");
+        std::fs::write(&path, b"hello world").unwrap();
+
+        request.headers.insert("Range".into(), "bytes=-5".into());
+        let response = ProtocolResponse::file(&request, &path).unwrap();
+        assert_eq!(response.status(), 206);
+        assert_eq!(response.headers()["Content-Length"], "5");
+        assert_eq!(body(response), b"world");
+
+        request.headers.insert("Range".into(), "bytes=20-".into());
+        let response = ProtocolResponse::file(&request, &path).unwrap();
+        assert_eq!(response.status(), 416);
+        assert_eq!(response.headers()["Content-Range"], "bytes */11");
+    }
+}
@@ -0,0 +1,202 @@
+//! Wraps `WKURLSchemeTask`, which is how responses make their way back to WebKit.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl};
+use objc_id::ShareId;
+
+use super::{ProtocolResponse, ResponseBody};
+use crate::error::{Error, UrlErrorCode, URL_ERROR_DOMAIN};
+use crate::foundation::{id, to_bool, NSData, NSInteger, NSString, ObjcValue, BOOL};
+
+/// How many bytes of a streamed body are sent to WebKit at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+lazy_static! {
+    /// Tasks that are in flight, keyed by their address, so that `webView:stopURLSchemeTask:` can
+    /// flag them as stopped.
+    static ref TASKS: Mutex<HashMap<usize, Arc<AtomicBool>>> = Mutex::new(HashMap::new());
+}
+
+/// A retained `WKURLSchemeTask`. It's only ever messaged on the main thread (see `on_main`), so
+/// it's fine to hold on to it from others.
+#[derive(Debug)]
+struct SchemeTask(ShareId<Object>);
+
+unsafe impl Send for SchemeTask {}
+unsafe impl Sync for SchemeTask {}
+
+/// A pending custom protocol request. Respond to it with `respond()` (or `fail()`), from any
+/// thread; if it's dropped without either, the request fails.
+#[derive(Debug)]
+#[must_use = "dropping a task fails the request"]
+pub struct ProtocolTask {
+    task: SchemeTask,
+    stopped: Arc<AtomicBool>,
+    finished: bool
+}
+
+impl ProtocolTask {
+    /// Retains and tracks a `WKURLSchemeTask`.
+    pub(crate) fn new(task: id) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        TASKS.lock().unwrap().insert(task as usize, stopped.clone());
+
+        ProtocolTask {
+            task: SchemeTask(unsafe { ShareId::from_ptr(task) }),
+            stopped,
+            finished: false
+        }
+    }
+
+    /// Whether WebKit has stopped the task (e.g, the page navigated away, or a `<video>` seeked
+    /// elsewhere). There's no point responding once this is `true`; anything you send is
+    /// dropped.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Sends `response`. Streamed bodies are read on the calling thread, so if you're serving
+    /// anything large, call this from a background thread.
+    ///
+    /// Off the main thread, this waits on the main queue for every chunk it hands to WebKit. Don't
+    /// call it from a thread the main thread is itself blocked waiting on (e.g, by joining it, or
+    /// holding a lock it needs) - that deadlocks.
+    pub fn respond(mut self, response: ProtocolResponse) {
+        let (status, headers, body) = response.into_parts();
+
+        self.send(move |task| unsafe {
+            let request: id = msg_send![task, request];
+            let url: id = msg_send![request, URL];
+            let version = NSString::new("HTTP/1.1");
+
+            let headers: id = ObjcValue::Dictionary(
+                headers
+                    .into_iter()
+                    .map(|(name, value)| (name, ObjcValue::String(value)))
+                    .collect()
+            )
+            .into();
+
+            let response: id = msg_send![class!(NSHTTPURLResponse), alloc];
+            let response: id = msg_send![response, initWithURL:url
+                statusCode:status as NSInteger
+                HTTPVersion:&*version
+                headerFields:headers
+            ];
+
+            let _: () = msg_send![task, didReceiveResponse: response];
+            let _: () = msg_send![response, release];
+        });
+
+        match body {
+            ResponseBody::Bytes(bytes) => self.send_data(bytes),
+
+            ResponseBody::Stream { mut reader, .. } => loop {
+                if self.is_stopped() {
+                    break;
+                }
+
+                let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+
+                match (&mut reader).take(CHUNK_SIZE as u64).read_to_end(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(_) => self.send_data(chunk),
+                    Err(error) => {
+                        let error = Error::with_description(URL_ERROR_DOMAIN, UrlErrorCode::Unknown.into(), error.to_string());
+                        return self.fail(error);
+                    }
+                }
+            }
+        }
+
+        self.finished = true;
+        self.send(|task| unsafe {
+            let _: () = msg_send![task, didFinish];
+        });
+        untrack(&self.task);
+    }
+
+    /// Fails the request with `error`.
+    pub fn fail(mut self, error: Error) {
+        self.finished = true;
+        self.send(move |task| unsafe {
+            let _: () = msg_send![task, didFailWithError: error.into_nserror()];
+        });
+        untrack(&self.task);
+    }
+
+    fn send_data(&self, bytes: Vec<u8>) {
+        if bytes.is_empty() {
+            return;
+        }
+
+        self.send(move |task| unsafe {
+            let data = NSData::new(bytes);
+            let _: () = msg_send![task, didReceiveData:&*data];
+        });
+    }
+
+    /// Runs `handler` with the task on the main thread, unless WebKit has stopped it. Checking
+    /// there (where `stop_task` also runs) means we can't race a stop, which would raise an
+    /// Objective-C exception.
+    fn send<F: FnOnce(&Object) + Send>(&self, handler: F) {
+        let task = &self.task;
+        let stopped = &self.stopped;
+
+        on_main(move || {
+            if !stopped.load(Ordering::SeqCst) {
+                handler(&task.0);
+            }
+        });
+    }
+}
+
+impl Drop for ProtocolTask {
+    /// WebKit expects every task to be completed, so anything left unanswered is failed.
+    fn drop(&mut self) {
+        if !self.finished {
+            self.finished = true;
+
+            let error = Error::with_description(
+                URL_ERROR_DOMAIN,
+                UrlErrorCode::ResourceUnavailable.into(),
+                "The custom protocol handler didn't respond"
+            );
+
+            self.send(move |task| unsafe {
+                let _: () = msg_send![task, didFailWithError: error.into_nserror()];
+            });
+
+            untrack(&self.task);
+        }
+    }
+}
+
+/// Called from `webView:stopURLSchemeTask:`.
+pub(crate) fn stop_task(task: id) {
+    if let Some(stopped) = TASKS.lock().unwrap().remove(&(task as usize)) {
+        stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+fn untrack(task: &SchemeTask) {
+    TASKS.lock().unwrap().remove(&(&*task.0 as *const Object as usize));
+}
+
+/// Runs `handler` on the main thread, waiting for it to finish. Waiting keeps a fast reader from
+/// queueing up an entire file's worth of chunks ahead of WebKit, but means the main thread must
+/// not be waiting on the caller (see `ProtocolTask::respond`).
+fn on_main<F: FnOnce() + Send>(handler: F) {
+    let is_main_thread: BOOL = unsafe { msg_send![class!(NSThread), isMainThread] };
+
+    match to_bool(is_main_thread) {
+        true => handler(),
+        false => dispatch::Queue::main().exec_sync(handler)
+    }
+}
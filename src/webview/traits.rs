@@ -2,8 +2,11 @@
 //! `WKWebView`. It allows you to do things such as handle opening a file (for uploads or
 //! in-browser-processing), handling navigation actions or JS message callbacks, and so on.
 
+use crate::foundation::ObjcValue;
 use crate::webview::actions::{NavigationAction, NavigationResponse, OpenPanelParameters};
 use crate::webview::enums::{NavigationPolicy, NavigationResponsePolicy};
+use crate::webview::javascript::{ScriptMessage, ScriptReply};
+use crate::webview::protocol::{ProtocolRequest, ProtocolResponse, ProtocolTask};
 use crate::webview::WebView;
 
 /// You can implement this on structs to handle callbacks from the underlying `WKWebView`.
@@ -41,14 +44,48 @@ pub trait WebViewDelegate {
     /// `webkit.messageHandlers.notify.postMessage({...})` it would wind up here, with `name` being
     /// `notify` and `body` being your arguments.
     ///
-    /// Note that at the moment, you really should handle bridging JSON/stringification yourself.
+    /// This is only called for messages whose body is a string. To receive anything else (objects,
+    /// numbers, and so on), implement `on_script_message` instead.
     fn on_message(&self, _name: &str, _body: &str) {}
 
-    /// Called when a custom protocol URI is requested.
+    /// Called when a JS message is passed by the browser process, with the body converted to an
+    /// `ObjcValue` (or, with the `serde` feature, deserializable via `ScriptMessage::deserialize`).
+    ///
+    /// By default, this forwards string bodies to `on_message`.
+    fn on_script_message(&self, message: ScriptMessage) {
+        if let ObjcValue::String(body) = &message.body {
+            self.on_message(&message.name, body);
+        }
+    }
+
+    /// Called when a JS message is posted to a handler added with
+    /// `WebViewConfig::add_reply_handler`. On the JS side, `postMessage` returns a promise;
+    /// resolve or reject it with `reply`, now or later.
+    ///
+    /// By default, this rejects the promise.
+    fn on_message_with_reply(&self, message: ScriptMessage, reply: ScriptReply) {
+        reply.reject(format!("No handler for '{}'", message.name));
+    }
+
+    /// Called when a custom protocol URI is requested. This is the simple version: return the
+    /// whole body (its content type is guessed from the bytes and URI), or `None` for a 404. For
+    /// control over status codes and headers, streaming, or responding asynchronously, implement
+    /// `on_custom_protocol_task` instead.
     fn on_custom_protocol_request(&self, _uri: &str) -> Option<Vec<u8>> {
         None
     }
 
+    /// Called when a custom protocol URI is requested. Respond through `task`, whenever and from
+    /// whichever thread you like.
+    ///
+    /// By default, this responds with whatever `on_custom_protocol_request` returns.
+    fn on_custom_protocol_task(&self, request: ProtocolRequest, task: ProtocolTask) {
+        task.respond(match self.on_custom_protocol_request(&request.url) {
+            Some(content) => ProtocolResponse::bytes_for(&request, content),
+            None => ProtocolResponse::not_found()
+        });
+    }
+
     /// Given a callback handler, you can decide what policy should be taken for a given browser
     /// action. By default, this is `NavigationPolicy::Allow`.
     fn policy_for_navigation_action<F: Fn(NavigationPolicy)>(&self, _action: NavigationAction, handler: F) {